@@ -0,0 +1,355 @@
+//! Drawing on the screen while recording
+//!
+//! For training sessions and walkthroughs, the presenter can draw over what is being
+//! recorded, like a telestrator: freehand lines, arrows or highlighter strokes. The
+//! drawings live in a transparent window covering the recorded display, or the display
+//! the recorded window is on, which ScreenCaptureKit records along with everything
+//! else, so they end up in the video exactly as seen on screen. In a window recording
+//! only what is drawn over the window is recorded.
+//!
+//! The window lets clicks through until drawing is switched on (from the recording
+//! view or with [`crate::hotkey::DRAW_SHORTCUT`]); while drawing, the app's own
+//! windows are raised above it so the Stop Drawing button can still be clicked.
+//! Switching it off leaves the drawings up until they are erased with
+//! [`crate::hotkey::ERASE_SHORTCUT`] or the recording stops. The two shortcuts are
+//! only registered while drawing is available. Turned on, with the tool to draw with, in `annotations.json` in
+//! the data folder. AppKit objects must only be touched from the main thread, where
+//! the Dioxus UI runs.
+
+use cocoa::base::{id, nil, BOOL, NO, YES};
+use cocoa::foundation::{NSPoint, NSRect};
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::sync::{Mutex, Once};
+
+use crate::hotkey;
+use crate::screens;
+use crate::user_data;
+
+/// `NSWindowStyleMaskBorderless`
+const BORDERLESS: u64 = 0;
+/// `NSBackingStoreBuffered`
+const BUFFERED: u64 = 2;
+/// `NSFloatingWindowLevel`
+const FLOATING_LEVEL: i64 = 3;
+/// `NSRoundLineCapStyle` and `NSRoundLineJoinStyle`
+const ROUND: u64 = 1;
+
+const PEN_WIDTH: f64 = 4.0;
+const HIGHLIGHT_WIDTH: f64 = 22.0;
+const HIGHLIGHT_ALPHA: f64 = 0.35;
+/// Length of each side of an arrowhead, and its angle to the shaft
+const ARROW_HEAD_LENGTH: f64 = 18.0;
+const ARROW_HEAD_ANGLE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tool {
+    /// Red freehand line
+    #[default]
+    Pen,
+    /// Red straight arrow from where the drag started to where it ends
+    Arrow,
+    /// Wide, translucent yellow line
+    Highlight,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnnotationSettings {
+    /// Offer drawing while recording a display or window
+    pub enabled: bool,
+    pub tool: Tool,
+}
+
+/// Settings in effect, loaded on first use
+static CURRENT: Mutex<Option<AnnotationSettings>> = Mutex::new(None);
+
+impl AnnotationSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("annotations.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save, and use these settings from now on
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize annotation settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save annotation settings: {e}"))?;
+        *CURRENT.lock().unwrap() = Some(self.clone());
+        Ok(())
+    }
+}
+
+pub fn current() -> AnnotationSettings {
+    CURRENT.lock().unwrap().get_or_insert_with(AnnotationSettings::load).clone()
+}
+
+/// Pick up settings written to `annotations.json` by something other than
+/// [`AnnotationSettings::save`]
+pub fn reload() {
+    *CURRENT.lock().unwrap() = Some(AnnotationSettings::load());
+}
+
+struct Stroke {
+    tool: Tool,
+    /// In the view's coordinates
+    points: Vec<NSPoint>,
+}
+
+thread_local! {
+    static WINDOW: Cell<id> = Cell::new(nil);
+    static CANVAS: Cell<id> = Cell::new(nil);
+    /// Display the window covers
+    static DISPLAY: Cell<Option<u32>> = Cell::new(None);
+    static DRAWING: Cell<bool> = Cell::new(false);
+    static STROKES: RefCell<Vec<Stroke>> = RefCell::new(Vec::new());
+    /// The app's windows raised above the drawing window, with their usual levels
+    static RAISED: RefCell<Vec<(id, i64)>> = RefCell::new(Vec::new());
+}
+
+fn location(view: &Object, event: id) -> NSPoint {
+    unsafe {
+        let in_window: NSPoint = msg_send![event, locationInWindow];
+        msg_send![view, convertPoint: in_window fromView: nil]
+    }
+}
+
+extern "C" fn accepts_first_mouse(_view: &Object, _cmd: Sel, _event: id) -> BOOL {
+    YES
+}
+
+extern "C" fn mouse_down(view: &Object, _cmd: Sel, event: id) {
+    let point = location(view, event);
+    let tool = current().tool;
+    STROKES.with(|strokes| strokes.borrow_mut().push(Stroke { tool, points: vec![point] }));
+}
+
+extern "C" fn mouse_dragged(view: &Object, _cmd: Sel, event: id) {
+    let point = location(view, event);
+    STROKES.with(|strokes| {
+        if let Some(stroke) = strokes.borrow_mut().last_mut() {
+            if stroke.tool == Tool::Arrow {
+                // Only the start and the current end
+                stroke.points.truncate(1);
+            }
+            stroke.points.push(point);
+        }
+    });
+    unsafe {
+        let _: () = msg_send![view, setNeedsDisplay: YES];
+    }
+}
+
+unsafe fn line(from: NSPoint, to: NSPoint) -> id {
+    let path: id = msg_send![class!(NSBezierPath), bezierPath];
+    let _: () = msg_send![path, moveToPoint: from];
+    let _: () = msg_send![path, lineToPoint: to];
+    path
+}
+
+unsafe fn stroke_path(stroke: &Stroke) -> id {
+    let first = stroke.points[0];
+    let last = *stroke.points.last().unwrap();
+    if stroke.tool != Tool::Arrow {
+        let path: id = msg_send![class!(NSBezierPath), bezierPath];
+        let _: () = msg_send![path, moveToPoint: first];
+        for point in &stroke.points[1..] {
+            let _: () = msg_send![path, lineToPoint: *point];
+        }
+        return path;
+    }
+    let path = line(first, last);
+    let angle = (last.y - first.y).atan2(last.x - first.x);
+    for side in [ARROW_HEAD_ANGLE, -ARROW_HEAD_ANGLE] {
+        let back = angle + std::f64::consts::PI + side;
+        let tip = NSPoint::new(
+            last.x + ARROW_HEAD_LENGTH * back.cos(),
+            last.y + ARROW_HEAD_LENGTH * back.sin(),
+        );
+        let _: () = msg_send![path, appendBezierPath: line(last, tip)];
+    }
+    path
+}
+
+extern "C" fn draw_rect(_view: &Object, _cmd: Sel, _dirty: NSRect) {
+    STROKES.with(|strokes| unsafe {
+        let red: id = msg_send![class!(NSColor), systemRedColor];
+        let yellow: id = msg_send![class!(NSColor), systemYellowColor];
+        let highlight: id = msg_send![yellow, colorWithAlphaComponent: HIGHLIGHT_ALPHA];
+        for stroke in strokes.borrow().iter() {
+            let path = stroke_path(stroke);
+            let (color, width) = match stroke.tool {
+                Tool::Highlight => (highlight, HIGHLIGHT_WIDTH),
+                Tool::Pen | Tool::Arrow => (red, PEN_WIDTH),
+            };
+            let _: () = msg_send![color, set];
+            let _: () = msg_send![path, setLineWidth: width];
+            let _: () = msg_send![path, setLineCapStyle: ROUND];
+            let _: () = msg_send![path, setLineJoinStyle: ROUND];
+            let _: () = msg_send![path, stroke];
+        }
+    });
+}
+
+/// `NSView` subclass that records mouse drags as strokes and draws them
+fn canvas_class() -> &'static Class {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        let mut decl = ClassDecl::new("TalkaAnnotationCanvas", class!(NSView)).unwrap();
+        unsafe {
+            decl.add_method(
+                sel!(acceptsFirstMouse:),
+                accepts_first_mouse as extern "C" fn(&Object, Sel, id) -> BOOL,
+            );
+            decl.add_method(sel!(mouseDown:), mouse_down as extern "C" fn(&Object, Sel, id));
+            decl.add_method(sel!(mouseDragged:), mouse_dragged as extern "C" fn(&Object, Sel, id));
+            decl.add_method(sel!(drawRect:), draw_rect as extern "C" fn(&Object, Sel, NSRect));
+        }
+        decl.register();
+    });
+    Class::get("TalkaAnnotationCanvas").unwrap()
+}
+
+/// Transparent window over all of `display_id`, that clicks pass through
+unsafe fn show(display_id: u32) {
+    let screen = screens::screen_for(Some(display_id));
+    let frame: NSRect = msg_send![screen, frame];
+    let window: id = msg_send![class!(NSWindow), alloc];
+    let window: id = msg_send![window,
+        initWithContentRect: frame
+        styleMask: BORDERLESS
+        backing: BUFFERED
+        defer: NO
+    ];
+    let clear: id = msg_send![class!(NSColor), clearColor];
+    let _: () = msg_send![window, setReleasedWhenClosed: NO];
+    let _: () = msg_send![window, setOpaque: NO];
+    let _: () = msg_send![window, setBackgroundColor: clear];
+    let _: () = msg_send![window, setHasShadow: NO];
+    let _: () = msg_send![window, setLevel: FLOATING_LEVEL];
+    let _: () = msg_send![window, setIgnoresMouseEvents: YES];
+
+    let canvas: id = msg_send![canvas_class(), alloc];
+    let bounds = NSRect::new(NSPoint::new(0.0, 0.0), frame.size);
+    let canvas: id = msg_send![canvas, initWithFrame: bounds];
+    let _: () = msg_send![window, setContentView: canvas];
+    let _: () = msg_send![canvas, release];
+    let _: () = msg_send![window, orderFrontRegardless];
+
+    WINDOW.with(|cell| cell.set(window));
+    CANVAS.with(|cell| cell.set(canvas));
+}
+
+/// Put the app's other visible windows (the main window among them) above the
+/// drawing window, so their buttons keep working while drawing
+unsafe fn raise_app_windows(overlay: id) {
+    let app: id = msg_send![class!(NSApplication), sharedApplication];
+    let windows: id = msg_send![app, windows];
+    let count: usize = msg_send![windows, count];
+    for i in 0..count {
+        let window: id = msg_send![windows, objectAtIndex: i];
+        let visible: BOOL = msg_send![window, isVisible];
+        let level: i64 = msg_send![window, level];
+        if window == overlay || visible == NO || level >= FLOATING_LEVEL {
+            continue;
+        }
+        let _: id = msg_send![window, retain];
+        let _: () = msg_send![window, setLevel: FLOATING_LEVEL];
+        let _: () = msg_send![window, orderFrontRegardless];
+        RAISED.with(|raised| raised.borrow_mut().push((window, level)));
+    }
+}
+
+/// Put the windows moved by [`raise_app_windows`] back at their own levels
+unsafe fn lower_app_windows() {
+    for (window, level) in RAISED.with(|raised| std::mem::take(&mut *raised.borrow_mut())) {
+        let _: () = msg_send![window, setLevel: level];
+        let _: () = msg_send![window, release];
+    }
+}
+
+fn hide() {
+    unsafe { lower_app_windows() };
+    CANVAS.with(|cell| cell.set(nil));
+    WINDOW.with(|cell| {
+        let window = cell.replace(nil);
+        if window != nil {
+            unsafe {
+                let _: () = msg_send![window, close];
+                let _: () = msg_send![window, release];
+            }
+        }
+    });
+    DRAWING.with(|drawing| drawing.set(false));
+    STROKES.with(|strokes| strokes.borrow_mut().clear());
+}
+
+/// Cover `display` while it is being recorded with annotations turned on, and take
+/// the window and its drawings down otherwise
+///
+/// Called from the UI poll loop.
+pub fn sync(display: Option<u32>) {
+    let display = display.filter(|_| current().enabled);
+    if DISPLAY.with(Cell::get) == display {
+        return;
+    }
+    hide();
+    if let Some(display_id) = display {
+        unsafe { show(display_id) };
+        hotkey::register_annotations();
+        println!("✏️  Drawing available on display {}", display_id);
+    } else {
+        hotkey::unregister_annotations();
+    }
+    DISPLAY.with(|cell| cell.set(display));
+}
+
+/// Whether the drawing window is up
+pub fn available() -> bool {
+    WINDOW.with(Cell::get) != nil
+}
+
+pub fn is_drawing() -> bool {
+    DRAWING.with(Cell::get)
+}
+
+/// Switch between drawing and clicking through to the apps underneath
+pub fn toggle_drawing() {
+    let window = WINDOW.with(Cell::get);
+    if window == nil {
+        return;
+    }
+    let drawing = !is_drawing();
+    DRAWING.with(|cell| cell.set(drawing));
+    unsafe {
+        let _: () = msg_send![window, setIgnoresMouseEvents: if drawing { NO } else { YES }];
+        if drawing {
+            raise_app_windows(window);
+        } else {
+            lower_app_windows();
+        }
+    }
+}
+
+/// Erase everything drawn so far
+pub fn clear() {
+    STROKES.with(|strokes| strokes.borrow_mut().clear());
+    let canvas = CANVAS.with(Cell::get);
+    if canvas != nil {
+        unsafe {
+            let _: () = msg_send![canvas, setNeedsDisplay: YES];
+        }
+    }
+}
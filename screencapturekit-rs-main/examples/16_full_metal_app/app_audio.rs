@@ -0,0 +1,130 @@
+//! Audio from only the selected window's application
+//!
+//! ScreenCaptureKit picks the audio to capture by application, and a single-window
+//! filter passes everything the system plays. When a window is picked, the recorder
+//! instead captures the window's display limited to the window's application, with
+//! the application's other windows left out, and crops the stream to the window.
+//! The picture is the same, but music playing in another app stays out of the
+//! recording.
+//!
+//! The window is found by its window ID, so another window with the same title is
+//! never captured instead, and a [`WindowTracker`] keeps the crop on the window as it
+//! is moved or resized.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use screencapturekit::cg::CGRect;
+use screencapturekit::prelude::*;
+use screencapturekit::shareable_content::SCShareableContent;
+
+/// How often a tracked window's position is looked up
+const TRACK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Capture of one window that only carries its application's audio
+pub struct WindowAppCapture {
+    pub filter: SCContentFilter,
+    /// The window, in the display's coordinates
+    pub source_rect: CGRect,
+    pub application_name: String,
+    pub window_id: u32,
+    pub display_id: u32,
+}
+
+/// The window's frame in the coordinates of the display containing its center
+fn locate(content: &SCShareableContent, window: &SCWindow) -> Option<(SCDisplay, CGRect)> {
+    let frame = window.frame();
+    let center = frame.center();
+    let display = content.displays().into_iter().find(|d| {
+        let bounds = d.frame();
+        (bounds.min_x()..bounds.max_x()).contains(&center.x) && (bounds.min_y()..bounds.max_y()).contains(&center.y)
+    })?;
+    let bounds = display.frame();
+    let rect = CGRect::new(frame.x - bounds.x, frame.y - bounds.y, frame.width, frame.height);
+    Some((display, rect))
+}
+
+/// Look up the on-screen window `window_id` and build its application-scoped capture
+pub fn for_window(window_id: u32) -> Result<WindowAppCapture, String> {
+    let content = SCShareableContent::get().map_err(|e| format!("Failed to get shareable content: {e:?}"))?;
+    let windows = content.windows();
+    let window = windows
+        .iter()
+        .find(|w| w.is_on_screen() && w.window_id() == window_id)
+        .ok_or_else(|| "The picked window is no longer on screen".to_string())?;
+    let application = window
+        .owning_application()
+        .ok_or_else(|| "The picked window has no owning application".to_string())?;
+    let (display, source_rect) =
+        locate(&content, window).ok_or_else(|| "The picked window is not on a connected display".to_string())?;
+
+    let other_windows: Vec<_> = windows
+        .iter()
+        .filter(|w| {
+            w.window_id() != window_id
+                && w.owning_application().is_some_and(|app| app.process_id() == application.process_id())
+        })
+        .collect();
+    let filter = SCContentFilter::builder()
+        .display(&display)
+        .include_applications(&[&application], &other_windows)
+        .build();
+
+    Ok(WindowAppCapture {
+        filter,
+        source_rect,
+        application_name: application.application_name(),
+        window_id,
+        display_id: display.display_id(),
+    })
+}
+
+/// Follows a captured window around its display, in the background
+///
+/// Stops when dropped.
+pub struct WindowTracker {
+    stop: Arc<AtomicBool>,
+    latest: Arc<Mutex<Option<CGRect>>>,
+}
+
+impl WindowTracker {
+    pub fn start(capture: &WindowAppCapture) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let latest = Arc::new(Mutex::new(None));
+        let (window_id, display_id) = (capture.window_id, capture.display_id);
+        let stop_clone = Arc::clone(&stop);
+        let latest_clone = Arc::clone(&latest);
+        std::thread::spawn(move || {
+            while !stop_clone.load(Ordering::Relaxed) {
+                std::thread::sleep(TRACK_INTERVAL);
+                let Ok(content) = SCShareableContent::get() else {
+                    continue;
+                };
+                let rect = content
+                    .windows()
+                    .iter()
+                    .find(|w| w.window_id() == window_id)
+                    .and_then(|window| locate(&content, window))
+                    // The filter is tied to the display; a window moved off it keeps its last crop
+                    .filter(|(display, _)| display.display_id() == display_id)
+                    .map(|(_, rect)| rect);
+                if rect.is_some() {
+                    *latest_clone.lock().unwrap() = rect;
+                }
+            }
+        });
+        Self { stop, latest }
+    }
+
+    /// Where the window is now, if that is not `current`
+    pub fn moved(&self, current: CGRect) -> Option<CGRect> {
+        self.latest.lock().unwrap().filter(|rect| *rect != current)
+    }
+}
+
+impl Drop for WindowTracker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,189 @@
+//! Archival re-encoding of old recordings
+//!
+//! Recordings are captured at high bitrates; archiving re-encodes them to HEVC with
+//! mono audio, which typically shrinks them several times while keeping them
+//! watchable. Jobs run one at a time on a background thread so the library stays
+//! responsive, and the archived file replaces the original only after it verifies.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Mutex, OnceLock};
+
+use crate::library;
+use crate::media;
+use crate::sidecar::RecordingSidecar;
+
+/// Compression profile used when archiving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchivePreset {
+    /// HEVC at the original resolution and frame rate, mono audio
+    Standard,
+    /// HEVC scaled down to 720p at 15 fps, low-bitrate mono audio
+    Compact,
+}
+
+impl ArchivePreset {
+    pub const ALL: [Self; 2] = [Self::Standard, Self::Compact];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Standard => "Standard (HEVC, mono)",
+            Self::Compact => "Compact (720p, 15 fps)",
+        }
+    }
+
+    pub fn ffmpeg_args(self) -> &'static [&'static str] {
+        match self {
+            Self::Standard => &[
+                "-c:v", "hevc_videotoolbox", "-q:v", "50", "-tag:v", "hvc1",
+                "-c:a", "aac", "-ac", "1", "-b:a", "64k",
+            ],
+            Self::Compact => &[
+                "-vf", "scale=-2:'min(720,ih)'", "-r", "15",
+                "-c:v", "hevc_videotoolbox", "-q:v", "40", "-tag:v", "hvc1",
+                "-c:a", "aac", "-ac", "1", "-b:a", "48k",
+            ],
+        }
+    }
+}
+
+/// Progress of an archive job, as shown in the library
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArchiveJobState {
+    Queued,
+    Running,
+    Done { saved_bytes: u64 },
+    Failed(String),
+}
+
+impl ArchiveJobState {
+    pub const fn is_pending(&self) -> bool {
+        matches!(self, Self::Queued | Self::Running)
+    }
+}
+
+impl std::fmt::Display for ArchiveJobState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Queued => write!(f, "Queued for archiving"),
+            Self::Running => write!(f, "Archiving…"),
+            Self::Done { saved_bytes } => write!(
+                f,
+                "Archived, saved {:.1} MB",
+                *saved_bytes as f64 / (1024.0 * 1024.0)
+            ),
+            Self::Failed(e) => write!(f, "Archiving failed: {e}"),
+        }
+    }
+}
+
+static QUEUE: OnceLock<Sender<(String, ArchivePreset)>> = OnceLock::new();
+/// Jobs by recording path, in submission order
+static JOBS: Mutex<Vec<(String, ArchiveJobState)>> = Mutex::new(Vec::new());
+
+fn set_state(path: &str, state: ArchiveJobState) {
+    let mut jobs = JOBS.lock().unwrap();
+    match jobs.iter_mut().find(|(p, _)| p == path) {
+        Some((_, existing)) => *existing = state,
+        None => jobs.push((path.to_string(), state)),
+    }
+}
+
+/// Snapshot of every job submitted this session
+pub fn jobs() -> Vec<(String, ArchiveJobState)> {
+    JOBS.lock().unwrap().clone()
+}
+
+/// Queue a recording for archiving; ignored if it is already queued or running
+pub fn enqueue(path: &str, preset: ArchivePreset) {
+    let already_pending = JOBS
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(p, state)| p == path && state.is_pending());
+    if already_pending {
+        return;
+    }
+
+    let queue = QUEUE.get_or_init(|| {
+        let (tx, rx) = channel::<(String, ArchivePreset)>();
+        std::thread::spawn(move || {
+            for (path, preset) in rx {
+                set_state(&path, ArchiveJobState::Running);
+                let state = match archive_recording(&path, preset) {
+                    Ok(saved_bytes) => ArchiveJobState::Done { saved_bytes },
+                    Err(e) => {
+                        eprintln!("❌ Failed to archive {}: {}", path, e);
+                        ArchiveJobState::Failed(e)
+                    }
+                };
+                set_state(&path, state);
+            }
+        });
+        tx
+    });
+
+    set_state(path, ArchiveJobState::Queued);
+    if queue.send((path.to_string(), preset)).is_err() {
+        set_state(path, ArchiveJobState::Failed("Archive worker stopped".to_string()));
+    }
+}
+
+/// Re-encode `path` and swap the result in, returning the bytes reclaimed
+fn archive_recording(path: &str, preset: ArchivePreset) -> Result<u64, String> {
+    let source = Path::new(path);
+    let stem = source
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| format!("Invalid recording path {path}"))?;
+    // Hidden while encoding so the library doesn't list the partial file
+    let temp_output = source.with_file_name(format!(".{stem}_archiving.mp4"));
+    let final_output = source.with_file_name(format!("{stem}.mp4"));
+    let temp_output_str = temp_output.to_string_lossy().into_owned();
+    let final_output_str = final_output.to_string_lossy().into_owned();
+
+    let original_size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read {path}: {e}"))?
+        .len();
+    println!("🗜️  Archiving {} ({})", path, preset.label());
+
+    let encoded = media::transcode(path, &temp_output_str, preset.ffmpeg_args())
+        .and_then(|()| media::verify_recording(&temp_output_str));
+    let duration = match encoded {
+        Ok(duration) => duration,
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp_output);
+            return Err(e);
+        }
+    };
+    let archived_size = std::fs::metadata(&temp_output).map_or(0, |m| m.len());
+    if archived_size >= original_size {
+        let _ = std::fs::remove_file(&temp_output);
+        return Err("Recording is already smaller than its archived copy would be".to_string());
+    }
+
+    // Swap the archived copy in, carrying the metadata over; the original is only
+    // removed once the copy is in place, so a failed move leaves it untouched
+    let mut sidecar = RecordingSidecar::load(path).unwrap_or_default();
+    if let Err(e) = std::fs::rename(&temp_output, &final_output) {
+        let _ = std::fs::remove_file(&temp_output);
+        return Err(format!("Failed to move archived file into place: {e}"));
+    }
+    sidecar.duration_seconds = Some(duration);
+    sidecar.archive_preset = Some(preset);
+    sidecar.save(&final_output_str)?;
+    // A .mov original sits next to its .mp4 copy; an .mp4 was replaced by the rename
+    if final_output != source {
+        library::delete_recording(path).map_err(|e| format!("Failed to remove {path}: {e}"))?;
+    }
+
+    let saved_bytes = original_size.saturating_sub(archived_size);
+    println!(
+        "✅ Archived {} ({:.1} MB saved)",
+        final_output_str,
+        saved_bytes as f64 / (1024.0 * 1024.0)
+    );
+    Ok(saved_bytes)
+}
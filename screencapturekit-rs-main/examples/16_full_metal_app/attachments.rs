@@ -0,0 +1,119 @@
+//! Notes and files attached to a recording
+//!
+//! Before a recording is uploaded, the trim editor can attach a text note or small
+//! files such as the agenda PDF or the slide deck. They are kept in the recording's
+//! sidecar until the upload, which sends them through the storage attachment
+//! endpoints linked to the recording's `file_id`. Files stay where they are; only
+//! their paths are remembered. Any that cannot be sent are kept in the sidecar and can
+//! be retried from the library.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+use crate::sidecar::RecordingSidecar;
+
+/// Largest file that can be attached
+pub const MAX_FILE_BYTES: u64 = 25 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Attachment {
+    Note { text: String },
+    File { path: String },
+}
+
+impl Attachment {
+    /// Short description for the attachment list
+    pub fn label(&self) -> String {
+        match self {
+            Self::Note { text } => {
+                let first_line = text.lines().next().unwrap_or_default().trim();
+                let preview: String = first_line.chars().take(40).collect();
+                if preview.chars().count() < first_line.chars().count() {
+                    format!("📝 {}…", preview.trim_end())
+                } else {
+                    format!("📝 {preview}")
+                }
+            }
+            Self::File { path } => {
+                let name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path);
+                format!("📎 {name}")
+            }
+        }
+    }
+}
+
+pub fn list(recording_path: &str) -> Vec<Attachment> {
+    RecordingSidecar::load(recording_path)
+        .map(|s| s.attachments)
+        .unwrap_or_default()
+}
+
+pub fn add_note(recording_path: &str, text: &str) -> Result<(), String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("The note is empty".to_string());
+    }
+    RecordingSidecar::update(recording_path, |s| {
+        s.attachments.push(Attachment::Note { text: text.to_string() });
+    })
+}
+
+/// Attach files, refusing the whole batch if one is missing or too large
+pub fn add_files(recording_path: &str, paths: &[String]) -> Result<(), String> {
+    for path in paths {
+        let size = std::fs::metadata(path)
+            .map_err(|e| format!("Cannot read {path}: {e}"))?
+            .len();
+        if size > MAX_FILE_BYTES {
+            let name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path);
+            return Err(format!(
+                "{name} is larger than {} MB",
+                MAX_FILE_BYTES / (1024 * 1024)
+            ));
+        }
+    }
+    RecordingSidecar::update(recording_path, |s| {
+        for path in paths {
+            let attachment = Attachment::File { path: path.clone() };
+            if !s.attachments.contains(&attachment) {
+                s.attachments.push(attachment);
+            }
+        }
+    })
+}
+
+pub fn remove(recording_path: &str, index: usize) -> Result<(), String> {
+    RecordingSidecar::update(recording_path, |s| {
+        if index < s.attachments.len() {
+            s.attachments.remove(index);
+        }
+    })
+}
+
+/// Ask the user for files to attach with the standard file picker
+///
+/// Blocks until the picker is closed; empty when it was cancelled.
+pub fn choose_files() -> Result<Vec<String>, String> {
+    let script = "set picked to choose file with prompt \"Attach files to the recording\" with multiple selections allowed\n\
+                  set paths to \"\"\n\
+                  repeat with f in picked\n\
+                  set paths to paths & POSIX path of f & linefeed\n\
+                  end repeat\n\
+                  return paths";
+    let output = Command::new("osascript")
+        .args(["-e", script])
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {e}"))?;
+    if !output.status.success() {
+        // The picker reports cancellation as an error
+        return Ok(Vec::new());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
@@ -0,0 +1,305 @@
+//! Labeled microphone tracks, for meetings with interpreters
+//!
+//! ScreenCaptureKit records one microphone, so each further microphone set up here
+//! (an interpreter on a second input device, say) is recorded alongside by `ffmpeg`
+//! into its own audio file while the recording runs. When the recording stops the
+//! files are added to it as extra audio tracks without re-encoding anything, with
+//! their labels ("Interpreter – Spanish") as track titles and their languages set,
+//! so players offer them by name. The labels also go into the sidecar and the
+//! uploaded call metadata. Configured in `audio_tracks.json` in the data folder.
+//!
+//! The extra microphones start a fraction of a second after the recording itself.
+//! `ffmpeg` reports when its first audio was captured, on the host clock the
+//! recording's own timestamps use, and each track is shifted by how much later that
+//! was than the recording's start when it is added, so it stays in sync. Adding the
+//! tracks waits for `ffmpeg`, so it happens on a thread of its own. Segmented recordings are recorded without them, since each part would need its
+//! own share of every track, and for the same reason a recording with them cannot
+//! be paused.
+
+use screencapturekit::audio_devices::AudioInputDevice;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStderr, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::frame_health;
+use crate::sidecar::RecordingSidecar;
+use crate::user_data;
+
+/// How long `ffmpeg` gets to finish a track after being asked to stop
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Languages offered for tracks, as ISO 639-2 codes for the file's metadata
+pub const LANGUAGES: &[(&str, &str)] = &[
+    ("", "Not set"),
+    ("eng", "English"),
+    ("spa", "Spanish"),
+    ("fra", "French"),
+    ("deu", "German"),
+    ("ita", "Italian"),
+    ("por", "Portuguese"),
+    ("nld", "Dutch"),
+    ("zho", "Chinese"),
+    ("jpn", "Japanese"),
+    ("kor", "Korean"),
+    ("ara", "Arabic"),
+    ("rus", "Russian"),
+];
+
+/// What is on one microphone track
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackLabel {
+    /// Shown by players, e.g. "Interpreter – Spanish"
+    pub label: String,
+    /// ISO 639-2 code, empty when not set
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub language: String,
+}
+
+/// A microphone recorded into a track of its own
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExtraMicrophone {
+    /// Audio input device ID, as [`AudioInputDevice::list`] reports it
+    pub device_id: String,
+    #[serde(flatten)]
+    pub track: TrackLabel,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioTrackSettings {
+    /// Label of the microphone chosen in the capture settings
+    pub primary: TrackLabel,
+    pub extra: Vec<ExtraMicrophone>,
+}
+
+impl AudioTrackSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("audio_tracks.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize audio track settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save audio track settings: {e}"))
+    }
+}
+
+/// An extra microphone being recorded
+struct TrackRecorder {
+    process: Child,
+    path: String,
+    track: TrackLabel,
+    /// Seconds between the start of the recording and of the track, as measured
+    /// when `ffmpeg` reported its first audio timestamp
+    offset: Arc<Mutex<Option<f64>>>,
+    /// Seconds between the start of the recording and starting `ffmpeg`, which the
+    /// track is shifted by when `ffmpeg` reported no timestamp
+    spawned_after: f64,
+}
+
+static RECORDERS: Mutex<Vec<TrackRecorder>> = Mutex::new(Vec::new());
+
+/// `recording_x_track2.m4a` for the second extra track of `recording_x.mp4`
+fn track_path(recording_path: &str, number: usize) -> String {
+    let path = Path::new(recording_path);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    path.with_file_name(format!("{stem}_track{number}.m4a")).to_string_lossy().into_owned()
+}
+
+/// Find the input's start time, on the host clock, in `ffmpeg`'s log and store how
+/// long after `started_at` it was in `offset`; reads the log to the end, so `ffmpeg`
+/// never waits for room in the pipe
+fn read_start_time(stderr: ChildStderr, started_at: f64, offset: &Mutex<Option<f64>>) {
+    for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+        // "  Duration: N/A, start: 183742.519417, bitrate: 1536 kb/s"
+        let start = line
+            .split_once("start: ")
+            .and_then(|(_, rest)| rest.split(',').next())
+            .and_then(|value| value.trim().parse::<f64>().ok());
+        if let Some(start) = start.filter(|_| offset.lock().unwrap().is_none()) {
+            *offset.lock().unwrap() = Some((start - started_at).max(0.0));
+        }
+    }
+}
+
+/// Start recording the extra microphones for the recording at `recording_path`,
+/// which started at `started_at` seconds on the host clock
+pub fn start(recording_path: &str, started_at: f64) {
+    let settings = AudioTrackSettings::load();
+    if settings.extra.is_empty() {
+        return;
+    }
+    let devices = AudioInputDevice::list();
+    let mut recorders = RECORDERS.lock().unwrap();
+    for (number, mic) in settings.extra.iter().enumerate() {
+        let Some(device) = devices.iter().find(|d| d.id == mic.device_id) else {
+            eprintln!("⚠️  Microphone for \"{}\" is not connected; recording without it", mic.track.label);
+            continue;
+        };
+        let path = track_path(recording_path, number + 1);
+        let spawned_after = (frame_health::host_seconds() - started_at).max(0.0);
+        // At the info level ffmpeg logs the input's start time
+        let spawned = Command::new("ffmpeg")
+            .args(["-hide_banner", "-nostats", "-v", "info", "-y", "-f", "avfoundation", "-i"])
+            .arg(format!(":{}", device.name))
+            .args(["-c:a", "aac", "-b:a", "128k", &path])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn();
+        match spawned {
+            Ok(mut process) => {
+                println!("🎙️  Recording \"{}\" from {}", mic.track.label, device.name);
+                let offset = Arc::new(Mutex::new(None));
+                if let Some(stderr) = process.stderr.take() {
+                    let offset = Arc::clone(&offset);
+                    std::thread::spawn(move || read_start_time(stderr, started_at, &offset));
+                }
+                recorders.push(TrackRecorder { process, path, track: mic.track.clone(), offset, spawned_after });
+            }
+            Err(e) => eprintln!("⚠️  Failed to record \"{}\" (is ffmpeg installed?): {}", mic.track.label, e),
+        }
+    }
+}
+
+/// Whether extra microphones are being recorded
+pub fn is_recording() -> bool {
+    !RECORDERS.lock().unwrap().is_empty()
+}
+
+/// A finished track file
+struct Track {
+    path: String,
+    label: TrackLabel,
+    /// Seconds into the recording the track starts at
+    offset: f64,
+}
+
+/// Stop the extra microphones, returning the finished track files
+fn stop() -> Vec<Track> {
+    let recorders = std::mem::take(&mut *RECORDERS.lock().unwrap());
+    recorders
+        .into_iter()
+        .filter_map(|mut recorder| {
+            // `q` asks ffmpeg to finish the file properly
+            if let Some(mut stdin) = recorder.process.stdin.take() {
+                let _ = stdin.write_all(b"q");
+            }
+            let deadline = Instant::now() + STOP_TIMEOUT;
+            loop {
+                match recorder.process.try_wait() {
+                    Ok(Some(_)) => break,
+                    Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+                    _ => {
+                        let _ = recorder.process.kill();
+                        let _ = recorder.process.wait();
+                        break;
+                    }
+                }
+            }
+            let offset = recorder.offset.lock().unwrap().unwrap_or(recorder.spawned_after);
+            Path::new(&recorder.path).exists().then_some(Track { path: recorder.path, label: recorder.track, offset })
+        })
+        .collect()
+}
+
+/// Stop the extra microphones and throw their files away, for a cancelled recording
+pub fn discard() {
+    for track in stop() {
+        let _ = std::fs::remove_file(track.path);
+    }
+}
+
+/// Number of audio tracks in `path`, as `ffprobe` counts them
+fn audio_track_count(path: &str) -> Result<usize, String> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "a", "-show_entries", "stream=index", "-of", "csv=p=0", path])
+        .output()
+        .map_err(|e| format!("Failed to run ffprobe: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("ffprobe failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().filter(|l| !l.trim().is_empty()).count())
+}
+
+/// `-metadata:s:a:N` options labeling audio track `index`
+fn label_args(index: usize, track: &TrackLabel) -> Vec<String> {
+    let mut args = Vec::new();
+    if !track.label.is_empty() {
+        args.extend([format!("-metadata:s:a:{index}"), format!("title={}", track.label)]);
+    }
+    if !track.language.is_empty() {
+        args.extend([format!("-metadata:s:a:{index}"), format!("language={}", track.language)]);
+    }
+    args
+}
+
+/// Stop the extra microphones and add them, labeled and in sync, to the finished
+/// recording
+///
+/// The microphone ScreenCaptureKit recorded is its last audio track, and gets the
+/// primary label when `has_microphone`. On failure the track files are kept next
+/// to the recording. Waits for `ffmpeg`, up to [`STOP_TIMEOUT`] per track and then
+/// the remux, so call it off the capture backend's thread.
+pub fn finish(recording_path: &str, has_microphone: bool) -> Result<(), String> {
+    let tracks = stop();
+    let settings = AudioTrackSettings::load();
+    let primary = (has_microphone && !settings.primary.label.is_empty()).then_some(settings.primary);
+    if tracks.is_empty() && primary.is_none() {
+        return Ok(());
+    }
+
+    let own_tracks = audio_track_count(recording_path)?;
+    let primary = primary.filter(|_| own_tracks > 0);
+    let path = Path::new(recording_path);
+    let output = path
+        .with_extension(format!("tracks.{}", path.extension().and_then(|e| e.to_str()).unwrap_or("mp4")))
+        .to_string_lossy()
+        .into_owned();
+    let mut command = Command::new("ffmpeg");
+    command.args(["-v", "error", "-y", "-i", recording_path]);
+    for track in &tracks {
+        // Starts that much into the recording
+        command.args(["-itsoffset", &format!("{:.3}", track.offset), "-i", &track.path]);
+    }
+    command.args(["-map", "0"]);
+    for input in 1..=tracks.len() {
+        command.args(["-map", &format!("{input}:a")]);
+    }
+    command.args(["-c", "copy"]);
+    if let Some(ref primary) = primary {
+        command.args(label_args(own_tracks - 1, primary));
+    }
+    for (number, track) in tracks.iter().enumerate() {
+        command.args(label_args(own_tracks + number, &track.label));
+    }
+    let result = command
+        .args(["-movflags", "+faststart", &output])
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg (is it installed?): {e}"))?;
+    if !result.status.success() {
+        let _ = std::fs::remove_file(&output);
+        return Err(format!("Failed to add microphone tracks: {}", String::from_utf8_lossy(&result.stderr).trim()));
+    }
+    std::fs::rename(&output, recording_path).map_err(|e| format!("Failed to replace {recording_path}: {e}"))?;
+    for track in &tracks {
+        let _ = std::fs::remove_file(&track.path);
+    }
+
+    let labels: Vec<TrackLabel> = primary.into_iter().chain(tracks.into_iter().map(|track| track.label)).collect();
+    println!("🎙️  Added {} labeled microphone track(s)", labels.len());
+    RecordingSidecar::update(recording_path, |s| s.audio_tracks = labels)
+}
@@ -5,9 +5,17 @@
 
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Mutex;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+pub use crate::talka_api::{MeetingEvent, UserProfile};
+use crate::calendar_privacy;
+use crate::talka_api;
+use crate::talka_api::{ApiError, CacheValidators, Conditional, MeetingEventsPage, MeetingEventsQuery, TalkaClient};
+use crate::user_data;
+
 const AUTH0_DOMAIN: &str = "login.talka.ai";
 const CLIENT_ID: &str = "ZTQTqV6jnRjRFPPQlVbITW6L5FkM4jB8";
 const CLIENT_SECRET: &str = "d4AkZz2BagYrEO38QoSwkMJOFp_e75DpTykVkdeOujKqsgcbT0-_1qbgX-schvpu";
@@ -108,6 +116,8 @@ pub enum AuthError {
     AccessDenied,
     ExpiredToken,
     InvalidRequest(String),
+    /// The API is rate limiting; retry after this long
+    RateLimited(Duration),
     Unknown(String),
 }
 
@@ -120,6 +130,7 @@ impl std::fmt::Display for AuthError {
             Self::AccessDenied => write!(f, "Access denied by user"),
             Self::ExpiredToken => write!(f, "Device code expired"),
             Self::InvalidRequest(msg) => write!(f, "Invalid request: {msg}"),
+            Self::RateLimited(wait) => write!(f, "Rate limited, retry in {}s", wait.as_secs()),
             Self::Unknown(msg) => write!(f, "Unknown error: {msg}"),
         }
     }
@@ -131,7 +142,7 @@ impl std::error::Error for AuthError {}
 ///
 /// Returns the verification URI and user code that should be displayed to the user
 pub async fn start_device_flow() -> Result<(String, String, DeviceCodeResponse), AuthError> {
-    let client = reqwest::Client::new();
+    let client = talka_api::http();
     let url = format!("https://{}/oauth/device/code", AUTH0_DOMAIN);
 
     let request = DeviceCodeRequest {
@@ -174,7 +185,7 @@ pub async fn start_device_flow() -> Result<(String, String, DeviceCodeResponse),
 /// This should be called repeatedly (respecting the interval) until the user completes
 /// authentication or the device code expires
 pub async fn poll_for_token(device_code: &str) -> Result<AuthTokens, AuthError> {
-    let client = reqwest::Client::new();
+    let client = talka_api::http();
     let url = format!("https://{}/oauth/token", AUTH0_DOMAIN);
 
     let request = TokenRequest {
@@ -235,7 +246,7 @@ pub async fn poll_for_token(device_code: &str) -> Result<AuthTokens, AuthError>
 
 /// Refresh an access token using a refresh token
 pub async fn refresh_access_token(refresh_token: &str) -> Result<AuthTokens, AuthError> {
-    let client = reqwest::Client::new();
+    let client = talka_api::http();
     let url = format!("https://{}/oauth/token", AUTH0_DOMAIN);
 
     let request = RefreshTokenRequest {
@@ -298,15 +309,14 @@ pub async fn refresh_access_token(refresh_token: &str) -> Result<AuthTokens, Aut
 
 /// Get the path to the token storage file
 fn get_token_file_path() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    PathBuf::from(home).join(".talka_tokens.json")
+    user_data::file("tokens.json")
 }
 
 /// Save tokens to disk
 pub fn save_tokens(tokens: &AuthTokens) -> Result<(), std::io::Error> {
     let path = get_token_file_path();
     let json = serde_json::to_string_pretty(tokens)?;
-    fs::write(&path, json)?;
+    user_data::write_private(&path, json)?;
     Ok(())
 }
 
@@ -326,78 +336,12 @@ pub fn load_tokens() -> Option<AuthTokens> {
     }
 }
 
-/// User profile information from Auth0
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct UserProfile {
-    pub sub: String, // User ID
-    #[serde(default)]
-    pub name: String,
-    #[serde(default)]
-    pub email: String,
-    #[serde(default)]
-    pub picture: String,
-    #[serde(default)]
-    pub nickname: String,
-}
-
-impl UserProfile {
-    /// Get user initials for avatar
-    pub fn initials(&self) -> String {
-        if !self.name.is_empty() {
-            self.name
-                .split_whitespace()
-                .take(2)
-                .filter_map(|s| s.chars().next())
-                .collect::<String>()
-                .to_uppercase()
-        } else if !self.email.is_empty() {
-            self.email.chars().next().unwrap().to_uppercase().to_string()
-        } else {
-            "U".to_string()
-        }
-    }
-
-    /// Get display name
-    pub fn display_name(&self) -> String {
-        if !self.name.is_empty() {
-            self.name.clone()
-        } else if !self.nickname.is_empty() {
-            self.nickname.clone()
-        } else if !self.email.is_empty() {
-            self.email.split('@').next().unwrap_or("User").to_string()
-        } else {
-            "User".to_string()
-        }
-    }
-}
-
 /// Fetch user profile from Auth0
 pub async fn get_user_profile(access_token: &str) -> Result<UserProfile, AuthError> {
-    let client = reqwest::Client::new();
-    let url = format!("https://{}/userinfo", AUTH0_DOMAIN);
-
-    let response = client
-        .get(&url)
-        .bearer_auth(access_token)
-        .send()
-        .await
-        .map_err(|e| AuthError::NetworkError(e.to_string()))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(AuthError::NetworkError(format!(
-            "Failed to fetch user profile: HTTP {}: {}",
-            status, text
-        )));
-    }
-
-    let profile: UserProfile = response
-        .json()
-        .await
-        .map_err(|e| AuthError::NetworkError(format!("Failed to parse profile: {}", e)))?;
-
-    Ok(profile)
+    TalkaClient::new(access_token).user_profile().await.map_err(|e| match e {
+        ApiError::InvalidResponse(msg) => AuthError::NetworkError(format!("Failed to parse profile: {}", msg)),
+        e => AuthError::NetworkError(format!("Failed to fetch user profile: {}", e)),
+    })
 }
 
 /// Logout - delete stored tokens
@@ -406,82 +350,220 @@ pub fn logout() -> Result<(), std::io::Error> {
     if path.exists() {
         fs::remove_file(&path)?;
     }
+    forget_event_validators();
     Ok(())
 }
 
-/// Meeting event information from the calendar API
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct MeetingEvent {
-    pub bot_meeting_id: String,
-    pub event_id: String,
-    pub event_status: String,
-    pub event_summary: String,
-    pub join: bool,
-    pub join_toggle: Option<bool>,
-    pub meeting_participants: Vec<String>,
-    pub meeting_start_time: String, // ISO 8601 format: "2026-01-19T14:30:00"
-    pub meeting_url: String,
-    pub platform: String,
-    pub user_id: String,
+/// Make the next meeting events request unconditional
+///
+/// Needed whenever the events held locally no longer match what the server sent,
+/// since a 304 would otherwise keep them that way.
+pub fn forget_event_validators() {
+    EVENT_VALIDATORS.lock().unwrap().take();
 }
 
-impl MeetingEvent {
-    /// Parse the meeting start time and format it for display
-    /// Returns formatted string like "19 Jan, MON 9:30 AM"
-    pub fn formatted_start_time(&self) -> String {
-        use chrono::{DateTime, Local};
-        
-        // Parse the ISO 8601 timestamp
-        if let Ok(dt) = DateTime::parse_from_rfc3339(&self.meeting_start_time) {
-            // Convert to local timezone
-            let local_dt: DateTime<Local> = dt.with_timezone(&Local);
-            
-            // Format: "19 Jan, MON 9:30 AM"
-            local_dt.format("%d %b, %a %I:%M %p").to_string().to_uppercase()
-        } else {
-            // Fallback if parsing fails
-            self.meeting_start_time.clone()
+/// The soonest meeting starting within `window` from now
+pub fn next_meeting(events: &[MeetingEvent], window: chrono::Duration) -> Option<&MeetingEvent> {
+    let now = chrono::Utc::now();
+    events
+        .iter()
+        .filter_map(|event| event.start_time().map(|start| (start, event)))
+        .filter(|(start, _)| *start > now && *start < now + window)
+        .min_by_key(|(start, _)| *start)
+        .map(|(_, event)| event)
+}
+
+/// Events requested per page
+const EVENTS_PAGE_SIZE: u32 = 100;
+/// Safety stop in case the server keeps handing out cursors
+const MAX_EVENT_PAGES: usize = 50;
+/// First wait after a 429 without `Retry-After`; doubled on each one that follows
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(60);
+const MAX_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+/// Validators of the last single-page response for each range
+///
+/// Range bounds are whole hours, so repeated refreshes send the same query and the
+/// server can answer 304 when nothing changed.
+static EVENT_VALIDATORS: Mutex<Option<HashMap<EventRange, CacheValidators>>> = Mutex::new(None);
+/// Until when meeting event requests are held back, and the wait that led there
+static RATE_LIMITED: Mutex<Option<(Instant, Duration)>> = Mutex::new(None);
+
+/// `time` rounded down to the hour
+fn hour_start(time: chrono::DateTime<chrono::Utc>) -> chrono::DateTime<chrono::Utc> {
+    use chrono::Timelike;
+    time.with_minute(0)
+        .and_then(|t| t.with_second(0))
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or(time)
+}
+
+/// Window of meeting start times to fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventRange {
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+}
+
+impl EventRange {
+    /// Everything the calendar view shows: the past week through the next month
+    pub fn calendar() -> Self {
+        let now = hour_start(chrono::Utc::now());
+        Self {
+            from: now - chrono::Duration::days(7),
+            to: now + chrono::Duration::days(30),
+        }
+    }
+
+    /// Meetings that are in progress or about to start, for frequent refreshes
+    pub fn upcoming() -> Self {
+        let now = hour_start(chrono::Utc::now());
+        Self {
+            from: now - chrono::Duration::hours(2),
+            to: now + chrono::Duration::days(2),
         }
     }
+
+    fn contains(&self, event: &MeetingEvent) -> bool {
+        event
+            .start_time()
+            .is_some_and(|start| start >= self.from && start < self.to)
+    }
 }
 
-/// Fetch meeting events from the Talka backend
-pub async fn get_meeting_events(access_token: &str) -> Result<Vec<MeetingEvent>, AuthError> {
-    let client = reqwest::Client::new();
-    let url = "https://meeting-bot-scheduler.talka.ai/meeting-events";
+/// Hold back meeting event requests after a 429, for as long as the server asked
+/// or, without `Retry-After`, for twice as long as last time
+fn note_rate_limited(retry_after: Option<Duration>) -> Duration {
+    let mut limited = RATE_LIMITED.lock().unwrap();
+    let wait = retry_after
+        .unwrap_or_else(|| limited.map_or(RATE_LIMIT_BACKOFF, |(_, last)| last * 2))
+        .min(MAX_RATE_LIMIT_BACKOFF);
+    *limited = Some((Instant::now() + wait, wait));
+    eprintln!("⏳ Meeting events API is rate limiting, backing off for {}s", wait.as_secs());
+    wait
+}
 
-    let response = client
-        .get(url)
-        .header("authorization", access_token)
-        .send()
-        .await
-        .map_err(|e| AuthError::NetworkError(e.to_string()))?;
+/// Time left before meeting event requests may be sent again
+fn rate_limit_remaining() -> Option<Duration> {
+    let limited = *RATE_LIMITED.lock().unwrap();
+    limited
+        .map(|(until, _)| until.saturating_duration_since(Instant::now()))
+        .filter(|wait| !wait.is_zero())
+}
 
-    let status = response.status();
+async fn get_meeting_events_page(
+    client: &TalkaClient,
+    range: EventRange,
+    cursor: Option<&str>,
+    validators: &CacheValidators,
+) -> Result<Conditional<MeetingEventsPage>, AuthError> {
+    let query = MeetingEventsQuery {
+        start: range.from.to_rfc3339(),
+        end: range.to.to_rfc3339(),
+        limit: EVENTS_PAGE_SIZE,
+        cursor: cursor.map(str::to_string),
+    };
+    client.meeting_events(&query, validators).await.map_err(|e| match e {
+        ApiError::RateLimited { retry_after } => AuthError::RateLimited(note_rate_limited(retry_after)),
+        ApiError::InvalidResponse(msg) => AuthError::NetworkError(format!("Failed to parse meeting events: {}", msg)),
+        e => AuthError::NetworkError(format!("Failed to fetch meeting events: {}", e)),
+    })
+}
 
-    if !status.is_success() {
-        let text = response.text().await.unwrap_or_default();
-        return Err(AuthError::NetworkError(format!(
-            "Failed to fetch meeting events: HTTP {}: {}",
-            status, text
-        )));
+/// Fetch the meeting events starting within `range`, following every page
+///
+/// Returns `None` when the server reports the events unchanged since the last fetch
+/// of the same range. While the API is rate limiting, fails with
+/// [`AuthError::RateLimited`] without sending anything. With the calendar turned off
+/// in the privacy settings nothing is sent and no events are returned.
+pub async fn get_meeting_events(
+    access_token: &str,
+    range: EventRange,
+) -> Result<Option<Vec<MeetingEvent>>, AuthError> {
+    if !calendar_privacy::fetches_events() {
+        return Ok(Some(Vec::new()));
+    }
+    if let Some(wait) = rate_limit_remaining() {
+        return Err(AuthError::RateLimited(wait));
+    }
+    let client = TalkaClient::new(access_token);
+    let mut events = Vec::new();
+    let mut cursor: Option<String> = None;
+    let cached = EVENT_VALIDATORS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|all| all.get(&range).cloned())
+        .unwrap_or_default();
+    let unconditional = CacheValidators::default();
+    let mut validators = CacheValidators::default();
+    let mut pages = 0;
+
+    while pages < MAX_EVENT_PAGES {
+        // Only the first page is conditional: validators are only kept when the
+        // whole range fit on one page, so any change shows up in it
+        let conditional = if pages == 0 { &cached } else { &unconditional };
+        let page = match get_meeting_events_page(&client, range, cursor.as_deref(), conditional).await? {
+            Conditional::NotModified => {
+                println!("Meeting events unchanged");
+                *RATE_LIMITED.lock().unwrap() = None;
+                return Ok(None);
+            }
+            Conditional::Modified { value, validators: fresh } => {
+                if pages == 0 {
+                    validators = fresh;
+                }
+                value
+            }
+        };
+        pages += 1;
+        match page {
+            MeetingEventsPage::Paged { events: page, next_cursor } => {
+                events.extend(page);
+                cursor = next_cursor.filter(|c| !c.is_empty());
+            }
+            MeetingEventsPage::Unpaged(all) => {
+                events.extend(all);
+                cursor = None;
+            }
+        }
+        if cursor.is_none() {
+            break;
+        }
+    }
+    *RATE_LIMITED.lock().unwrap() = None;
+    {
+        let mut all = EVENT_VALIDATORS.lock().unwrap();
+        let all = all.get_or_insert_with(HashMap::new);
+        // One entry per kind of range: earlier windows of the same length are over
+        all.retain(|known, _| known.to - known.from != range.to - range.from);
+        if pages == 1 && !validators.is_empty() {
+            all.insert(range, validators);
+        }
+    }
+    if cursor.is_some() {
+        eprintln!("⚠️  Stopped fetching meeting events after {} pages", MAX_EVENT_PAGES);
     }
 
-    let response_text = response.text().await
-        .map_err(|e| AuthError::NetworkError(format!("Failed to read response: {}", e)))?;
-
-    let mut events: Vec<MeetingEvent> = serde_json::from_str(&response_text)
-        .map_err(|e| AuthError::NetworkError(format!("Failed to parse meeting events: {}", e)))?;
-    
     // Sort by meeting_start_time (earliest to latest)
     events.sort_by(|a, b| a.meeting_start_time.cmp(&b.meeting_start_time));
-    
+    calendar_privacy::restrict(&mut events);
+
     println!("Meeting events API response: {} events returned", events.len());
-    for event in &events {
-        println!("  - {} at {}", event.event_summary, event.formatted_start_time());
-    }
-    
-    Ok(events)
+
+    Ok(Some(events))
+}
+
+/// Fold freshly fetched events for `range` into the known events
+///
+/// Events in `range` that the server no longer returned were deleted or moved and are
+/// dropped; events outside it are kept until a wider fetch covers them again.
+pub fn merge_meeting_events(known: &mut Vec<MeetingEvent>, fresh: Vec<MeetingEvent>, range: EventRange) {
+    known.retain(|event| !range.contains(event) && !fresh.iter().any(|f| f.event_id == event.event_id));
+    known.extend(fresh);
+    known.sort_by(|a, b| a.meeting_start_time.cmp(&b.meeting_start_time));
+    // Events from before the settings changed go by them too
+    calendar_privacy::restrict(known);
 }
 
 /// Get valid tokens - either from cache or by authenticating
@@ -0,0 +1,236 @@
+//! Folder-sync backup
+//!
+//! Mirrors finished recordings and their sidecars into a folder the user picks, such
+//! as one in iCloud Drive or Dropbox, whose own client then syncs them. Configured in
+//! `backup.json` in the data folder; an empty folder turns backup off.
+//!
+//! Copies are written under a hidden `.partial` name and renamed into place, so sync
+//! clients never pick up half a recording. A file in the folder that did not come from
+//! this recording is never overwritten: the copy gets a numbered name instead.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::library::{self, LibraryEntry};
+use crate::sidecar::{BackupCopy, RecordingSidecar};
+use crate::user_data;
+
+/// How often new and changed recordings are looked for
+pub const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    /// Destination folder; empty when backup is off
+    pub folder: String,
+}
+
+impl BackupSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("backup.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize backup settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save backup settings: {e}"))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.folder.trim().is_empty()
+    }
+}
+
+/// Sync state of one recording, as shown in the library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackupStatus {
+    Pending,
+    Copying,
+    BackedUp,
+    Failed(String),
+}
+
+impl std::fmt::Display for BackupStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pending => write!(f, "⏳ Waiting to back up"),
+            Self::Copying => write!(f, "☁️ Backing up…"),
+            Self::BackedUp => write!(f, "☁️ Backed up"),
+            Self::Failed(e) => write!(f, "Backup failed: {e}"),
+        }
+    }
+}
+
+/// Copies in progress or failed this session, by recording path
+static ACTIVITY: Mutex<Vec<(String, BackupStatus)>> = Mutex::new(Vec::new());
+
+fn set_activity(path: &str, status: Option<BackupStatus>) {
+    let mut activity = ACTIVITY.lock().unwrap();
+    activity.retain(|(p, _)| p != path);
+    if let Some(status) = status {
+        activity.push((path.to_string(), status));
+    }
+}
+
+/// Only recordings that finished and passed verification are mirrored
+fn is_complete(entry: &LibraryEntry) -> bool {
+    entry.damage().is_none() && entry.sidecar.as_ref().is_some_and(|s| s.duration_seconds.is_some())
+}
+
+/// Never copied, copied to a different folder, or changed since (trimmed, archived)
+fn needs_copy(entry: &LibraryEntry, folder: &Path) -> bool {
+    entry
+        .sidecar
+        .as_ref()
+        .and_then(|s| s.backup.as_ref())
+        .map_or(true, |copy| copy.size_bytes != entry.size_bytes || !Path::new(&copy.path).starts_with(folder))
+}
+
+/// Backup state for the library, `None` when backup is off or the recording is unfinished
+pub fn status(entry: &LibraryEntry, settings: &BackupSettings) -> Option<BackupStatus> {
+    if !settings.is_enabled() || !is_complete(entry) {
+        return None;
+    }
+    let active = ACTIVITY
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(p, _)| *p == entry.path)
+        .map(|(_, status)| status.clone());
+    Some(active.unwrap_or(if needs_copy(entry, Path::new(settings.folder.trim())) {
+        BackupStatus::Pending
+    } else {
+        BackupStatus::BackedUp
+    }))
+}
+
+/// Destination for `file_name` in `folder` that does not clobber someone else's file
+///
+/// `previous` is where this recording was last copied to, which may be reused.
+fn destination(folder: &Path, file_name: &str, previous: Option<&str>) -> PathBuf {
+    let candidate = folder.join(file_name);
+    if !candidate.exists() || previous == candidate.to_str() {
+        return candidate;
+    }
+    let name = Path::new(file_name);
+    let stem = name.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    let ext = name.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    (2..)
+        .map(|n| folder.join(format!("{stem} ({n}).{ext}")))
+        .find(|p| !p.exists() || previous == p.to_str())
+        .expect("unbounded range")
+}
+
+/// Copy through a hidden temporary file so sync clients only see complete files
+fn copy_atomically(from: &Path, to: &Path) -> Result<(), String> {
+    let name = to.file_name().and_then(|n| n.to_str()).unwrap_or("recording");
+    let partial = to.with_file_name(format!(".{name}.partial"));
+    std::fs::copy(from, &partial)
+        .and_then(|_| std::fs::rename(&partial, to))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&partial);
+            format!("Failed to copy to {}: {e}", to.display())
+        })
+}
+
+fn back_up(entry: &LibraryEntry, folder: &Path) -> Result<(), String> {
+    let mut sidecar = entry.sidecar.clone().unwrap_or_default();
+    let previous = sidecar.backup.as_ref().map(|c| c.path.clone());
+    let target = destination(folder, &entry.file_name, previous.as_deref());
+
+    println!("☁️  Backing up {} to {}", entry.path, target.display());
+    copy_atomically(Path::new(&entry.path), &target)?;
+
+    sidecar.backup = Some(BackupCopy {
+        path: target.to_string_lossy().into_owned(),
+        size_bytes: entry.size_bytes,
+        backed_up_at: chrono::Utc::now().to_rfc3339(),
+    });
+    sidecar.save(&entry.path)?;
+    copy_atomically(
+        &RecordingSidecar::path_for(&entry.path),
+        &RecordingSidecar::path_for(&target.to_string_lossy()),
+    )
+}
+
+/// Keep the mirrored sidecar in step with tag and note edits
+fn refresh_sidecar(entry: &LibraryEntry) -> Result<(), String> {
+    let Some(copy) = entry.sidecar.as_ref().and_then(|s| s.backup.as_ref()) else {
+        return Ok(());
+    };
+    let local = RecordingSidecar::path_for(&entry.path);
+    let mirrored = RecordingSidecar::path_for(&copy.path);
+    let changed = std::fs::read(&local).ok() != std::fs::read(&mirrored).ok();
+    if changed && Path::new(&copy.path).exists() {
+        copy_atomically(&local, &mirrored)?;
+    }
+    Ok(())
+}
+
+/// Held while a sync runs, so the periodic sync and one started from Settings never
+/// copy the same recording at once
+static SYNCING: Mutex<()> = Mutex::new(());
+
+/// Mirror every finished recording that is new or changed since its last backup
+///
+/// Blocking; run it from a background task. A sync started while another runs waits
+/// for it, then only copies what is still missing.
+pub fn sync_completed() {
+    let _syncing = SYNCING.lock().unwrap_or_else(|e| e.into_inner());
+    let settings = BackupSettings::load();
+    if !settings.is_enabled() {
+        return;
+    }
+    let folder = PathBuf::from(settings.folder.trim());
+    if !folder.is_dir() {
+        eprintln!("⚠️  Backup folder {} is not available", folder.display());
+        return;
+    }
+
+    for entry in library::list_recordings().iter().filter(|e| is_complete(e)) {
+        if !needs_copy(entry, &folder) {
+            if let Err(e) = refresh_sidecar(entry) {
+                eprintln!("⚠️  {}", e);
+            }
+            continue;
+        }
+        set_activity(&entry.path, Some(BackupStatus::Copying));
+        match back_up(entry, &folder) {
+            Ok(()) => set_activity(&entry.path, None),
+            Err(e) => {
+                eprintln!("❌ Backup of {} failed: {}", entry.path, e);
+                set_activity(&entry.path, Some(BackupStatus::Failed(e)));
+            }
+        }
+    }
+}
+
+/// Ask the user for a backup folder with the standard folder picker
+///
+/// Blocks until the picker is closed; `Ok(None)` when it was cancelled.
+pub fn choose_folder() -> Result<Option<String>, String> {
+    let output = Command::new("osascript")
+        .args([
+            "-e",
+            "POSIX path of (choose folder with prompt \"Choose a folder to back up recordings to\")",
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {e}"))?;
+    if !output.status.success() {
+        // The picker reports cancellation as an error
+        return Ok(None);
+    }
+    let folder = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok((!folder.is_empty()).then_some(folder))
+}
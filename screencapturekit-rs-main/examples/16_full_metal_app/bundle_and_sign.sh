@@ -9,7 +9,8 @@ set -e
 
 SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
 APP_NAME="TalkaRecall"
-VERSION="1.0.0"
+# Same version the updater compares against (CARGO_PKG_VERSION)
+VERSION="$(sed -n 's/^version = "\(.*\)"/\1/p' "${SCRIPT_DIR}/../../Cargo.toml" | head -n 1)"
 BINARY_NAME="16_full_metal_app"
 
 echo "🔨 Building ${APP_NAME} for macOS using cargo-bundle..."
@@ -91,8 +92,8 @@ echo "🔧 Removing legacy compatibility flags..."
 /usr/libexec/PlistBuddy -c "Delete :CSResourcesFileMapped" "${BUNDLE_PATH}/Contents/Info.plist" 2>/dev/null || true
 
 # Add privacy permissions
-/usr/libexec/PlistBuddy -c "Add :NSCameraUsageDescription string 'This app needs screen recording permission to capture your screen.'" "${BUNDLE_PATH}/Contents/Info.plist" 2>/dev/null || \
-/usr/libexec/PlistBuddy -c "Set :NSCameraUsageDescription 'This app needs screen recording permission to capture your screen.'" "${BUNDLE_PATH}/Contents/Info.plist"
+/usr/libexec/PlistBuddy -c "Add :NSCameraUsageDescription string 'This app shows your camera on screen while recording with a profile that includes the webcam.'" "${BUNDLE_PATH}/Contents/Info.plist" 2>/dev/null || \
+/usr/libexec/PlistBuddy -c "Set :NSCameraUsageDescription 'This app shows your camera on screen while recording with a profile that includes the webcam.'" "${BUNDLE_PATH}/Contents/Info.plist"
 
 /usr/libexec/PlistBuddy -c "Add :NSMicrophoneUsageDescription string 'This app needs microphone access to capture audio.'" "${BUNDLE_PATH}/Contents/Info.plist" 2>/dev/null || \
 /usr/libexec/PlistBuddy -c "Set :NSMicrophoneUsageDescription 'This app needs microphone access to capture audio.'" "${BUNDLE_PATH}/Contents/Info.plist"
@@ -108,6 +109,14 @@ echo "🔧 Removing legacy compatibility flags..."
 /usr/libexec/PlistBuddy -c "Add :LSApplicationCategoryType string 'public.app-category.developer-tools'" "${BUNDLE_PATH}/Contents/Info.plist" 2>/dev/null || \
 /usr/libexec/PlistBuddy -c "Set :LSApplicationCategoryType 'public.app-category.developer-tools'" "${BUNDLE_PATH}/Contents/Info.plist"
 
+# Register the talka-recorder:// URL scheme for runbook and calendar links
+/usr/libexec/PlistBuddy -c "Delete :CFBundleURLTypes" "${BUNDLE_PATH}/Contents/Info.plist" 2>/dev/null || true
+/usr/libexec/PlistBuddy -c "Add :CFBundleURLTypes array" "${BUNDLE_PATH}/Contents/Info.plist"
+/usr/libexec/PlistBuddy -c "Add :CFBundleURLTypes:0 dict" "${BUNDLE_PATH}/Contents/Info.plist"
+/usr/libexec/PlistBuddy -c "Add :CFBundleURLTypes:0:CFBundleURLName string 'ai.talka.recall'" "${BUNDLE_PATH}/Contents/Info.plist"
+/usr/libexec/PlistBuddy -c "Add :CFBundleURLTypes:0:CFBundleURLSchemes array" "${BUNDLE_PATH}/Contents/Info.plist"
+/usr/libexec/PlistBuddy -c "Add :CFBundleURLTypes:0:CFBundleURLSchemes:0 string 'talka-recorder'" "${BUNDLE_PATH}/Contents/Info.plist"
+
 echo "✅ Info.plist updated"
 
 # Copy app icon if it exists
@@ -328,7 +337,7 @@ macOS Gatekeeper:
   xattr -cr "/Applications/${APP_NAME}.app"
 
 Token Storage:
-- Authentication tokens are stored in: ~/.talka_tokens.json
+- Authentication tokens are stored in: ~/Library/Application Support/Talka Recorder/tokens.json
 - Tokens are preserved across app restarts
 - Each user has their own tokens
 
@@ -337,7 +346,7 @@ macOS Permissions:
 - Microphone permission if using audio capture
 
 Troubleshooting:
-- If authentication fails, delete ~/.talka_tokens.json and restart
+- If authentication fails, delete ~/Library/Application Support/Talka Recorder/tokens.json and restart
 - Check System Settings for Screen Recording permissions
 - Ensure you have an internet connection for authentication
 - If macOS blocks the app: xattr -cr "/Applications/${APP_NAME}.app"
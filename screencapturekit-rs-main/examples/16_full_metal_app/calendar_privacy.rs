@@ -0,0 +1,136 @@
+//! How much of the calendar the app gets to see
+//!
+//! By default meeting events are fetched in full: title, start time, participants
+//! and the meeting link. Limited to titles, everything but the title and start time
+//! is dropped as soon as the events arrive, so participants never reach the
+//! sidecars, the uploaded call metadata or the recording titles. Turned off, no
+//! events are requested at all and the ones already known are forgotten, which
+//! also turns off everything that builds on them (the next-meeting reminder, the
+//! menu bar countdown, picking a profile by meeting).
+//!
+//! Separately, meeting titles can be kept out of the console log and the session
+//! journal. Window titles of meeting apps repeat the meeting's title, so those are
+//! replaced too, with a short tag that stays the same for the same title so log
+//! lines can still be matched up. Configured in `calendar_privacy.json` in the data
+//! folder.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::auth;
+use crate::talka_api::MeetingEvent;
+use crate::user_data;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarAccess {
+    #[default]
+    Full,
+    /// Only titles and start times are kept
+    TitlesOnly,
+    /// Meeting events are not fetched
+    Off,
+}
+
+impl CalendarAccess {
+    pub const ALL: [Self; 3] = [Self::Full, Self::TitlesOnly, Self::Off];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Full => "Full details",
+            Self::TitlesOnly => "Titles and times only",
+            Self::Off => "Off",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarPrivacy {
+    pub access: CalendarAccess,
+    /// Keep meeting titles out of the console log and the session journal
+    pub anonymize_titles: bool,
+}
+
+/// Settings in effect, loaded on first use
+static CURRENT: Mutex<Option<CalendarPrivacy>> = Mutex::new(None);
+
+impl CalendarPrivacy {
+    fn config_path() -> PathBuf {
+        user_data::file("calendar_privacy.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save, and use these settings from now on
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize calendar privacy settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save calendar privacy settings: {e}"))?;
+        use_settings(self.clone());
+        Ok(())
+    }
+}
+
+/// Switch to `settings`
+///
+/// Events fetched under a narrower access were cut down or dropped, so after a
+/// change of access they are requested again in full rather than confirmed as
+/// unchanged.
+fn use_settings(settings: CalendarPrivacy) {
+    let mut current = CURRENT.lock().unwrap();
+    if current.as_ref().map_or(true, |previous| previous.access != settings.access) {
+        auth::forget_event_validators();
+    }
+    *current = Some(settings);
+}
+
+pub fn current() -> CalendarPrivacy {
+    CURRENT.lock().unwrap().get_or_insert_with(CalendarPrivacy::load).clone()
+}
+
+/// Pick up settings written to `calendar_privacy.json` by something other than
+/// [`CalendarPrivacy::save`]
+pub fn reload() {
+    use_settings(CalendarPrivacy::load());
+}
+
+/// Whether meeting events may be requested
+pub fn fetches_events() -> bool {
+    current().access != CalendarAccess::Off
+}
+
+/// Cut `events` down to what the settings allow
+pub fn restrict(events: &mut Vec<MeetingEvent>) {
+    match current().access {
+        CalendarAccess::Full => {}
+        CalendarAccess::TitlesOnly => {
+            for event in events.iter_mut() {
+                event.meeting_participants.clear();
+                event.meeting_url.clear();
+            }
+        }
+        CalendarAccess::Off => events.clear(),
+    }
+}
+
+/// `title` as it may appear in logs: unchanged, or a tag such as "meeting #3f2a"
+pub fn redact(title: &str) -> String {
+    if !current().anonymize_titles || title.trim().is_empty() {
+        return title.to_string();
+    }
+    format!("meeting #{:04x}", fnv1a(title.trim()) & 0xffff)
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` gives the same tag in every build
+fn fnv1a(text: &str) -> u64 {
+    text.bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
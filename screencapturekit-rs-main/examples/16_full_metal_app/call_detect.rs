@@ -0,0 +1,135 @@
+//! Detecting that the user has joined a call
+//!
+//! Having Zoom or a browser open says nothing; what matters is the window a provider
+//! shows only while a call is in progress. Each [`CallProvider`] names the apps it
+//! runs in and recognises its in-call window titles, so supporting another service
+//! is a matter of adding an entry to [`PROVIDERS`].
+
+use screencapturekit::shareable_content::SCShareableContent;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::calendar_privacy;
+
+/// How often windows are inspected for calls
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+const BROWSERS: &[&str] = &[
+    "com.google.Chrome",
+    "com.apple.Safari",
+    "company.thebrowser.Browser",
+    "com.microsoft.edgemac",
+    "com.brave.Browser",
+    "org.mozilla.firefox",
+];
+
+/// A calling service and how to tell that one of its calls is in progress
+pub struct CallProvider {
+    /// Sent as `CallMetadata.provider`
+    pub name: &'static str,
+    /// Bundle identifiers of the apps the calls happen in
+    pub bundle_ids: &'static [&'static str],
+    /// Whether a window with this title belongs to an active call
+    pub is_call_window: fn(&str) -> bool,
+}
+
+pub const PROVIDERS: &[CallProvider] = &[
+    CallProvider {
+        name: "Zoom",
+        bundle_ids: &["us.zoom.xos"],
+        // The home window is "Zoom" or "Zoom Workplace"; calls get their own window
+        is_call_window: |title| title.starts_with("Zoom Meeting") || title.starts_with("Zoom Webinar"),
+    },
+    CallProvider {
+        name: "Google Meet",
+        bundle_ids: BROWSERS,
+        // "Meet - abc-defg-hij" in a call, plain "Google Meet" on the landing page
+        is_call_window: |title| title.starts_with("Meet - ") || title.starts_with("Meet – "),
+    },
+    CallProvider {
+        name: "Microsoft Teams",
+        bundle_ids: &["com.microsoft.teams2", "com.microsoft.teams"],
+        is_call_window: |title| {
+            title.contains("| Microsoft Teams")
+                && (title.starts_with("Meeting") || title.starts_with("Call with"))
+        },
+    },
+    CallProvider {
+        name: "Slack Huddle",
+        bundle_ids: &["com.tinyspeck.slackmacgap"],
+        is_call_window: |title| title.to_lowercase().contains("huddle"),
+    },
+];
+
+/// A call found on screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedCall {
+    pub provider: &'static str,
+    pub window_title: String,
+}
+
+/// Find the first on-screen window that belongs to a provider's call
+pub fn detect() -> Result<Option<DetectedCall>, String> {
+    let content = SCShareableContent::get().map_err(|e| format!("Failed to list windows: {e:?}"))?;
+    for window in content.windows() {
+        if !window.is_on_screen() {
+            continue;
+        }
+        let (Some(title), Some(app)) = (window.title(), window.owning_application()) else {
+            continue;
+        };
+        let bundle_id = app.bundle_identifier();
+        let provider = PROVIDERS
+            .iter()
+            .find(|p| p.bundle_ids.contains(&bundle_id.as_str()) && (p.is_call_window)(&title));
+        if let Some(provider) = provider {
+            return Ok(Some(DetectedCall {
+                provider: provider.name,
+                window_title: title,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// The call in progress, and whether the user dismissed the prompt for it
+static CURRENT: Mutex<Option<(DetectedCall, bool)>> = Mutex::new(None);
+
+/// Update the current call from a fresh detection
+///
+/// A dismissal sticks for as long as the same provider's call stays on screen.
+pub fn update(detected: Option<DetectedCall>) {
+    let mut current = CURRENT.lock().unwrap();
+    *current = match (current.take(), detected) {
+        (Some((previous, dismissed)), Some(call)) if previous.provider == call.provider => {
+            Some((call, dismissed))
+        }
+        (_, Some(call)) => {
+            println!("📞 {} call detected: {}", call.provider, calendar_privacy::redact(&call.window_title));
+            Some((call, false))
+        }
+        (_, None) => None,
+    };
+}
+
+/// The call currently on screen, if any
+pub fn current() -> Option<DetectedCall> {
+    CURRENT.lock().unwrap().as_ref().map(|(call, _)| call.clone())
+}
+
+/// The call to offer recording for: on screen and not dismissed
+pub fn prompt() -> Option<DetectedCall> {
+    CURRENT
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|(_, dismissed)| !dismissed)
+        .map(|(call, _)| call.clone())
+}
+
+/// Stop offering to record the current call
+pub fn dismiss() {
+    if let Some((_, dismissed)) = CURRENT.lock().unwrap().as_mut() {
+        *dismissed = true;
+    }
+}
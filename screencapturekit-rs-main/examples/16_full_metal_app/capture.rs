@@ -1,11 +1,13 @@
 //! Screen capture handler
 
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use screencapturekit::output::{CVPixelBufferIOSurface, IOSurface};
 use screencapturekit::prelude::*;
 
+use crate::frame_health;
 use crate::waveform::WaveformBuffer;
 
 pub struct CaptureState {
@@ -13,6 +15,17 @@ pub struct CaptureState {
     pub audio_waveform: Mutex<WaveformBuffer>,
     pub mic_waveform: Mutex<WaveformBuffer>,
     pub latest_surface: Mutex<Option<IOSurface>>,
+    /// Set when `latest_surface` changes; the preview clears it once it has drawn it
+    pub surface_dirty: AtomicBool,
+    /// Set by the preview window; the capture backend takes the snapshot and clears it
+    pub snapshot_requested: AtomicBool,
+    /// Path and save time of the most recent snapshot, for on-screen confirmation
+    pub last_snapshot: Mutex<Option<(String, Instant)>>,
+    /// Media time written so far in the current recording, in milliseconds; set by
+    /// the capture backend from the recording output
+    recorded_millis: AtomicU64,
+    /// Set by the capture backend while the recording is paused
+    pub recording_paused: AtomicBool,
 }
 
 impl CaptureState {
@@ -22,8 +35,22 @@ impl CaptureState {
             audio_waveform: Mutex::new(WaveformBuffer::new(4096)),
             mic_waveform: Mutex::new(WaveformBuffer::new(4096)),
             latest_surface: Mutex::new(None),
+            surface_dirty: AtomicBool::new(false),
+            snapshot_requested: AtomicBool::new(false),
+            last_snapshot: Mutex::new(None),
+            recorded_millis: AtomicU64::new(0),
+            recording_paused: AtomicBool::new(false),
         }
     }
+
+    /// How much of the current recording has been written, pauses not included
+    pub fn recorded(&self) -> Duration {
+        Duration::from_millis(self.recorded_millis.load(Ordering::Relaxed))
+    }
+
+    pub fn set_recorded(&self, seconds: f64) {
+        self.recorded_millis.store((seconds.max(0.0) * 1000.0) as u64, Ordering::Relaxed);
+    }
 }
 
 pub struct CaptureHandler {
@@ -46,10 +73,16 @@ impl SCStreamOutputTrait for CaptureHandler {
         match output_type {
             SCStreamOutputType::Screen => {
                 self.state.frame_count.fetch_add(1, Ordering::Relaxed);
+                if let Some(status) = sample.frame_status() {
+                    if let Some(captured_at) = sample.presentation_timestamp().as_seconds() {
+                        frame_health::record_frame(captured_at, status);
+                    }
+                }
                 if let Some(pixel_buffer) = sample.image_buffer() {
                     if pixel_buffer.is_backed_by_iosurface() {
                         if let Some(surface) = pixel_buffer.iosurface() {
                             *self.state.latest_surface.lock().unwrap() = Some(surface);
+                            self.state.surface_dirty.store(true, Ordering::Release);
                         }
                     }
                 }
@@ -0,0 +1,228 @@
+//! Capture and recording options, shared by the main window and the Metal overlay
+//!
+//! Frame rate, cursor, audio, microphone and the other stream options, plus the
+//! recording codec, file type and segment length, live in one [`CaptureSettings`]
+//! stored in `capture.json` in the data folder. Settings edits it with selects and
+//! checkboxes; the overlay's `ConfigMenu` and `RecordingConfigMenu` step through the
+//! same values with the arrow keys. The capture backend builds its stream
+//! configuration from it, and each recording picks up the recording options. The idle
+//! timeout for [`crate::idle`] is kept here too.
+
+use screencapturekit::prelude::*;
+use screencapturekit::stream::configuration::PixelFormat;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[cfg(feature = "macos_15_0")]
+use crate::recording::RecordingConfig;
+use crate::user_data;
+
+pub const FPS_OPTIONS: [u32; 6] = [10, 15, 30, 60, 120, 240];
+pub const QUEUE_OPTIONS: [u32; 4] = [3, 5, 8, 12];
+pub const SAMPLE_RATE_OPTIONS: [i32; 3] = [44100, 48000, 96000];
+pub const CHANNEL_OPTIONS: [i32; 2] = [1, 2];
+/// Pixel formats by their four-character code
+pub const PIXEL_FORMATS: [&str; 4] = ["BGRA", "l10r", "420v", "420f"];
+/// Recording codecs by their remote config name, with the name shown
+pub const CODECS: [(&str, &str); 2] = [("h264", "H.264"), ("hevc", "HEVC")];
+pub const FILE_TYPES: [(&str, &str); 2] = [("mp4", "MP4"), ("mov", "MOV")];
+/// Recording part lengths in minutes; `None` records one file
+pub const SEGMENT_OPTIONS: [Option<u32>; 4] = [None, Some(5), Some(15), Some(30)];
+/// Minutes without a recording before capture stops; `None` never stops it
+pub const IDLE_STOP_OPTIONS: [Option<u32>; 5] = [None, Some(10), Some(20), Some(30), Some(60)];
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureSettings {
+    pub fps: u32,
+    pub shows_cursor: bool,
+    pub captures_audio: bool,
+    pub captures_microphone: bool,
+    /// ID of the microphone to record; empty uses the system default
+    pub microphone_device: String,
+    /// Leave this app's own sounds out of the recording
+    pub excludes_own_audio: bool,
+    pub sample_rate: i32,
+    pub channel_count: i32,
+    pub scales_to_fit: bool,
+    pub preserves_aspect_ratio: bool,
+    pub opaque: bool,
+    pub shadows_only: bool,
+    pub ignores_shadows: bool,
+    /// One of [`PIXEL_FORMATS`]
+    pub pixel_format: String,
+    pub queue_depth: u32,
+    /// One of [`CODECS`]
+    pub codec: String,
+    /// One of [`FILE_TYPES`]
+    pub file_type: String,
+    pub segment_minutes: Option<u32>,
+    /// Stop capture after this many minutes without a recording
+    pub idle_stop_minutes: Option<u32>,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            fps: 10,
+            shows_cursor: true,
+            captures_audio: true,
+            captures_microphone: true,
+            microphone_device: String::new(),
+            excludes_own_audio: true,
+            sample_rate: 48000,
+            channel_count: 1,
+            scales_to_fit: true,
+            preserves_aspect_ratio: true,
+            opaque: false,
+            shadows_only: false,
+            ignores_shadows: false,
+            pixel_format: "420v".to_string(),
+            queue_depth: 8,
+            codec: "h264".to_string(),
+            file_type: "mp4".to_string(),
+            segment_minutes: None,
+            idle_stop_minutes: None,
+        }
+    }
+}
+
+impl CaptureSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("capture.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize capture settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save capture settings: {e}"))
+    }
+
+    /// Stream configuration with these options, before cropping
+    pub fn stream_config(&self) -> SCStreamConfiguration {
+        let mut config = SCStreamConfiguration::new()
+            .with_width(1920)
+            .with_height(1080)
+            .with_fps(self.fps)
+            .with_shows_cursor(self.shows_cursor)
+            .with_captures_audio(self.captures_audio)
+            .with_excludes_current_process_audio(self.excludes_own_audio)
+            .with_captures_microphone(self.captures_microphone)
+            .with_channel_count(self.channel_count)
+            .with_sample_rate(self.sample_rate)
+            .with_scales_to_fit(self.scales_to_fit)
+            .with_preserves_aspect_ratio(self.preserves_aspect_ratio)
+            .with_queue_depth(self.queue_depth)
+            .with_pixel_format(pixel_format(&self.pixel_format));
+        config.set_should_be_opaque(self.opaque);
+        config.set_captures_shadows_only(self.shadows_only);
+        config.set_ignores_shadows_display(self.ignores_shadows);
+        if !self.microphone_device.is_empty() {
+            config.set_microphone_capture_device_id(&self.microphone_device);
+        }
+        config
+    }
+
+    /// Recording options as the recorder takes them
+    #[cfg(feature = "macos_15_0")]
+    pub fn recording_config(&self) -> RecordingConfig {
+        use screencapturekit::recording_output::{SCRecordingOutputCodec, SCRecordingOutputFileType};
+
+        RecordingConfig {
+            codec: if self.codec == "hevc" {
+                SCRecordingOutputCodec::HEVC
+            } else {
+                SCRecordingOutputCodec::H264
+            },
+            file_type: if self.file_type == "mov" {
+                SCRecordingOutputFileType::MOV
+            } else {
+                SCRecordingOutputFileType::MP4
+            },
+            segment_minutes: self.segment_minutes,
+        }
+    }
+
+    /// How long capture may run without a recording before it stops
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_stop_minutes
+            .map(|minutes| Duration::from_secs(u64::from(minutes) * 60))
+    }
+
+    /// Name of the chosen microphone, or "Default"
+    pub fn microphone_name(&self) -> String {
+        if self.microphone_device.is_empty() {
+            return "Default".to_string();
+        }
+        AudioInputDevice::list()
+            .into_iter()
+            .find(|device| device.id == self.microphone_device)
+            .map_or_else(|| "Unavailable".to_string(), |device| device.name)
+    }
+
+    /// Move to the next microphone (or the previous one), with the default before the first
+    pub fn step_microphone(&mut self, increase: bool) {
+        let mut choices = vec![String::new()];
+        choices.extend(AudioInputDevice::list().into_iter().map(|device| device.id));
+        let current = choices.iter().position(|id| *id == self.microphone_device).unwrap_or(0);
+        let next = if increase {
+            (current + 1) % choices.len()
+        } else {
+            (current + choices.len() - 1) % choices.len()
+        };
+        self.microphone_device = choices.swap_remove(next);
+    }
+}
+
+/// The option after `current` in `options` (before it when not `increase`), wrapping
+/// around; the first option when `current` is not one of them
+pub fn step<T: PartialEq + Clone>(options: &[T], current: &T, increase: bool) -> T {
+    let next = match options.iter().position(|option| option == current) {
+        Some(i) if increase => (i + 1) % options.len(),
+        Some(i) => (i + options.len() - 1) % options.len(),
+        None => 0,
+    };
+    options[next].clone()
+}
+
+/// The code after `current` in `choices`, such as [`CODECS`], as [`step`] picks it
+#[cfg(feature = "macos_15_0")]
+pub fn step_choice(choices: &[(&str, &str)], current: &str, increase: bool) -> String {
+    let codes: Vec<&str> = choices.iter().map(|(code, _)| *code).collect();
+    step(&codes, &current, increase).to_string()
+}
+
+/// Pixel format for one of [`PIXEL_FORMATS`], 4:2:0 video range for anything else
+pub fn pixel_format(code: &str) -> PixelFormat {
+    match code {
+        "BGRA" => PixelFormat::BGRA,
+        "l10r" => PixelFormat::l10r,
+        "420f" => PixelFormat::YCbCr_420f,
+        _ => PixelFormat::YCbCr_420v,
+    }
+}
+
+/// How `segment_minutes` is shown
+#[cfg(feature = "macos_15_0")]
+pub fn segment_label(segment_minutes: Option<u32>) -> String {
+    segment_minutes.map_or_else(|| "Off".to_string(), |minutes| format!("{minutes} min"))
+}
+
+/// Display name for a code in `choices`, such as [`CODECS`]
+#[cfg(feature = "macos_15_0")]
+pub fn choice_label<'a>(choices: &[(&str, &'a str)], code: &str) -> &'a str {
+    choices
+        .iter()
+        .find(|(known, _)| *known == code)
+        .map_or("?", |(_, label)| label)
+}
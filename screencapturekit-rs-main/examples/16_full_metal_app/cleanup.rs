@@ -0,0 +1,125 @@
+//! Background cleanup of old local recordings
+//!
+//! Only recordings whose sidecar confirms a finished upload are ever deleted; anything
+//! pending, failed or without a sidecar is left alone regardless of the limits.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::library;
+use crate::sidecar::SidecarUploadState;
+use crate::user_data;
+
+/// How often the maintenance task runs
+pub const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const BYTES_PER_GB: u64 = 1024 * 1024 * 1024;
+
+/// Storage limits for local recordings, read from `cleanup.json` in the data folder
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CleanupPolicy {
+    /// Total size of local recordings to stay under
+    pub max_total_gb: u64,
+    /// Uploaded recordings older than this are removed
+    pub max_age_days: u64,
+}
+
+impl Default for CleanupPolicy {
+    fn default() -> Self {
+        Self {
+            max_total_gb: 20,
+            max_age_days: 30,
+        }
+    }
+}
+
+impl CleanupPolicy {
+    fn config_path() -> PathBuf {
+        user_data::file("cleanup.json")
+    }
+
+    /// Load the policy, falling back to the defaults when no config file exists
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    const fn max_total_bytes(&self) -> u64 {
+        self.max_total_gb.saturating_mul(BYTES_PER_GB)
+    }
+
+    const fn max_age(&self) -> Duration {
+        Duration::from_secs(self.max_age_days.saturating_mul(24 * 60 * 60))
+    }
+}
+
+/// What a cleanup pass removed
+#[derive(Debug, Default)]
+pub struct CleanupReport {
+    pub removed: Vec<(String, u64)>,
+    pub reclaimed_bytes: u64,
+    pub remaining_bytes: u64,
+    /// Recordings that would have been removed but are not confirmed uploaded
+    pub kept_not_uploaded: usize,
+}
+
+impl std::fmt::Display for CleanupReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "removed {} recording(s), reclaimed {:.2} GB, {:.2} GB still in use",
+            self.removed.len(),
+            self.reclaimed_bytes as f64 / BYTES_PER_GB as f64,
+            self.remaining_bytes as f64 / BYTES_PER_GB as f64,
+        )?;
+        if self.kept_not_uploaded > 0 {
+            write!(f, " ({} not yet uploaded, kept)", self.kept_not_uploaded)?;
+        }
+        Ok(())
+    }
+}
+
+/// Apply `policy` once, deleting the oldest uploaded recordings first
+pub fn run_cleanup(policy: &CleanupPolicy) -> CleanupReport {
+    let mut recordings = library::list_recordings();
+    recordings.sort_by_key(|r| r.modified);
+
+    let mut report = CleanupReport {
+        remaining_bytes: recordings.iter().map(|r| r.size_bytes).sum(),
+        ..CleanupReport::default()
+    };
+    let now = SystemTime::now();
+
+    for recording in &recordings {
+        let too_old = now
+            .duration_since(recording.modified)
+            .is_ok_and(|age| age > policy.max_age());
+        let over_cap = report.remaining_bytes > policy.max_total_bytes();
+        if !too_old && !over_cap {
+            continue;
+        }
+        let uploaded = recording
+            .sidecar
+            .as_ref()
+            .is_some_and(|s| s.upload_state == SidecarUploadState::Uploaded);
+        if !uploaded {
+            report.kept_not_uploaded += 1;
+            continue;
+        }
+
+        match library::delete_recording(&recording.path) {
+            Ok(()) => {
+                report.reclaimed_bytes += recording.size_bytes;
+                report.remaining_bytes -= recording.size_bytes;
+                report.removed.push((recording.path.clone(), recording.size_bytes));
+            }
+            Err(e) => eprintln!("⚠️  Failed to remove {}: {}", recording.path, e),
+        }
+    }
+
+    report
+}
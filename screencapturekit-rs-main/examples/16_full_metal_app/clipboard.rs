@@ -0,0 +1,95 @@
+//! System clipboard
+//!
+//! Writes straight to the general `NSPasteboard`: text such as links and sign-in
+//! codes, and images such as screenshots. Failures come back as messages suitable
+//! for a [`toast`](crate::toast). Screenshots only replace what the user has copied
+//! when they turn that on, in `clipboard.json` in the data folder.
+
+use cocoa::base::{id, nil, BOOL, NO};
+use cocoa::foundation::{NSArray, NSString};
+use objc::{class, msg_send, sel, sel_impl};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::user_data;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardSettings {
+    /// Put each new screenshot on the clipboard
+    pub copy_screenshots: bool,
+}
+
+impl ClipboardSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("clipboard.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize clipboard settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save clipboard settings: {e}"))
+    }
+}
+
+/// `NSPasteboardTypeString`
+const STRING_TYPE: &str = "public.utf8-plain-text";
+
+fn general_pasteboard() -> id {
+    unsafe { msg_send![class!(NSPasteboard), generalPasteboard] }
+}
+
+/// Replace the clipboard contents with `text`
+pub fn copy_text(text: &str) -> Result<(), String> {
+    let written: BOOL = unsafe {
+        let pasteboard = general_pasteboard();
+        let _: i64 = msg_send![pasteboard, clearContents];
+        let ns_text = NSString::alloc(nil).init_str(text);
+        let ns_type = NSString::alloc(nil).init_str(STRING_TYPE);
+        let written: BOOL = msg_send![pasteboard, setString: ns_text forType: ns_type];
+        let _: () = msg_send![ns_text, release];
+        let _: () = msg_send![ns_type, release];
+        written
+    };
+    if written == NO {
+        return Err("Could not copy to the clipboard".to_string());
+    }
+    println!("📋 Copied to clipboard");
+    Ok(())
+}
+
+/// Replace the clipboard contents with the image stored at `path`
+pub fn copy_image(path: &str) -> Result<(), String> {
+    if !Path::new(path).exists() {
+        return Err(format!("{path} no longer exists"));
+    }
+    let written: BOOL = unsafe {
+        let ns_path = NSString::alloc(nil).init_str(path);
+        let image: id = msg_send![class!(NSImage), alloc];
+        let image: id = msg_send![image, initWithContentsOfFile: ns_path];
+        let _: () = msg_send![ns_path, release];
+        if image == nil {
+            return Err(format!("{path} is not a readable image"));
+        }
+
+        let pasteboard = general_pasteboard();
+        let _: i64 = msg_send![pasteboard, clearContents];
+        let objects = NSArray::arrayWithObject(nil, image);
+        let written: BOOL = msg_send![pasteboard, writeObjects: objects];
+        let _: () = msg_send![image, release];
+        written
+    };
+    if written == NO {
+        return Err("Could not copy the image to the clipboard".to_string());
+    }
+    println!("📋 Copied image to clipboard: {}", path);
+    Ok(())
+}
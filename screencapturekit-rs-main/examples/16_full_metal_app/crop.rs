@@ -0,0 +1,208 @@
+//! Output crop and aspect ratio
+//!
+//! Crops the captured source to a region, e.g. a 16:9 or 4:3 area of an ultrawide
+//! display, by setting the stream's source rectangle, so the encoder only ever sees
+//! the cropped picture. The region is stored relative to the source (0–1 on both
+//! axes) in `crop.json` in the data folder, and is adjusted in the crop editor over
+//! a frozen frame of the uncropped source.
+
+use screencapturekit::cg::CGRect;
+use screencapturekit::output::IOSurface;
+use screencapturekit::prelude::*;
+use screencapturekit::shareable_content::SCShareableContent;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::live_preview;
+use crate::media;
+use crate::user_data;
+
+/// Smallest crop, as a fraction of the source
+const MIN_SIZE: f64 = 0.1;
+/// Width of the frozen frame shown in the editor
+const FRAME_WIDTH: u32 = 960;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AspectRatio {
+    #[default]
+    Free,
+    Widescreen,
+    Standard,
+    Square,
+}
+
+impl AspectRatio {
+    pub const ALL: [Self; 4] = [Self::Free, Self::Widescreen, Self::Standard, Self::Square];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Free => "free",
+            Self::Widescreen => "16:9",
+            Self::Standard => "4:3",
+            Self::Square => "1:1",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|ratio| ratio.as_str() == value)
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Free => "Free",
+            Self::Widescreen => "16:9",
+            Self::Standard => "4:3",
+            Self::Square => "Square",
+        }
+    }
+
+    /// Width over height, `None` when free
+    pub fn ratio(self) -> Option<f64> {
+        match self {
+            Self::Free => None,
+            Self::Widescreen => Some(16.0 / 9.0),
+            Self::Standard => Some(4.0 / 3.0),
+            Self::Square => Some(1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CropSettings {
+    pub enabled: bool,
+    pub aspect: AspectRatio,
+    /// Left edge, as a fraction of the source width
+    pub x: f64,
+    /// Top edge, as a fraction of the source height
+    pub y: f64,
+    pub width: f64,
+    /// Only used with a free aspect ratio; fixed ratios derive it from the width
+    pub height: f64,
+}
+
+impl Default for CropSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            aspect: AspectRatio::Free,
+            x: 0.0,
+            y: 0.0,
+            width: 1.0,
+            height: 1.0,
+        }
+    }
+}
+
+impl CropSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("crop.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize crop settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json).map_err(|e| format!("Failed to save crop settings: {e}"))
+    }
+
+    /// Crop as `(x, y, width, height)` fractions of a source `source_aspect` wide
+    /// per unit of height, kept inside the source and at the chosen aspect ratio
+    pub fn normalized(&self, source_aspect: f64) -> (f64, f64, f64, f64) {
+        if !self.enabled {
+            return (0.0, 0.0, 1.0, 1.0);
+        }
+        self.fitted(source_aspect)
+    }
+
+    /// Move the stored region inside the source and to the aspect ratio, so the
+    /// editor's sliders show what will be captured
+    pub fn fit(&mut self, source_aspect: f64) {
+        (self.x, self.y, self.width, self.height) = self.fitted(source_aspect);
+    }
+
+    fn fitted(&self, source_aspect: f64) -> (f64, f64, f64, f64) {
+        let mut width = self.width.clamp(MIN_SIZE, 1.0);
+        let mut height = match self.aspect.ratio() {
+            Some(ratio) => width * source_aspect / ratio,
+            None => self.height.clamp(MIN_SIZE, 1.0),
+        };
+        if height > 1.0 {
+            width /= height;
+            height = 1.0;
+        }
+        let x = self.x.clamp(0.0, 1.0 - width);
+        let y = self.y.clamp(0.0, 1.0 - height);
+        (x, y, width, height)
+    }
+}
+
+/// Nearest even pixel count, as the encoder requires
+fn even(value: f64) -> u32 {
+    ((value / 2.0).round() as u32).max(1) * 2
+}
+
+/// Stream configuration and output size for a source
+///
+/// `region` is the part of the filter's content that makes up the source, in points
+/// (the whole display, or the window for window sources); `size` is its size in
+/// pixels. Without a region the source cannot be cropped and `base` is used as is.
+pub fn configure(
+    base: &SCStreamConfiguration,
+    region: Option<CGRect>,
+    (width, height): (u32, u32),
+    settings: &CropSettings,
+) -> (SCStreamConfiguration, (u32, u32)) {
+    let mut config = base.clone();
+    let Some(region) = region else {
+        return (config, (width, height));
+    };
+    let (x, y, w, h) = settings.normalized(f64::from(width) / f64::from(height.max(1)));
+    config.set_source_rect(CGRect::new(
+        region.x + x * region.width,
+        region.y + y * region.height,
+        w * region.width,
+        h * region.height,
+    ));
+    if settings.enabled {
+        println!("🔲 Cropping output to {:.0}x{:.0} pt", w * region.width, h * region.height);
+    }
+    (config, (even(f64::from(width) * w), even(f64::from(height) * h)))
+}
+
+/// The whole of `display_id`, in its own coordinates
+pub fn display_region(display_id: u32) -> Option<CGRect> {
+    let content = SCShareableContent::get().ok()?;
+    let display = content.displays().into_iter().find(|d| d.display_id() == display_id)?;
+    let frame = display.frame();
+    Some(CGRect::new(0.0, 0.0, frame.width, frame.height))
+}
+
+/// Still of the uncropped source shown under the crop editor
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrozenFrame {
+    /// JPEG `data:` URI
+    pub image: String,
+    /// Width over height
+    pub aspect: f64,
+}
+
+/// The latest captured frame, for the crop editor
+pub fn freeze_frame(surface: Option<IOSurface>) -> Option<FrozenFrame> {
+    let surface = surface?;
+    if surface.height() == 0 {
+        return None;
+    }
+    let jpeg = live_preview::snapshot_jpeg(&surface, FRAME_WIDTH)?;
+    Some(FrozenFrame {
+        image: format!("data:image/jpeg;base64,{}", media::base64_encode(&jpeg)),
+        aspect: surface.width() as f64 / surface.height() as f64,
+    })
+}
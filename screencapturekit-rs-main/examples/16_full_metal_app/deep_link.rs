@@ -0,0 +1,82 @@
+//! `talka-recorder://` links
+//!
+//! Runbooks and calendar invites link to actions such as
+//! `talka-recorder://record?source=display1`, `talka-recorder://stop` and
+//! `talka-recorder://open-settings`. macOS hands the URL to the running app (launching
+//! it first if needed), and the UI turns it into capture commands. Links that start a
+//! recording are confirmed in the window first, so a link alone cannot start one.
+
+use std::sync::Mutex;
+
+/// Links macOS has opened that the UI has not handled yet
+static PENDING: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Keep links from an `Opened` event, which can arrive before the window is up
+pub fn queue(urls: impl IntoIterator<Item = String>) {
+    PENDING.lock().unwrap().extend(urls);
+}
+
+/// Links opened since the last call, oldest first
+pub fn take_pending() -> Vec<String> {
+    std::mem::take(&mut *PENDING.lock().unwrap())
+}
+
+/// URL scheme registered in the bundle's `Info.plist`
+pub const SCHEME: &str = "talka-recorder";
+
+/// Source named in a `record` link
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkSource {
+    /// `displayN`, counting displays from 1 in the order macOS lists them
+    Display(usize),
+}
+
+impl LinkSource {
+    pub fn label(self) -> String {
+        match self {
+            Self::Display(index) => format!("display {index}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeepLink {
+    /// Start recording, from `source` or else from the current or a newly picked source
+    Record { source: Option<LinkSource> },
+    Stop,
+    OpenSettings,
+}
+
+fn parse_source(value: &str) -> Result<LinkSource, String> {
+    value
+        .strip_prefix("display")
+        .and_then(|n| n.parse::<usize>().ok())
+        .filter(|&n| n >= 1)
+        .map(LinkSource::Display)
+        .ok_or_else(|| format!("Unknown source '{value}', expected e.g. display1"))
+}
+
+/// Parse a `talka-recorder://` URL
+pub fn parse(url: &str) -> Result<DeepLink, String> {
+    let rest = url
+        .strip_prefix(SCHEME)
+        .and_then(|r| r.strip_prefix("://").or_else(|| r.strip_prefix(':')))
+        .ok_or_else(|| format!("Not a {SCHEME}:// link: {url}"))?;
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+    match action.trim_matches('/') {
+        "record" => {
+            let mut source = None;
+            for pair in query.split('&').filter(|p| !p.is_empty()) {
+                match pair.split_once('=') {
+                    Some(("source", value)) => source = Some(parse_source(value)?),
+                    _ => return Err(format!("Unknown parameter '{pair}' in {url}")),
+                }
+            }
+            Ok(DeepLink::Record { source })
+        }
+        "stop" => Ok(DeepLink::Stop),
+        "open-settings" => Ok(DeepLink::OpenSettings),
+        other => Err(format!("Unknown action '{other}' in {url}")),
+    }
+}
@@ -0,0 +1,83 @@
+//! Handing recordings to the rest of macOS
+//!
+//! System UI such as the share sheet has to be driven from the main thread, which is
+//! where Dioxus event handlers run. Quick Look and Finder are reached through their
+//! command-line entry points instead.
+
+use cocoa::base::{id, nil};
+use cocoa::foundation::{NSArray, NSPoint, NSRect, NSSize, NSString};
+use objc::{class, msg_send, sel, sel_impl};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// `NSRectEdgeMinY`: open the picker below the anchor
+const PREFERRED_EDGE_MIN_Y: u64 = 1;
+
+fn ensure_exists(path: &str) -> Result<(), String> {
+    if Path::new(path).exists() {
+        Ok(())
+    } else {
+        Err(format!("{path} no longer exists"))
+    }
+}
+
+/// Preview a recording in a Quick Look panel
+pub fn quick_look(path: &str) -> Result<(), String> {
+    ensure_exists(path)?;
+    // `qlmanage` stays running until the panel is closed, so don't wait for it
+    Command::new("qlmanage")
+        .args(["-p", path])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open Quick Look: {e}"))
+}
+
+/// Open a Finder window with the recording selected
+pub fn reveal_in_finder(path: &str) -> Result<(), String> {
+    ensure_exists(path)?;
+    Command::new("open")
+        .args(["-R", path])
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to reveal in Finder: {e}"))
+}
+
+/// Show the system share sheet (AirDrop, Messages, Mail, ...) for a recording
+///
+/// The picker is anchored to the bottom of the app window that currently has focus.
+pub fn share_file(path: &str) -> Result<(), String> {
+    ensure_exists(path)?;
+
+    unsafe {
+        let app: id = msg_send![class!(NSApplication), sharedApplication];
+        let window: id = msg_send![app, keyWindow];
+        if window == nil {
+            return Err("No window to show the share menu from".to_string());
+        }
+        let view: id = msg_send![window, contentView];
+
+        let ns_path = NSString::alloc(nil).init_str(path);
+        let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+        let _: () = msg_send![ns_path, release];
+        let items = NSArray::arrayWithObject(nil, url);
+
+        // Deliberately not released: AppKit does not keep the picker alive while its
+        // menu is open, and one small object per share is not worth tracking
+        let picker: id = msg_send![class!(NSSharingServicePicker), alloc];
+        let picker: id = msg_send![picker, initWithItems: items];
+
+        let bounds: NSRect = msg_send![view, bounds];
+        let anchor = NSRect::new(
+            NSPoint::new(bounds.size.width / 2.0, 0.0),
+            NSSize::new(1.0, 1.0),
+        );
+        let _: () = msg_send![picker,
+            showRelativeToRect: anchor
+            ofView: view
+            preferredEdge: PREFERRED_EDGE_MIN_Y
+        ];
+    }
+    Ok(())
+}
@@ -7,8 +7,29 @@ pub enum CaptureCommand {
     TakeScreenshot,
     StartRecording,
     StopRecording,
+    /// Stop writing the recording for now, without ending it
+    PauseRecording,
+    ResumeRecording,
+    /// Upload the recording waiting in the trim editor, optionally cut to `(start, end)` seconds
+    UploadRecording { trim: Option<(f64, f64)> },
+    /// Upload a recording that has been through its post-processing
+    UploadProcessed { path: String },
+    /// Join the parts of the segmented recording waiting in the trim editor
+    MergeSegments,
     CancelRecording,
+    /// Mark the current moment of the recording
+    AddBookmark,
     SelectSource,
+    /// Capture a display directly, counting from 1, instead of opening the picker
+    SelectDisplay(usize),
+    /// Show the whole source so the crop can be adjusted over it
+    EditCrop,
+    /// Capture with the saved crop again
+    ApplyCrop,
+    /// Capture with the options saved in Settings
+    ApplyCaptureSettings,
+    ShowPreview,
+    ShowPictureInPicture,
     ToggleMicrophone,
     Quit,
     Logout,
@@ -0,0 +1,238 @@
+//! Environment diagnostics
+//!
+//! A pass/fail report on what recording depends on: screen recording and microphone
+//! permissions, the macOS version against the features this build was compiled with,
+//! reachability of the Talka endpoints and free disk space. Available as
+//! `16_full_metal_app doctor` from a terminal and from Settings.
+
+use cocoa::base::id;
+use objc::{class, msg_send, sel, sel_impl};
+use std::fmt;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::library;
+
+/// Endpoints recording and upload rely on
+const ENDPOINTS: [(&str, &str); 3] = [
+    ("Sign-in", "https://login.talka.ai"),
+    ("Calendar", "https://meeting-bot-scheduler.talka.ai"),
+    ("Storage", "https://storage.talka.ai"),
+];
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Free space below which long recordings may not fit
+const LOW_DISK_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    pub fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let icon = match self.status {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️ ",
+            CheckStatus::Fail => "❌",
+        };
+        write!(f, "{icon} {}: {}", self.name, self.detail)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    /// No check failed; warnings are allowed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.status != CheckStatus::Fail)
+    }
+
+    /// Checks that did not pass
+    pub fn problems(&self) -> impl Iterator<Item = &Check> {
+        self.checks.iter().filter(|c| c.status != CheckStatus::Pass)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            writeln!(f, "{check}")?;
+        }
+        let failed = self.checks.iter().filter(|c| c.status == CheckStatus::Fail).count();
+        if failed == 0 {
+            write!(f, "All checks passed")
+        } else {
+            write!(f, "{failed} check{} failed", if failed == 1 { "" } else { "s" })
+        }
+    }
+}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+}
+
+#[link(name = "AVFoundation", kind = "framework")]
+extern "C" {
+    static AVMediaTypeAudio: id;
+}
+
+fn screen_recording() -> Check {
+    if unsafe { CGPreflightScreenCaptureAccess() } {
+        Check::new("Screen recording", CheckStatus::Pass, "Allowed")
+    } else {
+        Check::new(
+            "Screen recording",
+            CheckStatus::Fail,
+            "Not allowed; enable it in System Settings → Privacy & Security → Screen Recording",
+        )
+    }
+}
+
+fn microphone() -> Check {
+    // AVAuthorizationStatus
+    let status: i64 = unsafe { msg_send![class!(AVCaptureDevice), authorizationStatusForMediaType: AVMediaTypeAudio] };
+    match status {
+        3 => Check::new("Microphone", CheckStatus::Pass, "Allowed"),
+        0 => Check::new("Microphone", CheckStatus::Warn, "Not asked yet; macOS will ask when the microphone is first used"),
+        1 => Check::new("Microphone", CheckStatus::Fail, "Restricted by a device management profile"),
+        _ => Check::new(
+            "Microphone",
+            CheckStatus::Fail,
+            "Not allowed; enable it in System Settings → Privacy & Security → Microphone",
+        ),
+    }
+}
+
+#[repr(C)]
+struct OperatingSystemVersion {
+    major: i64,
+    minor: i64,
+    patch: i64,
+}
+
+/// Newest macOS feature gate compiled in, with what it enables
+fn required_macos() -> (i64, i64, &'static str) {
+    if cfg!(feature = "macos_26_0") {
+        (26, 0, "native screenshots")
+    } else if cfg!(feature = "macos_15_2") {
+        (15, 2, "recording and upload")
+    } else if cfg!(feature = "macos_15_0") {
+        (15, 0, "recording and upload")
+    } else {
+        (14, 0, "capture and preview only; recording needs a macos_15_0 build")
+    }
+}
+
+fn macos_version() -> Check {
+    let version: OperatingSystemVersion = unsafe {
+        let info: id = msg_send![class!(NSProcessInfo), processInfo];
+        msg_send![info, operatingSystemVersion]
+    };
+    let (major, minor, features) = required_macos();
+    let running = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if (version.major, version.minor) >= (major, minor) {
+        let status = if cfg!(feature = "macos_15_0") { CheckStatus::Pass } else { CheckStatus::Warn };
+        Check::new("macOS", status, format!("{running}; built for {major}.{minor}+ ({features})"))
+    } else {
+        Check::new(
+            "macOS",
+            CheckStatus::Fail,
+            format!("{running} is older than the {major}.{minor} this build requires ({features})"),
+        )
+    }
+}
+
+async fn endpoint(client: &reqwest::Client, name: &str, url: &str) -> Check {
+    let label = format!("{name} server");
+    // Any HTTP answer, even an error status, means the server is reachable
+    match client.head(url).send().await {
+        Ok(response) => Check::new(label, CheckStatus::Pass, format!("Reachable (HTTP {})", response.status().as_u16())),
+        Err(e) if e.is_timeout() => Check::new(label, CheckStatus::Fail, format!("{url} timed out")),
+        Err(e) => Check::new(label, CheckStatus::Fail, format!("{url} unreachable: {e}")),
+    }
+}
+
+/// Free bytes on the volume holding `path`, from `df`
+fn free_bytes(path: &str) -> Result<u64, String> {
+    let output = Command::new("df")
+        .args(["-Pk", path])
+        .output()
+        .map_err(|e| format!("Failed to run df: {e}"))?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .nth(1)
+        .and_then(|line| line.split_whitespace().nth(3))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+        .ok_or_else(|| "Unexpected df output".to_string())
+}
+
+pub fn disk_space() -> Check {
+    match free_bytes(&library::recordings_dir()) {
+        Ok(bytes) => {
+            let detail = format!("{:.1} GB free for recordings", bytes as f64 / (1024.0 * 1024.0 * 1024.0));
+            let status = if bytes < LOW_DISK_BYTES { CheckStatus::Warn } else { CheckStatus::Pass };
+            Check::new("Disk space", status, detail)
+        }
+        Err(e) => Check::new("Disk space", CheckStatus::Warn, e),
+    }
+}
+
+/// Run every check
+pub async fn run() -> Report {
+    let mut checks = vec![screen_recording(), microphone(), macos_version()];
+
+    match reqwest::Client::builder().timeout(NETWORK_TIMEOUT).build() {
+        Ok(client) => {
+            for (name, url) in ENDPOINTS {
+                checks.push(endpoint(&client, name, url).await);
+            }
+        }
+        Err(e) => checks.push(Check::new("Network", CheckStatus::Fail, format!("Failed to create HTTP client: {e}"))),
+    }
+
+    checks.push(disk_space());
+    Report { checks }
+}
+
+/// `doctor` subcommand: print the report and return the process exit code
+pub fn run_cli() -> i32 {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("❌ Failed to start runtime: {}", e);
+            return 1;
+        }
+    };
+    let report = runtime.block_on(run());
+    println!("🩺 Talka Recorder diagnostics\n{}", report);
+    if report.passed() {
+        0
+    } else {
+        1
+    }
+}
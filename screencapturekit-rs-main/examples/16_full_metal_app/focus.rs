@@ -0,0 +1,167 @@
+//! Keeping notifications out of recordings
+//!
+//! Two layers, both optional and configured in `focus.json` in the data folder:
+//!
+//! - When a whole display is recorded, Notification Center is excluded from the
+//!   capture, so banners may still appear on screen but never in the recording.
+//! - macOS has no public API to switch Focus modes, so the user can name a Shortcut
+//!   (built with the "Set Focus" action) to run when recording starts, and another to
+//!   run when it stops. The stop shortcut only runs if the start one succeeded, which
+//!   leaves a Focus mode the user had already enabled alone. Shortcuts run one at a
+//!   time on a worker thread, since they can take seconds.
+
+use screencapturekit::prelude::*;
+use screencapturekit::shareable_content::SCShareableContent;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::OnceLock;
+
+use crate::user_data;
+
+/// Owner of notification banners and alerts
+const NOTIFICATION_CENTER_BUNDLE_ID: &str = "com.apple.notificationcenterui";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusSettings {
+    /// Leave notification banners out of display recordings
+    pub hide_notifications: bool,
+    /// Shortcut that turns a Focus mode on; empty to skip
+    pub focus_on_shortcut: String,
+    /// Shortcut that turns it back off
+    pub focus_off_shortcut: String,
+}
+
+impl Default for FocusSettings {
+    fn default() -> Self {
+        Self {
+            hide_notifications: true,
+            focus_on_shortcut: String::new(),
+            focus_off_shortcut: String::new(),
+        }
+    }
+}
+
+impl FocusSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("focus.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize focus settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save focus settings: {e}"))
+    }
+}
+
+/// Whether the Focus shortcut ran for the current recording
+static FOCUS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+fn run_shortcut(name: &str) -> Result<(), String> {
+    let output = Command::new("shortcuts")
+        .args(["run", name])
+        .output()
+        .map_err(|e| format!("Failed to run shortcut '{name}': {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Shortcut '{name}' failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+enum Switch {
+    On(String),
+    Off,
+}
+
+/// Shortcuts waiting to run, in the order recordings started and stopped
+static QUEUE: OnceLock<Sender<Switch>> = OnceLock::new();
+
+fn queue(switch: Switch) {
+    let queue = QUEUE.get_or_init(|| {
+        let (tx, rx) = channel::<Switch>();
+        std::thread::spawn(move || {
+            for switch in rx {
+                match switch {
+                    Switch::On(name) => switch_on(&name),
+                    Switch::Off => restore_now(),
+                }
+            }
+        });
+        tx
+    });
+    let _ = queue.send(switch);
+}
+
+fn switch_on(name: &str) {
+    if FOCUS_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    match run_shortcut(name) {
+        Ok(()) => {
+            println!("🌙 Focus enabled for recording");
+            FOCUS_ENABLED.store(true, Ordering::Relaxed);
+        }
+        Err(e) => eprintln!("⚠️  {}", e),
+    }
+}
+
+/// Run the Focus-on shortcut in the background, if one is configured
+pub fn enable(settings: &FocusSettings) {
+    let name = settings.focus_on_shortcut.trim();
+    if !name.is_empty() {
+        queue(Switch::On(name.to_string()));
+    }
+}
+
+/// Undo [`enable`] in the background, if it switched Focus on
+pub fn restore() {
+    queue(Switch::Off);
+}
+
+/// Undo [`enable`] before returning, for when the app is about to quit
+pub fn restore_now() {
+    if !FOCUS_ENABLED.swap(false, Ordering::Relaxed) {
+        return;
+    }
+    let name = FocusSettings::load().focus_off_shortcut;
+    if name.trim().is_empty() {
+        return;
+    }
+    match run_shortcut(name.trim()) {
+        Ok(()) => println!("☀️  Focus restored"),
+        Err(e) => eprintln!("⚠️  {}", e),
+    }
+}
+
+/// Filter for recording `display_id` with Notification Center left out
+pub fn filter_without_notifications(display_id: u32) -> Result<SCContentFilter, String> {
+    let content = SCShareableContent::get().map_err(|e| format!("Failed to get shareable content: {e:?}"))?;
+    let displays = content.displays();
+    let display = displays
+        .iter()
+        .find(|d| d.display_id() == display_id)
+        .ok_or_else(|| format!("Display {display_id} is no longer connected"))?;
+    let applications = content.applications();
+    let excluded: Vec<_> = applications
+        .iter()
+        .filter(|app| app.bundle_identifier() == NOTIFICATION_CENTER_BUNDLE_ID)
+        .collect();
+    Ok(SCContentFilter::builder()
+        .display(display)
+        .exclude_applications(&excluded, &[])
+        .build())
+}
@@ -0,0 +1,203 @@
+//! Dropped frames and encoder lag while recording
+//!
+//! ScreenCaptureKit drops frames quietly when the Mac cannot keep up: once the
+//! stream's frame queue is full, because the encoder or this app is behind, new
+//! frames are thrown away. Frames that do arrive then come in late, so every frame
+//! is timed from the moment it was captured (its presentation timestamp, on the host
+//! clock) to the moment it reaches the capture handler. Frames that never arrive
+//! leave gaps in the presentation timestamps, which at the recording's frame rate
+//! tell how many were dropped. Separately, the media time the recording output has
+//! written is compared with the time spent recording: when the encoder cannot keep up
+//! the file falls behind. When too many frames in a few seconds arrive late or are
+//! dropped, or the file falls behind, the recording is marked overloaded and the main
+//! window warns about it, until things have been fine for a while.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use screencapturekit::SCFrameStatus;
+
+/// A frame delivered this long after it was captured counts as late
+const LATE_AFTER: Duration = Duration::from_millis(150);
+/// Frames are judged in windows of this length
+const WINDOW: Duration = Duration::from_secs(5);
+/// Share of late or dropped frames in a window that counts as overloaded
+const OVERLOADED_SHARE: f64 = 0.1;
+/// A timestamp gap this many frame intervals long means frames were dropped
+const GAP_INTERVALS: f64 = 1.5;
+/// Share of the time spent recording that the file may fall behind by
+const WRITER_LAG_SHARE: f64 = 0.2;
+/// How long the warning stays up after the last overloaded window
+const CLEAR_AFTER: Duration = Duration::from_secs(15);
+
+#[repr(C)]
+#[derive(Default)]
+struct MachTimebaseInfo {
+    numer: u32,
+    denom: u32,
+}
+
+extern "C" {
+    fn mach_absolute_time() -> u64;
+    fn mach_timebase_info(info: *mut MachTimebaseInfo) -> i32;
+}
+
+struct Window {
+    started: Instant,
+    frames: u32,
+    late: u32,
+    dropped: u32,
+}
+
+/// Frame timing of the recording in progress
+struct Cadence {
+    /// Seconds between frames at the recording's frame rate
+    interval: f64,
+    /// Timestamp of the last frame, while frames are expected at that rate
+    last_frame: Option<f64>,
+}
+
+/// The file's media time at the start of a writer window, and when that was
+struct WriterWindow {
+    started: Instant,
+    recorded: f64,
+}
+
+static CURRENT_WINDOW: Mutex<Option<Window>> = Mutex::new(None);
+static CADENCE: Mutex<Option<Cadence>> = Mutex::new(None);
+static WRITER_WINDOW: Mutex<Option<WriterWindow>> = Mutex::new(None);
+/// When the last overloaded window ended
+static LAST_OVERLOADED: Mutex<Option<Instant>> = Mutex::new(None);
+/// Late frames since the recording started
+static LATE_FRAMES: AtomicU64 = AtomicU64::new(0);
+/// Frames dropped since the recording started
+static DROPPED_FRAMES: AtomicU64 = AtomicU64::new(0);
+
+/// Now on the host clock that sample timestamps use, in seconds
+pub fn host_seconds() -> f64 {
+    let mut info = MachTimebaseInfo::default();
+    unsafe {
+        mach_timebase_info(&mut info);
+        mach_absolute_time() as f64 * f64::from(info.numer) / f64::from(info.denom) / 1e9
+    }
+}
+
+/// Mark the recording overloaded, logging `why` when it was not already
+fn mark_overloaded(why: impl FnOnce() -> String) {
+    let mut last = LAST_OVERLOADED.lock().unwrap();
+    if !last.is_some_and(|at| at.elapsed() < CLEAR_AFTER) {
+        eprintln!("⚠️  {}; the system is overloaded", why());
+    }
+    *last = Some(Instant::now());
+}
+
+/// Frames missing between the last frame and one captured at `presentation_seconds`
+fn dropped_before(presentation_seconds: f64, status: SCFrameStatus) -> u32 {
+    let mut cadence = CADENCE.lock().unwrap();
+    let Some(cadence) = cadence.as_mut() else {
+        return 0;
+    };
+    // Frames only keep coming at the frame rate while the screen changes; after an
+    // idle frame, or while the stream is suspended, the next one is due whenever
+    let last = if status.has_content() {
+        cadence.last_frame.replace(presentation_seconds)
+    } else {
+        cadence.last_frame.take()
+    };
+    let gap = last.map_or(0.0, |last| presentation_seconds - last);
+    if status.has_content() && gap > cadence.interval * GAP_INTERVALS {
+        (gap / cadence.interval).round() as u32 - 1
+    } else {
+        0
+    }
+}
+
+/// Note a frame captured at `presentation_seconds` that has just been delivered
+///
+/// Called from the capture handler for each screen frame, with or without content.
+pub fn record_frame(presentation_seconds: f64, status: SCFrameStatus) {
+    let dropped = dropped_before(presentation_seconds, status);
+    if !status.has_content() {
+        return;
+    }
+    let late = host_seconds() - presentation_seconds > LATE_AFTER.as_secs_f64();
+    if late {
+        LATE_FRAMES.fetch_add(1, Ordering::Relaxed);
+    }
+    DROPPED_FRAMES.fetch_add(u64::from(dropped), Ordering::Relaxed);
+    let mut current = CURRENT_WINDOW.lock().unwrap();
+    let window = current.get_or_insert_with(|| Window { started: Instant::now(), frames: 0, late: 0, dropped: 0 });
+    window.frames += 1;
+    window.late += u32::from(late);
+    window.dropped += dropped;
+    if window.started.elapsed() < WINDOW {
+        return;
+    }
+    let expected = window.frames + window.dropped;
+    if f64::from(window.late + window.dropped) / f64::from(expected) >= OVERLOADED_SHARE {
+        let (late, dropped) = (window.late, window.dropped);
+        mark_overloaded(|| format!("Of {expected} frames, {late} arrived late and {dropped} were dropped"));
+    }
+    *current = None;
+}
+
+/// Note that the recording output has written `recorded_seconds` of media so far
+///
+/// Called from the capture backend while recording, and not while paused, when the
+/// file is not expected to grow.
+pub fn record_written(recorded_seconds: f64, paused: bool) {
+    let mut current = WRITER_WINDOW.lock().unwrap();
+    if paused {
+        *current = None;
+        return;
+    }
+    let window = current.get_or_insert_with(|| WriterWindow { started: Instant::now(), recorded: recorded_seconds });
+    let elapsed = window.started.elapsed().as_secs_f64();
+    if elapsed < WINDOW.as_secs_f64() {
+        return;
+    }
+    let written = recorded_seconds - window.recorded;
+    if written < elapsed * (1.0 - WRITER_LAG_SHARE) {
+        mark_overloaded(|| format!("The encoder wrote {written:.1} s of video in {elapsed:.1} s"));
+    }
+    *current = None;
+}
+
+/// Whether frames have recently been arriving late or being dropped, or the
+/// encoder falling behind
+pub fn overloaded() -> bool {
+    let mut last = LAST_OVERLOADED.lock().unwrap();
+    if last.is_some_and(|at| at.elapsed() >= CLEAR_AFTER) {
+        *last = None;
+        println!("✅ Recording is keeping up again");
+    }
+    last.is_some()
+}
+
+/// Frames that arrived late since the recording started
+pub fn late_frames() -> u64 {
+    LATE_FRAMES.load(Ordering::Relaxed)
+}
+
+/// Frames dropped since the recording started
+pub fn dropped_frames() -> u64 {
+    DROPPED_FRAMES.load(Ordering::Relaxed)
+}
+
+/// Start counting afresh for a new recording at `fps` frames per second
+pub fn reset(fps: u32) {
+    stop();
+    *CADENCE.lock().unwrap() = Some(Cadence { interval: 1.0 / f64::from(fps.max(1)), last_frame: None });
+}
+
+/// Stop judging frames when the recording or the capture stops, and take the
+/// warning down
+pub fn stop() {
+    LATE_FRAMES.store(0, Ordering::Relaxed);
+    DROPPED_FRAMES.store(0, Ordering::Relaxed);
+    *CURRENT_WINDOW.lock().unwrap() = None;
+    *CADENCE.lock().unwrap() = None;
+    *WRITER_WINDOW.lock().unwrap() = None;
+    *LAST_OVERLOADED.lock().unwrap() = None;
+}
@@ -0,0 +1,129 @@
+//! GPU the preview renders on, and how often
+//!
+//! On Macs with more than one GPU (an integrated and a discrete one, or an eGPU) the
+//! preview windows use the low-power integrated GPU unless the user picks another in
+//! Settings, since `Device::system_default` is the discrete one and keeps it awake for
+//! the whole meeting. The choice is stored by name in `gpu.json` in the data folder;
+//! when that GPU is gone (an unplugged eGPU) the automatic choice applies again.
+//!
+//! In low-power mode the preview is also drawn at most about 12 times a second
+//! instead of as fast as the display allows.
+
+use metal::Device;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::user_data;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GpuSettings {
+    /// Name of the GPU to render on; empty picks the low-power one
+    pub device: String,
+    /// Cap the preview's frame rate to save battery
+    pub low_power_preview: bool,
+}
+
+impl GpuSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("gpu.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize GPU settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json).map_err(|e| format!("Failed to save GPU settings: {e}"))?;
+        if self.device != current().device {
+            CHANGED.store(true, Ordering::Relaxed);
+        }
+        *CURRENT.lock().unwrap() = Some(self.clone());
+        Ok(())
+    }
+}
+
+/// A GPU as listed in Settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuInfo {
+    pub name: String,
+    pub low_power: bool,
+    pub removable: bool,
+}
+
+impl GpuInfo {
+    pub fn label(&self) -> String {
+        if self.removable {
+            format!("{} (external)", self.name)
+        } else if self.low_power {
+            format!("{} (integrated)", self.name)
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+/// Settings as last saved, loaded on first use
+static CURRENT: Mutex<Option<GpuSettings>> = Mutex::new(None);
+
+/// Set when the GPU choice changes, until the preview renderer picks it up
+static CHANGED: AtomicBool = AtomicBool::new(false);
+
+pub fn current() -> GpuSettings {
+    CURRENT.lock().unwrap().get_or_insert_with(GpuSettings::load).clone()
+}
+
+/// Pick up settings written to `gpu.json` by something other than [`GpuSettings::save`]
+pub fn reload() {
+    let settings = GpuSettings::load();
+    if settings.device != current().device {
+        CHANGED.store(true, Ordering::Relaxed);
+    }
+    *CURRENT.lock().unwrap() = Some(settings);
+}
+
+pub fn low_power_preview() -> bool {
+    current().low_power_preview
+}
+
+/// Whether the GPU choice changed since the last call
+pub fn take_changed() -> bool {
+    CHANGED.swap(false, Ordering::Relaxed)
+}
+
+/// GPUs that can drive the preview, excluding headless ones
+pub fn available() -> Vec<GpuInfo> {
+    Device::all()
+        .iter()
+        .filter(|device| !device.is_headless())
+        .map(|device| GpuInfo {
+            name: device.name().to_string(),
+            low_power: device.is_low_power(),
+            removable: device.is_removable(),
+        })
+        .collect()
+}
+
+/// Device the preview should render on with `settings`
+pub fn choose(settings: &GpuSettings) -> Option<Device> {
+    let devices: Vec<Device> = Device::all().into_iter().filter(|device| !device.is_headless()).collect();
+    let wanted = settings.device.trim();
+    if !wanted.is_empty() {
+        if let Some(device) = devices.iter().find(|device| device.name() == wanted) {
+            return Some(device.clone());
+        }
+        eprintln!("⚠️  GPU {} not found, using the low-power one", wanted);
+    }
+    devices
+        .iter()
+        .find(|device| device.is_low_power())
+        .cloned()
+        .or_else(Device::system_default)
+}
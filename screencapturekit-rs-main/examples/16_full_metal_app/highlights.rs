@@ -0,0 +1,212 @@
+//! Highlight reel export
+//!
+//! Once a recording has been trimmed and post-processed for upload, every bookmark
+//! becomes a short clip: a few seconds before the marked moment to catch what led up
+//! to it, and a little longer after. Clips are cut without re-encoding from the file
+//! that is uploaded, into a folder next to it
+//! (`recording_123_highlights/highlight_01.mp4`), together with a `highlights.json`
+//! manifest carrying the timestamps, as offsets into that file, and the metadata the
+//! recording is uploaded with.
+//!
+//! Bookmarks close enough together for their clips to overlap share one clip.
+//! Configured in `highlights.json` in the data folder.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::media;
+use crate::remote_config;
+use crate::sidecar::RecordingSidecar;
+use crate::toast;
+use crate::user_data;
+
+pub const MANIFEST_NAME: &str = "highlights.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HighlightSettings {
+    pub enabled: bool,
+    /// Seconds kept before each bookmark
+    pub before_seconds: f64,
+    /// Seconds kept after each bookmark
+    pub after_seconds: f64,
+}
+
+impl Default for HighlightSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            before_seconds: 15.0,
+            after_seconds: 30.0,
+        }
+    }
+}
+
+impl HighlightSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("highlights.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize highlight settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save highlight settings: {e}"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighlightClip {
+    /// File name inside the highlights folder
+    pub file: String,
+    /// Bookmarks covered by the clip, as offsets into the recording
+    pub bookmarks: Vec<f64>,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighlightManifest {
+    /// File name of the source recording
+    pub recording: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub recorded_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub participants: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Storage file ID, once the recording has been uploaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<String>,
+    pub clips: Vec<HighlightClip>,
+}
+
+/// `recording_123.mp4` → `recording_123_highlights`
+pub fn folder_for(recording_path: &str) -> PathBuf {
+    let path = Path::new(recording_path);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    path.with_file_name(format!("{stem}_highlights"))
+}
+
+/// Clip ranges for `bookmarks`, merging ranges that overlap
+fn clip_ranges(bookmarks: &[f64], duration: f64, settings: &HighlightSettings) -> Vec<(f64, f64, Vec<f64>)> {
+    let mut sorted: Vec<f64> = bookmarks.iter().copied().filter(|t| (0.0..=duration).contains(t)).collect();
+    sorted.sort_by(f64::total_cmp);
+
+    let mut ranges: Vec<(f64, f64, Vec<f64>)> = Vec::new();
+    for bookmark in sorted {
+        let start = (bookmark - settings.before_seconds).max(0.0);
+        let end = (bookmark + settings.after_seconds).min(duration);
+        match ranges.last_mut() {
+            Some((_, last_end, marks)) if start <= *last_end => {
+                *last_end = last_end.max(end);
+                marks.push(bookmark);
+            }
+            _ => ranges.push((start, end, vec![bookmark])),
+        }
+    }
+    ranges
+}
+
+/// Cut the bookmarked moments of a finished recording into its highlights folder
+///
+/// Returns the manifest path, or `None` when the recording has no bookmarks.
+/// Blocking; run it from a background thread.
+pub fn export(recording_path: &str, settings: &HighlightSettings) -> Result<Option<PathBuf>, String> {
+    let sidecar = RecordingSidecar::load(recording_path).unwrap_or_default();
+    if sidecar.bookmarks.is_empty() {
+        return Ok(None);
+    }
+    let duration = match sidecar.duration_seconds {
+        Some(duration) => duration,
+        None => media::duration_seconds(recording_path)?,
+    };
+
+    let folder = folder_for(recording_path);
+    std::fs::create_dir_all(&folder).map_err(|e| format!("Failed to create {}: {e}", folder.display()))?;
+    let extension = Path::new(recording_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("mp4");
+
+    let mut clips = Vec::new();
+    for (index, (start, end, bookmarks)) in clip_ranges(&sidecar.bookmarks, duration, settings).into_iter().enumerate() {
+        let file = format!("highlight_{:02}.{extension}", index + 1);
+        let output = folder.join(&file);
+        match media::cut_passthrough(recording_path, start, end, &output.to_string_lossy()) {
+            Ok(()) => clips.push(HighlightClip {
+                file,
+                bookmarks,
+                start_seconds: start,
+                end_seconds: end,
+            }),
+            Err(e) => eprintln!("⚠️  Highlight {} ({:.1}s - {:.1}s) failed: {}", index + 1, start, end, e),
+        }
+    }
+    if clips.is_empty() {
+        return Err("No highlight clips could be cut".to_string());
+    }
+
+    let event = sidecar.calendar_event.as_ref();
+    let manifest = HighlightManifest {
+        recording: Path::new(recording_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string(),
+        title: event.map(|e| e.title.clone()),
+        recorded_at: sidecar.recorded_at.clone(),
+        provider: sidecar.provider.clone(),
+        participants: event.map(|e| e.participants.clone()).unwrap_or_default(),
+        tags: sidecar.tags.clone(),
+        file_id: sidecar.file_id.clone(),
+        clips,
+    };
+    let manifest_path = folder.join(MANIFEST_NAME);
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize highlight manifest: {e}"))?;
+    std::fs::write(&manifest_path, json)
+        .map_err(|e| format!("Failed to write {}: {e}", manifest_path.display()))?;
+    println!("🎞️  {} highlight clips saved to {}", manifest.clips.len(), folder.display());
+    Ok(Some(manifest_path))
+}
+
+/// Export if highlights are enabled, reporting the result with a toast
+///
+/// Blocking; the post-processing worker calls it once the recording is trimmed, so
+/// the manifest is in place before the upload records the file ID in it.
+pub fn export_if_enabled(recording_path: &str) {
+    let settings = HighlightSettings::load();
+    if !settings.enabled || !remote_config::flag("highlights", true) {
+        return;
+    }
+    match export(recording_path, &settings) {
+        Ok(Some(_)) => toast::show("Highlight clips saved"),
+        Ok(None) => {}
+        Err(e) => toast::error(format!("Highlight export failed: {e}")),
+    }
+}
+
+/// Record the storage file ID in an existing manifest once the upload finishes
+pub fn set_file_id(recording_path: &str, file_id: &str) -> Result<(), String> {
+    let manifest_path = folder_for(recording_path).join(MANIFEST_NAME);
+    let Ok(json) = std::fs::read_to_string(&manifest_path) else {
+        return Ok(());
+    };
+    let mut manifest: HighlightManifest =
+        serde_json::from_str(&json).map_err(|e| format!("Failed to read {}: {e}", manifest_path.display()))?;
+    manifest.file_id = Some(file_id.to_string());
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize highlight manifest: {e}"))?;
+    std::fs::write(&manifest_path, json).map_err(|e| format!("Failed to write {}: {e}", manifest_path.display()))
+}
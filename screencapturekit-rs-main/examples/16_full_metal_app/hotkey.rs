@@ -0,0 +1,195 @@
+//! Global hotkeys
+//!
+//! ⌃⌥B marks the current moment of a recording from any app, so the recorder window
+//! does not need to be in front during a meeting. While drawing is available in a
+//! recording, ⌃⌥D switches drawing on the screen on and off and ⌃⌥E erases the
+//! drawings (see [`crate::annotations`]); other apps get those shortcuts back when the
+//! recording stops. Registered through Carbon's `RegisterEventHotKey`, which,
+//! unlike an `NSEvent` monitor, needs no Accessibility permission. Presses are only
+//! flagged here; the UI loop acts on them, turning bookmarks into
+//! [`crate::dioxus_ui::CaptureCommand::AddBookmark`].
+
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Shown next to the bookmark button
+pub const BOOKMARK_SHORTCUT: &str = "⌃⌥B";
+pub const DRAW_SHORTCUT: &str = "⌃⌥D";
+pub const ERASE_SHORTCUT: &str = "⌃⌥E";
+
+/// `kVK_ANSI_B`
+const KEY_CODE_B: u32 = 11;
+/// `kVK_ANSI_D`
+const KEY_CODE_D: u32 = 2;
+/// `kVK_ANSI_E`
+const KEY_CODE_E: u32 = 14;
+/// `controlKey | optionKey`
+const MODIFIERS: u32 = 0x1000 | 0x0800;
+/// `'keyb'`
+const EVENT_CLASS_KEYBOARD: u32 = u32::from_be_bytes(*b"keyb");
+/// `kEventHotKeyPressed`
+const EVENT_HOT_KEY_PRESSED: u32 = 5;
+/// Identifies our hotkeys to Carbon, `'tlka'`
+const SIGNATURE: u32 = u32::from_be_bytes(*b"tlka");
+/// `kEventParamDirectObject`
+const PARAM_DIRECT_OBJECT: u32 = u32::from_be_bytes(*b"----");
+/// `typeEventHotKeyID`
+const TYPE_HOT_KEY_ID: u32 = u32::from_be_bytes(*b"hkid");
+
+const BOOKMARK_ID: u32 = 1;
+const DRAW_ID: u32 = 2;
+const ERASE_ID: u32 = 3;
+
+#[repr(C)]
+#[derive(Default)]
+struct EventHotKeyId {
+    signature: u32,
+    id: u32,
+}
+
+#[repr(C)]
+struct EventTypeSpec {
+    event_class: u32,
+    event_kind: u32,
+}
+
+type EventHandler = extern "C" fn(call: *mut c_void, event: *mut c_void, user_data: *mut c_void) -> i32;
+
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn GetApplicationEventTarget() -> *mut c_void;
+    fn InstallEventHandler(
+        target: *mut c_void,
+        handler: EventHandler,
+        num_types: u32,
+        types: *const EventTypeSpec,
+        user_data: *mut c_void,
+        out_ref: *mut *mut c_void,
+    ) -> i32;
+    fn GetEventParameter(
+        event: *mut c_void,
+        name: u32,
+        desired_type: u32,
+        actual_type: *mut u32,
+        buffer_size: usize,
+        actual_size: *mut usize,
+        data: *mut c_void,
+    ) -> i32;
+    fn RegisterEventHotKey(
+        key_code: u32,
+        modifiers: u32,
+        id: EventHotKeyId,
+        target: *mut c_void,
+        options: u32,
+        out_ref: *mut *mut c_void,
+    ) -> i32;
+    fn UnregisterEventHotKey(hot_key: *mut c_void) -> i32;
+}
+
+static REGISTERED: AtomicBool = AtomicBool::new(false);
+/// `EventHotKeyRef`s of the drawing hotkeys while they are registered
+static ANNOTATION_KEYS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+static BOOKMARK_PRESSED: AtomicBool = AtomicBool::new(false);
+static DRAW_PRESSED: AtomicBool = AtomicBool::new(false);
+static ERASE_PRESSED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_hot_key(_call: *mut c_void, event: *mut c_void, _user_data: *mut c_void) -> i32 {
+    let mut id = EventHotKeyId::default();
+    let status = unsafe {
+        GetEventParameter(
+            event,
+            PARAM_DIRECT_OBJECT,
+            TYPE_HOT_KEY_ID,
+            std::ptr::null_mut(),
+            std::mem::size_of::<EventHotKeyId>(),
+            std::ptr::null_mut(),
+            (&mut id as *mut EventHotKeyId).cast(),
+        )
+    };
+    if status != 0 || id.signature != SIGNATURE {
+        return status;
+    }
+    match id.id {
+        BOOKMARK_ID => BOOKMARK_PRESSED.store(true, Ordering::Relaxed),
+        DRAW_ID => DRAW_PRESSED.store(true, Ordering::Relaxed),
+        ERASE_ID => ERASE_PRESSED.store(true, Ordering::Relaxed),
+        _ => {}
+    }
+    0
+}
+
+/// Register `key_code` with the handler installed by [`register`], returning its
+/// `EventHotKeyRef`
+fn register_key(key_code: u32, id: u32, shortcut: &str) -> Option<*mut c_void> {
+    let id = EventHotKeyId { signature: SIGNATURE, id };
+    let mut hot_key_ref = std::ptr::null_mut();
+    let status = unsafe { RegisterEventHotKey(key_code, MODIFIERS, id, GetApplicationEventTarget(), 0, &mut hot_key_ref) };
+    if status != 0 {
+        // Usually another app already owns the shortcut
+        eprintln!("⚠️  Failed to register hotkey {} (OSStatus {})", shortcut, status);
+        return None;
+    }
+    Some(hot_key_ref)
+}
+
+/// Register the bookmark hotkey; call once from the main thread
+pub fn register() {
+    if REGISTERED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    let spec = EventTypeSpec {
+        event_class: EVENT_CLASS_KEYBOARD,
+        event_kind: EVENT_HOT_KEY_PRESSED,
+    };
+    let mut handler_ref = std::ptr::null_mut();
+    let status = unsafe {
+        InstallEventHandler(GetApplicationEventTarget(), on_hot_key, 1, &spec, std::ptr::null_mut(), &mut handler_ref)
+    };
+    if status != 0 {
+        eprintln!("⚠️  Failed to install hotkey handler (OSStatus {})", status);
+        return;
+    }
+    if register_key(KEY_CODE_B, BOOKMARK_ID, BOOKMARK_SHORTCUT).is_some() {
+        println!("⌨️  Press {} to bookmark a recording", BOOKMARK_SHORTCUT);
+    }
+}
+
+/// Register the drawing hotkeys too, while drawing is available; from the main
+/// thread, after [`register`]
+pub fn register_annotations() {
+    let mut keys = ANNOTATION_KEYS.lock().unwrap();
+    if !REGISTERED.load(Ordering::Relaxed) || !keys.is_empty() {
+        return;
+    }
+    let draw = register_key(KEY_CODE_D, DRAW_ID, DRAW_SHORTCUT);
+    let erase = register_key(KEY_CODE_E, ERASE_ID, ERASE_SHORTCUT);
+    if draw.is_some() && erase.is_some() {
+        println!("⌨️  Press {} to draw on the screen and {} to erase", DRAW_SHORTCUT, ERASE_SHORTCUT);
+    }
+    keys.extend(draw.into_iter().chain(erase).map(|key| key as usize));
+}
+
+/// Give the drawing hotkeys back to other apps; from the main thread
+pub fn unregister_annotations() {
+    for key in ANNOTATION_KEYS.lock().unwrap().drain(..) {
+        unsafe { UnregisterEventHotKey(key as *mut c_void) };
+    }
+    DRAW_PRESSED.store(false, Ordering::Relaxed);
+    ERASE_PRESSED.store(false, Ordering::Relaxed);
+}
+
+/// Whether the hotkey was pressed since the last call
+pub fn take_bookmark_press() -> bool {
+    BOOKMARK_PRESSED.swap(false, Ordering::Relaxed)
+}
+
+/// Whether the drawing hotkey was pressed since the last call
+pub fn take_draw_press() -> bool {
+    DRAW_PRESSED.swap(false, Ordering::Relaxed)
+}
+
+/// Whether the erase hotkey was pressed since the last call
+pub fn take_erase_press() -> bool {
+    ERASE_PRESSED.swap(false, Ordering::Relaxed)
+}
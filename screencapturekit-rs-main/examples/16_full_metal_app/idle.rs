@@ -0,0 +1,55 @@
+//! Capture left running without a recording
+//!
+//! Sharing a source keeps ScreenCaptureKit busy and the screen-recording indicator
+//! lit even when nothing is being recorded, which is easy to forget after a meeting.
+//! The capture loop reports each pass whether it is capturing without recording; the
+//! main window shows how long that has gone on and, with a timeout chosen in
+//! Settings, capture stops by itself once it has been idle that long. The source is
+//! kept, so one click resumes.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Idle time after which the main window starts showing it
+pub const SHOW_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// When capture last started running without a recording
+static IDLE_SINCE: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Set when capture was stopped for being idle, until it is resumed or dismissed
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+/// Note what capture is doing; `true` when it has now run without a recording for
+/// `timeout` and should be stopped
+pub fn tick(capturing: bool, recording: bool, timeout: Option<Duration>) -> bool {
+    if capturing {
+        STOPPED.store(false, Ordering::Relaxed);
+    }
+    let mut since = IDLE_SINCE.lock().unwrap();
+    if !capturing || recording {
+        *since = None;
+        return false;
+    }
+    let started = *since.get_or_insert_with(Instant::now);
+    if timeout.is_some_and(|timeout| started.elapsed() >= timeout) {
+        *since = None;
+        STOPPED.store(true, Ordering::Relaxed);
+        return true;
+    }
+    false
+}
+
+/// How long capture has been running without a recording, if it is
+pub fn idle_for() -> Option<Duration> {
+    IDLE_SINCE.lock().unwrap().map(|since| since.elapsed())
+}
+
+/// Whether capture was stopped for being idle
+pub fn stopped() -> bool {
+    STOPPED.load(Ordering::Relaxed)
+}
+
+pub fn clear_stopped() {
+    STOPPED.store(false, Ordering::Relaxed);
+}
@@ -1,18 +1,168 @@
 //! Input handling and picker utilities
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use screencapturekit::content_sharing_picker::{
     SCContentSharingPicker, SCContentSharingPickerConfiguration, SCContentSharingPickerMode,
     SCPickedSource, SCPickerOutcome,
 };
+use screencapturekit::error::SCStreamErrorCode;
 use screencapturekit::prelude::*;
 
 use crate::capture::{CaptureHandler, CaptureState};
+use crate::frame_health;
+use crate::journal;
+use crate::journal::JournalEvent;
 
-/// Result type for picker callbacks: (filter, width, height, source)
-pub type PickerResult = Option<(SCContentFilter, u32, u32, SCPickedSource)>;
+/// A source chosen in the picker or with [`select_display`]
+pub struct PickedSource {
+    pub filter: SCContentFilter,
+    pub width: u32,
+    pub height: u32,
+    pub source: SCPickedSource,
+    /// A whole display was picked, rather than some of the apps on it
+    pub whole_display: bool,
+    /// The picked window, for window sources
+    pub window_id: Option<u32>,
+}
+
+/// How a picker opened with [`open_picker`] (or a [`select_display`]) ended
+pub enum PickerEvent {
+    Picked(PickedSource),
+    Cancelled,
+    Failed(String),
+}
+
+/// Source being captured, as last picked, with the picked window for window sources
+static CURRENT_SOURCE: Mutex<Option<(SCPickedSource, Option<u32>)>> = Mutex::new(None);
+
+pub fn current_source() -> Option<SCPickedSource> {
+    CURRENT_SOURCE.lock().unwrap().as_ref().map(|(source, _)| source.clone())
+}
+
+/// The window being captured, when a single window was picked
+pub fn current_window_id() -> Option<u32> {
+    CURRENT_SOURCE.lock().unwrap().as_ref().and_then(|(_, window_id)| *window_id)
+}
+
+pub fn set_current_source(source: Option<SCPickedSource>, window_id: Option<u32>) {
+    *CURRENT_SOURCE.lock().unwrap() = source.map(|source| (source, window_id));
+    *SOURCE_DISPLAY.lock().unwrap() = None;
+}
+
+/// Display the current source is shown on, once known
+static SOURCE_DISPLAY: Mutex<Option<u32>> = Mutex::new(None);
+
+/// The picked display, or the display the picked window is on
+pub fn source_display() -> Option<u32> {
+    *SOURCE_DISPLAY.lock().unwrap()
+}
+
+/// Record where the source set with [`set_current_source`] is shown
+pub fn set_source_display(display_id: Option<u32>) {
+    *SOURCE_DISPLAY.lock().unwrap() = display_id;
+}
+
+/// How long the picker stays open before the UI offers to stop waiting for it
+const PICKER_STUCK_AFTER: Duration = Duration::from_secs(15);
+
+/// The open picker's number and when it opened
+static OPEN_PICKER: Mutex<Option<(u64, Instant)>> = Mutex::new(None);
+static NEXT_PICKER: AtomicU64 = AtomicU64::new(0);
+
+/// Whether the picker has been open long enough that it may be stuck
+pub fn picker_stuck() -> bool {
+    OPEN_PICKER
+        .lock()
+        .unwrap()
+        .is_some_and(|(_, opened)| opened.elapsed() >= PICKER_STUCK_AFTER)
+}
+
+/// Stop waiting for the open picker, e.g. when it is stuck off screen
+///
+/// ScreenCaptureKit has no way to close the picker, so whatever it reports later is
+/// ignored and the picker can be opened again.
+pub fn dismiss_picker() {
+    if OPEN_PICKER.lock().unwrap().take().is_some() {
+        println!("⚠️  Stopped waiting for the content picker");
+    }
+}
+
+/// Note a picker opening and return its number
+fn begin_picker() -> u64 {
+    let id = NEXT_PICKER.fetch_add(1, Ordering::Relaxed);
+    *OPEN_PICKER.lock().unwrap() = Some((id, Instant::now()));
+    id
+}
+
+/// Note picker `id` closing; `false` when it was dismissed or replaced meanwhile
+fn end_picker(id: u64) -> bool {
+    let mut open = OPEN_PICKER.lock().unwrap();
+    if open.is_some_and(|(open_id, _)| open_id == id) {
+        *open = None;
+        true
+    } else {
+        false
+    }
+}
+
+/// Tries at starting a stream when ScreenCaptureKit reports a passing error
+const START_ATTEMPTS: u32 = 4;
+/// Wait before the second try, doubled before each one after
+const START_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// Why capture last failed to start, until it starts or the user dismisses it
+static START_ERROR: Mutex<Option<String>> = Mutex::new(None);
+/// When to try starting again after a passing error, and which try that will be
+static RETRY: Mutex<Option<(Instant, u32)>> = Mutex::new(None);
+/// Which try the next start is; starts the user asks for are the first
+static ATTEMPT: AtomicU32 = AtomicU32::new(1);
+
+pub fn start_error() -> Option<String> {
+    START_ERROR.lock().unwrap().clone()
+}
+
+pub fn clear_start_error() {
+    *START_ERROR.lock().unwrap() = None;
+}
+
+fn fail_start(message: String) {
+    eprintln!("❌ {message}");
+    journal::record(JournalEvent::Error { message: message.clone() });
+    *START_ERROR.lock().unwrap() = Some(message);
+}
+
+/// Start failures that go away on their own: another stream still starting or
+/// stopping, or capture briefly unavailable (e.g. right after the display wakes)
+fn is_transient(error: &SCError) -> bool {
+    matches!(
+        error.stream_error_code(),
+        Some(
+            SCStreamErrorCode::FailedToStartAudioCapture
+                | SCStreamErrorCode::FailedToStart
+                | SCStreamErrorCode::AttemptToStartStreamState
+                | SCStreamErrorCode::AttemptToStopStreamState
+        )
+    )
+}
+
+/// Whether a start that hit a passing error is due to be tried again
+///
+/// The backend then starts capture again, so the wait never holds up its loop.
+pub fn retry_due() -> bool {
+    let mut retry = RETRY.lock().unwrap();
+    match *retry {
+        Some((at, attempt)) if Instant::now() >= at => {
+            *retry = None;
+            ATTEMPT.store(attempt, Ordering::Relaxed);
+            true
+        }
+        _ => false,
+    }
+}
 
 /// Format a picked source for display
 pub fn format_picked_source(source: &SCPickedSource) -> String {
@@ -29,7 +179,7 @@ pub fn format_picked_source(source: &SCPickedSource) -> String {
 }
 
 /// Open content picker without an existing stream
-pub fn open_picker(pending_picker: &Arc<Mutex<PickerResult>>) {
+pub fn open_picker(events: &Sender<PickerEvent>) {
     println!("📺 Opening content picker...");
     let mut config = SCContentSharingPickerConfiguration::new();
     config.set_allowed_picker_modes(&[
@@ -39,15 +189,16 @@ pub fn open_picker(pending_picker: &Arc<Mutex<PickerResult>>) {
         SCContentSharingPickerMode::SingleApplication,
         SCContentSharingPickerMode::MultipleApplications,
     ]);
-    let pending = Arc::clone(pending_picker);
+    let events = events.clone();
+    let id = begin_picker();
 
     SCContentSharingPicker::show(&config, move |outcome| {
-        handle_picker_outcome(outcome, &pending);
+        handle_picker_outcome(outcome, id, &events);
     });
 }
 
 /// Open content picker for an existing stream
-pub fn open_picker_for_stream(pending_picker: &Arc<Mutex<PickerResult>>, stream: &SCStream) {
+pub fn open_picker_for_stream(events: &Sender<PickerEvent>, stream: &SCStream) {
     println!("📺 Opening content picker for stream...");
     let mut config = SCContentSharingPickerConfiguration::new();
     config.set_allowed_picker_modes(&[
@@ -57,31 +208,78 @@ pub fn open_picker_for_stream(pending_picker: &Arc<Mutex<PickerResult>>, stream:
         SCContentSharingPickerMode::SingleApplication,
         SCContentSharingPickerMode::MultipleApplications,
     ]);
-    let pending = Arc::clone(pending_picker);
+    let events = events.clone();
+    let id = begin_picker();
 
     SCContentSharingPicker::show_for_stream(&config, stream, move |outcome| {
-        handle_picker_outcome(outcome, &pending);
+        handle_picker_outcome(outcome, id, &events);
+    });
+}
+
+/// Select a display without the picker, as if it had been picked
+///
+/// `index` counts from 1 in the order macOS lists the displays.
+pub fn select_display(index: usize, events: &Sender<PickerEvent>) {
+    let _ = events.send(match display_source(index) {
+        Ok(picked) => PickerEvent::Picked(picked),
+        Err(e) => PickerEvent::Failed(e),
     });
 }
 
-fn handle_picker_outcome(outcome: SCPickerOutcome, pending: &Arc<Mutex<PickerResult>>) {
-    match outcome {
+fn display_source(index: usize) -> Result<PickedSource, String> {
+    let content = screencapturekit::shareable_content::SCShareableContent::get()
+        .map_err(|e| format!("Failed to get shareable content: {e:?}"))?;
+    let displays = content.displays();
+    let display = index
+        .checked_sub(1)
+        .and_then(|i| displays.get(i))
+        .ok_or_else(|| format!("Display {index} not found ({} connected)", displays.len()))?;
+
+    Ok(PickedSource {
+        filter: SCContentFilter::builder().display(display).build(),
+        width: display.width(),
+        height: display.height(),
+        source: SCPickedSource::Display(display.display_id()),
+        whole_display: true,
+        window_id: None,
+    })
+}
+
+/// Position of `display_id` in the order [`select_display`] counts, from 1
+pub fn display_index(display_id: u32) -> Option<usize> {
+    let content = screencapturekit::shareable_content::SCShareableContent::get().ok()?;
+    let displays = content.displays();
+    displays.iter().position(|d| d.display_id() == display_id).map(|i| i + 1)
+}
+
+fn handle_picker_outcome(outcome: SCPickerOutcome, id: u64, events: &Sender<PickerEvent>) {
+    if !end_picker(id) {
+        println!("⚠️  Ignoring a dismissed picker's result");
+        return;
+    }
+    let event = match outcome {
         SCPickerOutcome::Picked(result) => {
             let (width, height) = result.pixel_size();
-            let filter = result.filter();
-            let source = result.source();
-
-            if let Ok(mut pending) = pending.lock() {
-                *pending = Some((filter, width, height, source));
-            }
+            let windows = result.windows();
+            PickerEvent::Picked(PickedSource {
+                filter: result.filter(),
+                width,
+                height,
+                source: result.source(),
+                whole_display: windows.is_empty() && result.applications().is_empty(),
+                window_id: (windows.len() == 1).then(|| windows[0].window_id()),
+            })
         }
         SCPickerOutcome::Cancelled => {
             println!("⚠️  Picker cancelled");
+            PickerEvent::Cancelled
         }
         SCPickerOutcome::Error(e) => {
             eprintln!("❌ Picker error: {e}");
+            PickerEvent::Failed(e)
         }
-    }
+    };
+    let _ = events.send(event);
 }
 
 /// Start capture with the given filter and configuration
@@ -95,6 +293,10 @@ pub fn start_capture(
     capturing: &Arc<AtomicBool>,
     mic_only: bool,
 ) {
+    // A start asked for meanwhile replaces any retry still waiting
+    *RETRY.lock().unwrap() = None;
+    let attempt = ATTEMPT.swap(1, Ordering::Relaxed);
+
     // Get the filter to use
     let filter_to_use = if let Some(filter) = current_filter {
         filter.clone()
@@ -107,12 +309,12 @@ pub fn start_capture(
                 if let Some(display) = displays.first() {
                     SCContentFilter::builder().display(display).build()
                 } else {
-                    println!("❌ No displays available for mic-only capture");
+                    fail_start("No displays available for mic-only capture".to_string());
                     return;
                 }
             }
             Err(e) => {
-                println!("❌ Failed to get shareable content: {e:?}");
+                fail_start(format!("Failed to get shareable content: {e}"));
                 return;
             }
         }
@@ -141,21 +343,30 @@ pub fn start_capture(
         Ok(()) => {
             capturing.store(true, Ordering::Relaxed);
             *stream = Some(s);
+            clear_start_error();
+            journal::record(JournalEvent::CaptureStarted);
             println!("✅ Capture started");
         }
-        Err(e) => {
-            eprintln!("❌ Failed to start capture: {e:?}");
+        Err(e) if attempt < START_ATTEMPTS && is_transient(&e) => {
+            let delay = START_RETRY_DELAY * 2u32.pow(attempt - 1);
+            eprintln!("⚠️  {e}; retrying in {} ms", delay.as_millis());
+            *RETRY.lock().unwrap() = Some((Instant::now() + delay, attempt + 1));
         }
+        Err(e) => fail_start(e.to_string()),
     }
 }
 
-/// Stop the current capture
-pub fn stop_capture(stream: &mut Option<SCStream>, capturing: &Arc<AtomicBool>) {
+/// Stop the current capture, noting `reason` in the journal
+pub fn stop_capture(stream: &mut Option<SCStream>, capturing: &Arc<AtomicBool>, reason: &str) {
     println!("⏹️  Stopping capture...");
+    *RETRY.lock().unwrap() = None;
     if let Some(ref mut s) = stream {
         let _ = s.stop_capture();
+        journal::record(JournalEvent::CaptureStopped { reason: reason.to_string() });
     }
     *stream = None;
     capturing.store(false, Ordering::Relaxed);
+    // No frames come now, so an overload warning would never clear by itself
+    frame_health::stop();
     println!("✅ Capture stopped");
 }
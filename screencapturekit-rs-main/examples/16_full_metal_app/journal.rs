@@ -0,0 +1,98 @@
+//! Session journal for auditing
+//!
+//! Compliance teams reviewing recorded meetings need to know when capture ran, what
+//! was shared, when recordings started and stopped and whether they were uploaded.
+//! Each of these is appended, with its time, to one file per day in the `Journal`
+//! folder of the data folder: `2026-01-19.jsonl`, one JSON object per line. Lines are
+//! only ever appended, never rewritten. Diagnostics in Settings shows today's
+//! journal and opens the folder, and the main window draws today's sessions from it
+//! (see `timeline`).
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::user_data;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEvent {
+    CaptureStarted,
+    CaptureStopped { reason: String },
+    SourceSelected { source: String },
+    RecordingStarted { path: String },
+    RecordingStopped { path: String },
+    RecordingCancelled { path: String },
+    UploadStarted { path: String },
+    UploadFinished { path: String, file_id: String },
+    UploadFailed { path: String, error: String },
+    Error { message: String },
+}
+
+impl std::fmt::Display for JournalEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = |path: &str| {
+            std::path::Path::new(path)
+                .file_name()
+                .map_or_else(|| path.to_string(), |n| n.to_string_lossy().into_owned())
+        };
+        match self {
+            Self::CaptureStarted => write!(f, "Capture started"),
+            Self::CaptureStopped { reason } => write!(f, "Capture stopped ({reason})"),
+            Self::SourceSelected { source } => write!(f, "Source selected: {source}"),
+            Self::RecordingStarted { path } => write!(f, "Recording started: {}", name(path)),
+            Self::RecordingStopped { path } => write!(f, "Recording stopped: {}", name(path)),
+            Self::RecordingCancelled { path } => write!(f, "Recording cancelled: {}", name(path)),
+            Self::UploadStarted { path } => write!(f, "Upload started: {}", name(path)),
+            Self::UploadFinished { path, file_id } => write!(f, "Uploaded {} as {file_id}", name(path)),
+            Self::UploadFailed { path, error } => write!(f, "Upload of {} failed: {error}", name(path)),
+            Self::Error { message } => write!(f, "Error: {message}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// RFC 3339 local time
+    pub at: String,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+/// Folder the daily journal files are written to
+pub fn dir() -> PathBuf {
+    user_data::journal_dir()
+}
+
+fn path_for(day: NaiveDate) -> PathBuf {
+    dir().join(format!("{}.jsonl", day.format("%Y-%m-%d")))
+}
+
+/// Append `event` to today's journal, logging rather than failing if it cannot be
+pub fn record(event: JournalEvent) {
+    let now = Local::now();
+    let entry = JournalEntry { at: now.to_rfc3339(), event };
+    let line = match serde_json::to_string(&entry) {
+        Ok(line) => line,
+        Err(e) => {
+            eprintln!("⚠️  Failed to serialize journal entry: {}", e);
+            return;
+        }
+    };
+    let path = path_for(now.date_naive());
+    if let Err(e) = user_data::append_private(&path, format!("{line}\n")) {
+        eprintln!("⚠️  Failed to write to {}: {}", path.display(), e);
+    }
+}
+
+/// Entries recorded on `day`, oldest first; lines that do not parse are skipped
+pub fn entries(day: NaiveDate) -> Vec<JournalEntry> {
+    std::fs::read_to_string(path_for(day))
+        .map(|text| text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Entries recorded today, oldest first
+pub fn today() -> Vec<JournalEntry> {
+    entries(Local::now().date_naive())
+}
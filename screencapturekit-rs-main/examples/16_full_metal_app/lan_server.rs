@@ -0,0 +1,113 @@
+//! Pieces shared by the small HTTP servers the app runs for other devices
+//!
+//! The live preview and the phone remote both hand out links carrying a secret
+//! token, and serve each connected browser on its own thread. The token is the only
+//! thing keeping other people on the network out, so it comes from the system's
+//! secure random number generator, the number of client threads is capped so a
+//! misbehaving client cannot exhaust the app's threads, and so is the size of a
+//! request, so one cannot exhaust its memory.
+
+use std::ffi::c_void;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Browsers served at once by one server; more are turned away until one leaves
+pub const MAX_CLIENTS: usize = 8;
+
+#[link(name = "Security", kind = "framework")]
+extern "C" {
+    fn SecRandomCopyBytes(rnd: *const c_void, count: usize, bytes: *mut u8) -> i32;
+}
+
+/// 128 random bits as hex, for the secret path component of a server's URLs
+pub fn new_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    // A null generator is kSecRandomDefault
+    let status = unsafe { SecRandomCopyBytes(std::ptr::null(), bytes.len(), bytes.as_mut_ptr()) };
+    if status != 0 {
+        return Err(format!("Failed to generate an access token (error {status})"));
+    }
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// This Mac's Bonjour name, which other devices on the network can resolve
+pub fn local_host_name() -> Option<String> {
+    let output = std::process::Command::new("scutil")
+        .args(["--get", "LocalHostName"])
+        .output()
+        .ok()?;
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Clients a server is serving
+#[derive(Clone, Default)]
+pub struct Clients(Arc<AtomicUsize>);
+
+/// A client's place among [`Clients`], freed when dropped
+pub struct Slot(Arc<AtomicUsize>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Clients {
+    /// Take a place for a new client, unless [`MAX_CLIENTS`] are already being served
+    ///
+    /// The client's thread should hold the slot until it is done.
+    pub fn admit(&self) -> Option<Slot> {
+        if self.0.fetch_add(1, Ordering::SeqCst) >= MAX_CLIENTS {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        Some(Slot(Arc::clone(&self.0)))
+    }
+}
+
+/// Answer for clients turned away by [`Clients::admit`]
+pub const BUSY_RESPONSE: &str =
+    "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 5\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+/// Most bytes read of a request's line and headers
+pub const MAX_REQUEST_HEAD: u64 = 8 * 1024;
+
+const TOO_LARGE_RESPONSE: &str =
+    "HTTP/1.1 431 Request Header Fields Too Large\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+/// Read a request's line and headers from `stream`, returning its method and path
+///
+/// The headers are read so the browser is not cut off mid-request, but no more than
+/// [`MAX_REQUEST_HEAD`] bytes, however long the client keeps sending. `None` when
+/// the request ends early or is too large; a too large one is answered with 431.
+pub fn read_request(stream: &TcpStream) -> std::io::Result<Option<(String, String)>> {
+    let mut reader = BufReader::new(stream.take(MAX_REQUEST_HEAD));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut complete = request_line.ends_with('\n');
+    let mut header = String::new();
+    while complete {
+        header.clear();
+        reader.read_line(&mut header)?;
+        if !header.ends_with('\n') {
+            complete = false;
+        } else if header.trim().is_empty() {
+            break;
+        }
+    }
+    if !complete {
+        if reader.get_ref().limit() == 0 {
+            let mut stream = stream;
+            stream.write_all(TOO_LARGE_RESPONSE.as_bytes())?;
+        }
+        return Ok(None);
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("GET").to_string();
+    let path = parts.next().unwrap_or("/").to_string();
+    Ok(Some((method, path)))
+}
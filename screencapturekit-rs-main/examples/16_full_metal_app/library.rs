@@ -0,0 +1,282 @@
+//! Local recordings library
+//!
+//! Lists the recordings on disk together with their sidecar metadata and runs the
+//! per-recording actions offered in the library view. Listings come from the
+//! [`library_index`](crate::library_index) database, with a directory scan as fallback.
+
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::highlights;
+use crate::library_index;
+use crate::media;
+use crate::sidecar::{RecordingSidecar, SegmentInfo, SidecarUploadState};
+use crate::time_format;
+use crate::upload_queue;
+use crate::user_data;
+
+/// Directory new recordings are written to
+pub fn recordings_dir() -> String {
+    user_data::recordings_dir().to_string_lossy().into_owned()
+}
+
+/// A recording file found in [`recordings_dir`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryEntry {
+    pub path: String,
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub modified: SystemTime,
+    pub sidecar: Option<RecordingSidecar>,
+}
+
+impl LibraryEntry {
+    /// Calendar meeting title, then capture source, then file name
+    pub fn title(&self) -> String {
+        self.sidecar
+            .as_ref()
+            .and_then(|s| {
+                s.calendar_event
+                    .as_ref()
+                    .map(|e| e.title.clone())
+                    .or_else(|| (!s.source_name.is_empty()).then(|| s.source_name.clone()))
+            })
+            .unwrap_or_else(|| self.file_name.clone())
+    }
+
+    /// Group and part number when this file is one part of a segmented recording
+    pub fn segment(&self) -> Option<&SegmentInfo> {
+        self.sidecar.as_ref().and_then(|s| s.segment.as_ref())
+    }
+
+    /// Why the recording failed its post-recording integrity check, if it did
+    pub fn damage(&self) -> Option<&str> {
+        self.sidecar
+            .as_ref()
+            .filter(|s| s.upload_state == SidecarUploadState::Damaged)
+            .map(|s| s.upload_error.as_deref().unwrap_or("Recording is damaged"))
+    }
+
+    pub fn tags(&self) -> &[String] {
+        self.sidecar.as_ref().map(|s| s.tags.as_slice()).unwrap_or_default()
+    }
+
+    pub fn notes(&self) -> &str {
+        self.sidecar.as_ref().map_or("", |s| s.notes.as_str())
+    }
+
+    /// Case-insensitive match against the fields covered by library search
+    fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let sidecar = self.sidecar.as_ref();
+        [self.file_name.as_str(), &self.title(), self.notes()]
+            .into_iter()
+            .chain(sidecar.map(|s| s.source_name.as_str()))
+            .chain(sidecar.and_then(|s| s.transcript.as_deref()))
+            .chain(self.tags().iter().map(String::as_str))
+            .any(|field| field.to_lowercase().contains(&query))
+    }
+
+    /// Seconds into the recording where `query` is first spoken, for opening a
+    /// search result at the match
+    pub fn transcript_match(&self, query: &str) -> Option<f64> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return None;
+        }
+        self.sidecar
+            .as_ref()?
+            .transcript_cues
+            .iter()
+            .find(|cue| cue.text.to_lowercase().contains(&query))
+            .map(|cue| cue.start_seconds)
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.sidecar.as_ref().is_some_and(|s| s.archive_preset.is_some())
+    }
+
+    pub fn is_mov(&self) -> bool {
+        Path::new(&self.path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("mov"))
+    }
+
+    /// Size and duration for display, e.g. "42.1 MB · 12:04"
+    pub fn summary(&self) -> String {
+        let size = format!("{:.1} MB", self.size_bytes as f64 / (1024.0 * 1024.0));
+        match self.sidecar.as_ref().and_then(|s| s.duration_seconds) {
+            Some(seconds) => format!("{size} · {}", time_format::offset(seconds.round() as u64)),
+            None => size,
+        }
+    }
+}
+
+/// Recording files (`recording_*.mp4` / `.mov`), newest first
+pub fn list_recordings() -> Vec<LibraryEntry> {
+    library_index::recordings().unwrap_or_else(|e| {
+        eprintln!("⚠️  {e}");
+        scan_recordings()
+    })
+}
+
+/// Recordings matching a search query, newest first
+///
+/// Covers titles, capture sources, calendar event names, tags, notes and transcripts.
+pub fn search_recordings(query: &str) -> Vec<LibraryEntry> {
+    let query = query.trim();
+    if query.is_empty() {
+        return list_recordings();
+    }
+    library_index::search(query).unwrap_or_else(|e| {
+        eprintln!("⚠️  {e}");
+        scan_recordings().into_iter().filter(|r| r.matches(query)).collect()
+    })
+}
+
+/// Rebuild the library index from the recordings directory
+///
+/// Picks up files that were added, changed or removed outside the app.
+pub fn refresh_index() {
+    if let Err(e) = library_index::sync(&scan_recordings()) {
+        eprintln!("⚠️  Failed to refresh library index: {e}");
+    }
+}
+
+/// Delete a recording together with its sidecar and index entry
+pub fn delete_recording(path: &str) -> std::io::Result<()> {
+    std::fs::remove_file(path)?;
+    let _ = std::fs::remove_file(RecordingSidecar::path_for(path));
+    let _ = std::fs::remove_dir_all(highlights::folder_for(path));
+    let _ = library_index::remove(path);
+    upload_queue::changed();
+    Ok(())
+}
+
+fn scan_recordings() -> Vec<LibraryEntry> {
+    let Ok(entries) = std::fs::read_dir(recordings_dir()) else {
+        return Vec::new();
+    };
+
+    let mut recordings: Vec<LibraryEntry> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            let file_name = path.file_name()?.to_str()?.to_string();
+            let ext = path.extension()?.to_str()?;
+            if !file_name.starts_with("recording_") || !matches!(ext, "mp4" | "mov") {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            let path = path.to_str()?.to_string();
+            Some(LibraryEntry {
+                sidecar: RecordingSidecar::load(&path),
+                size_bytes: metadata.len(),
+                modified: metadata.modified().ok()?,
+                file_name,
+                path,
+            })
+        })
+        .collect();
+
+    recordings.sort_by(|a, b| b.modified.cmp(&a.modified));
+    recordings
+}
+
+/// Keep a recording that failed verification out of the upload path
+///
+/// The file stays on disk and shows up as damaged in the library so it can be
+/// recovered by hand; cleanup never removes it because it is not uploaded.
+pub fn mark_damaged(path: &str, error: &str) {
+    let result = RecordingSidecar::update(path, |s| {
+        s.upload_state = SidecarUploadState::Damaged;
+        s.upload_error = Some(error.to_string());
+    });
+    if let Err(e) = result {
+        eprintln!("⚠️  Could not mark {path} as damaged: {e}");
+    }
+}
+
+/// Split comma-separated input into trimmed tags, dropping blanks and duplicates
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// Save the user's tags and notes for a recording
+///
+/// Recordings made before sidecars existed get a new sidecar holding just these.
+pub fn annotate(path: &str, tags: Vec<String>, notes: String) -> Result<(), String> {
+    let mut sidecar = RecordingSidecar::load(path).unwrap_or_default();
+    sidecar.tags = tags;
+    sidecar.notes = notes.trim().to_string();
+    sidecar.save(path)
+}
+
+/// Losslessly rewrap a MOV recording as MP4, copying its metadata to the new file
+pub fn remux_to_mp4(path: &str) -> Result<String, String> {
+    let output = media::remux_passthrough(path, "mp4")?;
+    if let Some(sidecar) = RecordingSidecar::load(path) {
+        sidecar.save(&output)?;
+    }
+    Ok(output)
+}
+
+/// Paths of every part of the segmented recording `group`, in order
+pub fn segment_paths(entries: &[LibraryEntry], group: &str) -> Vec<String> {
+    let mut parts: Vec<_> = entries
+        .iter()
+        .filter_map(|e| e.segment().filter(|s| s.group == group).map(|s| (s.index, e)))
+        .collect();
+    parts.sort_by_key(|(index, _)| *index);
+    parts.into_iter().map(|(_, e)| e.path.clone()).collect()
+}
+
+/// Losslessly join the parts of a segmented recording into one file
+///
+/// The merged file replaces the parts: its sidecar carries the combined duration and
+/// bookmarks, and the part files are removed once the merge is confirmed readable.
+pub fn merge_segments(parts: &[String]) -> Result<String, String> {
+    let first = parts.first().ok_or_else(|| "No segments to merge".to_string())?;
+    let first_path = Path::new(first);
+    let stem = first_path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    let base = stem.rsplit_once("_part").map_or(stem, |(base, _)| base);
+    let ext = first_path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let output = first_path
+        .with_file_name(format!("{base}.{ext}"))
+        .to_string_lossy()
+        .into_owned();
+
+    media::concat_passthrough(parts, &output)?;
+    let duration = media::duration_seconds(&output)
+        .map_err(|e| format!("Merged file is not readable: {e}"))?;
+
+    if let Some(mut merged) = RecordingSidecar::load(first) {
+        let mut offset = 0.0;
+        let mut bookmarks = Vec::new();
+        for part in parts {
+            let part_sidecar = RecordingSidecar::load(part);
+            if let Some(ref s) = part_sidecar {
+                bookmarks.extend(s.bookmarks.iter().map(|t| t + offset));
+            }
+            offset += part_sidecar
+                .and_then(|s| s.duration_seconds)
+                .or_else(|| media::duration_seconds(part).ok())
+                .unwrap_or(0.0);
+        }
+        merged.segment = None;
+        merged.duration_seconds = Some(duration);
+        merged.bookmarks = bookmarks;
+        merged.save(&output)?;
+    }
+
+    for part in parts {
+        let _ = delete_recording(part);
+    }
+    Ok(output)
+}
@@ -0,0 +1,278 @@
+//! SQLite index of local recordings
+//!
+//! Mirrors each recording's sidecar into `library.sqlite` in the data folder so the
+//! library, cleanup and recovery views can query recordings without scanning the
+//! recordings directory and parsing every sidecar. Sidecars stay the source of truth: the index is
+//! updated whenever one is saved or a recording is deleted, and reconciled against the
+//! disk with [`sync`].
+
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::library::LibraryEntry;
+use crate::sidecar::RecordingSidecar;
+use crate::user_data;
+
+/// Bumped whenever [`SCHEMA`] changes; older databases are rebuilt from the sidecars
+const SCHEMA_VERSION: i32 = 4;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS recordings (
+        path              TEXT PRIMARY KEY,
+        file_name         TEXT NOT NULL,
+        size_bytes        INTEGER NOT NULL,
+        modified_ms       INTEGER NOT NULL,
+        recorded_at       TEXT,
+        source_name       TEXT NOT NULL DEFAULT '',
+        upload_state      TEXT,
+        file_id           TEXT,
+        calendar_event_id TEXT,
+        calendar_title    TEXT NOT NULL DEFAULT '',
+        segment_group     TEXT,
+        notes             TEXT NOT NULL DEFAULT '',
+        transcript        TEXT NOT NULL DEFAULT '',
+        sidecar           TEXT
+    );
+    CREATE INDEX IF NOT EXISTS recordings_modified ON recordings(modified_ms);
+    CREATE INDEX IF NOT EXISTS recordings_calendar ON recordings(calendar_event_id);
+    CREATE TABLE IF NOT EXISTS bookmarks (
+        recording_path TEXT NOT NULL REFERENCES recordings(path) ON DELETE CASCADE,
+        offset_seconds REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS bookmarks_recording ON bookmarks(recording_path);
+    CREATE TABLE IF NOT EXISTS tags (
+        recording_path TEXT NOT NULL REFERENCES recordings(path) ON DELETE CASCADE,
+        tag            TEXT NOT NULL COLLATE NOCASE,
+        PRIMARY KEY (recording_path, tag)
+    );
+    CREATE INDEX IF NOT EXISTS tags_tag ON tags(tag);
+";
+
+static INDEX: OnceLock<Option<Mutex<Connection>>> = OnceLock::new();
+
+fn db_path() -> PathBuf {
+    user_data::file("library.sqlite")
+}
+
+fn open() -> rusqlite::Result<Connection> {
+    let path = db_path();
+    let mut conn = Connection::open(&path)?;
+    user_data::restrict(&path);
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version != SCHEMA_VERSION {
+        // Everything in the index can be recovered from the sidecars
+        conn.execute_batch(
+            "DROP TABLE IF EXISTS tags;
+             DROP TABLE IF EXISTS bookmarks;
+             DROP TABLE IF EXISTS recordings;",
+        )?;
+    }
+    conn.execute_batch(SCHEMA)?;
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    relocate_legacy(&mut conn)?;
+    Ok(conn)
+}
+
+/// Point rows for recordings moved out of `/tmp` at where they are now
+fn relocate_legacy(conn: &mut Connection) -> rusqlite::Result<()> {
+    let paths: Vec<String> = conn
+        .prepare("SELECT path FROM recordings WHERE path LIKE '/tmp/recording_%'")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    let moves: Vec<(String, String)> = paths
+        .into_iter()
+        .filter_map(|old| {
+            let new = user_data::relocated(&old);
+            (new != old).then_some((old, new))
+        })
+        .collect();
+    if moves.is_empty() {
+        return Ok(());
+    }
+    let tx = conn.transaction()?;
+    // Bookmarks and tags refer to the path, so they are checked once all have moved
+    tx.execute_batch("PRAGMA defer_foreign_keys = ON;")?;
+    for (old, new) in &moves {
+        tx.execute("UPDATE recordings SET path = ?2 WHERE path = ?1", params![old, new])?;
+        tx.execute("UPDATE bookmarks SET recording_path = ?2 WHERE recording_path = ?1", params![old, new])?;
+        tx.execute("UPDATE tags SET recording_path = ?2 WHERE recording_path = ?1", params![old, new])?;
+    }
+    tx.commit()?;
+    println!("📦 Updated {} library entries for recordings moved out of /tmp", moves.len());
+    Ok(())
+}
+
+/// Run `f` against the shared connection, opening the database on first use
+fn with_index<T>(f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let index = INDEX.get_or_init(|| match open() {
+        Ok(conn) => Some(Mutex::new(conn)),
+        Err(e) => {
+            eprintln!("⚠️  Library index unavailable, falling back to directory scans: {e}");
+            None
+        }
+    });
+    let conn = index.as_ref().ok_or_else(|| "Library index unavailable".to_string())?;
+    let mut conn = conn.lock().unwrap();
+    f(&mut conn).map_err(|e| format!("Library index error: {e}"))
+}
+
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn from_millis(ms: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(ms.max(0) as u64)
+}
+
+fn upsert_entry(conn: &Connection, entry: &LibraryEntry) -> rusqlite::Result<()> {
+    let sidecar = entry.sidecar.as_ref();
+    let upload_state = sidecar
+        .and_then(|s| serde_json::to_value(s.upload_state).ok())
+        .and_then(|v| v.as_str().map(str::to_string));
+    let calendar_event = sidecar.and_then(|s| s.calendar_event.as_ref());
+    conn.execute(
+        "INSERT INTO recordings (path, file_name, size_bytes, modified_ms, recorded_at,
+             source_name, upload_state, file_id, calendar_event_id, calendar_title,
+             segment_group, notes, transcript, sidecar)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+         ON CONFLICT(path) DO UPDATE SET
+             file_name = excluded.file_name, size_bytes = excluded.size_bytes,
+             modified_ms = excluded.modified_ms, recorded_at = excluded.recorded_at,
+             source_name = excluded.source_name, upload_state = excluded.upload_state,
+             file_id = excluded.file_id, calendar_event_id = excluded.calendar_event_id,
+             calendar_title = excluded.calendar_title, segment_group = excluded.segment_group, notes = excluded.notes,
+             transcript = excluded.transcript, sidecar = excluded.sidecar",
+        params![
+            entry.path,
+            entry.file_name,
+            entry.size_bytes as i64,
+            to_millis(entry.modified),
+            sidecar.map(|s| s.recorded_at.as_str()),
+            sidecar.map_or("", |s| s.source_name.as_str()),
+            upload_state,
+            sidecar.and_then(|s| s.file_id.as_deref()),
+            calendar_event.map(|e| e.event_id.as_str()),
+            calendar_event.map_or("", |e| e.title.as_str()),
+            sidecar.and_then(|s| s.segment.as_ref()).map(|s| s.group.as_str()),
+            sidecar.map_or("", |s| s.notes.as_str()),
+            sidecar.and_then(|s| s.transcript.as_deref()).unwrap_or(""),
+            sidecar.and_then(|s| serde_json::to_string(s).ok()),
+        ],
+    )?;
+
+    conn.execute("DELETE FROM bookmarks WHERE recording_path = ?1", params![entry.path])?;
+    let mut insert = conn.prepare_cached(
+        "INSERT INTO bookmarks (recording_path, offset_seconds) VALUES (?1, ?2)",
+    )?;
+    for offset in sidecar.map(|s| s.bookmarks.as_slice()).unwrap_or_default() {
+        insert.execute(params![entry.path, offset])?;
+    }
+
+    conn.execute("DELETE FROM tags WHERE recording_path = ?1", params![entry.path])?;
+    let mut insert = conn.prepare_cached(
+        "INSERT OR IGNORE INTO tags (recording_path, tag) VALUES (?1, ?2)",
+    )?;
+    for tag in sidecar.map(|s| s.tags.as_slice()).unwrap_or_default() {
+        insert.execute(params![entry.path, tag])?;
+    }
+    Ok(())
+}
+
+/// Record the current state of a recording and its sidecar
+///
+/// Called on every sidecar save, which may happen before the recording file itself
+/// exists; such entries show up with a size of zero until the next update.
+pub fn upsert(path: &str, sidecar: &RecordingSidecar) -> Result<(), String> {
+    let metadata = std::fs::metadata(path).ok();
+    let entry = LibraryEntry {
+        path: path.to_string(),
+        file_name: Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        size_bytes: metadata.as_ref().map_or(0, std::fs::Metadata::len),
+        modified: metadata
+            .and_then(|m| m.modified().ok())
+            .unwrap_or_else(SystemTime::now),
+        sidecar: Some(sidecar.clone()),
+    };
+    with_index(|conn| {
+        let tx = conn.transaction()?;
+        upsert_entry(&tx, &entry)?;
+        tx.commit()
+    })
+}
+
+/// Forget a recording that was deleted from disk
+pub fn remove(path: &str) -> Result<(), String> {
+    with_index(|conn| conn.execute("DELETE FROM recordings WHERE path = ?1", params![path]))
+        .map(|_| ())
+}
+
+/// Replace the index contents with `entries`, as found by a directory scan
+pub fn sync(entries: &[LibraryEntry]) -> Result<(), String> {
+    with_index(|conn| {
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM recordings", [])?;
+        for entry in entries {
+            upsert_entry(&tx, entry)?;
+        }
+        tx.commit()
+    })
+}
+
+fn query_entries(
+    conn: &Connection,
+    filter: &str,
+    params: impl rusqlite::Params,
+) -> rusqlite::Result<Vec<LibraryEntry>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT path, file_name, size_bytes, modified_ms, sidecar
+         FROM recordings {filter} ORDER BY modified_ms DESC"
+    ))?;
+    let rows = stmt.query_map(params, |row| {
+        let sidecar: Option<String> = row.get(4)?;
+        Ok(LibraryEntry {
+            path: row.get(0)?,
+            file_name: row.get(1)?,
+            size_bytes: row.get::<_, i64>(2)?.max(0) as u64,
+            modified: from_millis(row.get(3)?),
+            sidecar: sidecar.and_then(|json| serde_json::from_str(&json).ok()),
+        })
+    })?;
+    rows.collect()
+}
+
+/// All indexed recordings, newest first
+pub fn recordings() -> Result<Vec<LibraryEntry>, String> {
+    with_index(|conn| query_entries(conn, "", []))
+}
+
+/// Recordings whose file name, source, calendar event, notes, transcript or tags
+/// contain `query` (case-insensitive), newest first
+pub fn search(query: &str) -> Result<Vec<LibraryEntry>, String> {
+    let pattern = format!(
+        "%{}%",
+        query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+    );
+    with_index(|conn| {
+        query_entries(
+            conn,
+            "WHERE file_name LIKE ?1 ESCAPE '\\'
+                OR source_name LIKE ?1 ESCAPE '\\'
+                OR calendar_title LIKE ?1 ESCAPE '\\'
+                OR notes LIKE ?1 ESCAPE '\\'
+                OR transcript LIKE ?1 ESCAPE '\\'
+                OR EXISTS (SELECT 1 FROM tags
+                           WHERE tags.recording_path = recordings.path
+                             AND tags.tag LIKE ?1 ESCAPE '\\')",
+            params![pattern],
+        )
+    })
+}
@@ -0,0 +1,329 @@
+//! Live MJPEG preview over HTTP
+//!
+//! Serves downscaled captured frames so a producer can watch what is being recorded
+//! from a browser, on this Mac or (when allowed) from another machine on the network.
+//! The stream is a `multipart/x-mixed-replace` MJPEG feed, which every browser shows
+//! in a plain `<img>` tag. URLs carry a random token, so only people who were given
+//! the link can watch.
+//!
+//! Frames are JPEG-encoded by Core Image straight from the capture's IOSurface, one
+//! thread per connected viewer (up to [`lan_server::MAX_CLIENTS`]); nothing is
+//! encoded while nobody is watching.
+
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSString;
+use objc::rc::autoreleasepool;
+use objc::{class, msg_send, sel, sel_impl};
+use screencapturekit::output::IOSurface;
+use serde::{Deserialize, Serialize};
+use std::ffi::c_void;
+use std::io::Write;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::capture::CaptureState;
+use crate::lan_server;
+use crate::user_data;
+
+const BOUNDARY: &str = "talkaframe";
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const JPEG_QUALITY: f64 = 0.6;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LivePreviewSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Listen on all interfaces instead of only this Mac
+    pub allow_network: bool,
+    /// Frames are scaled down to at most this width
+    pub max_width: u32,
+    pub fps: u32,
+    /// Secret path component of every URL
+    pub token: String,
+}
+
+impl Default for LivePreviewSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8089,
+            allow_network: false,
+            max_width: 960,
+            fps: 4,
+            token: String::new(),
+        }
+    }
+}
+
+impl LivePreviewSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("live_preview.json")
+    }
+
+    /// Load the settings, generating the access token on first use
+    pub fn load() -> Self {
+        let mut settings: Self = std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        if settings.token.is_empty() {
+            match lan_server::new_token() {
+                Ok(token) => {
+                    settings.token = token;
+                    let _ = settings.save();
+                }
+                Err(e) => eprintln!("⚠️  {}", e),
+            }
+        }
+        settings
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize live preview settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save live preview settings: {e}"))
+    }
+
+    /// Address viewers should open
+    pub fn viewer_url(&self) -> String {
+        let host = if self.allow_network {
+            lan_server::local_host_name().map_or_else(|| "localhost".to_string(), |name| format!("{name}.local"))
+        } else {
+            "localhost".to_string()
+        };
+        format!("http://{host}:{}/{}/", self.port, self.token)
+    }
+}
+
+struct Server {
+    settings: LivePreviewSettings,
+    stop: Arc<AtomicBool>,
+    /// Accept loop, which owns the listening socket
+    thread: JoinHandle<()>,
+}
+
+impl Server {
+    /// Stop accepting, and wait until the port is free to listen on again
+    fn shut_down(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.thread.join();
+    }
+}
+
+static SERVER: Mutex<Option<Server>> = Mutex::new(None);
+
+/// Start, restart or stop the server to match `settings`
+pub fn apply(settings: &LivePreviewSettings, capture_state: &Arc<CaptureState>) -> Result<(), String> {
+    let mut server = SERVER.lock().unwrap();
+    if server.as_ref().is_some_and(|s| s.settings == *settings) && settings.enabled {
+        return Ok(());
+    }
+    if let Some(previous) = server.take() {
+        previous.shut_down();
+    }
+    if !settings.enabled {
+        return Ok(());
+    }
+    if settings.token.is_empty() {
+        return Err("Live preview has no access token".to_string());
+    }
+
+    let ip = if settings.allow_network { [0, 0, 0, 0] } else { [127, 0, 0, 1] };
+    let addr = SocketAddr::from((ip, settings.port));
+    let listener = TcpListener::bind(addr).map_err(|e| format!("Cannot listen on port {}: {e}", settings.port))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure preview server: {e}"))?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_clone = Arc::clone(&stop);
+    let settings_clone = settings.clone();
+    let capture_state = Arc::clone(capture_state);
+    let thread = std::thread::spawn(move || accept_loop(&listener, &settings_clone, &capture_state, &stop_clone));
+
+    println!("📡 Live preview at {}", settings.viewer_url());
+    *server = Some(Server {
+        settings: settings.clone(),
+        stop,
+        thread,
+    });
+    Ok(())
+}
+
+fn accept_loop(
+    listener: &TcpListener,
+    settings: &LivePreviewSettings,
+    capture_state: &Arc<CaptureState>,
+    stop: &Arc<AtomicBool>,
+) {
+    let clients = lan_server::Clients::default();
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((mut stream, peer)) => {
+                let Some(slot) = clients.admit() else {
+                    let _ = stream.write_all(lan_server::BUSY_RESPONSE.as_bytes());
+                    continue;
+                };
+                let settings = settings.clone();
+                let capture_state = Arc::clone(capture_state);
+                let stop = Arc::clone(stop);
+                std::thread::spawn(move || {
+                    let _slot = slot;
+                    if let Err(e) = handle_client(stream, &settings, &capture_state, &stop) {
+                        println!("📡 Preview viewer {} disconnected: {}", peer, e);
+                    }
+                });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+            Err(e) => eprintln!("⚠️  Preview server accept failed: {}", e),
+        }
+    }
+    println!("📡 Live preview stopped");
+}
+
+fn handle_client(
+    mut stream: TcpStream,
+    settings: &LivePreviewSettings,
+    capture_state: &Arc<CaptureState>,
+    stop: &Arc<AtomicBool>,
+) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let Some((_, path)) = lan_server::read_request(&stream)? else {
+        return Ok(());
+    };
+    let base = format!("/{}/", settings.token);
+
+    if path == base {
+        let page = format!(
+            "<!doctype html><title>Talka live preview</title>\
+             <body style=\"margin:0;background:#111;display:flex;align-items:center;justify-content:center;height:100vh\">\
+             <img src=\"{base}stream.mjpg\" style=\"max-width:100%;max-height:100%\"></body>"
+        );
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+            page.len()
+        )?;
+        return Ok(());
+    }
+    if path != format!("{base}stream.mjpg") {
+        // Same answer for wrong tokens and unknown paths
+        write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")?;
+        return Ok(());
+    }
+
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: multipart/x-mixed-replace; boundary={BOUNDARY}\r\n\
+         Cache-Control: no-cache\r\nConnection: close\r\n\r\n"
+    )?;
+    let frame_interval = Duration::from_millis(1000 / u64::from(settings.fps.clamp(1, 30)));
+    let encoder = JpegEncoder::new();
+    while !stop.load(Ordering::Relaxed) {
+        let surface = capture_state.latest_surface.lock().unwrap().clone();
+        if let Some(jpeg) = surface.and_then(|s| encoder.encode(s.as_ptr(), s.width(), settings.max_width)) {
+            write!(
+                stream,
+                "--{BOUNDARY}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+                jpeg.len()
+            )?;
+            stream.write_all(&jpeg)?;
+            stream.write_all(b"\r\n")?;
+        }
+        std::thread::sleep(frame_interval);
+    }
+    Ok(())
+}
+
+/// One JPEG of `surface`, at most `max_width` pixels wide
+pub fn snapshot_jpeg(surface: &IOSurface, max_width: u32) -> Option<Vec<u8>> {
+    JpegEncoder::new().encode(surface.as_ptr(), surface.width(), max_width)
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CGAffineTransform {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+    tx: f64,
+    ty: f64,
+}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGColorSpaceCreateDeviceRGB() -> *mut c_void;
+    fn CGColorSpaceRelease(space: *mut c_void);
+}
+
+/// Core Image context and color space reused across a viewer's frames
+struct JpegEncoder {
+    context: id,
+    color_space: *mut c_void,
+}
+
+impl JpegEncoder {
+    fn new() -> Self {
+        unsafe {
+            let context: id = msg_send![class!(CIContext), contextWithOptions: nil];
+            let context: id = msg_send![context, retain];
+            Self {
+                context,
+                color_space: CGColorSpaceCreateDeviceRGB(),
+            }
+        }
+    }
+
+    /// JPEG of the IOSurface, scaled down to at most `max_width` pixels wide
+    fn encode(&self, surface: *const c_void, width: usize, max_width: u32) -> Option<Vec<u8>> {
+        if width == 0 {
+            return None;
+        }
+        let scale = (f64::from(max_width) / width as f64).min(1.0);
+        autoreleasepool(|| unsafe {
+            let image: id = msg_send![class!(CIImage), imageWithIOSurface: surface];
+            if image == nil {
+                return None;
+            }
+            let transform = CGAffineTransform { a: scale, b: 0.0, c: 0.0, d: scale, tx: 0.0, ty: 0.0 };
+            let scaled: id = msg_send![image, imageByApplyingTransform: transform];
+
+            let quality_key = NSString::alloc(nil).init_str("kCGImageDestinationLossyCompressionQuality");
+            let quality: id = msg_send![class!(NSNumber), numberWithDouble: JPEG_QUALITY];
+            let options: id = msg_send![class!(NSDictionary), dictionaryWithObject: quality forKey: quality_key];
+            let _: () = msg_send![quality_key, release];
+
+            let data: id = msg_send![self.context,
+                JPEGRepresentationOfImage: scaled
+                colorSpace: self.color_space
+                options: options
+            ];
+            if data == nil {
+                return None;
+            }
+            let bytes: *const u8 = msg_send![data, bytes];
+            let length: usize = msg_send![data, length];
+            Some(std::slice::from_raw_parts(bytes, length).to_vec())
+        })
+    }
+}
+
+impl Drop for JpegEncoder {
+    fn drop(&mut self) {
+        unsafe {
+            let _: () = msg_send![self.context, release];
+            CGColorSpaceRelease(self.color_space);
+        }
+    }
+}
@@ -17,6 +17,12 @@
 //!
 //! # With recording support (macOS 15.0+)
 //! cargo run --example 16_full_metal_app --features macos_15_0
+//!
+//! # With a synthetic test pattern instead of real capture
+//! cargo run --example 16_full_metal_app --features macos_15_0 -- --mock-capture
+//!
+//! # Record a few seconds of the test pattern without a window (CI)
+//! cargo run --example 16_full_metal_app --features macos_15_0 -- mock-selftest
 //! ```
 
 #![allow(
@@ -27,19 +33,81 @@
     clippy::cast_possible_truncation
 )]
 
+mod annotations;
+mod app_audio;
+mod archive;
+mod attachments;
+mod audio_tracks;
 mod auth;
+mod backup;
+mod calendar_privacy;
+mod call_detect;
 mod capture;
+mod capture_settings;
+mod clipboard;
+mod crop;
+#[cfg(feature = "macos_15_0")]
+mod cleanup;
+mod deep_link;
+mod desktop;
+mod doctor;
+mod focus;
 mod font;
+mod frame_health;
+mod gpu;
+mod highlights;
+mod hotkey;
+mod idle;
 mod input;
+mod journal;
+mod lan_server;
+mod library;
+mod library_index;
+mod live_preview;
+mod media;
+mod meeting_summary;
+mod menu_bar;
+mod naming;
+#[cfg(feature = "macos_15_0")]
+mod network;
+mod mock_capture;
 mod overlay;
+mod pin;
+mod pipeline;
+mod power;
+mod preflight;
 mod preview_window;
 #[cfg(feature = "macos_15_0")]
+mod presenter;
+#[cfg(feature = "macos_15_0")]
+mod profiles;
+#[cfg(feature = "macos_15_0")]
 mod recording;
+mod recording_timer;
+mod remote;
+mod remote_config;
 mod renderer;
+mod screen_access;
+mod screens;
 mod screenshot;
+mod session;
+mod settings_bundle;
+mod sidecar;
+mod standby;
+mod talka_api;
+mod thermal;
+mod time_format;
+mod timeline;
+mod toast;
 mod ui;
+mod updater;
+mod webhook;
 #[cfg(feature = "macos_15_0")]
 mod upload;
+#[cfg(feature = "macos_15_0")]
+mod upload_queue;
+mod upload_state;
+mod user_data;
 mod vertex;
 mod waveform;
 mod dioxus_ui;
@@ -51,16 +119,21 @@ use std::sync::{Arc, Mutex};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::thread;
 use tokio::runtime::Runtime;
+use tokio::sync::watch;
 
 use screencapturekit::prelude::*;
 
 use capture::CaptureState;
-use input::{format_picked_source, PickerResult};
-use overlay::default_stream_config;
+use capture_settings::CaptureSettings;
+use crop::CropSettings;
+use input::{format_picked_source, PickedSource, PickerEvent};
+use media::TrimSession;
+use preview_window::{PreviewManager, PreviewSource, PreviewStyle};
 use dioxus_ui::CaptureCommand;
+use upload_state::{UploadPhase, UploadState};
 
 #[cfg(feature = "macos_15_0")]
-use recording::{RecordingConfig, RecordingState};
+use recording::RecordingState;
 
 #[derive(Clone, Debug, PartialEq)]
 enum AuthState {
@@ -72,6 +145,15 @@ enum AuthState {
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("doctor") => std::process::exit(doctor::run_cli()),
+        Some("mock-selftest") => std::process::exit(mock_capture::run_selftest()),
+        Some(command @ ("export-settings" | "import-settings")) => {
+            std::process::exit(settings_bundle::run_cli(command, args.get(2).map(String::as_str)))
+        }
+        _ => {}
+    }
     run_app();
 }
 
@@ -88,8 +170,10 @@ fn run_app() {
     let is_recording = Arc::new(AtomicBool::new(false));
     let source_name = Arc::new(Mutex::new(String::from("No source selected")));
     let auth_state_shared: Arc<Mutex<AuthState>> = Arc::new(Mutex::new(AuthState::Checking));
-    let upload_status_str = Arc::new(Mutex::new(String::from("")));
-    let uploaded_file_id = Arc::new(Mutex::new(String::new()));
+    let upload_state = Arc::new(watch::Sender::new(UploadState::default()));
+    let trim_session: Arc<Mutex<Option<TrimSession>>> = Arc::new(Mutex::new(None));
+    let recording_path = Arc::new(Mutex::new(String::new()));
+    let uploaded_path = Arc::new(Mutex::new(String::new()));
     
     // Shared auth tokens for upload
     let auth_tokens_shared: Arc<Mutex<Option<auth::AuthTokens>>> = Arc::new(Mutex::new(None));
@@ -103,9 +187,14 @@ fn run_app() {
     runtime_handle.spawn(async move {
         match authenticate_user_with_ui(&auth_state_clone).await {
             Ok((tokens, profile)) => {
+                let access_token = tokens.access_token.clone();
                 *auth_state_clone.lock().unwrap() = AuthState::Authenticated { profile };
                 *auth_tokens_clone.lock().unwrap() = Some(tokens);
                 println!("✅ Authenticated successfully");
+                // Titles fall back to the cached convention until this lands
+                if let Err(e) = naming::refresh(&access_token).await {
+                    eprintln!("⚠️  {}", e);
+                }
             }
             Err(e) => {
                 *auth_state_clone.lock().unwrap() = AuthState::Error(e.clone());
@@ -116,13 +205,27 @@ fn run_app() {
 
     // Create capture state
     let capture_state = Arc::new(CaptureState::new());
+    if let Err(e) = live_preview::apply(&live_preview::LivePreviewSettings::load(), &capture_state) {
+        eprintln!("⚠️  Live preview unavailable: {}", e);
+    }
+    let remote_controls = remote::Controls {
+        commands: cmd_tx.clone(),
+        is_recording: Arc::clone(&is_recording),
+        capture_state: Arc::clone(&capture_state),
+    };
+    if let Err(e) = remote::apply(&remote::RemoteSettings::load(), &remote_controls) {
+        eprintln!("⚠️  Remote control unavailable: {}", e);
+    }
     
     // Start capture backend thread
     let is_capturing_clone = Arc::clone(&is_capturing);
     let is_recording_clone = Arc::clone(&is_recording);
     let source_name_clone = Arc::clone(&source_name);
-    let upload_status_clone = Arc::clone(&upload_status_str);
-    let uploaded_file_id_clone = Arc::clone(&uploaded_file_id);
+    let upload_state_backend = Arc::clone(&upload_state);
+    let trim_session_backend = Arc::clone(&trim_session);
+    let recording_path_backend = Arc::clone(&recording_path);
+    let uploaded_path_backend = Arc::clone(&uploaded_path);
+    let meeting_events_backend = Arc::clone(&meeting_events_shared);
     let capture_state_backend = Arc::clone(&capture_state);
     let auth_tokens_backend = Arc::clone(&auth_tokens_shared);
     let runtime_handle_capture = runtime_handle.clone();
@@ -133,14 +236,38 @@ fn run_app() {
             is_capturing_clone,
             is_recording_clone,
             source_name_clone,
-            upload_status_clone,
-            uploaded_file_id_clone,
+            upload_state_backend,
+            trim_session_backend,
+            recording_path_backend,
+            uploaded_path_backend,
             runtime_handle_capture,
             capture_state_backend,
             auth_tokens_backend,
+            meeting_events_backend,
         );
     });
 
+    // Bring back a recording that was still waiting to be uploaded when the app last
+    // quit, without holding up the window; the session keeps pointing at it meanwhile
+    #[cfg(feature = "macos_15_0")]
+    {
+        session::set_restoring(true);
+        let trim_session = Arc::clone(&trim_session);
+        std::thread::spawn(move || {
+            if let Some(pending) = session::pending_upload() {
+                match TrimSession::open(pending.path.clone()) {
+                    Ok(session) => {
+                        println!("♻️  Restored recording waiting for upload: {}", pending.path);
+                        *trim_session.lock().unwrap() = Some(session);
+                        media::load_thumbnails(&trim_session);
+                    }
+                    Err(e) => eprintln!("⚠️  Could not restore {}: {}", pending.path, e),
+                }
+            }
+            session::set_restoring(false);
+        });
+    }
+
     // Store state in static globals for the Dioxus app
     unsafe {
         GLOBAL_CMD_TX = Some(cmd_tx);
@@ -148,13 +275,23 @@ fn run_app() {
         GLOBAL_IS_RECORDING = Some(is_recording);
         GLOBAL_SOURCE_NAME = Some(source_name);
         GLOBAL_AUTH_STATE = Some(auth_state_shared.clone());
-        GLOBAL_UPLOAD_STATUS = Some(upload_status_str);
-        GLOBAL_UPLOADED_FILE_ID = Some(uploaded_file_id);
+        GLOBAL_UPLOAD_STATE = Some(upload_state);
         GLOBAL_CAPTURE_STATE = Some(capture_state);
         GLOBAL_MEETING_EVENTS = Some(meeting_events_shared.clone());
         GLOBAL_AUTH_TOKENS = Some(auth_tokens_shared.clone());
+        GLOBAL_TRIM_SESSION = Some(trim_session);
+        GLOBAL_RECORDING_PATH = Some(recording_path);
+        GLOBAL_UPLOADED_PATH = Some(uploaded_path);
     }
     
+    // Pick up server-side flags and limits; the cached copy is used until this lands
+    runtime_handle.spawn(async move {
+        match remote_config::refresh().await {
+            Ok(()) => println!("🛰️  Remote config updated"),
+            Err(e) => eprintln!("⚠️  {}", e),
+        }
+    });
+
     // Start meeting events fetching in background
     let meeting_events_clone = Arc::clone(&meeting_events_shared);
     let auth_tokens_fetch = Arc::clone(&auth_tokens_shared);
@@ -172,19 +309,21 @@ fn run_app() {
         };
         
         if let Some(token) = access_token {
-            match auth::get_meeting_events(&token).await {
-                Ok(events) => {
-                    *meeting_events_initial.lock().unwrap() = events;
-                }
-                Err(_) => {}
+            let range = auth::EventRange::calendar();
+            if let Ok(Some(events)) = auth::get_meeting_events(&token, range).await {
+                auth::merge_meeting_events(&mut meeting_events_initial.lock().unwrap(), events, range);
             }
         }
     });
     
-    // Periodic refresh every 5 minutes
+    // Refresh upcoming meetings every 5 minutes, and the whole calendar hourly;
+    // when the API rate limits, wait as long as it asks before polling again
     runtime_handle_events.spawn(async move {
-        loop {
-            tokio::time::sleep(tokio::time::Duration::from_secs(300)).await;
+        let interval = tokio::time::Duration::from_secs(300);
+        let mut delay = interval;
+        for refresh in 1u64.. {
+            tokio::time::sleep(delay).await;
+            delay = interval;
             
             let access_token = {
                 let guard = auth_tokens_fetch.lock().unwrap();
@@ -192,22 +331,117 @@ fn run_app() {
             };
             
             if let Some(token) = access_token {
-                match auth::get_meeting_events(&token).await {
-                    Ok(events) => {
-                        *meeting_events_clone.lock().unwrap() = events;
+                let range = if refresh % 12 == 0 {
+                    auth::EventRange::calendar()
+                } else {
+                    auth::EventRange::upcoming()
+                };
+                match auth::get_meeting_events(&token, range).await {
+                    Ok(Some(events)) => {
+                        auth::merge_meeting_events(&mut meeting_events_clone.lock().unwrap(), events, range);
                     }
+                    Ok(None) => {}
+                    Err(auth::AuthError::RateLimited(wait)) => delay = delay.max(wait),
                     Err(_) => {}
                 }
             }
         }
     });
 
+    // Periodically reclaim disk space from recordings that are safely uploaded
+    #[cfg(feature = "macos_15_0")]
+    runtime_handle.spawn(async move {
+        loop {
+            let policy = cleanup::CleanupPolicy::load();
+            if let Ok(report) = tokio::task::spawn_blocking(move || {
+                library::refresh_index();
+                cleanup::run_cleanup(&policy)
+            })
+            .await
+            {
+                for (path, size) in &report.removed {
+                    println!("🧹 Removed uploaded recording: {} ({} MB)", path, size / (1024 * 1024));
+                }
+                println!("🧹 Storage cleanup: {}", report);
+            }
+            tokio::time::sleep(cleanup::CLEANUP_INTERVAL).await;
+        }
+    });
+
+    // Upload queued recordings once the connection allows
+    #[cfg(feature = "macos_15_0")]
+    {
+        let auth_tokens_queue = Arc::clone(&auth_tokens_shared);
+        runtime_handle.spawn(async move {
+            loop {
+                upload_queue::wait_for_next_run().await;
+                if let Some(token) = upload_queue::access_token(&auth_tokens_queue).await {
+                    upload_queue::drain(&token).await;
+                }
+            }
+        });
+    }
+
+    // Mirror finished recordings into the backup folder, if one is set
+    runtime_handle.spawn(async move {
+        loop {
+            let _ = tokio::task::spawn_blocking(backup::sync_completed).await;
+            tokio::time::sleep(backup::SYNC_INTERVAL).await;
+        }
+    });
+
+    // Find out straight away whether Screen Recording is allowed, rather than when
+    // the picker fails
+    runtime_handle.spawn(async move {
+        let _ = tokio::task::spawn_blocking(screen_access::check).await;
+    });
+
+    // Watch for calls being joined so recording can be offered
+    runtime_handle.spawn(async move {
+        loop {
+            match tokio::task::spawn_blocking(call_detect::detect).await {
+                Ok(Ok(detected)) => call_detect::update(detected),
+                Ok(Err(e)) => eprintln!("⚠️  Call detection failed: {}", e),
+                Err(_) => {}
+            }
+            tokio::time::sleep(call_detect::POLL_INTERVAL).await;
+        }
+    });
+
+    // Check for signed updates in the background when automatic updates are on
+    runtime_handle.spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+        loop {
+            let ready = matches!(updater::state(), updater::UpdateState::Ready { .. });
+            if updater::UpdateSettings::load().automatic && !ready {
+                updater::check_and_download().await;
+            }
+            tokio::time::sleep(updater::CHECK_INTERVAL).await;
+        }
+    });
+
     // Launch Dioxus UI with custom window config
     let config = Config::new()
         .with_window(WindowBuilder::new()
             .with_title("Talka Recorder")
             .with_resizable(false)
-            .with_inner_size(dioxus::desktop::wry::dpi::LogicalSize::new(320.0, 440.0)));
+            .with_inner_size(dioxus::desktop::wry::dpi::LogicalSize::new(320.0, 440.0)))
+        // Registered on the event loop itself so the link that launched the app is
+        // not lost before the window mounts
+        .with_custom_event_handler(|event, _| {
+            match event {
+                dioxus::desktop::tao::event::Event::Opened { urls } => {
+                    deep_link::queue(urls.iter().map(|url| url.to_string()));
+                }
+                // Leave no Focus mode behind when the app quits mid-recording, and
+                // write the session changes still waiting to settle
+                dioxus::desktop::tao::event::Event::LoopDestroyed => {
+                    focus::restore_now();
+                    session::flush();
+                }
+                _ => {}
+            }
+        });
     
     dioxus::LaunchBuilder::desktop()
         .with_cfg(config)
@@ -220,11 +454,13 @@ static mut GLOBAL_IS_CAPTURING: Option<Arc<AtomicBool>> = None;
 static mut GLOBAL_IS_RECORDING: Option<Arc<AtomicBool>> = None;
 static mut GLOBAL_SOURCE_NAME: Option<Arc<Mutex<String>>> = None;
 static mut GLOBAL_AUTH_STATE: Option<Arc<Mutex<AuthState>>> = None;
-static mut GLOBAL_UPLOAD_STATUS: Option<Arc<Mutex<String>>> = None;
-static mut GLOBAL_UPLOADED_FILE_ID: Option<Arc<Mutex<String>>> = None;
+static mut GLOBAL_UPLOAD_STATE: Option<Arc<watch::Sender<UploadState>>> = None;
 static mut GLOBAL_CAPTURE_STATE: Option<Arc<CaptureState>> = None;
 static mut GLOBAL_MEETING_EVENTS: Option<Arc<Mutex<Vec<auth::MeetingEvent>>>> = None;
 static mut GLOBAL_AUTH_TOKENS: Option<Arc<Mutex<Option<auth::AuthTokens>>>> = None;
+static mut GLOBAL_TRIM_SESSION: Option<Arc<Mutex<Option<TrimSession>>>> = None;
+static mut GLOBAL_RECORDING_PATH: Option<Arc<Mutex<String>>> = None;
+static mut GLOBAL_UPLOADED_PATH: Option<Arc<Mutex<String>>> = None;
 
 fn get_global_state() -> (
     Option<Sender<CaptureCommand>>,
@@ -232,8 +468,6 @@ fn get_global_state() -> (
     Arc<AtomicBool>,
     Arc<Mutex<String>>,
     Arc<Mutex<AuthState>>,
-    Arc<Mutex<String>>,
-    Arc<Mutex<String>>,
     Arc<CaptureState>,
     Arc<Mutex<Vec<auth::MeetingEvent>>>,
     Arc<Mutex<Option<auth::AuthTokens>>>,
@@ -245,8 +479,6 @@ fn get_global_state() -> (
             GLOBAL_IS_RECORDING.clone().unwrap(),
             GLOBAL_SOURCE_NAME.clone().unwrap(),
             GLOBAL_AUTH_STATE.clone().unwrap(),
-            GLOBAL_UPLOAD_STATUS.clone().unwrap(),
-            GLOBAL_UPLOADED_FILE_ID.clone().unwrap(),
             GLOBAL_CAPTURE_STATE.clone().unwrap(),
             GLOBAL_MEETING_EVENTS.clone().unwrap(),
             GLOBAL_AUTH_TOKENS.clone().unwrap(),
@@ -254,55 +486,215 @@ fn get_global_state() -> (
     }
 }
 
+/// Recording waiting in the trim editor, if any
+fn get_trim_session() -> Arc<Mutex<Option<TrimSession>>> {
+    unsafe { GLOBAL_TRIM_SESSION.clone().unwrap() }
+}
+
+/// File the active recording is being written to (empty when not recording)
+fn get_recording_path() -> Arc<Mutex<String>> {
+    unsafe { GLOBAL_RECORDING_PATH.clone().unwrap() }
+}
+
+/// Local file of the recording shown in the upload status card
+fn get_uploaded_path() -> Arc<Mutex<String>> {
+    unsafe { GLOBAL_UPLOADED_PATH.clone().unwrap() }
+}
+
+/// The upload shown in the status card, published by the capture backend
+fn get_upload_state() -> Arc<watch::Sender<UploadState>> {
+    unsafe { GLOBAL_UPLOAD_STATE.clone().unwrap() }
+}
+
 fn app_with_backend() -> Element {
-    let (_cmd_tx, is_capturing, is_recording, source_name, auth_state, upload_status, uploaded_file_id, _capture_state, meeting_events, auth_tokens) = get_global_state();
+    let (_cmd_tx, is_capturing, is_recording, source_name, auth_state, _capture_state, meeting_events, auth_tokens) = get_global_state();
 
     let mut is_capturing_sig = use_signal(|| is_capturing.load(Ordering::Relaxed));
     let mut is_recording_sig = use_signal(|| is_recording.load(Ordering::Relaxed));
     let mut source_name_sig = use_signal(|| source_name.lock().unwrap().clone());
     let mut auth_state_sig = use_signal(|| auth_state.lock().unwrap().clone());
-    let mut upload_status_sig = use_signal(|| upload_status.lock().unwrap().clone());
-    let mut uploaded_file_id_sig = use_signal(|| uploaded_file_id.lock().unwrap().clone());
+    let mut upload_state_sig = use_signal(|| get_upload_state().borrow().clone());
     let mut frame_count_sig = use_signal(|| 0u64);
     let mut capture_info_sig = use_signal(|| String::from(""));
     let mut recording_duration_sig = use_signal(|| String::from(""));
-    let mut recording_start_time_sig = use_signal(|| None::<std::time::Instant>);
+    let mut recording_paused_sig = use_signal(|| false);
     let mut meeting_events_sig = use_signal(|| meeting_events.lock().unwrap().clone());
-    let mut show_calendar_view = use_signal(|| false);
+    // Reopen the view that was open when the app last quit
+    let restored_view = session::current().view;
+    let mut show_calendar_view = use_signal(|| restored_view == session::OpenView::Calendar);
+    let mut show_library_view = use_signal(|| restored_view == session::OpenView::Library);
+    let mut show_settings_view = use_signal(|| restored_view == session::OpenView::Settings);
+    let mut trim_session_sig = use_signal(|| get_trim_session().lock().unwrap().clone());
+    let mut recording_path_sig = use_signal(|| get_recording_path().lock().unwrap().clone());
+    let mut uploaded_path_sig = use_signal(|| get_uploaded_path().lock().unwrap().clone());
+    let mut call_prompt_sig = use_signal(call_detect::prompt);
+    let mut thermal_warning_sig = use_signal(thermal::under_pressure);
+    let mut overload_warning_sig = use_signal(frame_health::overloaded);
+    let mut toast_sig = use_signal(toast::current);
+
+    // A `record` link waiting for the user to confirm it, with the source it names
+    let mut record_link_sig = use_signal(|| None::<Option<deep_link::LinkSource>>);
+
+    // Follow the upload as the backend publishes it
+    use_future(move || async move {
+        let mut updates = get_upload_state().subscribe();
+        while updates.changed().await.is_ok() {
+            let next = updates.borrow_and_update().clone();
+            upload_state_sig.set(next);
+        }
+    });
 
     // Poll for updates every 100ms
     use_future(move || async move {
+        let mut menu_bar_title: Option<Option<String>> = None;
+        let mut copied_snapshot: Option<String> = None;
+        let mut last_console_check = std::time::Instant::now();
+        // Another user is switched in, and whether capture was stopped for them
+        let mut switched_out = false;
+        let mut stopped_for_switch = false;
+        hotkey::register();
         loop {
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            let (_, is_cap, is_rec, src_name, auth, upl, file_id, cap_state, mtg_events, _) = get_global_state();
+            let (cmd_tx, is_cap, is_rec, src_name, auth, cap_state, mtg_events, _) = get_global_state();
             let was_recording = *is_recording_sig.read();
             let is_recording_now = is_rec.load(Ordering::Relaxed);
+            if hotkey::take_bookmark_press() && is_recording_now {
+                if let Some(ref sender) = cmd_tx {
+                    let _ = sender.send(CaptureCommand::AddBookmark);
+                }
+            }
+            // talka-recorder:// links, including the one the app was launched with
+            for url in deep_link::take_pending() {
+                println!("🔗 Opened {}", url);
+                match deep_link::parse(&url) {
+                    Ok(deep_link::DeepLink::OpenSettings) => {
+                        show_settings_view.set(true);
+                        show_library_view.set(false);
+                        show_calendar_view.set(false);
+                    }
+                    Ok(deep_link::DeepLink::Record { source }) => record_link_sig.set(Some(source)),
+                    Ok(link) => {
+                        tokio::spawn(run_deep_link(link));
+                    }
+                    Err(e) => eprintln!("⚠️  {}", e),
+                }
+            }
+            let recorded_display = input::source_display().filter(|_| is_recording_now);
+            annotations::sync(recorded_display);
+            if hotkey::take_draw_press() {
+                annotations::toggle_drawing();
+            }
+            if hotkey::take_erase_press() {
+                annotations::clear();
+            }
+            #[cfg(feature = "macos_15_0")]
+            presenter::sync(profiles::active().filter(|_| is_recording_now).as_ref(), recorded_display);
+
+            // With fast user switching, finish the recording and let go of the screen
+            // while someone else is using the Mac
+            if last_console_check.elapsed() >= user_data::CONSOLE_CHECK_INTERVAL {
+                last_console_check = std::time::Instant::now();
+                let on_console = user_data::on_console();
+                if !on_console && !switched_out {
+                    let capturing = is_cap.load(Ordering::Relaxed);
+                    if let Some(ref sender) = cmd_tx {
+                        if is_recording_now {
+                            let _ = sender.send(CaptureCommand::StopRecording);
+                        }
+                        if capturing {
+                            let _ = sender.send(CaptureCommand::StopCapture);
+                        }
+                    }
+                    stopped_for_switch = is_recording_now || capturing;
+                    println!("👥 Another user switched in");
+                } else if on_console && switched_out && stopped_for_switch {
+                    toast::show("Capture stopped while another user was using this Mac");
+                }
+                switched_out = !on_console;
+            }
             
             is_capturing_sig.set(is_cap.load(Ordering::Relaxed));
             is_recording_sig.set(is_recording_now);
             source_name_sig.set(src_name.lock().unwrap().clone());
             auth_state_sig.set(auth.lock().unwrap().clone());
-            upload_status_sig.set(upl.lock().unwrap().clone());
-            uploaded_file_id_sig.set(file_id.lock().unwrap().clone());
-            meeting_events_sig.set(mtg_events.lock().unwrap().clone());
+            let trim_session_now = get_trim_session().lock().unwrap().clone();
+            let uploaded_path_now = get_uploaded_path().lock().unwrap().clone();
+
+            // Keep the saved session in step with what is on screen
+            let view = if *show_settings_view.read() {
+                session::OpenView::Settings
+            } else if *show_library_view.read() {
+                session::OpenView::Library
+            } else if *show_calendar_view.read() {
+                session::OpenView::Calendar
+            } else {
+                session::OpenView::Main
+            };
+            let pending_path = trim_session_now
+                .as_ref()
+                .filter(|s| s.segments.is_empty())
+                .map(|s| s.path.clone())
+                .or_else(|| Some(uploaded_path_now.clone()).filter(|p| !p.is_empty()));
+            // Left alone while the pending upload is still being reopened
+            let restoring = session::is_restoring();
+            session::update(|s| {
+                s.view = view;
+                if !restoring && s.pending_upload.as_ref().map(|p| &p.path) != pending_path.as_ref() {
+                    s.pending_upload = pending_path.map(|path| session::PendingUpload { path, note: String::new() });
+                }
+            });
+
+            if *trim_session_sig.read() != trim_session_now {
+                trim_session_sig.set(trim_session_now);
+            }
+            recording_path_sig.set(get_recording_path().lock().unwrap().clone());
+            uploaded_path_sig.set(uploaded_path_now);
+            thermal_warning_sig.set(thermal::under_pressure());
+            overload_warning_sig.set(frame_health::overloaded());
+            let call_prompt_now = call_detect::prompt();
+            if *call_prompt_sig.read() != call_prompt_now {
+                call_prompt_sig.set(call_prompt_now);
+            }
+            let meeting_events_now = mtg_events.lock().unwrap().clone();
+            if *meeting_events_sig.read() != meeting_events_now {
+                meeting_events_sig.set(meeting_events_now);
+            }
+
+            // New screenshots go to the clipboard, if the user asked for that
+            let snapshot = cap_state.last_snapshot.lock().unwrap().as_ref().map(|(path, _)| path.clone());
+            if snapshot.is_some() && snapshot != copied_snapshot {
+                if let Some(path) = snapshot.as_ref().filter(|_| clipboard::ClipboardSettings::load().copy_screenshots) {
+                    match clipboard::copy_image(path) {
+                        Ok(()) => toast::show("Screenshot copied to clipboard"),
+                        Err(e) => toast::error(e),
+                    }
+                }
+                copied_snapshot = snapshot;
+            }
+            let toast_now = toast::current();
+            if *toast_sig.read() != toast_now {
+                toast_sig.set(toast_now);
+            }
+
+            // Countdown to the next meeting in the menu bar
+            let countdown = menu_bar::countdown_title(&mtg_events.lock().unwrap());
+            if menu_bar_title.as_ref() != Some(&countdown) {
+                menu_bar::set_title(countdown.as_deref());
+                menu_bar_title = Some(countdown);
+            }
             
-            // Track recording start time
-            if is_recording_now && !was_recording {
-                recording_start_time_sig.set(Some(std::time::Instant::now()));
-            } else if !is_recording_now && was_recording {
-                recording_start_time_sig.set(None);
+            // Recording duration, from the media time written so far rather than
+            // the wall clock, so it agrees with the finished file
+            let recorded = is_recording_now.then(|| cap_state.recorded());
+            if let Some(recorded) = recorded {
+                recording_duration_sig.set(time_format::clock(recorded));
+            } else if was_recording {
                 recording_duration_sig.set(String::new());
             }
-            
-            // Update recording duration
-            if is_recording_now {
-                if let Some(start_time) = *recording_start_time_sig.read() {
-                    let elapsed = start_time.elapsed();
-                    let secs = elapsed.as_secs();
-                    let mins = secs / 60;
-                    let secs = secs % 60;
-                    recording_duration_sig.set(format!("{:02}:{:02}", mins, secs));
-                }
+            recording_timer::sync(recorded, recorded_display);
+            let paused = is_recording_now && cap_state.recording_paused.load(Ordering::Relaxed);
+            if *recording_paused_sig.read() != paused {
+                recording_paused_sig.set(paused);
             }
             
             // Update frame count and capture info
@@ -339,13 +731,27 @@ fn app_with_backend() -> Element {
                 Header { 
                     auth_state: auth_state_sig.read().clone(),
                     show_calendar_view: *show_calendar_view.read(),
+                    show_library_view: *show_library_view.read(),
+                    on_library_click: move |_| {
+                        let current = *show_library_view.read();
+                        show_library_view.set(!current);
+                        show_calendar_view.set(false);
+                        show_settings_view.set(false);
+                    },
+                    on_settings_click: move |_| {
+                        show_settings_view.set(true);
+                        show_library_view.set(false);
+                        show_calendar_view.set(false);
+                    },
                     on_calendar_click: move |_| {
                         let current = *show_calendar_view.read();
                         show_calendar_view.set(!current);
+                        show_library_view.set(false);
+                        show_settings_view.set(false);
                         
                         // Refresh meeting events when calendar is opened
                         if !current {
-                            let (_, _, _, _, _, _, _, _, mtg_events, auth_tkns) = get_global_state();
+                            let (_, _, _, _, _, _, mtg_events, auth_tkns) = get_global_state();
                             
                             let events_clone = Arc::clone(&mtg_events);
                             let tokens_clone = Arc::clone(&auth_tkns);
@@ -357,20 +763,32 @@ fn app_with_backend() -> Element {
                                 };
                                 
                                 if let Some(token) = access_token {
-                                    match auth::get_meeting_events(&token).await {
-                                        Ok(events) => {
-                                            *events_clone.lock().unwrap() = events;
-                                        }
-                                        Err(_) => {}
+                                    let range = auth::EventRange::calendar();
+                                    if let Ok(Some(events)) = auth::get_meeting_events(&token, range).await {
+                                        auth::merge_meeting_events(&mut events_clone.lock().unwrap(), events, range);
                                     }
                                 }
                             });
                         }
                     },
                 }
-                
+
+                ScreenAccessGate {}
+
                 // Calendar events view (full overlay)
-                if *show_calendar_view.read() {
+                if *show_settings_view.read() {
+                    SettingsView {
+                        on_close: move |_| {
+                            show_settings_view.set(false);
+                        },
+                    }
+                } else if *show_library_view.read() {
+                    LibraryView {
+                        on_close: move |_| {
+                            show_library_view.set(false);
+                        },
+                    }
+                } else if *show_calendar_view.read() {
                     CalendarEventsView {
                         events: meeting_events_sig.read().clone(),
                         on_close: move |_| {
@@ -382,17 +800,47 @@ fn app_with_backend() -> Element {
                     NextMeetingNotification {
                         events: meeting_events_sig.read().clone(),
                     }
+
+                    // Offer to record a call the user just joined
+                    if let Some(call) = call_prompt_sig.read().clone().filter(|_| !*is_recording_sig.read()) {
+                        CallPrompt { call }
+                    }
+
+                    // Confirm recordings asked for by a link
+                    if let Some(source) = *record_link_sig.read() {
+                        RecordLinkPrompt {
+                            source,
+                            on_done: move |_| record_link_sig.set(None),
+                        }
+                    }
                     
+                    CaptureErrorBar {}
+
+                    PickerStuckBar {}
+
+                    IdleCaptureBar {}
+
+                    SlowUploadBar {}
+
                     // Main content area - centered
                     MainContent { 
                         is_capturing: *is_capturing_sig.read(),
                         is_recording: *is_recording_sig.read(),
                         source_name: source_name_sig.read().clone(),
                         recording_duration: recording_duration_sig.read().clone(),
-                        upload_status: upload_status_sig.read().clone(),
-                        uploaded_file_id: uploaded_file_id_sig.read().clone(),
+                        recording_paused: *recording_paused_sig.read(),
+                        upload_state: upload_state_sig.read().clone(),
+                        trim_session: trim_session_sig.read().clone(),
+                        recording_path: recording_path_sig.read().clone(),
+                        uploaded_path: uploaded_path_sig.read().clone(),
+                        thermal_warning: *thermal_warning_sig.read(),
+                        overload_warning: *overload_warning_sig.read(),
                     }
                 }
+
+                if let Some(toast) = toast_sig.read().clone() {
+                    div { class: if toast.is_error { "toast toast-error" } else { "toast" }, "{toast.message}" }
+                }
             }
         }
     }
@@ -435,7 +883,7 @@ fn LoginOverlay(auth_state: AuthState) -> Element {
                                         class: "copy-btn",
                                         onclick: move |_| {
                                             // Copy to clipboard
-                                            let _ = copy_to_clipboard(&uri_clone);
+                                            copy_to_clipboard(&uri_clone);
                                         },
                                         "Copy"
                                     }
@@ -450,7 +898,7 @@ fn LoginOverlay(auth_state: AuthState) -> Element {
                                         class: "copy-btn",
                                         onclick: move |_| {
                                             // Copy to clipboard
-                                            let _ = copy_to_clipboard(&code_clone);
+                                            copy_to_clipboard(&code_clone);
                                         },
                                         "Copy"
                                     }
@@ -483,8 +931,69 @@ fn LoginOverlay(auth_state: AuthState) -> Element {
     }
 }
 
+/// Start recording `source`, or the current source, picking one first if needed, once the pre-flight checks pass
+async fn record_from(source: Option<deep_link::LinkSource>) {
+    let (tx, is_capturing, is_recording, _, _, _, _, _) = get_global_state();
+    let Some(sender) = tx else { return };
+
+    if is_recording.load(Ordering::Relaxed) {
+        println!("⏺ Already recording");
+        return;
+    }
+    match source {
+        Some(deep_link::LinkSource::Display(index)) => {
+            let _ = sender.send(CaptureCommand::SelectDisplay(index));
+        }
+        None if !is_capturing.load(Ordering::Relaxed) => {
+            let _ = sender.send(CaptureCommand::SelectSource);
+        }
+        None => {}
+    }
+
+    // Capture starts once the source is picked; give the user time to pick one
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(60);
+    while !is_capturing.load(Ordering::Relaxed) {
+        if std::time::Instant::now() > deadline {
+            eprintln!("⚠️  No source selected, not recording");
+            return;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    }
+
+    // Same checks as the Start Recording button; with problems, the user decides there
+    let report = match standby::fresh_checks() {
+        Some(report) => report,
+        None => {
+            let (_, _, _, _, _, cap_state, _, auth_tokens) = get_global_state();
+            preflight::run(cap_state, auth_tokens).await
+        }
+    };
+    let problems: Vec<String> = report.problems().map(|check| format!("{}: {}", check.name, check.detail)).collect();
+    if !problems.is_empty() {
+        toast::error(format!("Not recording yet. {}. Press Start Recording to record anyway.", problems.join("; ")));
+        return;
+    }
+    let _ = sender.send(CaptureCommand::StartRecording);
+}
+
+/// Drive the backend through the steps a `record` or `stop` link asks for
+async fn run_deep_link(link: deep_link::DeepLink) {
+    let (tx, _, is_recording, _, _, _, _, _) = get_global_state();
+    let Some(sender) = tx else { return };
+
+    match link {
+        deep_link::DeepLink::Record { source } => record_from(source).await,
+        deep_link::DeepLink::Stop => {
+            if is_recording.load(Ordering::Relaxed) {
+                let _ = sender.send(CaptureCommand::StopRecording);
+            }
+        }
+        deep_link::DeepLink::OpenSettings => {}
+    }
+}
+
 #[component]
-fn Header(auth_state: AuthState, show_calendar_view: bool, on_calendar_click: EventHandler<()>) -> Element {
+fn Header(auth_state: AuthState, show_calendar_view: bool, show_library_view: bool, on_calendar_click: EventHandler<()>, on_library_click: EventHandler<()>, on_settings_click: EventHandler<()>) -> Element {
     const LOGO_SVG: &str = "data:image/svg+xml;base64,PHN2ZyB3aWR0aD0iMzkxIiBoZWlnaHQ9IjE2OCIgdmlld0JveD0iMCAwIDM5MSAxNjgiIGZpbGw9Im5vbmUiIHhtbG5zPSJodHRwOi8vd3d3LnczLm9yZy8yMDAwL3N2ZyI+CjxyZWN0IHg9IjI0IiB5PSI1MiIgd2lkdGg9IjI0IiBoZWlnaHQ9IjY0IiByeD0iMTIiIGZpbGw9IiM2NDhGRkYiLz4KPHJlY3QgeD0iNTYiIHk9IjM0IiB3aWR0aD0iMjQiIGhlaWdodD0iMTAwIiByeD0iMTIiIGZpbGw9IiMyNkM0ODUiLz4KPHJlY3QgeD0iODgiIHk9IjUyIiB3aWR0aD0iMjQiIGhlaWdodD0iNjQiIHJ4PSIxMiIgZmlsbD0iI0UwMUU1QSIvPgo8cmVjdCB4PSIxMjAiIHk9IjY4IiB3aWR0aD0iMjQiIGhlaWdodD0iMzIiIHJ4PSIxMiIgZmlsbD0iI0Y2QUUyRCIvPgo8cGF0aCBkPSJNMjA3LjA0IDc0LjE2VjEwMEMyMDcuMDQgMTAyLjEzMyAyMDcuNDkzIDEwMy42NTMgMjA4LjQgMTA0LjU2QzIwOS4zMDcgMTA1LjQxMyAyMTAuODggMTA1Ljg0IDIxMy4xMiAxMDUuODRIMjE4LjQ4VjExMkgyMTEuOTJDMjA3Ljg2NyAxMTIgMjA0LjgyNyAxMTEuMDY3IDIwMi44IDEwOS4yQzIwMC43NzMgMTA3LjMzMyAxOTkuNzYgMTA0LjI2NyAxOTkuNzYgMTAwVjc0LjE2SDE5NC4wOFY2OC4xNkgxOTkuNzZWNTcuMTJIMjA3LjA0VjY4LjE2SDIxOC40OFY3NC4xNkgyMDcuMDRaIiBmaWxsPSJibGFjayIvPgo8cGF0aCBkPSJNMjI0LjU4MSA4OS45MkMyMjQuNTgxIDg1LjQ0IDIyNS40ODcgODEuNTIgMjI3LjMwMSA3OC4xNkMyMjkuMTE0IDc0Ljc0NjcgMjMxLjU5NCA3Mi4xMDY3IDIzNC43NDEgNzAuMjRDMjM3Ljk0MSA2OC4zNzMzIDI0MS40ODcgNjcuNDQgMjQ1LjM4MSA2Ny40NEMyNDkuMjIxIDY3LjQ0IDI1Mi41NTQgNjguMjY2NyAyNTUuMzgxIDY5LjkyQzI1OC4yMDcgNzEuNTczMyAyNjAuMzE0IDczLjY1MzMgMjYxLjcwMSA3Ni4xNlY2OC4xNkgyNjkuMDYxVjExMkgyNjEuNzAxVjEwMy44NEMyNjAuMjYxIDEwNi40IDI1OC4xMDEgMTA4LjUzMyAyNTUuMjIxIDExMC4yNEMyNTIuMzk0IDExMS44OTMgMjQ5LjA4NyAxMTIuNzIgMjQ1LjMwMSAxMTIuNzJDMjQxLjQwNyAxMTIuNzIgMjM3Ljg4NyAxMTEuNzYgMjM0Ljc0MSAxMDkuODRDMjMxLjU5NCAxMDcuOTIgMjI5LjExNCAxMDUuMjI3IDIyNy4zMDEgMTAxLjc2QzIyNS40ODcgOTguMjkzMyAyMjQuNTgxIDk0LjM0NjcgMjI0LjU4MSA4OS45MlpNMjYxLjcwMSA5MEMyNjEuNzAxIDg2LjY5MzMgMjYxLjAzNCA4My44MTMzIDI1OS43MDEgODEuMzZDMjU4LjM2NyA3OC45MDY3IDI1Ni41NTQgNzcuMDQgMjU0LjI2MSA3NS43NkMyNTIuMDIxIDc0LjQyNjcgMjQ5LjU0MSA3My43NiAyNDYuODIxIDczLjc2QzI0NC4xMDEgNzMuNzYgMjQxLjYyMSA3NC40IDIzOS4zODEgNzUuNjhDMjM3LjE0MSA3Ni45NiAyMzUuMzU0IDc4LjgyNjcgMjM0LjAyMSA4MS4yOEMyMzIuNjg3IDgzLjczMzMgMjMyLjAyMSA4Ni42MTMzIDIzMi4wMjEgODkuOTJDMjMyLjAyMSA5My4yOCAyMzIuNjg3IDk2LjIxMzMgMjM0LjAyMSA5OC43MkMyMzUuMzU0IDEwMS4xNzMgMjM3LjE0MSAxMDMuMDY3IDIzOS4zODEgMTA0LjRDMjQxLjYyMSAxMDUuNjggMjQ0LjEwMSAxMDYuMzIgMjQ2LjgyMSAxMDYuMzJDMjQ5LjU0MSAxMDYuMzIgMjUyLjAyMSAxMDUuNjggMjU0LjI2MSAxMDQuNEMyNTYuNTU0IDEwMy4wNjcgMjU4LjM2NyAxMDEuMTczIDI1OS43MDEgOTguNzJDMjYxLjAzNCA5Ni4yMTMzIDI2MS43MDEgOTMuMzA2NyAyNjEuNzAxIDkwWiIgZmlsbD0iYmxhY2siLz4KPHBhdGggZD0iTTI4OC42NDMgNTIuOFYxMTJIMjgxLjM2M1Y1Mi44SDI4OC42NDNaIiBmaWxsPSJibGFjayIvPgo8cGF0aCBkPSJNMzI1LjUzMSAxMTJMMzA4LjMzMSA5Mi42NFYxMTJIMzAxLjA1MVY1Mi44SDMwOC4zMzFWODcuNkwzMjUuMjExIDY4LjE2SDMzNS4zNzFMMzE0LjczMSA5MEwzMzUuNDUxIDExMkgzMjUuNTMxWiIgZmlsbD0iYmxhY2siLz4KPHBhdGggZD0iTTMzOS41MDMgODkuOTJDMzM5LjUwMyA4NS40NCAzNDAuNDA5IDgxLjUyIDM0Mi4yMjMgNzguMTZDMzQ0LjAzNiA3NC43NDY3IDM0Ni41MTYgNzIuMTA2NyAzNDkuNjYzIDcwLjI0QzM1Mi44NjMgNjguMzczMyAzNTYuNDA5IDY3LjQ0IDM2MC4zMDMgNjcuNDRDMzY0LjE0MyA2Ny40NCAzNjcuNDc2IDY4LjI2NjcgMzcwLjMwMyA2OS45MkMzNzMuMTI5IDcxLjU3MzMgMzc1LjIzNiA3My42NTMzIDM3Ni42MjMgNzYuMTZWNjguMTZIMzgzLjk4M1YxMTJIMzc2LjYyM1YxMDMuODRDMzc1LjE4MyAxMDYuNCAzNzMuMDIzIDEwOC41MzMgMzcwLjE0MyAxMTAuMjRDMzY3LjMxNiAxMTEuODkzIDM2NC4wMDkgMTEyLjcyIDM2MC4yMjMgMTEyLjcyQzM1Ni4zMjkgMTEyLjcyIDM1Mi44MDkgMTExLjc2IDM0OS42NjMgMTA5Ljg0QzM0Ni41MTYgMTA3LjkyIDM0NC4wMzYgMTA1LjIyNyAzNDIuMjIzIDEwMS43NkMzNDAuNDA5IDk4LjI5MzMgMzM5LjUwMyA5NC4zNDY3IDMzOS41MDMgODkuOTJaTTM3Ni42MjMgOTBDMzc2LjYyMyA4Ni42OTMzIDM3NS45NTYgODMuODEzMyAzNzQuNjIzIDgxLjM2QzM3My4yODkgNzguOTA2NyAzNzEuNDc2IDc3LjA0IDM2OS4xODMgNzUuNzZDMzY2Ljk0MyA3NC40MjY3IDM2NC40NjMgNzMuNzYgMzYxLjc0MyA3My43NkMzNTkuMDIzIDczLjc2IDM1Ni41NDMgNzQuNCAzNTQuMzAzIDc1LjY4QzM1Mi4wNjMgNzYuOTYgMzUwLjI3NiA3OC44MjY3IDM0OC45NDMgODEuMjhDMzQ3LjYwOSA4My43MzMzIDM0Ni45NDMgODYuNjEzMyAzNDYuOTQzIDg5LjkyQzM0Ni45NDMgOTMuMjggMzQ3LjYwOSA5Ni4yMTMzIDM0OC45NDMgOTguNzJDMzUwLjI3NiAxMDEuMTczIDM1Mi4wNjMgMTAzLjA2NyAzNTQuMzAzIDEwNC40QzM1Ni41NDMgMTA1LjY4IDM1OS4wMjMgMTA2LjMyIDM2MS43NDMgMTA2LjMyQzM2NC40NjMgMTA2LjMyIDM2Ni45NDMgMTA1LjY4IDM2OS4xODMgMTA0LjRDMzcxLjQ3NiAxMDMuMDY3IDM3My4yODkgMTAxLjE3MyAzNzQuNjIzIDk4LjcyQzM3NS45NTYgOTYuMjEzMyAzNzYuNjIzIDkzLjMwNjcgMzc2LjYyMyA5MFoiIGZpbGw9ImJsYWNrIi8+Cjwvc3ZnPgo=";
     
     let profile = match auth_state {
@@ -493,6 +1002,10 @@ fn Header(auth_state: AuthState, show_calendar_view: bool, on_calendar_click: Ev
     };
     
     let mut show_dropdown = use_signal(|| false);
+    let mut pinned = use_signal(|| session::current().pinned);
+
+    // Also puts the pin from the last session back when the window opens
+    use_effect(move || pin::apply(&dioxus::desktop::window(), *pinned.read()));
     
     rsx! {
         header { id: "app-header",
@@ -504,6 +1017,26 @@ fn Header(auth_state: AuthState, show_calendar_view: bool, on_calendar_click: Ev
             }
             
             div { class: "header-actions",
+                // Keep the window above full-screen meeting apps
+                button {
+                    class: if *pinned.read() { "calendar-button active" } else { "calendar-button" },
+                    onclick: move |_| {
+                        let now_pinned = !*pinned.read();
+                        pinned.set(now_pinned);
+                        session::update(|s| s.pinned = now_pinned);
+                    },
+                    title: if *pinned.read() { "Unpin Window" } else { "Keep Window on Top, Even Over Full-Screen Apps" },
+                    dangerous_inner_html: r#"<svg width="20" height="20" viewBox="0 0 20 20" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M7 3h6l-1 5 3 3H5l3-3-1-5Z" stroke="currentColor" stroke-width="1.5" stroke-linejoin="round" fill="none"/><path d="M10 11v6" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/></svg>"#
+                }
+
+                // Recordings library icon button
+                button {
+                    class: if show_library_view { "calendar-button active" } else { "calendar-button" },
+                    onclick: move |_| on_library_click.call(()),
+                    title: "View Local Recordings",
+                    dangerous_inner_html: r#"<svg width="20" height="20" viewBox="0 0 20 20" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M3 6a2 2 0 0 1 2-2h3l2 2h5a2 2 0 0 1 2 2v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V6Z" stroke="currentColor" stroke-width="1.5" fill="none"/></svg>"#
+                }
+
                 // Calendar icon button
                 button {
                     class: if show_calendar_view { "calendar-button active" } else { "calendar-button" },
@@ -536,11 +1069,20 @@ fn Header(auth_state: AuthState, show_calendar_view: bool, on_calendar_click: Ev
                                     }
                                 }
                                 div { class: "dropdown-divider" }
+                                button {
+                                    class: "dropdown-item dropdown-button",
+                                    onclick: move |_| {
+                                        show_dropdown.set(false);
+                                        on_settings_click.call(());
+                                    },
+                                    "⚙️ Settings"
+                                }
                                 button {
                                     class: "dropdown-item dropdown-button",
                                     onclick: move |_| {
                                         let _ = auth::logout();
                                         println!("🔓 Logged out successfully");
+                                        session::flush();
                                         std::process::exit(0);
                                     },
                                     "🚪 Logout"
@@ -555,32 +1097,67 @@ fn Header(auth_state: AuthState, show_calendar_view: bool, on_calendar_click: Ev
 }
 
 #[component]
-fn CalendarEventsView(events: Vec<auth::MeetingEvent>, on_close: EventHandler<()>) -> Element {
-    let mut current_page = use_signal(|| 0);
-    
-    const EVENTS_PER_PAGE: usize = 10;
-    let total_pages = (events.len() + EVENTS_PER_PAGE - 1) / EVENTS_PER_PAGE;
-    let current_page_num = *current_page.read();
-    
-    let start_idx = current_page_num * EVENTS_PER_PAGE;
-    let end_idx = (start_idx + EVENTS_PER_PAGE).min(events.len());
-    let page_events: Vec<_> = events.iter().skip(start_idx).take(end_idx - start_idx).collect();
-    
-    let subtitle = if events.is_empty() {
-        "No meetings scheduled".to_string()
-    } else if total_pages > 1 {
-        format!("Page {} of {}", current_page_num + 1, total_pages)
-    } else {
-        format!("{} meeting{}", events.len(), if events.len() == 1 { "" } else { "s" })
+fn SettingsView(on_close: EventHandler<()>) -> Element {
+    // Most recent journal entries listed under Diagnostics
+    const JOURNAL_ENTRIES_SHOWN: usize = 20;
+
+    let mut settings = use_signal(updater::UpdateSettings::load);
+    let mut update_state = use_signal(updater::state);
+    let mut install_error = use_signal(String::new);
+    let mut webhook_settings = use_signal(webhook::WebhookSettings::load);
+    let mut webhook_status = use_signal(String::new);
+    let mut focus_settings = use_signal(focus::FocusSettings::load);
+    let mut focus_status = use_signal(String::new);
+    let mut battery_settings = use_signal(power::BatterySettings::load);
+    let mut battery_status = use_signal(String::new);
+    let mut live_settings = use_signal(live_preview::LivePreviewSettings::load);
+    let mut live_status = use_signal(String::new);
+    let mut backup_settings = use_signal(backup::BackupSettings::load);
+    let mut backup_status = use_signal(String::new);
+    let mut highlight_settings = use_signal(highlights::HighlightSettings::load);
+    let mut highlight_status = use_signal(String::new);
+    let mut diagnostics = use_signal(|| None::<doctor::Report>);
+    let mut diagnosing = use_signal(|| false);
+    let mut journal_entries = use_signal(journal::today);
+    let mut bundle_status = use_signal(String::new);
+    let mut gpu_settings = use_signal(gpu::current);
+    let gpus = use_hook(gpu::available);
+    // Bumped on import so sections that load their own settings start over
+    let mut imports = use_signal(|| 0u32);
+
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            let latest = updater::state();
+            if *update_state.read() != latest {
+                update_state.set(latest);
+            }
+        }
+    });
+
+    let mut save = move |updated: updater::UpdateSettings| {
+        if let Err(e) = updated.save() {
+            eprintln!("⚠️  {}", e);
+        }
+        settings.set(updated);
     };
-    
+    let mut save_gpu = move |updated: gpu::GpuSettings| {
+        if let Err(e) = updated.save() {
+            eprintln!("⚠️  {}", e);
+        }
+        gpu_settings.set(updated);
+    };
+    let state_now = update_state.read().clone();
+    let busy = matches!(state_now, updater::UpdateState::Checking | updater::UpdateState::Downloading { .. });
+    let placeholders = webhook::PLACEHOLDERS.join(", ");
+
     rsx! {
         div { class: "calendar-overlay",
             div { class: "calendar-container",
                 div { class: "calendar-header",
                     div { class: "calendar-header-content",
-                        h2 { class: "calendar-title", "Upcoming Meetings" }
-                        p { class: "calendar-subtitle", "{subtitle}" }
+                        h2 { class: "calendar-title", "Settings" }
+                        p { class: "calendar-subtitle", "Talka Recorder {updater::CURRENT_VERSION}" }
                     }
                     button {
                         class: "calendar-close-btn",
@@ -588,57 +1165,662 @@ fn CalendarEventsView(events: Vec<auth::MeetingEvent>, on_close: EventHandler<()
                         dangerous_inner_html: r#"<svg width="20" height="20" viewBox="0 0 20 20" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M15 5L5 15M5 5l10 10" stroke="currentColor" stroke-width="2" stroke-linecap="round"/></svg>"#
                     }
                 }
-                
+
                 div { class: "calendar-content",
-                    if events.is_empty() {
-                        div { class: "no-events",
-                            div { class: "no-events-icon",
-                                dangerous_inner_html: r#"<svg width="48" height="48" viewBox="0 0 48 48" fill="none" xmlns="http://www.w3.org/2000/svg"><rect x="8" y="12" width="32" height="28" rx="3" stroke="currentColor" stroke-width="2" fill="none"/><path d="M8 18h32" stroke="currentColor" stroke-width="2"/><path d="M16 8v6M32 8v6" stroke="currentColor" stroke-width="2" stroke-linecap="round"/><circle cx="16" cy="26" r="1.5" fill="currentColor"/><circle cx="24" cy="26" r="1.5" fill="currentColor"/><circle cx="32" cy="26" r="1.5" fill="currentColor"/></svg>"#
-                            }
-                            div { class: "no-events-text", "No upcoming meetings scheduled" }
-                        }
-                    } else {
-                        for event in page_events.iter() {
-                            div { class: "meeting-card",
-                                div { class: "meeting-card-left",
-                                    div { class: "meeting-time",
-                                        "{event.formatted_start_time()}"
+                    div { class: "settings-section",
+                        div { class: "settings-section-title", "Updates" }
+                        label { class: "settings-row",
+                            span { "Channel" }
+                            select {
+                                class: "library-editor-input settings-select",
+                                value: "{settings.read().channel.as_str()}",
+                                onchange: move |evt| {
+                                    if let Some(channel) = updater::UpdateChannel::from_str(&evt.value()) {
+                                        let mut updated = settings.read().clone();
+                                        updated.channel = channel;
+                                        save(updated);
                                     }
-                                    div { class: "meeting-title",
-                                        "{event.event_summary}"
+                                },
+                                for channel in updater::UpdateChannel::ALL {
+                                    option {
+                                        value: "{channel.as_str()}",
+                                        selected: settings.read().channel == channel,
+                                        "{channel.label()}"
                                     }
                                 }
+                            }
+                        }
+                        label { class: "settings-row",
+                            span { "Update automatically" }
+                            input {
+                                r#type: "checkbox",
+                                checked: settings.read().automatic,
+                                onchange: move |evt| {
+                                    let mut updated = settings.read().clone();
+                                    updated.automatic = evt.checked();
+                                    save(updated);
+                                },
+                            }
+                        }
+
+                        if !state_now.to_string().is_empty() {
+                            div { class: "library-notes", "{state_now}" }
+                        }
+                        if !install_error.read().is_empty() {
+                            div { class: "library-damage", "{install_error}" }
+                        }
+
+                        div { class: "library-editor-actions",
+                            if let updater::UpdateState::Ready { ref app_path, .. } = state_now {
                                 button {
-                                    class: "meeting-join-btn",
+                                    class: "btn btn-primary",
                                     onclick: {
-                                        let url = event.meeting_url.clone();
+                                        let app_path = app_path.clone();
                                         move |_| {
-                                            let _ = std::process::Command::new("open").arg(&url).spawn();
+                                            let (_, _, is_rec, _, _, _, _, _) = get_global_state();
+                                            if is_rec.load(Ordering::Relaxed) {
+                                                install_error.set("Stop the recording before updating".to_string());
+                                            } else if let Err(e) = updater::install_and_relaunch(&app_path) {
+                                                eprintln!("❌ Failed to install update: {}", e);
+                                                install_error.set(e);
+                                            }
                                         }
                                     },
-                                    title: "Open meeting link",
-                                    dangerous_inner_html: r#"<svg width="18" height="18" viewBox="0 0 64 64" fill="currentColor" xmlns="http://www.w3.org/2000/svg"><path d="M36.026,20.058l-21.092,0c-1.65,0 -2.989,1.339 -2.989,2.989l0,25.964c0,1.65 1.339,2.989 2.989,2.989l26.024,0c1.65,0 2.989,-1.339 2.989,-2.989l0,-20.953l3.999,0l0,21.948c0,3.308 -2.686,5.994 -5.995,5.995l-28.01,0c-3.309,0 -5.995,-2.687 -5.995,-5.995l0,-27.954c0,-3.309 2.686,-5.995 5.995,-5.995l22.085,0l0,4.001Z"/><path d="M55.925,25.32l-4.005,0l0,-10.481l-27.894,27.893l-2.832,-2.832l27.895,-27.895l-10.484,0l0,-4.005l17.318,0l0.002,0.001l0,17.319Z"/></svg>"#
+                                    "Restart to Update"
+                                }
+                            } else {
+                                button {
+                                    class: "btn btn-secondary",
+                                    disabled: busy,
+                                    onclick: move |_| {
+                                        install_error.set(String::new());
+                                        tokio::spawn(updater::check_and_download());
+                                    },
+                                    "Check for Updates"
                                 }
                             }
                         }
                     }
-                }
-                
-                if total_pages > 1 {
-                    div { class: "calendar-pagination",
-                        button {
-                            class: "pagination-btn",
-                            disabled: current_page_num == 0,
-                            onclick: move |_| {
-                                if current_page_num > 0 {
-                                    current_page.set(current_page_num - 1);
+
+                    CaptureSettingsSection { key: "{imports}" }
+
+                    div { class: "settings-section",
+                        div { class: "settings-section-title", "Preview" }
+                        label { class: "settings-row",
+                            span { "Low-power preview (about 12 fps)" }
+                            input {
+                                r#type: "checkbox",
+                                checked: gpu_settings.read().low_power_preview,
+                                onchange: move |evt| {
+                                    let mut updated = gpu_settings.read().clone();
+                                    updated.low_power_preview = evt.checked();
+                                    save_gpu(updated);
+                                },
+                            }
+                        }
+                        if gpus.len() > 1 {
+                            label { class: "settings-row",
+                                span { "Render on" }
+                                select {
+                                    class: "library-editor-input settings-select",
+                                    value: "{gpu_settings.read().device}",
+                                    onchange: move |evt| {
+                                        let mut updated = gpu_settings.read().clone();
+                                        updated.device = evt.value();
+                                        save_gpu(updated);
+                                    },
+                                    option {
+                                        value: "",
+                                        selected: gpu_settings.read().device.is_empty(),
+                                        "Automatic (low power)"
+                                    }
+                                    for info in gpus.iter() {
+                                        option {
+                                            value: "{info.name}",
+                                            selected: gpu_settings.read().device == info.name,
+                                            "{info.label()}"
+                                        }
+                                    }
                                 }
-                            },
-                            dangerous_inner_html: r#"<svg width="16" height="16" viewBox="0 0 16 16" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M10 12L6 8l4-4" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"/></svg>"#
+                            }
+                            div { class: "library-notes", "The integrated GPU uses the least battery. A change applies once all preview windows are closed." }
                         }
-                        
-                        div { class: "pagination-info",
-                            "Page {current_page_num + 1} of {total_pages}"
+                    }
+
+                    div { class: "settings-section",
+                        div { class: "settings-section-title", "Notifications While Recording" }
+                        label { class: "settings-row",
+                            span { "Hide banners from display recordings" }
+                            input {
+                                r#type: "checkbox",
+                                checked: focus_settings.read().hide_notifications,
+                                onchange: move |evt| focus_settings.write().hide_notifications = evt.checked(),
+                            }
+                        }
+                        input {
+                            class: "library-editor-input",
+                            placeholder: "Shortcut to turn Focus on (optional)",
+                            value: "{focus_settings.read().focus_on_shortcut}",
+                            oninput: move |evt| focus_settings.write().focus_on_shortcut = evt.value(),
+                        }
+                        input {
+                            class: "library-editor-input",
+                            placeholder: "Shortcut to turn Focus off",
+                            value: "{focus_settings.read().focus_off_shortcut}",
+                            oninput: move |evt| focus_settings.write().focus_off_shortcut = evt.value(),
+                        }
+                        div { class: "library-notes", "Create these in the Shortcuts app with the \"Set Focus\" action." }
+                        if !focus_status.read().is_empty() {
+                            div { class: "library-notes", "{focus_status}" }
+                        }
+                        div { class: "library-editor-actions",
+                            button {
+                                class: "btn btn-secondary",
+                                onclick: move |_| {
+                                    match focus_settings.read().save() {
+                                        Ok(()) => focus_status.set("Saved".to_string()),
+                                        Err(e) => focus_status.set(e),
+                                    }
+                                },
+                                "Save"
+                            }
+                        }
+                    }
+
+                    div { class: "settings-section",
+                        div { class: "settings-section-title", "Battery" }
+                        label { class: "settings-row",
+                            span { "Low battery below" }
+                            input {
+                                class: "library-editor-input settings-select",
+                                r#type: "number",
+                                min: "5",
+                                max: "95",
+                                value: "{battery_settings.read().threshold_percent}",
+                                oninput: move |evt| {
+                                    if let Ok(percent) = evt.value().parse::<u8>() {
+                                        battery_settings.write().threshold_percent = percent.clamp(5, 95);
+                                    }
+                                },
+                            }
+                        }
+                        label { class: "settings-row",
+                            span { "Reduce frame rate and resolution" }
+                            input {
+                                r#type: "checkbox",
+                                checked: battery_settings.read().reduce_quality,
+                                onchange: move |evt| battery_settings.write().reduce_quality = evt.checked(),
+                            }
+                        }
+                        label { class: "settings-row",
+                            span { "Wait for power before uploading" }
+                            input {
+                                r#type: "checkbox",
+                                checked: battery_settings.read().defer_uploads,
+                                onchange: move |evt| battery_settings.write().defer_uploads = evt.checked(),
+                            }
+                        }
+                        if !battery_status.read().is_empty() {
+                            div { class: "library-notes", "{battery_status}" }
+                        }
+                        div { class: "library-editor-actions",
+                            button {
+                                class: "btn btn-secondary",
+                                onclick: move |_| {
+                                    match battery_settings.read().save() {
+                                        Ok(()) => battery_status.set("Saved".to_string()),
+                                        Err(e) => battery_status.set(e),
+                                    }
+                                },
+                                "Save"
+                            }
+                        }
+                    }
+
+                    RecordingTimerSection { key: "{imports}" }
+
+                    StandbySettingsSection { key: "{imports}" }
+
+                    ClipboardSettingsSection { key: "{imports}" }
+
+                    CalendarPrivacySection { key: "{imports}" }
+                    RemoteControlSection { key: "{imports}" }
+
+                    AudioTrackSettingsSection { key: "{imports}" }
+
+                    AnnotationSettingsSection { key: "{imports}" }
+
+                    ProfileSettingsSection { key: "{imports}" }
+
+                    MeteredUploadsSection { key: "{imports}" }
+
+                    div { class: "settings-section",
+                        div { class: "settings-section-title", "Highlights" }
+                        div { class: "library-notes", "Bookmark moments with {hotkey::BOOKMARK_SHORTCUT} while recording; each becomes a clip of the trimmed recording when it is processed for upload." }
+                        label { class: "settings-row",
+                            span { "Export highlight clips" }
+                            input {
+                                r#type: "checkbox",
+                                checked: highlight_settings.read().enabled,
+                                onchange: move |evt| highlight_settings.write().enabled = evt.checked(),
+                            }
+                        }
+                        label { class: "settings-row",
+                            span { "Seconds before bookmark" }
+                            input {
+                                class: "library-editor-input settings-select",
+                                r#type: "number",
+                                min: "0",
+                                max: "300",
+                                value: "{highlight_settings.read().before_seconds}",
+                                oninput: move |evt| {
+                                    if let Ok(seconds) = evt.value().parse::<f64>() {
+                                        highlight_settings.write().before_seconds = seconds.clamp(0.0, 300.0);
+                                    }
+                                },
+                            }
+                        }
+                        label { class: "settings-row",
+                            span { "Seconds after bookmark" }
+                            input {
+                                class: "library-editor-input settings-select",
+                                r#type: "number",
+                                min: "1",
+                                max: "300",
+                                value: "{highlight_settings.read().after_seconds}",
+                                oninput: move |evt| {
+                                    if let Ok(seconds) = evt.value().parse::<f64>() {
+                                        highlight_settings.write().after_seconds = seconds.clamp(1.0, 300.0);
+                                    }
+                                },
+                            }
+                        }
+                        if !highlight_status.read().is_empty() {
+                            div { class: "library-notes", "{highlight_status}" }
+                        }
+                        div { class: "library-editor-actions",
+                            button {
+                                class: "btn btn-secondary",
+                                onclick: move |_| {
+                                    match highlight_settings.read().save() {
+                                        Ok(()) => highlight_status.set("Saved".to_string()),
+                                        Err(e) => highlight_status.set(e),
+                                    }
+                                },
+                                "Save"
+                            }
+                        }
+                    }
+
+                    div { class: "settings-section",
+                        div { class: "settings-section-title", "Backup Folder" }
+                        div { class: "library-notes", "Finished recordings are copied here, e.g. a folder in iCloud Drive or Dropbox." }
+                        input {
+                            class: "library-editor-input",
+                            placeholder: "No folder: backup is off",
+                            value: "{backup_settings.read().folder}",
+                            oninput: move |evt| backup_settings.write().folder = evt.value(),
+                        }
+                        if !backup_status.read().is_empty() {
+                            div { class: "library-notes", "{backup_status}" }
+                        }
+                        div { class: "library-editor-actions",
+                            button {
+                                class: "btn btn-text",
+                                onclick: move |_| {
+                                    spawn(async move {
+                                        match tokio::task::spawn_blocking(backup::choose_folder).await {
+                                            Ok(Ok(Some(folder))) => backup_settings.write().folder = folder,
+                                            Ok(Ok(None)) => {}
+                                            Ok(Err(e)) => backup_status.set(e),
+                                            Err(e) => backup_status.set(format!("Folder picker failed: {}", e)),
+                                        }
+                                    });
+                                },
+                                "Choose…"
+                            }
+                            button {
+                                class: "btn btn-secondary",
+                                onclick: move |_| {
+                                    let settings = backup_settings.read().clone();
+                                    if settings.is_enabled() && !std::path::Path::new(settings.folder.trim()).is_dir() {
+                                        backup_status.set("That folder does not exist".to_string());
+                                        return;
+                                    }
+                                    match settings.save() {
+                                        Ok(()) => {
+                                            backup_status.set("Saved".to_string());
+                                            tokio::task::spawn_blocking(backup::sync_completed);
+                                        }
+                                        Err(e) => backup_status.set(e),
+                                    }
+                                },
+                                "Save"
+                            }
+                        }
+                    }
+
+                    div { class: "settings-section",
+                        div { class: "settings-section-title", "Live Preview" }
+                        label { class: "settings-row",
+                            span { "Serve a live MJPEG preview" }
+                            input {
+                                r#type: "checkbox",
+                                checked: live_settings.read().enabled,
+                                onchange: move |evt| live_settings.write().enabled = evt.checked(),
+                            }
+                        }
+                        label { class: "settings-row",
+                            span { "Allow viewers on other machines" }
+                            input {
+                                r#type: "checkbox",
+                                checked: live_settings.read().allow_network,
+                                onchange: move |evt| live_settings.write().allow_network = evt.checked(),
+                            }
+                        }
+                        label { class: "settings-row",
+                            span { "Port" }
+                            input {
+                                class: "library-editor-input settings-select",
+                                r#type: "number",
+                                min: "1024",
+                                max: "65535",
+                                value: "{live_settings.read().port}",
+                                oninput: move |evt| {
+                                    if let Ok(port) = evt.value().parse::<u16>() {
+                                        live_settings.write().port = port.max(1024);
+                                    }
+                                },
+                            }
+                        }
+                        if live_settings.read().enabled {
+                            div { class: "library-notes", "{live_settings.read().viewer_url()}" }
+                        }
+                        if !live_status.read().is_empty() {
+                            div { class: "library-notes", "{live_status}" }
+                        }
+                        div { class: "library-editor-actions",
+                            if live_settings.read().enabled {
+                                button {
+                                    class: "btn btn-text",
+                                    onclick: move |_| copy_to_clipboard(&live_settings.read().viewer_url()),
+                                    "Copy Link"
+                                }
+                            }
+                            button {
+                                class: "btn btn-secondary",
+                                onclick: move |_| {
+                                    let settings = live_settings.read().clone();
+                                    let (_, _, _, _, _, capture_state, _, _) = get_global_state();
+                                    let result = settings.save().and_then(|()| live_preview::apply(&settings, &capture_state));
+                                    match result {
+                                        Ok(()) => live_status.set("Saved".to_string()),
+                                        Err(e) => live_status.set(e),
+                                    }
+                                },
+                                "Save"
+                            }
+                        }
+                    }
+
+                    div { class: "settings-section",
+                        div { class: "settings-section-title", "Upload Webhook" }
+                        input {
+                            class: "library-editor-input",
+                            r#type: "url",
+                            placeholder: "https://hooks.slack.com/services/…",
+                            value: "{webhook_settings.read().url}",
+                            oninput: move |evt| webhook_settings.write().url = evt.value(),
+                        }
+                        textarea {
+                            class: "library-editor-input",
+                            rows: "3",
+                            value: "{webhook_settings.read().template}",
+                            oninput: move |evt| webhook_settings.write().template = evt.value(),
+                        }
+                        div { class: "library-notes", "Available: {placeholders}" }
+                        if !webhook_status.read().is_empty() {
+                            div { class: "library-notes", "{webhook_status}" }
+                        }
+                        div { class: "library-editor-actions",
+                            button {
+                                class: "btn btn-text",
+                                disabled: !webhook_settings.read().is_enabled(),
+                                onclick: move |_| {
+                                    let settings = webhook_settings.read().clone();
+                                    webhook_status.set("Sending test…".to_string());
+                                    spawn(async move {
+                                        match webhook::send_test(&settings).await {
+                                            Ok(()) => webhook_status.set("Test message sent".to_string()),
+                                            Err(e) => webhook_status.set(e),
+                                        }
+                                    });
+                                },
+                                "Send Test"
+                            }
+                            button {
+                                class: "btn btn-secondary",
+                                onclick: move |_| {
+                                    match webhook_settings.read().save() {
+                                        Ok(()) => webhook_status.set("Webhook saved".to_string()),
+                                        Err(e) => webhook_status.set(e),
+                                    }
+                                },
+                                "Save"
+                            }
+                        }
+                    }
+
+                    div { class: "settings-section",
+                        div { class: "settings-section-title", "Settings File" }
+                        div { class: "library-notes", "Move these settings to another Mac, or set up new ones the same way. Sign-in is not included." }
+                        if !bundle_status.read().is_empty() {
+                            div { class: "library-notes", "{bundle_status}" }
+                        }
+                        div { class: "library-editor-actions",
+                            button {
+                                class: "btn btn-text",
+                                onclick: move |_| {
+                                    spawn(async move {
+                                        let result = tokio::task::spawn_blocking(|| {
+                                            let Some(file) = settings_bundle::choose_import_file()? else {
+                                                return Ok(None);
+                                            };
+                                            settings_bundle::import(std::path::Path::new(&file)).map(Some)
+                                        })
+                                        .await;
+                                        match result {
+                                            Ok(Ok(Some(count))) => {
+                                                settings.set(updater::UpdateSettings::load());
+                                                webhook_settings.set(webhook::WebhookSettings::load());
+                                                focus_settings.set(focus::FocusSettings::load());
+                                                battery_settings.set(power::BatterySettings::load());
+                                                live_settings.set(live_preview::LivePreviewSettings::load());
+                                                backup_settings.set(backup::BackupSettings::load());
+                                                highlight_settings.set(highlights::HighlightSettings::load());
+                                                gpu_settings.set(gpu::current());
+                                                imports += 1;
+                                                bundle_status.set(format!("Imported {} settings", count));
+                                            }
+                                            Ok(Ok(None)) => {}
+                                            Ok(Err(e)) => bundle_status.set(e),
+                                            Err(e) => bundle_status.set(format!("Import failed: {}", e)),
+                                        }
+                                    });
+                                },
+                                "Import…"
+                            }
+                            button {
+                                class: "btn btn-secondary",
+                                onclick: move |_| {
+                                    spawn(async move {
+                                        let result = tokio::task::spawn_blocking(|| {
+                                            let Some(file) = settings_bundle::choose_export_file()? else {
+                                                return Ok(None);
+                                            };
+                                            settings_bundle::export(std::path::Path::new(&file)).map(Some)
+                                        })
+                                        .await;
+                                        match result {
+                                            Ok(Ok(Some(count))) => bundle_status.set(format!("Exported {} settings", count)),
+                                            Ok(Ok(None)) => {}
+                                            Ok(Err(e)) => bundle_status.set(e),
+                                            Err(e) => bundle_status.set(format!("Export failed: {}", e)),
+                                        }
+                                    });
+                                },
+                                "Export…"
+                            }
+                        }
+                    }
+
+                    div { class: "settings-section",
+                        div { class: "settings-section-title", "Diagnostics" }
+                        if let Some(report) = diagnostics.read().clone() {
+                            for check in report.checks.iter() {
+                                div {
+                                    class: if check.status == doctor::CheckStatus::Fail { "library-damage" } else { "library-notes" },
+                                    "{check}"
+                                }
+                            }
+                        }
+                        div { class: "library-editor-actions",
+                            button {
+                                class: "btn btn-secondary",
+                                disabled: *diagnosing.read(),
+                                onclick: move |_| {
+                                    diagnosing.set(true);
+                                    spawn(async move {
+                                        diagnostics.set(Some(doctor::run().await));
+                                        diagnosing.set(false);
+                                    });
+                                },
+                                if *diagnosing.read() { "Checking…" } else { "Run Checks" }
+                            }
+                        }
+                        div { class: "settings-row", span { "Today's session journal" } }
+                        if journal_entries.read().is_empty() {
+                            div { class: "library-notes", "Nothing recorded today" }
+                        }
+                        for entry in journal_entries.read().iter().rev().take(JOURNAL_ENTRIES_SHOWN) {
+                            div { class: "library-notes",
+                                "{time_format::parse(&entry.at).map(time_format::time).unwrap_or_default()} · {entry.event}"
+                            }
+                        }
+                        div { class: "library-notes", "Capture, recording and upload events are kept in a file per day for audits." }
+                        div { class: "library-editor-actions",
+                            button {
+                                class: "btn btn-secondary",
+                                onclick: move |_| journal_entries.set(journal::today()),
+                                "Refresh"
+                            }
+                            button {
+                                class: "btn btn-text",
+                                onclick: move |_| {
+                                    if let Err(e) = std::process::Command::new("open").arg(journal::dir()).spawn() {
+                                        eprintln!("⚠️  Failed to open the journal folder: {}", e);
+                                    }
+                                },
+                                "Show in Finder"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn CalendarEventsView(events: Vec<auth::MeetingEvent>, on_close: EventHandler<()>) -> Element {
+    let mut current_page = use_signal(|| 0);
+    
+    const EVENTS_PER_PAGE: usize = 10;
+    let total_pages = (events.len() + EVENTS_PER_PAGE - 1) / EVENTS_PER_PAGE;
+    let current_page_num = *current_page.read();
+    
+    let start_idx = current_page_num * EVENTS_PER_PAGE;
+    let end_idx = (start_idx + EVENTS_PER_PAGE).min(events.len());
+    let page_events: Vec<_> = events.iter().skip(start_idx).take(end_idx - start_idx).collect();
+    
+    let calendar_off = !calendar_privacy::fetches_events();
+    let subtitle = if calendar_off {
+        "Calendar turned off in Settings".to_string()
+    } else if events.is_empty() {
+        "No meetings scheduled".to_string()
+    } else if total_pages > 1 {
+        format!("Page {} of {}", current_page_num + 1, total_pages)
+    } else {
+        format!("{} meeting{}", events.len(), if events.len() == 1 { "" } else { "s" })
+    };
+    
+    rsx! {
+        div { class: "calendar-overlay",
+            div { class: "calendar-container",
+                div { class: "calendar-header",
+                    div { class: "calendar-header-content",
+                        h2 { class: "calendar-title", "Upcoming Meetings" }
+                        p { class: "calendar-subtitle", "{subtitle}" }
+                    }
+                    button {
+                        class: "calendar-close-btn",
+                        onclick: move |_| on_close.call(()),
+                        dangerous_inner_html: r#"<svg width="20" height="20" viewBox="0 0 20 20" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M15 5L5 15M5 5l10 10" stroke="currentColor" stroke-width="2" stroke-linecap="round"/></svg>"#
+                    }
+                }
+                
+                div { class: "calendar-content",
+                    if events.is_empty() {
+                        div { class: "no-events",
+                            div { class: "no-events-icon",
+                                dangerous_inner_html: r#"<svg width="48" height="48" viewBox="0 0 48 48" fill="none" xmlns="http://www.w3.org/2000/svg"><rect x="8" y="12" width="32" height="28" rx="3" stroke="currentColor" stroke-width="2" fill="none"/><path d="M8 18h32" stroke="currentColor" stroke-width="2"/><path d="M16 8v6M32 8v6" stroke="currentColor" stroke-width="2" stroke-linecap="round"/><circle cx="16" cy="26" r="1.5" fill="currentColor"/><circle cx="24" cy="26" r="1.5" fill="currentColor"/><circle cx="32" cy="26" r="1.5" fill="currentColor"/></svg>"#
+                            }
+                            div { class: "no-events-text",
+                                if calendar_off { "Meetings are not fetched while the calendar is turned off in Settings" } else { "No upcoming meetings scheduled" }
+                            }
+                        }
+                    } else {
+                        for event in page_events.iter() {
+                            div { class: "meeting-card",
+                                div { class: "meeting-card-left",
+                                    div { class: "meeting-time",
+                                        "{meeting_start_text(event)}"
+                                    }
+                                    div { class: "meeting-title",
+                                        "{event.event_summary}"
+                                    }
+                                }
+                                button {
+                                    class: "meeting-join-btn",
+                                    onclick: {
+                                        let url = event.meeting_url.clone();
+                                        move |_| {
+                                            let _ = std::process::Command::new("open").arg(&url).spawn();
+                                        }
+                                    },
+                                    title: "Open meeting link",
+                                    dangerous_inner_html: r#"<svg width="18" height="18" viewBox="0 0 64 64" fill="currentColor" xmlns="http://www.w3.org/2000/svg"><path d="M36.026,20.058l-21.092,0c-1.65,0 -2.989,1.339 -2.989,2.989l0,25.964c0,1.65 1.339,2.989 2.989,2.989l26.024,0c1.65,0 2.989,-1.339 2.989,-2.989l0,-20.953l3.999,0l0,21.948c0,3.308 -2.686,5.994 -5.995,5.995l-28.01,0c-3.309,0 -5.995,-2.687 -5.995,-5.995l0,-27.954c0,-3.309 2.686,-5.995 5.995,-5.995l22.085,0l0,4.001Z"/><path d="M55.925,25.32l-4.005,0l0,-10.481l-27.894,27.893l-2.832,-2.832l27.895,-27.895l-10.484,0l0,-4.005l17.318,0l0.002,0.001l0,17.319Z"/></svg>"#
+                                }
+                            }
+                        }
+                    }
+                }
+                
+                if total_pages > 1 {
+                    div { class: "calendar-pagination",
+                        button {
+                            class: "pagination-btn",
+                            disabled: current_page_num == 0,
+                            onclick: move |_| {
+                                if current_page_num > 0 {
+                                    current_page.set(current_page_num - 1);
+                                }
+                            },
+                            dangerous_inner_html: r#"<svg width="16" height="16" viewBox="0 0 16 16" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M10 12L6 8l4-4" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"/></svg>"#
+                        }
+                        
+                        div { class: "pagination-info",
+                            "Page {current_page_num + 1} of {total_pages}"
                         }
                         
                         button {
@@ -659,46 +1841,2053 @@ fn CalendarEventsView(events: Vec<auth::MeetingEvent>, on_close: EventHandler<()
 }
 
 #[component]
-fn NextMeetingNotification(events: Vec<auth::MeetingEvent>) -> Element {
-    use chrono::{DateTime, Utc, Duration as ChronoDuration};
-    
-    // Find the next upcoming meeting (within next 24 hours)
-    let now = Utc::now();
-    let next_24_hours = now + ChronoDuration::hours(24);
-    
-    let next_meeting = events.iter().find(|event| {
-        if let Ok(dt) = DateTime::parse_from_rfc3339(&event.meeting_start_time) {
-            let event_time = dt.with_timezone(&Utc);
-            event_time > now && event_time < next_24_hours
-        } else {
-            false
+fn LibraryView(on_close: EventHandler<()>) -> Element {
+    let mut entries = use_signal(library::list_recordings);
+    let mut action_status = use_signal(String::new);
+    // Recording whose tags and notes are being edited, with the draft values,
+    // picked up again from the last session if it ended mid-edit
+    let restored_draft = session::current().metadata_draft;
+    let mut editing = use_signal(|| restored_draft.as_ref().map(|d| d.path.clone()));
+    let mut draft_tags = use_signal(|| restored_draft.as_ref().map(|d| d.tags.clone()).unwrap_or_default());
+    let mut draft_notes = use_signal(|| restored_draft.as_ref().map(|d| d.notes.clone()).unwrap_or_default());
+    let save_draft = move || {
+        let draft = editing.read().clone().map(|path| session::MetadataDraft {
+            path,
+            tags: draft_tags.read().clone(),
+            notes: draft_notes.read().clone(),
+        });
+        session::update(|s| s.metadata_draft = draft);
+    };
+    let mut query = use_signal(String::new);
+    // Search once typing pauses, off the UI thread
+    use_effect(move || {
+        let searched = query.read().clone();
+        spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
+            if *query.peek() != searched {
+                return;
+            }
+            let results = tokio::task::spawn_blocking({
+                let searched = searched.clone();
+                move || library::search_recordings(&searched)
+            })
+            .await;
+            if let Ok(results) = results {
+                if *query.peek() == searched {
+                    entries.set(results);
+                }
+            }
+        });
+    });
+    // Recording whose archive preset is being chosen
+    let mut archiving = use_signal(|| None::<String>);
+    let mut archive_jobs = use_signal(archive::jobs);
+    // Recording whose summary export format is being chosen
+    let mut exporting = use_signal(|| None::<String>);
+
+    // Pick up new recordings and finished actions while the view is open
+    use_future(move || async move {
+        // Catch up with files changed outside the app; the first poll shows them
+        let _ = tokio::task::spawn_blocking(library::refresh_index).await;
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            let searched = query.peek().clone();
+            let Ok(latest) = tokio::task::spawn_blocking({
+                let searched = searched.clone();
+                move || library::search_recordings(&searched)
+            })
+            .await
+            else {
+                continue;
+            };
+            // Drop results for a query the user has since changed
+            if *query.peek() == searched && *entries.peek() != latest {
+                entries.set(latest);
+            }
+            let jobs = archive::jobs();
+            if *archive_jobs.read() != jobs {
+                archive_jobs.set(jobs);
+            }
+        }
+    });
+    let job_for = move |path: &str| {
+        archive_jobs
+            .read()
+            .iter()
+            .find(|(p, _)| p == path)
+            .map(|(_, state)| state.clone())
+    };
+
+    let entries_now = entries.read().clone();
+    // Settings cannot change while the library is open
+    let backup_settings = use_hook(backup::BackupSettings::load);
+    let searching = !query.read().trim().is_empty();
+    let subtitle = if searching {
+        format!("{} result{}", entries_now.len(), if entries_now.len() == 1 { "" } else { "s" })
+    } else if entries_now.is_empty() {
+        "No local recordings".to_string()
+    } else {
+        format!("{} recording{}", entries_now.len(), if entries_now.len() == 1 { "" } else { "s" })
+    };
+
+    rsx! {
+        div { class: "calendar-overlay",
+            div { class: "calendar-container",
+                div { class: "calendar-header",
+                    div { class: "calendar-header-content",
+                        h2 { class: "calendar-title", "Recordings" }
+                        p { class: "calendar-subtitle", "{subtitle}" }
+                    }
+                    button {
+                        class: "calendar-close-btn",
+                        onclick: move |_| on_close.call(()),
+                        dangerous_inner_html: r#"<svg width="20" height="20" viewBox="0 0 20 20" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M15 5L5 15M5 5l10 10" stroke="currentColor" stroke-width="2" stroke-linecap="round"/></svg>"#
+                    }
+                }
+
+                div { class: "library-search",
+                    input {
+                        class: "library-editor-input",
+                        r#type: "search",
+                        placeholder: "Search titles, tags, meetings, notes and transcripts",
+                        value: "{query}",
+                        oninput: move |evt| query.set(evt.value()),
+                    }
+                }
+
+                if !action_status.read().is_empty() {
+                    div { class: "library-status", "{action_status}" }
+                }
+
+                UploadQueuePanel {}
+
+                div { class: "calendar-content",
+                    if entries_now.is_empty() {
+                        div { class: "no-events",
+                            div { class: "no-events-text",
+                                if searching { "No recordings match your search" } else { "Finished recordings will appear here" }
+                            }
+                        }
+                    } else {
+                        for entry in entries_now.iter() {
+                            div { class: "meeting-card",
+                                div { class: "meeting-card-left",
+                                    div { class: "meeting-time", "{entry.summary()}" }
+                                    div { class: "meeting-title", "{entry.title()}" }
+                                    if let Some(damage) = entry.damage() {
+                                        div { class: "library-damage", "⚠️ {damage}" }
+                                    }
+                                    if !entry.tags().is_empty() {
+                                        div { class: "library-tags",
+                                            for tag in entry.tags().iter() {
+                                                span { class: "library-tag", "{tag}" }
+                                            }
+                                        }
+                                    }
+                                    if !entry.notes().is_empty() {
+                                        div { class: "library-notes", "{entry.notes()}" }
+                                    }
+                                    if let Some(job) = job_for(&entry.path).filter(|j| !matches!(j, archive::ArchiveJobState::Done { .. })) {
+                                        div { class: "library-notes", "{job}" }
+                                    } else if entry.is_archived() {
+                                        div { class: "library-notes", "🗜️ Archived" }
+                                    }
+                                    if entry.sidecar.as_ref().is_some_and(|s| s.upload_state == sidecar::SidecarUploadState::Queued) {
+                                        div { class: "library-notes", "⏳ Queued for upload" }
+                                        UploadNowButton { path: entry.path.clone() }
+                                    }
+                                    if entry.sidecar.as_ref().is_some_and(|s| s.file_id.is_some() && !s.unsent_attachments.is_empty()) {
+                                        RetryAttachmentsButton { path: entry.path.clone() }
+                                    }
+                                    if let Some(backup) = backup::status(entry, &backup_settings) {
+                                        div { class: "library-notes", "{backup}" }
+                                    }
+                                }
+                                div { class: "library-actions",
+                                    button {
+                                        class: "btn btn-text library-action",
+                                        title: "Play in QuickTime Player",
+                                        onclick: {
+                                            let path = entry.path.clone();
+                                            // Results found in the transcript open where the words are
+                                            // spoken; other matches cover the whole recording
+                                            let at = entry.transcript_match(&query.read()).unwrap_or(0.0);
+                                            move |_| {
+                                                if let Err(e) = media::play_at(&path, at) {
+                                                    action_status.set(e);
+                                                }
+                                            }
+                                        },
+                                        if let Some(at) = entry.transcript_match(&query.read()) {
+                                            "Play at {time_format::offset(at as u64)}"
+                                        } else {
+                                            "Play"
+                                        }
+                                    }
+                                    button {
+                                        class: "btn btn-text library-action",
+                                        title: "Send with AirDrop, Messages, Mail…",
+                                        onclick: {
+                                            let path = entry.path.clone();
+                                            move |_| {
+                                                if let Err(e) = desktop::share_file(&path) {
+                                                    action_status.set(e);
+                                                }
+                                            }
+                                        },
+                                        "Share"
+                                    }
+                                    button {
+                                        class: "btn btn-text library-action",
+                                        title: "Preview with Quick Look",
+                                        onclick: {
+                                            let path = entry.path.clone();
+                                            move |_| {
+                                                if let Err(e) = desktop::quick_look(&path) {
+                                                    action_status.set(e);
+                                                }
+                                            }
+                                        },
+                                        "Preview"
+                                    }
+                                    button {
+                                        class: "btn btn-text library-action",
+                                        title: "Reveal in Finder",
+                                        onclick: {
+                                            let path = entry.path.clone();
+                                            move |_| {
+                                                if let Err(e) = desktop::reveal_in_finder(&path) {
+                                                    action_status.set(e);
+                                                }
+                                            }
+                                        },
+                                        "Finder"
+                                    }
+                                    if !entry.is_archived() && entry.damage().is_none() && !job_for(&entry.path).is_some_and(|j| j.is_pending()) {
+                                        button {
+                                            class: "btn btn-text library-action",
+                                            title: "Re-encode to a smaller file for long-term storage",
+                                            onclick: {
+                                                let path = entry.path.clone();
+                                                move |_| archiving.set(Some(path.clone()))
+                                            },
+                                            "Archive"
+                                        }
+                                    }
+                                    button {
+                                        class: "btn btn-text library-action",
+                                        title: "Edit tags and notes",
+                                        onclick: {
+                                            let path = entry.path.clone();
+                                            let tags = entry.tags().join(", ");
+                                            let notes = entry.notes().to_string();
+                                            move |_| {
+                                                draft_tags.set(tags.clone());
+                                                draft_notes.set(notes.clone());
+                                                editing.set(Some(path.clone()));
+                                                save_draft();
+                                            }
+                                        },
+                                        "Tags"
+                                    }
+                                    if meeting_summary::available(&entry) {
+                                        button {
+                                            class: "btn btn-text library-action",
+                                            title: "Export a meeting summary with bookmarks and transcript",
+                                            onclick: {
+                                                let path = entry.path.clone();
+                                                move |_| exporting.set(Some(path.clone()))
+                                            },
+                                            "Summary"
+                                        }
+                                    }
+                                    if entry.is_mov() {
+                                        button {
+                                            class: "btn btn-text library-action",
+                                            title: "Rewrap as MP4 without re-encoding",
+                                            onclick: {
+                                                let path = entry.path.clone();
+                                                move |_| {
+                                                    let path = path.clone();
+                                                    action_status.set("Converting to MP4…".to_string());
+                                                    spawn(async move {
+                                                        let result = tokio::task::spawn_blocking(move || library::remux_to_mp4(&path)).await;
+                                                        action_status.set(match result {
+                                                            Ok(Ok(output)) => format!("Saved {}", output),
+                                                            Ok(Err(e)) => format!("Conversion failed: {}", e),
+                                                            Err(e) => format!("Conversion failed: {}", e),
+                                                        });
+                                                    });
+                                                }
+                                            },
+                                            "MP4"
+                                        }
+                                    }
+                                    if let Some(segment) = entry.segment().filter(|s| s.index == 1) {
+                                        button {
+                                            class: "btn btn-text library-action",
+                                            title: "Join all parts of this recording without re-encoding",
+                                            onclick: {
+                                                let parts = library::segment_paths(&entries_now, &segment.group);
+                                                move |_| {
+                                                    let parts = parts.clone();
+                                                    action_status.set(format!("Merging {} segments…", parts.len()));
+                                                    spawn(async move {
+                                                        let result = tokio::task::spawn_blocking(move || library::merge_segments(&parts)).await;
+                                                        action_status.set(match result {
+                                                            Ok(Ok(output)) => format!("Saved {}", output),
+                                                            Ok(Err(e)) => format!("Merge failed: {}", e),
+                                                            Err(e) => format!("Merge failed: {}", e),
+                                                        });
+                                                    });
+                                                }
+                                            },
+                                            "Merge"
+                                        }
+                                    }
+                                }
+                            }
+                            if exporting.read().as_deref() == Some(entry.path.as_str()) {
+                                div { class: "library-editor",
+                                    div { class: "library-notes", "Save the summary next to the recording, or copy it as Markdown." }
+                                    div { class: "library-editor-actions",
+                                        button {
+                                            class: "btn btn-text library-action",
+                                            onclick: move |_| exporting.set(None),
+                                            "Cancel"
+                                        }
+                                        button {
+                                            class: "btn btn-secondary library-action",
+                                            onclick: {
+                                                let entry = entry.clone();
+                                                move |_| {
+                                                    let markdown = meeting_summary::render(&entry, meeting_summary::SummaryFormat::Markdown);
+                                                    copy_to_clipboard(&markdown);
+                                                    exporting.set(None);
+                                                }
+                                            },
+                                            "Copy"
+                                        }
+                                        for format in meeting_summary::SummaryFormat::ALL {
+                                            button {
+                                                class: "btn btn-secondary library-action",
+                                                onclick: {
+                                                    let entry = entry.clone();
+                                                    move |_| {
+                                                        match meeting_summary::save(&entry, format) {
+                                                            Ok(path) => action_status.set(format!("Summary saved to {}", path)),
+                                                            Err(e) => action_status.set(e),
+                                                        }
+                                                        exporting.set(None);
+                                                    }
+                                                },
+                                                "Save {format.label()}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            if archiving.read().as_deref() == Some(entry.path.as_str()) {
+                                div { class: "library-editor",
+                                    div { class: "library-notes", "Archiving re-encodes the recording and replaces the original file." }
+                                    div { class: "library-editor-actions",
+                                        button {
+                                            class: "btn btn-text library-action",
+                                            onclick: move |_| archiving.set(None),
+                                            "Cancel"
+                                        }
+                                        for preset in archive::ArchivePreset::ALL {
+                                            button {
+                                                class: "btn btn-secondary library-action",
+                                                onclick: {
+                                                    let path = entry.path.clone();
+                                                    move |_| {
+                                                        archive::enqueue(&path, preset);
+                                                        archive_jobs.set(archive::jobs());
+                                                        archiving.set(None);
+                                                    }
+                                                },
+                                                "{preset.label()}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            if editing.read().as_deref() == Some(entry.path.as_str()) {
+                                div { class: "library-editor",
+                                    input {
+                                        class: "library-editor-input",
+                                        placeholder: "Tags, separated by commas",
+                                        value: "{draft_tags}",
+                                        oninput: move |evt| {
+                                            draft_tags.set(evt.value());
+                                            save_draft();
+                                        },
+                                    }
+                                    textarea {
+                                        class: "library-editor-input",
+                                        placeholder: "Notes",
+                                        rows: "3",
+                                        value: "{draft_notes}",
+                                        oninput: move |evt| {
+                                            draft_notes.set(evt.value());
+                                            save_draft();
+                                        },
+                                    }
+                                    div { class: "library-editor-actions",
+                                        button {
+                                            class: "btn btn-text library-action",
+                                            onclick: move |_| {
+                                                editing.set(None);
+                                                save_draft();
+                                            },
+                                            "Cancel"
+                                        }
+                                        button {
+                                            class: "btn btn-primary library-action",
+                                            onclick: {
+                                                let path = entry.path.clone();
+                                                move |_| {
+                                                    let tags = library::parse_tags(&draft_tags.read());
+                                                    let notes = draft_notes.read().clone();
+                                                    match library::annotate(&path, tags, notes) {
+                                                        Ok(()) => {
+                                                            editing.set(None);
+                                                            save_draft();
+                                                            entries.set(library::list_recordings());
+                                                        }
+                                                        Err(e) => action_status.set(format!("Could not save: {}", e)),
+                                                    }
+                                                }
+                                            },
+                                            "Save"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn NextMeetingNotification(events: Vec<auth::MeetingEvent>) -> Element {
+    // Find the next upcoming meeting (within next 24 hours)
+    let next_meeting = auth::next_meeting(&events, chrono::Duration::hours(24));
+    
+    if let Some(meeting) = next_meeting {
+        // Just the time for a meeting later today
+        let today = chrono::Local::now().date_naive();
+        let when = match meeting.start_time() {
+            Some(start) if start.with_timezone(&chrono::Local).date_naive() == today => time_format::time(start),
+            _ => meeting_start_text(meeting),
+        };
+        rsx! {
+            div { class: "next-meeting-bar",
+                div { class: "next-meeting-content",
+                    span { class: "next-meeting-label", "Next Meeting:" }
+                    span { class: "next-meeting-time", "{when}" }
+                    span { class: "next-meeting-title", "- {meeting.event_summary}" }
+                }
+            }
+        }
+    } else {
+        rsx! { div {} }
+    }
+}
+
+#[component]
+fn CallPrompt(call: call_detect::DetectedCall) -> Element {
+    rsx! {
+        div { class: "next-meeting-bar call-prompt",
+            div { class: "next-meeting-content",
+                span { class: "next-meeting-label", "{call.provider} call detected" }
+                button {
+                    class: "btn btn-success library-action",
+                    onclick: move |_| {
+                        call_detect::dismiss();
+                        tokio::spawn(record_from(None));
+                    },
+                    "Record"
+                }
+                button {
+                    class: "btn btn-text library-action",
+                    onclick: move |_| call_detect::dismiss(),
+                    "Not now"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn RecordLinkPrompt(source: Option<deep_link::LinkSource>, on_done: EventHandler<()>) -> Element {
+    let what = source.map_or_else(|| "recording".to_string(), |s| format!("recording {}", s.label()));
+    rsx! {
+        div { class: "next-meeting-bar call-prompt",
+            div { class: "next-meeting-content",
+                span { class: "next-meeting-label", "A link asked to start {what}" }
+                button {
+                    class: "btn btn-success library-action",
+                    onclick: move |_| {
+                        on_done.call(());
+                        tokio::spawn(record_from(source));
+                    },
+                    "Record"
+                }
+                button {
+                    class: "btn btn-text library-action",
+                    onclick: move |_| on_done.call(()),
+                    "Ignore"
+                }
+            }
+        }
+    }
+}
+
+/// Profile picker shown before recording starts
+#[cfg(feature = "macos_15_0")]
+#[component]
+fn ProfilePicker() -> Element {
+    let mut settings = use_signal(profiles::ProfileSettings::load);
+    let (_, _, _, _, _, _, mtg_events, _) = get_global_state();
+    let current = settings.read().clone();
+    let hint = if current.selected.is_empty() {
+        let events = mtg_events.lock().unwrap();
+        match current.matching(sidecar::current_meeting(&events)) {
+            Some(profile) => format!("{} for this meeting: {}", profile.name, profile.summary()),
+            None => "No profile matches this meeting; recording with the default settings".to_string(),
+        }
+    } else {
+        current.choose(None).map(|p| p.summary()).unwrap_or_default()
+    };
+
+    rsx! {
+        label { class: "settings-row",
+            span { "Profile" }
+            select {
+                class: "library-editor-input settings-select",
+                value: "{current.selected}",
+                onchange: move |evt| {
+                    let mut updated = settings.read().clone();
+                    updated.selected = evt.value();
+                    if let Err(e) = updated.save() {
+                        toast::error(e);
+                    }
+                    settings.set(updated);
+                },
+                option { value: "", selected: current.selected.is_empty(), "Automatic" }
+                for profile in current.profiles.iter() {
+                    option {
+                        value: "{profile.name}",
+                        selected: current.selected == profile.name,
+                        "{profile.name}"
+                    }
+                }
+            }
+        }
+        div { class: "library-notes", "{hint}" }
+    }
+}
+
+#[cfg(not(feature = "macos_15_0"))]
+#[component]
+fn ProfilePicker() -> Element {
+    rsx! {}
+}
+
+/// Keywords that pick each recording profile from the calendar, and its post-processing
+#[cfg(feature = "macos_15_0")]
+#[component]
+fn ProfileSettingsSection() -> Element {
+    let mut settings = use_signal(profiles::ProfileSettings::load);
+    // Keywords as typed, so commas and spaces survive until Save splits them
+    let mut keyword_drafts = use_signal(|| {
+        settings.peek().profiles.iter().map(|p| p.keywords.join(", ")).collect::<Vec<_>>()
+    });
+    let mut status = use_signal(String::new);
+    let current = settings.read().clone();
+
+    rsx! {
+        div { class: "settings-section",
+            div { class: "settings-section-title", "Recording Profiles" }
+            div { class: "library-notes",
+                "Meetings whose title contains one of a profile's keywords are recorded with it. Profiles are defined in {profiles::ProfileSettings::config_path().display()}."
+            }
+            for (index, profile) in current.profiles.iter().enumerate() {
+                div { key: "{profile.name}", class: "profile-settings",
+                    div { class: "settings-row",
+                        span { "{profile.name} ({profile.summary()})" }
+                        input {
+                            class: "library-editor-input settings-select",
+                            placeholder: "Keywords, comma separated",
+                            value: keyword_drafts.read().get(index).cloned().unwrap_or_default(),
+                            oninput: move |evt| {
+                                if let Some(draft) = keyword_drafts.write().get_mut(index) {
+                                    *draft = evt.value();
+                                }
+                            },
+                        }
+                    }
+                    div { class: "settings-row",
+                        span { "Before upload" }
+                        for step in pipeline::Step::all() {
+                            label { class: "pipeline-step",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: profile.step(&step).is_some(),
+                                    onchange: {
+                                        let step = step.clone();
+                                        move |evt: FormEvent| settings.write().profiles[index].set_step(step.clone(), evt.checked())
+                                    },
+                                }
+                                "{step.label()}"
+                            }
+                        }
+                    }
+                    if let Some(pipeline::Step::Transcode { preset }) = profile.step(&pipeline::Step::Transcode { preset: archive::ArchivePreset::Standard }).cloned() {
+                        label { class: "settings-row",
+                            span { "Transcode to" }
+                            select {
+                                class: "library-editor-input settings-select",
+                                onchange: move |evt| {
+                                    if let Some(preset) = evt.value().parse::<usize>().ok().and_then(|i| archive::ArchivePreset::ALL.get(i)) {
+                                        settings.write().profiles[index].set_step(pipeline::Step::Transcode { preset: *preset }, true);
+                                    }
+                                },
+                                for (i, option_preset) in archive::ArchivePreset::ALL.iter().enumerate() {
+                                    option { value: "{i}", selected: *option_preset == preset, "{option_preset.label()}" }
+                                }
+                            }
+                        }
+                    }
+                    if let Some(pipeline::Step::Transcribe { model }) = profile.step(&pipeline::Step::Transcribe { model: String::new() }).cloned() {
+                        label { class: "settings-row",
+                            span { "Whisper model" }
+                            input {
+                                class: "library-editor-input settings-select",
+                                placeholder: "Path to a ggml-*.bin model, or empty for the Models folder",
+                                value: "{model}",
+                                oninput: move |evt| {
+                                    settings.write().profiles[index].set_step(pipeline::Step::Transcribe { model: evt.value() }, true);
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "library-notes",
+                "Steps run in the order shown, each on the result of the one before. Normalizing and transcoding need ffmpeg; transcribing needs whisper.cpp (whisper-cli). Recordings without a profile are transcribed once a model is in the Models folder of the data folder."
+            }
+            if !status.read().is_empty() {
+                div { class: "library-notes", "{status}" }
+            }
+            div { class: "library-editor-actions",
+                button {
+                    class: "btn btn-secondary",
+                    onclick: move |_| {
+                        let mut updated = settings.read().clone();
+                        for (profile, draft) in updated.profiles.iter_mut().zip(keyword_drafts.read().iter()) {
+                            profile.keywords = draft
+                                .split(',')
+                                .map(|keyword| keyword.trim().to_string())
+                                .filter(|keyword| !keyword.is_empty())
+                                .collect();
+                        }
+                        match updated.save() {
+                            Ok(()) => {
+                                settings.set(updated);
+                                status.set("Saved".to_string());
+                            }
+                            Err(e) => status.set(e),
+                        }
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "macos_15_0"))]
+#[component]
+fn ProfileSettingsSection() -> Element {
+    rsx! {}
+}
+
+/// On-screen timer and hourly reminder while recording
+#[component]
+fn RecordingTimerSection() -> Element {
+    let mut settings = use_signal(recording_timer::TimerSettings::load);
+    let mut status = use_signal(String::new);
+
+    rsx! {
+        div { class: "settings-section",
+            div { class: "settings-section-title", "Recording Reminders" }
+            label { class: "settings-row",
+                span { "Show a timer on screen while recording" }
+                input {
+                    r#type: "checkbox",
+                    checked: settings.read().shows_timer,
+                    onchange: move |evt| settings.write().shows_timer = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Remind me every hour a recording runs" }
+                input {
+                    r#type: "checkbox",
+                    checked: settings.read().hourly_reminder,
+                    onchange: move |evt| settings.write().hourly_reminder = evt.checked(),
+                }
+            }
+            div { class: "library-notes", "The timer sits in the top-right corner of the main screen and is left out of the recording." }
+            if !status.read().is_empty() {
+                div { class: "library-notes", "{status}" }
+            }
+            div { class: "library-editor-actions",
+                button {
+                    class: "btn btn-secondary",
+                    onclick: move |_| {
+                        match settings.read().save() {
+                            Ok(()) => status.set("Saved".to_string()),
+                            Err(e) => status.set(e),
+                        }
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+/// Copying new screenshots to the clipboard
+#[component]
+fn ClipboardSettingsSection() -> Element {
+    let mut settings = use_signal(clipboard::ClipboardSettings::load);
+    let mut status = use_signal(String::new);
+
+    rsx! {
+        div { class: "settings-section",
+            div { class: "settings-section-title", "Screenshots" }
+            label { class: "settings-row",
+                span { "Copy new screenshots to the clipboard" }
+                input {
+                    r#type: "checkbox",
+                    checked: settings.read().copy_screenshots,
+                    onchange: move |evt| settings.write().copy_screenshots = evt.checked(),
+                }
+            }
+            div { class: "library-notes", "Screenshots are always saved to the Screenshots folder; copying one replaces whatever is on the clipboard." }
+            if !status.read().is_empty() {
+                div { class: "library-notes", "{status}" }
+            }
+            div { class: "library-editor-actions",
+                button {
+                    class: "btn btn-secondary",
+                    onclick: move |_| {
+                        match settings.read().save() {
+                            Ok(()) => status.set("Saved".to_string()),
+                            Err(e) => status.set(e),
+                        }
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+/// Preparing the recording as soon as a source is picked
+#[component]
+fn StandbySettingsSection() -> Element {
+    let mut settings = use_signal(standby::current);
+    let mut status = use_signal(String::new);
+
+    rsx! {
+        div { class: "settings-section",
+            div { class: "settings-section-title", "Standby" }
+            label { class: "settings-row",
+                span { "Get ready to record as soon as a source is picked" }
+                input {
+                    r#type: "checkbox",
+                    checked: settings.read().enabled,
+                    onchange: move |evt| settings.write().enabled = evt.checked(),
+                }
+            }
+            div { class: "library-notes", "Checks and recording settings are applied ahead of time, so Start Recording skips them. Nothing is recorded until you press Start." }
+            if !status.read().is_empty() {
+                div { class: "library-notes", "{status}" }
+            }
+            div { class: "library-editor-actions",
+                button {
+                    class: "btn btn-secondary",
+                    onclick: move |_| {
+                        match settings.read().save() {
+                            Ok(()) => status.set("Saved".to_string()),
+                            Err(e) => status.set(e),
+                        }
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+/// What the app may see of the calendar, and whether meeting titles are logged
+#[component]
+fn CalendarPrivacySection() -> Element {
+    let mut settings = use_signal(calendar_privacy::current);
+    let mut status = use_signal(String::new);
+    let current = settings.read().clone();
+
+    rsx! {
+        div { class: "settings-section",
+            div { class: "settings-section-title", "Calendar Privacy" }
+            label { class: "settings-row",
+                span { "Meeting details" }
+                select {
+                    class: "library-editor-input settings-select",
+                    onchange: move |evt| {
+                        if let Some(access) = evt.value().parse::<usize>().ok().and_then(|i| calendar_privacy::CalendarAccess::ALL.get(i)) {
+                            settings.write().access = *access;
+                        }
+                    },
+                    for (i, access) in calendar_privacy::CalendarAccess::ALL.iter().enumerate() {
+                        option { value: "{i}", selected: current.access == *access, "{access.label()}" }
+                    }
+                }
+            }
+            label { class: "settings-row",
+                span { "Hide meeting titles in logs and the journal" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.anonymize_titles,
+                    onchange: move |evt| settings.write().anonymize_titles = evt.checked(),
+                }
+            }
+            div { class: "library-notes", "With titles only, participants and meeting links are dropped as soon as events arrive. Turned off, no meetings are fetched, so reminders and picking a profile by meeting stop too." }
+            if !status.read().is_empty() {
+                div { class: "library-notes", "{status}" }
+            }
+            div { class: "library-editor-actions",
+                button {
+                    class: "btn btn-secondary",
+                    onclick: move |_| {
+                        match settings.read().save() {
+                            Ok(()) => {
+                                // Events already fetched go by the new settings straight away
+                                let (_, _, _, _, _, _, meeting_events, _) = get_global_state();
+                                calendar_privacy::restrict(&mut meeting_events.lock().unwrap());
+                                status.set("Saved".to_string());
+                            }
+                            Err(e) => status.set(e),
+                        }
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+/// Pause and stop the recording from a phone on the same network
+#[component]
+fn RemoteControlSection() -> Element {
+    let mut settings = use_signal(remote::RemoteSettings::load);
+    let mut status = use_signal(String::new);
+    let current = settings.read().clone();
+    // Saved settings take effect straight away
+    let save = |updated: &remote::RemoteSettings| {
+        let (tx, _, is_recording, _, _, capture_state, _, _) = get_global_state();
+        let Some(commands) = tx else {
+            return Err("Capture backend is not running".to_string());
+        };
+        let controls = remote::Controls { commands, is_recording, capture_state };
+        updated.save().and_then(|()| remote::apply(updated, &controls))
+    };
+
+    rsx! {
+        div { class: "settings-section",
+            div { class: "settings-section-title", "Remote Control" }
+            label { class: "settings-row",
+                span { "Control recordings from a phone on this network" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.enabled,
+                    onchange: move |evt| settings.write().enabled = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Port" }
+                input {
+                    class: "library-editor-input settings-select",
+                    r#type: "number",
+                    min: "1024",
+                    max: "65535",
+                    value: "{current.port}",
+                    oninput: move |evt| {
+                        if let Ok(port) = evt.value().parse::<u16>() {
+                            settings.write().port = port.max(1024);
+                        }
+                    },
+                }
+            }
+            if current.enabled {
+                div { class: "library-notes", "Open this link on the phone to pair it: {current.remote_url()}" }
+            }
+            div { class: "library-notes", "The page shows the recording's time, with Pause and Stop buttons. Anyone with the link can use it; a new link unpairs every phone that had the old one." }
+            if !status.read().is_empty() {
+                div { class: "library-notes", "{status}" }
+            }
+            div { class: "library-editor-actions",
+                if current.enabled {
+                    button {
+                        class: "btn btn-text",
+                        onclick: move |_| copy_to_clipboard(&settings.read().remote_url()),
+                        "Copy Link"
+                    }
+                    button {
+                        class: "btn btn-text",
+                        onclick: move |_| {
+                            let mut updated = settings.read().clone();
+                            let result = updated.new_link().and_then(|()| save(&updated));
+                            settings.set(updated);
+                            match result {
+                                Ok(()) => status.set("New link made; pair the phone again".to_string()),
+                                Err(e) => status.set(e),
+                            }
+                        },
+                        "New Link"
+                    }
+                }
+                button {
+                    class: "btn btn-secondary",
+                    onclick: move |_| {
+                        let updated = settings.read().clone();
+                        match save(&updated) {
+                            Ok(()) => status.set("Saved".to_string()),
+                            Err(e) => status.set(e),
+                        }
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+/// Extra microphones recorded into labeled tracks of their own
+#[component]
+fn AudioTrackSettingsSection() -> Element {
+    let mut settings = use_signal(audio_tracks::AudioTrackSettings::load);
+    let mut status = use_signal(String::new);
+    let microphones = use_hook(AudioInputDevice::list);
+    let current = settings.read().clone();
+
+    rsx! {
+        div { class: "settings-section",
+            div { class: "settings-section-title", "Microphone Tracks" }
+            label { class: "settings-row",
+                span { "Label for the capture microphone" }
+                input {
+                    class: "library-editor-input",
+                    placeholder: "e.g. Room",
+                    value: "{current.primary.label}",
+                    oninput: move |evt| settings.write().primary.label = evt.value(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Language" }
+                select {
+                    class: "library-editor-input settings-select",
+                    onchange: move |evt| settings.write().primary.language = evt.value(),
+                    for (code, name) in audio_tracks::LANGUAGES {
+                        option { value: "{code}", selected: current.primary.language == *code, "{name}" }
+                    }
+                }
+            }
+            for (index, mic) in current.extra.iter().enumerate() {
+                label { class: "settings-row",
+                    span { "Track {index + 2}" }
+                    select {
+                        class: "library-editor-input settings-select",
+                        onchange: move |evt| settings.write().extra[index].device_id = evt.value(),
+                        option { value: "", selected: mic.device_id.is_empty(), "Choose a microphone" }
+                        for device in microphones.iter() {
+                            option {
+                                value: "{device.id}",
+                                selected: mic.device_id == device.id,
+                                "{device.name}"
+                            }
+                        }
+                    }
+                }
+                label { class: "settings-row",
+                    input {
+                        class: "library-editor-input",
+                        placeholder: "e.g. Interpreter – Spanish",
+                        value: "{mic.track.label}",
+                        oninput: move |evt| settings.write().extra[index].track.label = evt.value(),
+                    }
+                    select {
+                        class: "library-editor-input settings-select",
+                        onchange: move |evt| settings.write().extra[index].track.language = evt.value(),
+                        for (code, name) in audio_tracks::LANGUAGES {
+                            option { value: "{code}", selected: mic.track.language == *code, "{name}" }
+                        }
+                    }
+                    button {
+                        class: "btn btn-text",
+                        onclick: move |_| {
+                            settings.write().extra.remove(index);
+                        },
+                        "Remove"
+                    }
+                }
+            }
+            div { class: "library-notes", "Each extra microphone is recorded into a track of its own, named by its label, for meetings with interpreters. Needs ffmpeg; segmented recordings are recorded without them." }
+            if !status.read().is_empty() {
+                div { class: "library-notes", "{status}" }
+            }
+            div { class: "library-editor-actions",
+                button {
+                    class: "btn btn-secondary",
+                    onclick: move |_| settings.write().extra.push(audio_tracks::ExtraMicrophone::default()),
+                    "Add Microphone"
+                }
+                button {
+                    class: "btn btn-secondary",
+                    onclick: move |_| {
+                        match settings.read().save() {
+                            Ok(()) => status.set("Saved".to_string()),
+                            Err(e) => status.set(e),
+                        }
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+/// Drawing on the screen during display and window recordings
+#[component]
+fn AnnotationSettingsSection() -> Element {
+    let mut settings = use_signal(annotations::current);
+    let mut status = use_signal(String::new);
+    let tools = [
+        (annotations::Tool::Pen, "pen", "Pen"),
+        (annotations::Tool::Arrow, "arrow", "Arrow"),
+        (annotations::Tool::Highlight, "highlight", "Highlighter"),
+    ];
+
+    rsx! {
+        div { class: "settings-section",
+            div { class: "settings-section-title", "Drawing" }
+            label { class: "settings-row",
+                span { "Draw on the screen while recording a display or window" }
+                input {
+                    r#type: "checkbox",
+                    checked: settings.read().enabled,
+                    onchange: move |evt| settings.write().enabled = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Draw with" }
+                select {
+                    class: "library-editor-input settings-select",
+                    onchange: move |evt| {
+                        if let Some(&(tool, _, _)) = tools.iter().find(|(_, value, _)| *value == evt.value()) {
+                            settings.write().tool = tool;
+                        }
+                    },
+                    for (tool, value, label) in tools {
+                        option { value: "{value}", selected: settings.read().tool == tool, "{label}" }
+                    }
+                }
+            }
+            div { class: "library-notes", "Press {hotkey::DRAW_SHORTCUT} to start or stop drawing and {hotkey::ERASE_SHORTCUT} to erase. Drawings are recorded as seen on screen and cleared when the recording stops." }
+            if !status.read().is_empty() {
+                div { class: "library-notes", "{status}" }
+            }
+            div { class: "library-editor-actions",
+                button {
+                    class: "btn btn-secondary",
+                    onclick: move |_| {
+                        match settings.read().save() {
+                            Ok(()) => status.set("Saved".to_string()),
+                            Err(e) => status.set(e),
+                        }
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+/// Switches drawing on the screen on and off during a recording
+#[component]
+fn DrawButton() -> Element {
+    let mut state = use_signal(|| (annotations::available(), annotations::is_drawing()));
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            let current = (annotations::available(), annotations::is_drawing());
+            if *state.read() != current {
+                state.set(current);
+            }
+        }
+    });
+    let (available, drawing) = *state.read();
+    if !available {
+        return rsx! {};
+    }
+
+    rsx! {
+        button {
+            class: if drawing { "btn btn-success" } else { "btn btn-secondary" },
+            title: "Draw on the screen ({hotkey::DRAW_SHORTCUT}); {hotkey::ERASE_SHORTCUT} erases",
+            onclick: move |_| annotations::toggle_drawing(),
+            if drawing { "✏️ Stop Drawing" } else { "✏️ Draw" }
+        }
+        if drawing {
+            button {
+                class: "btn btn-text",
+                onclick: move |_| annotations::clear(),
+                "Erase"
+            }
+        }
+    }
+}
+
+/// What large uploads do over a phone's mobile data
+#[cfg(feature = "macos_15_0")]
+#[component]
+fn MeteredUploadsSection() -> Element {
+    let mut settings = use_signal(network::NetworkSettings::load);
+    let mut status = use_signal(String::new);
+    let choices = [
+        (network::MeteredUploads::Ask, "ask", "Ask first"),
+        (network::MeteredUploads::Queue, "queue", "Wait for Wi-Fi"),
+        (network::MeteredUploads::Upload, "upload", "Upload anyway"),
+    ];
+
+    rsx! {
+        div { class: "settings-section",
+            div { class: "settings-section-title", "Mobile Data" }
+            label { class: "settings-row",
+                span { "Large uploads over a phone or hotspot" }
+                select {
+                    class: "library-editor-input settings-select",
+                    onchange: move |evt| {
+                        let Some(&(policy, _, _)) = choices.iter().find(|(_, value, _)| *value == evt.value()) else {
+                            return;
+                        };
+                        let updated = network::NetworkSettings { metered_uploads: policy };
+                        match updated.save() {
+                            Ok(()) => status.set(String::new()),
+                            Err(e) => status.set(e),
+                        }
+                        settings.set(updated);
+                    },
+                    for (policy, value, label) in choices {
+                        option { value: "{value}", selected: settings.read().metered_uploads == policy, "{label}" }
+                    }
+                }
+            }
+            div { class: "library-notes", "Tethering to a phone is detected from the network route. Queued uploads can be sent from the library at any time." }
+            if !status.read().is_empty() {
+                div { class: "library-notes", "{status}" }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "macos_15_0"))]
+#[component]
+fn MeteredUploadsSection() -> Element {
+    rsx! {}
+}
+
+/// Sends a queued recording now, whatever the connection
+#[cfg(feature = "macos_15_0")]
+#[component]
+fn UploadNowButton(path: String) -> Element {
+    let mut status = use_signal(String::new);
+    let mut uploading = use_signal(|| false);
+
+    rsx! {
+        button {
+            class: "btn btn-text library-action",
+            disabled: *uploading.read(),
+            onclick: move |_| {
+                let path = path.clone();
+                uploading.set(true);
+                spawn(async move {
+                    let (_, _, _, _, _, _, _, auth_tokens) = get_global_state();
+                    let result = match upload_queue::access_token(&auth_tokens).await {
+                        Some(token) => upload_queue::upload_now(&token, &path).await,
+                        None => Err("Sign in to upload".to_string()),
+                    };
+                    match result {
+                        Ok(_) => status.set("Uploaded".to_string()),
+                        Err(e) => status.set(e),
+                    }
+                    uploading.set(false);
+                });
+            },
+            if *uploading.read() { "Uploading…" } else { "Upload Now" }
+        }
+        if !status.read().is_empty() {
+            div { class: "library-notes", "{status}" }
+        }
+    }
+}
+
+#[cfg(not(feature = "macos_15_0"))]
+#[component]
+fn UploadNowButton(path: String) -> Element {
+    let _ = path;
+    rsx! {}
+}
+
+/// Sends the attachments that failed after an upload
+#[cfg(feature = "macos_15_0")]
+#[component]
+fn RetryAttachmentsButton(path: String) -> Element {
+    let mut status = use_signal(String::new);
+    let mut sending = use_signal(|| false);
+
+    rsx! {
+        button {
+            class: "btn btn-text library-action",
+            disabled: *sending.read(),
+            onclick: move |_| {
+                let path = path.clone();
+                sending.set(true);
+                spawn(async move {
+                    let (_, _, _, _, _, _, _, auth_tokens) = get_global_state();
+                    let result = match upload_queue::access_token(&auth_tokens).await {
+                        Some(token) => upload::retry_attachments(&token, &path).await,
+                        None => Err("Sign in to send attachments".to_string()),
+                    };
+                    match result {
+                        Ok(()) => status.set("Attachments sent".to_string()),
+                        Err(e) => status.set(e),
+                    }
+                    sending.set(false);
+                });
+            },
+            if *sending.read() { "Sending…" } else { "Retry Attachments" }
+        }
+        if !status.read().is_empty() {
+            div { class: "library-notes", "{status}" }
+        }
+    }
+}
+
+#[cfg(not(feature = "macos_15_0"))]
+#[component]
+fn RetryAttachmentsButton(path: String) -> Element {
+    let _ = path;
+    rsx! {}
+}
+
+/// Queued uploads in the order they go, with controls to rearrange them
+#[cfg(feature = "macos_15_0")]
+#[component]
+fn UploadQueuePanel() -> Element {
+    let mut queue = use_signal(upload_queue::entries);
+    let mut status = use_signal(String::new);
+
+    // Read again only when recordings enter or leave the queue, or it is rearranged
+    use_future(move || async move {
+        let mut changes = upload_queue::subscribe();
+        while changes.changed().await.is_ok() {
+            let latest = upload_queue::entries();
+            if *queue.read() != latest {
+                queue.set(latest);
+            }
+        }
+    });
+    let mut apply = move |result: Result<(), String>| {
+        match result {
+            Ok(()) => status.set(String::new()),
+            Err(e) => status.set(e),
+        }
+        queue.set(upload_queue::entries());
+    };
+
+    let queue_now = queue.read().clone();
+    if queue_now.is_empty() {
+        return rsx! {};
+    }
+    let last = queue_now.len() - 1;
+
+    rsx! {
+        div { class: "upload-queue",
+            div { class: "upload-queue-title", "Upload queue" }
+            for (index, entry) in queue_now.into_iter().enumerate() {
+                div { key: "{entry.path}", class: if entry.paused { "upload-queue-row paused" } else { "upload-queue-row" },
+                    span { class: "upload-queue-position", "{index + 1}" }
+                    span { class: "upload-queue-name", "{entry.file_name()}" }
+                    if entry.high_priority {
+                        span { class: "library-tag", "High priority" }
+                    }
+                    if entry.paused {
+                        span { class: "library-tag", "Paused" }
+                    }
+                    div { class: "library-actions",
+                        button {
+                            class: "btn btn-text library-action",
+                            title: "Upload earlier",
+                            disabled: index == 0,
+                            onclick: {
+                                let path = entry.path.clone();
+                                move |_| apply(upload_queue::move_entry(&path, true))
+                            },
+                            "↑"
+                        }
+                        button {
+                            class: "btn btn-text library-action",
+                            title: "Upload later",
+                            disabled: index == last,
+                            onclick: {
+                                let path = entry.path.clone();
+                                move |_| apply(upload_queue::move_entry(&path, false))
+                            },
+                            "↓"
+                        }
+                        button {
+                            class: "btn btn-text library-action",
+                            title: "Upload ahead of the rest, without waiting for a better connection",
+                            onclick: {
+                                let path = entry.path.clone();
+                                let high_priority = !entry.high_priority;
+                                move |_| apply(upload_queue::set_high_priority(&path, high_priority))
+                            },
+                            if entry.high_priority { "Normal Priority" } else { "Prioritize" }
+                        }
+                        if entry.high_priority {
+                            button {
+                                class: "btn btn-text library-action",
+                                title: "Hold back everything else in the queue",
+                                onclick: {
+                                    let path = entry.path.clone();
+                                    move |_| apply(upload_queue::pause_others(&path))
+                                },
+                                "Pause Others"
+                            }
+                        }
+                        button {
+                            class: "btn btn-text library-action",
+                            onclick: {
+                                let path = entry.path.clone();
+                                let paused = !entry.paused;
+                                move |_| apply(upload_queue::set_paused(&path, paused))
+                            },
+                            if entry.paused { "Resume" } else { "Pause" }
+                        }
+                        UploadNowButton { path: entry.path.clone() }
+                    }
+                }
+            }
+            if !status.read().is_empty() {
+                div { class: "library-notes", "{status}" }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "macos_15_0"))]
+#[component]
+fn UploadQueuePanel() -> Element {
+    rsx! {}
+}
+
+// Stream and recording options, the same ones the overlay's config menus change
+#[component]
+fn CaptureSettingsSection() -> Element {
+    let mut settings = use_signal(CaptureSettings::load);
+    let mut status = use_signal(String::new);
+    let microphones = use_hook(AudioInputDevice::list);
+    let current = settings.read().clone();
+
+    rsx! {
+        div { class: "settings-section",
+            div { class: "settings-section-title", "Capture" }
+            label { class: "settings-row",
+                span { "Frame rate" }
+                select {
+                    class: "library-editor-input settings-select",
+                    onchange: move |evt| {
+                        if let Ok(fps) = evt.value().parse() {
+                            settings.write().fps = fps;
+                        }
+                    },
+                    for fps in capture_settings::FPS_OPTIONS {
+                        option { value: "{fps}", selected: current.fps == fps, "{fps} fps" }
+                    }
+                }
+            }
+            label { class: "settings-row",
+                span { "Show cursor" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.shows_cursor,
+                    onchange: move |evt| settings.write().shows_cursor = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Capture system audio" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.captures_audio,
+                    onchange: move |evt| settings.write().captures_audio = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Leave out this app's sounds" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.excludes_own_audio,
+                    onchange: move |evt| settings.write().excludes_own_audio = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Capture microphone" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.captures_microphone,
+                    onchange: move |evt| settings.write().captures_microphone = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Microphone" }
+                select {
+                    class: "library-editor-input settings-select",
+                    onchange: move |evt| settings.write().microphone_device = evt.value(),
+                    option { value: "", selected: current.microphone_device.is_empty(), "System default" }
+                    for device in microphones.iter() {
+                        option {
+                            value: "{device.id}",
+                            selected: current.microphone_device == device.id,
+                            "{device.name}"
+                        }
+                    }
+                }
+            }
+            label { class: "settings-row",
+                span { "Sample rate" }
+                select {
+                    class: "library-editor-input settings-select",
+                    onchange: move |evt| {
+                        if let Ok(rate) = evt.value().parse() {
+                            settings.write().sample_rate = rate;
+                        }
+                    },
+                    for rate in capture_settings::SAMPLE_RATE_OPTIONS {
+                        option { value: "{rate}", selected: current.sample_rate == rate, "{rate} Hz" }
+                    }
+                }
+            }
+            label { class: "settings-row",
+                span { "Audio channels" }
+                select {
+                    class: "library-editor-input settings-select",
+                    onchange: move |evt| {
+                        if let Ok(channels) = evt.value().parse() {
+                            settings.write().channel_count = channels;
+                        }
+                    },
+                    for channels in capture_settings::CHANNEL_OPTIONS {
+                        option {
+                            value: "{channels}",
+                            selected: current.channel_count == channels,
+                            if channels == 1 { "Mono" } else { "Stereo" }
+                        }
+                    }
+                }
+            }
+            label { class: "settings-row",
+                span { "Scale to fit" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.scales_to_fit,
+                    onchange: move |evt| settings.write().scales_to_fit = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Preserve aspect ratio" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.preserves_aspect_ratio,
+                    onchange: move |evt| settings.write().preserves_aspect_ratio = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Opaque" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.opaque,
+                    onchange: move |evt| settings.write().opaque = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Capture shadows only" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.shadows_only,
+                    onchange: move |evt| settings.write().shadows_only = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Ignore display shadows" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.ignores_shadows,
+                    onchange: move |evt| settings.write().ignores_shadows = evt.checked(),
+                }
+            }
+            label { class: "settings-row",
+                span { "Pixel format" }
+                select {
+                    class: "library-editor-input settings-select",
+                    onchange: move |evt| settings.write().pixel_format = evt.value(),
+                    for format in capture_settings::PIXEL_FORMATS {
+                        option { value: "{format}", selected: current.pixel_format == format, "{format}" }
+                    }
+                }
+            }
+            label { class: "settings-row",
+                span { "Frame queue" }
+                select {
+                    class: "library-editor-input settings-select",
+                    onchange: move |evt| {
+                        if let Ok(depth) = evt.value().parse() {
+                            settings.write().queue_depth = depth;
+                        }
+                    },
+                    for depth in capture_settings::QUEUE_OPTIONS {
+                        option { value: "{depth}", selected: current.queue_depth == depth, "{depth} frames" }
+                    }
+                }
+            }
+            if cfg!(feature = "macos_15_0") {
+                label { class: "settings-row",
+                    span { "Video codec" }
+                    select {
+                        class: "library-editor-input settings-select",
+                        onchange: move |evt| settings.write().codec = evt.value(),
+                        for (code, name) in capture_settings::CODECS {
+                            option { value: "{code}", selected: current.codec == code, "{name}" }
+                        }
+                    }
+                }
+                label { class: "settings-row",
+                    span { "File type" }
+                    select {
+                        class: "library-editor-input settings-select",
+                        onchange: move |evt| settings.write().file_type = evt.value(),
+                        for (code, name) in capture_settings::FILE_TYPES {
+                            option { value: "{code}", selected: current.file_type == code, "{name}" }
+                        }
+                    }
+                }
+                label { class: "settings-row",
+                    span { "Split recordings" }
+                    select {
+                        class: "library-editor-input settings-select",
+                        onchange: move |evt| settings.write().segment_minutes = evt.value().parse().ok(),
+                        for minutes in capture_settings::SEGMENT_OPTIONS {
+                            option {
+                                value: minutes.map(|m| m.to_string()).unwrap_or_default(),
+                                selected: current.segment_minutes == minutes,
+                                if let Some(minutes) = minutes { "Every {minutes} min" } else { "Off" }
+                            }
+                        }
+                    }
+                }
+            }
+            label { class: "settings-row",
+                span { "Stop sharing when not recording for" }
+                select {
+                    class: "library-editor-input settings-select",
+                    onchange: move |evt| settings.write().idle_stop_minutes = evt.value().parse().ok(),
+                    for minutes in capture_settings::IDLE_STOP_OPTIONS {
+                        option {
+                            value: minutes.map(|m| m.to_string()).unwrap_or_default(),
+                            selected: current.idle_stop_minutes == minutes,
+                            if let Some(minutes) = minutes { "{minutes} min" } else { "Never" }
+                        }
+                    }
+                }
+            }
+            div { class: "library-notes", "The overlay's config menus change these same settings. Recording options apply from the next recording." }
+            if !status.read().is_empty() {
+                div { class: "library-notes", "{status}" }
+            }
+            div { class: "library-editor-actions",
+                button {
+                    class: "btn btn-secondary",
+                    onclick: move |_| {
+                        match settings.read().save() {
+                            Ok(()) => {
+                                let (tx, _, _, _, _, _, _, _) = get_global_state();
+                                if let Some(ref sender) = tx {
+                                    let _ = sender.send(CaptureCommand::ApplyCaptureSettings);
+                                }
+                                status.set("Saved".to_string());
+                            }
+                            Err(e) => status.set(e),
+                        }
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+// Covers the main window while Screen Recording is not allowed, with the way to fix it
+#[component]
+fn ScreenAccessGate() -> Element {
+    // Why access was denied, kept while checking again
+    let mut denied = use_signal(|| None::<String>);
+    let mut checking = use_signal(|| false);
+    use_future(move || async move {
+        loop {
+            let access = screen_access::state();
+            checking.set(access == screen_access::ScreenAccess::Checking);
+            match access {
+                screen_access::ScreenAccess::Granted if denied.read().is_some() => denied.set(None),
+                screen_access::ScreenAccess::Denied(reason) if denied.read().as_ref() != Some(&reason) => {
+                    denied.set(Some(reason))
+                }
+                _ => {}
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+    });
+
+    let Some(reason) = denied.read().clone() else {
+        return rsx! {};
+    };
+    rsx! {
+        div { class: "calendar-overlay",
+            div { class: "calendar-container",
+                div { class: "calendar-header",
+                    div { class: "calendar-header-content",
+                        h2 { class: "calendar-title", "Screen Recording Needed" }
+                        p { class: "calendar-subtitle", "{reason}" }
+                    }
+                }
+                div { class: "calendar-content",
+                    div { class: "library-notes",
+                        "Talka Recorder needs the Screen Recording permission to capture meetings. In System Settings → Privacy & Security → Screen Recording, switch it on for this app, then check again. macOS may ask you to quit and reopen the app."
+                    }
+                    div { class: "library-editor-actions",
+                        button {
+                            class: "btn btn-success",
+                            onclick: move |_| screen_access::request(),
+                            "Open System Settings"
+                        }
+                        button {
+                            class: "btn btn-secondary",
+                            disabled: *checking.read(),
+                            onclick: move |_| {
+                                checking.set(true);
+                                tokio::spawn(async move {
+                                    let _ = tokio::task::spawn_blocking(screen_access::check).await;
+                                });
+                            },
+                            if *checking.read() { "Checking…" } else { "Check Again" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Shown when capture failed to start, with a way to try again
+#[component]
+fn CaptureErrorBar() -> Element {
+    let mut error = use_signal(input::start_error);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+            let error_now = input::start_error();
+            if *error.read() != error_now {
+                error.set(error_now);
+            }
+        }
+    });
+
+    let Some(message) = error.read().clone() else {
+        return rsx! {};
+    };
+    rsx! {
+        div { class: "next-meeting-bar call-prompt",
+            div { class: "next-meeting-content",
+                span { class: "next-meeting-label", "Capture could not start: {message}" }
+                button {
+                    class: "btn btn-success library-action",
+                    onclick: move |_| {
+                        input::clear_start_error();
+                        let (tx, _, _, _, _, _, _, _) = get_global_state();
+                        if let Some(ref sender) = tx {
+                            let _ = sender.send(CaptureCommand::StartCapture);
+                        }
+                    },
+                    "Retry"
+                }
+                button {
+                    class: "btn btn-text library-action",
+                    onclick: move |_| input::clear_start_error(),
+                    "Dismiss"
+                }
+            }
+        }
+    }
+}
+
+// Starts recording once the pre-flight checks pass, or lists what needs a look first
+#[component]
+fn StartRecordingButton(is_capturing: bool) -> Element {
+    let mut checking = use_signal(|| false);
+    let mut warnings = use_signal(|| None::<doctor::Report>);
+    let mut ready = use_signal(standby::is_warm);
+
+    // In standby, keep the checks fresh so Start does not have to wait for them
+    use_future(move || async move {
+        let mut last_check: Option<std::time::Instant> = None;
+        loop {
+            let (_, is_cap, is_rec, _, _, cap_state, _, auth_tokens) = get_global_state();
+            let waiting = is_cap.load(Ordering::Relaxed) && !is_rec.load(Ordering::Relaxed);
+            if waiting && standby::current().enabled && last_check.map_or(true, |t| t.elapsed() >= standby::CHECK_INTERVAL) {
+                last_check = Some(std::time::Instant::now());
+                standby::refresh_checks(cap_state, auth_tokens).await;
+            }
+            let ready_now = standby::is_warm();
+            if *ready.read() != ready_now {
+                ready.set(ready_now);
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+        }
+    });
+
+    let mut start = move || {
+        warnings.set(None);
+        let (tx, _, _, _, _, _, _, _) = get_global_state();
+        if let Some(ref sender) = tx {
+            let _ = sender.send(CaptureCommand::StartRecording);
+        }
+    };
+
+    rsx! {
+        if let Some(report) = warnings.read().clone() {
+            div { class: "settings-section",
+                div { class: "settings-section-title", "Before You Record" }
+                for check in report.problems() {
+                    div { class: "library-notes", "{check}" }
+                }
+                div { class: "library-editor-actions",
+                    button {
+                        class: "btn btn-text",
+                        onclick: move |_| warnings.set(None),
+                        "Cancel"
+                    }
+                    button {
+                        class: "btn btn-secondary",
+                        onclick: move |_| start(),
+                        disabled: !is_capturing,
+                        "Record Anyway"
+                    }
+                }
+            }
+        } else {
+            button {
+                class: "btn btn-success btn-hero",
+                onclick: move |_| {
+                    if let Some(report) = standby::fresh_checks() {
+                        if report.problems().next().is_none() {
+                            start();
+                        } else {
+                            warnings.set(Some(report));
+                        }
+                        return;
+                    }
+                    checking.set(true);
+                    spawn(async move {
+                        let (_, _, _, _, _, cap_state, _, auth_tokens) = get_global_state();
+                        let report = preflight::run(cap_state, auth_tokens).await;
+                        checking.set(false);
+                        if report.problems().next().is_none() {
+                            start();
+                        } else {
+                            warnings.set(Some(report));
+                        }
+                    });
+                },
+                disabled: !is_capturing || *checking.read(),
+                if *checking.read() { "Checking…" } else { "Start Recording" }
+            }
+            if *ready.read() && is_capturing {
+                div { class: "library-notes", "⚡ Standing by: checks done and recording settings applied" }
+            }
+        }
+    }
+}
+
+// Today's captures and recordings as a strip, from the session journal
+#[component]
+fn TodayTimeline() -> Element {
+    let mut today = use_signal(timeline::today);
+
+    // Sessions end and uploads finish while the window shows this
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+            today.set(timeline::today());
+        }
+    });
+
+    let current = today.read().clone();
+    let (Some(from), Some(to)) = (current.from, current.to) else {
+        return rsx! {};
+    };
+    let now = chrono::Utc::now();
+    let captures: Vec<(String, String)> = current
+        .captures
+        .iter()
+        .map(|capture| {
+            let (left, width) = current.extent(&capture.span, now);
+            let source = capture.source.as_deref().unwrap_or("Capture");
+            (format!("left: {left:.2}%; width: {width:.2}%"), source.to_string())
+        })
+        .collect();
+    let recordings: Vec<(&'static str, String, String)> = current
+        .recordings
+        .iter()
+        .map(|recording| {
+            let (left, width) = current.extent(&recording.span, now);
+            let title = format!("{} · {}", recording.time_range(), recording.outcome.label());
+            (recording.outcome.css_class(), format!("left: {left:.2}%; width: {width:.2}%"), title)
+        })
+        .collect();
+    let errors: Vec<(String, String)> = current
+        .errors
+        .iter()
+        .map(|(at, message)| {
+            (format!("left: {:.2}%", current.position(*at)), format!("{} · {message}", time_format::time(*at)))
+        })
+        .collect();
+    // Newest first, which is what is usually being looked for
+    let rows: Vec<(String, String, &'static str, String)> = current
+        .recordings
+        .iter()
+        .rev()
+        .map(|recording| {
+            let what = recording.source.clone().unwrap_or_else(|| recording.file_name());
+            let detail = match recording.outcome {
+                timeline::Outcome::UploadFailed(ref error) => error.clone(),
+                _ => recording.file_name(),
+            };
+            (recording.time_range(), what, recording.outcome.label(), detail)
+        })
+        .collect();
+
+    rsx! {
+        div { class: "timeline",
+            div { class: "timeline-title", "Today" }
+            div { class: "timeline-strip",
+                for (style, source) in captures {
+                    div { class: "timeline-capture", style: "{style}", title: "{source}" }
+                }
+                for (class, style, title) in recordings {
+                    div { class: "{class}", style: "{style}", title: "{title}" }
+                }
+                for (style, message) in errors {
+                    div { class: "timeline-error", style: "{style}", title: "{message}" }
+                }
+            }
+            div { class: "timeline-hours",
+                span { "{time_format::time(from)}" }
+                span { "{time_format::time(to)}" }
+            }
+            if rows.is_empty() {
+                div { class: "library-notes", "No recordings yet today" }
+            }
+            for (range, what, outcome, detail) in rows {
+                div { class: "timeline-row", title: "{detail}",
+                    span { class: "timeline-row-time", "{range}" }
+                    span { class: "timeline-row-source", "{what}" }
+                    span { class: "timeline-row-outcome", "{outcome}" }
+                }
+            }
+        }
+    }
+}
+
+// Shown when capture has been running a while without a recording, or was stopped
+// for it, with a way to resume
+#[component]
+fn IdleCaptureBar() -> Element {
+    // Minutes idle once worth showing, and whether capture was stopped for it
+    let idle_now = || {
+        let minutes = idle::idle_for().filter(|d| *d >= idle::SHOW_AFTER).map(|d| d.as_secs() / 60);
+        (minutes, idle::stopped())
+    };
+    let mut idle_state = use_signal(idle_now);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+            let latest = idle_now();
+            if *idle_state.read() != latest {
+                idle_state.set(latest);
+            }
         }
     });
-    
-    if let Some(meeting) = next_meeting {
-        rsx! {
-            div { class: "next-meeting-bar",
+
+    let send = |command: CaptureCommand| {
+        let (tx, _, _, _, _, _, _, _) = get_global_state();
+        if let Some(ref sender) = tx {
+            let _ = sender.send(command);
+        }
+    };
+    match *idle_state.read() {
+        (_, true) => rsx! {
+            div { class: "next-meeting-bar call-prompt",
                 div { class: "next-meeting-content",
-                    span { class: "next-meeting-label", "Next Meeting:" }
-                    span { class: "next-meeting-time", "{meeting.formatted_start_time()}" }
-                    span { class: "next-meeting-title", "- {meeting.event_summary}" }
+                    span { class: "next-meeting-label", "Capture stopped because nothing was being recorded" }
+                    button {
+                        class: "btn btn-success library-action",
+                        onclick: move |_| {
+                            idle::clear_stopped();
+                            send(CaptureCommand::StartCapture);
+                        },
+                        "Resume"
+                    }
+                    button {
+                        class: "btn btn-text library-action",
+                        onclick: move |_| idle::clear_stopped(),
+                        "Dismiss"
+                    }
+                }
+            }
+        },
+        (Some(minutes), false) => rsx! {
+            div { class: "next-meeting-bar call-prompt",
+                div { class: "next-meeting-content",
+                    span { class: "next-meeting-label", "Sharing for {minutes} min without recording" }
+                    button {
+                        class: "btn btn-text library-action",
+                        onclick: move |_| send(CaptureCommand::StopCapture),
+                        "Stop Sharing"
+                    }
+                }
+            }
+        },
+        (None, false) => rsx! {},
+    }
+}
+
+// Shown when the content picker has been open a long time, in case it is stuck
+#[component]
+fn PickerStuckBar() -> Element {
+    let mut stuck = use_signal(input::picker_stuck);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            let stuck_now = input::picker_stuck();
+            if *stuck.read() != stuck_now {
+                stuck.set(stuck_now);
+            }
+        }
+    });
+
+    if !*stuck.read() {
+        return rsx! {};
+    }
+    rsx! {
+        div { class: "next-meeting-bar call-prompt",
+            div { class: "next-meeting-content",
+                span { class: "next-meeting-label", "Still waiting for you to choose what to share" }
+                button {
+                    class: "btn btn-text library-action",
+                    onclick: move |_| {
+                        input::dismiss_picker();
+                        stuck.set(false);
+                    },
+                    "Stop Waiting"
+                }
+            }
+        }
+    }
+}
+
+/// Asks whether to go ahead when the connection looks too slow for the upload
+#[cfg(feature = "macos_15_0")]
+#[component]
+fn SlowUploadBar() -> Element {
+    let mut prompt = use_signal(upload_queue::prompt);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+            let prompt_now = upload_queue::prompt();
+            if *prompt.read() != prompt_now {
+                prompt.set(prompt_now);
+            }
+        }
+    });
+
+    let Some(current) = prompt.read().clone() else {
+        return rsx! {};
+    };
+    rsx! {
+        div { class: "next-meeting-bar call-prompt",
+            div { class: "next-meeting-content",
+                span { class: "next-meeting-label", "{current.message}" }
+                button {
+                    class: "btn btn-success library-action",
+                    onclick: move |_| upload_queue::decide(upload_queue::Decision::UploadNow),
+                    "Upload now"
+                }
+                button {
+                    class: "btn btn-text library-action",
+                    onclick: move |_| upload_queue::decide(upload_queue::Decision::Queue),
+                    "Queue for later"
                 }
             }
         }
-    } else {
-        rsx! { div {} }
     }
 }
 
+#[cfg(not(feature = "macos_15_0"))]
 #[component]
-fn MainContent(is_capturing: bool, is_recording: bool, source_name: String, recording_duration: String, upload_status: String, uploaded_file_id: String) -> Element {
+fn SlowUploadBar() -> Element {
+    rsx! {}
+}
+
+#[component]
+fn MainContent(is_capturing: bool, is_recording: bool, source_name: String, recording_duration: String, recording_paused: bool, upload_state: UploadState, trim_session: Option<TrimSession>, recording_path: String, uploaded_path: String, thermal_warning: bool, overload_warning: bool) -> Element {
     let has_source = !source_name.is_empty() && source_name != "No source selected";
-    let show_upload_status = !upload_status.is_empty();
+    let show_upload_status = upload_state.phase != UploadPhase::Idle;
+    let mut show_crop = use_signal(|| false);
     
-    // Determine upload state from status message
-    let is_uploading = upload_status.contains("%") || upload_status.contains("Preparing") || upload_status.contains("Processing") || upload_status.contains("Finalizing");
-    let is_upload_complete = upload_status.contains("ready") && !uploaded_file_id.is_empty();
-    let is_upload_error = upload_status.contains("try again") || upload_status.contains("failed") || upload_status.contains("lost");
+    let is_uploading = upload_state.in_progress();
+    let is_upload_complete = upload_state.phase == UploadPhase::Complete && upload_state.file_id.is_some();
+    let is_upload_error = upload_state.is_error();
+    let waiting_for_power = upload_state.phase == UploadPhase::WaitingForPower;
+    let post_processing = upload_state.phase == UploadPhase::PostProcessing;
+    let upload_message = upload_state.message();
+    let uploaded_file_id = upload_state.file_id.unwrap_or_default();
     
     // Clone file_id for closures
     let file_id_for_copy = uploaded_file_id.clone();
@@ -728,14 +3917,33 @@ fn MainContent(is_capturing: bool, is_recording: bool, source_name: String, reco
                 // Recording state: Show timer and controls
                 div { class: "recording-view",
                     div { class: "timer-container",
-                        div { class: "pulse-dot" }
-                        div { class: "timer-display", "{recording_duration}" }
+                        if recording_paused {
+                            div { class: "timer-display", "⏸ {recording_duration} · Paused" }
+                        } else {
+                            div { class: "pulse-dot" }
+                            div { class: "timer-display", "{recording_duration}" }
+                        }
+                    }
+                    if !recording_path.is_empty() {
+                        div { class: "recording-path", title: "{recording_path}",
+                            "{std::path::Path::new(&recording_path).file_name().and_then(|n| n.to_str()).unwrap_or_default()}"
+                        }
+                    }
+                    if thermal_warning {
+                        div { class: "thermal-warning",
+                            "🌡️ Your Mac is running hot. Preview and recording quality are reduced until it cools down."
+                        }
+                    }
+                    if overload_warning {
+                        div { class: "thermal-warning",
+                            "⚠️ System overloaded — recording may be choppy. Quit apps you don't need, lower the frame rate in Settings, or record a single window instead of the whole display."
+                        }
                     }
                     div { class: "recording-actions",
                         button {
                             class: "btn btn-danger btn-large",
                             onclick: move |_| {
-                                let (tx, _, _, _, _, _, _, _, _, _) = get_global_state();
+                                let (tx, _, _, _, _, _, _, _) = get_global_state();
                                 if let Some(ref sender) = tx {
                                     let _ = sender.send(CaptureCommand::StopRecording);
                                 }
@@ -745,7 +3953,33 @@ fn MainContent(is_capturing: bool, is_recording: bool, source_name: String, reco
                         button {
                             class: "btn btn-secondary",
                             onclick: move |_| {
-                                let (tx, _, _, _, _, _, _, _, _, _) = get_global_state();
+                                let (tx, _, _, _, _, _, _, _) = get_global_state();
+                                if let Some(ref sender) = tx {
+                                    let _ = sender.send(if recording_paused {
+                                        CaptureCommand::ResumeRecording
+                                    } else {
+                                        CaptureCommand::PauseRecording
+                                    });
+                                }
+                            },
+                            if recording_paused { "▶ Resume" } else { "⏸ Pause" }
+                        }
+                        button {
+                            class: "btn btn-secondary",
+                            title: "Bookmark this moment ({hotkey::BOOKMARK_SHORTCUT} from any app)",
+                            onclick: move |_| {
+                                let (tx, _, _, _, _, _, _, _) = get_global_state();
+                                if let Some(ref sender) = tx {
+                                    let _ = sender.send(CaptureCommand::AddBookmark);
+                                }
+                            },
+                            "🔖 Bookmark"
+                        }
+                        DrawButton {}
+                        button {
+                            class: "btn btn-secondary",
+                            onclick: move |_| {
+                                let (tx, _, _, _, _, _, _, _) = get_global_state();
                                 if let Some(ref sender) = tx {
                                     // Cancel recording - will delete file and not upload
                                     let _ = sender.send(CaptureCommand::CancelRecording);
@@ -753,8 +3987,21 @@ fn MainContent(is_capturing: bool, is_recording: bool, source_name: String, reco
                             },
                             "Cancel"
                         }
+                        button {
+                            class: "btn btn-text",
+                            onclick: move |_| {
+                                let (tx, _, _, _, _, _, _, _) = get_global_state();
+                                if let Some(ref sender) = tx {
+                                    let _ = sender.send(CaptureCommand::ShowPictureInPicture);
+                                }
+                            },
+                            "Floating Preview"
+                        }
                     }
                 }
+            } else if let Some(session) = trim_session {
+                // Recording finished: trim before upload
+                TrimEditor { key: "{session.path}", session }
             } else if show_upload_status {
                 // Upload status state
                 div { class: "status-view",
@@ -784,12 +4031,14 @@ fn MainContent(is_capturing: bool, is_recording: bool, source_name: String, reco
                         }
                         
                         div { class: "status-title",
-                            if is_uploading { "Uploading Recording" }
+                            if post_processing { "Processing Recording" }
+                            else if is_uploading { "Uploading Recording" }
                             else if is_upload_complete { "Upload Complete" }
                             else if is_upload_error { "Upload Failed" }
+                            else if waiting_for_power { "Waiting for Power" }
                             else { "Processing" }
                         }
-                        div { class: "status-message", "{upload_status}" }
+                        div { class: "status-message", "{upload_message}" }
                         
                         // Show URL and actions when complete
                         if is_upload_complete {
@@ -808,7 +4057,7 @@ fn MainContent(is_capturing: bool, is_recording: bool, source_name: String, reco
                                         title: "Copy link to clipboard",
                                         onclick: move |_| {
                                             let url = format!("https://insights.talka.ai/activity/meeting?fileId={}", file_id_for_copy);
-                                            let _ = copy_to_clipboard(&url);
+                                            copy_to_clipboard(&url);
                                         },
                                         dangerous_inner_html: r#"<svg width="16" height="16" viewBox="0 0 16 16" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M10.5 2H3.5C2.67 2 2 2.67 2 3.5V11.5C2 11.78 2.22 12 2.5 12C2.78 12 3 11.78 3 11.5V3.5C3 3.22 3.22 3 3.5 3H10.5C10.78 3 11 2.78 11 2.5C11 2.22 10.78 2 10.5 2ZM12.5 4H5.5C4.67 4 4 4.67 4 5.5V12.5C4 13.33 4.67 14 5.5 14H12.5C13.33 14 14 13.33 14 12.5V5.5C14 4.67 13.33 4 12.5 4ZM12.5 12.5H5.5V5.5H12.5V12.5Z" fill="currentColor"/></svg>"#
                                     }
@@ -822,10 +4071,25 @@ fn MainContent(is_capturing: bool, is_recording: bool, source_name: String, reco
                                         },
                                         "Open Meeting"
                                     }
+                                    if !uploaded_path.is_empty() {
+                                        button {
+                                            class: "btn btn-secondary btn-action",
+                                            title: "Send the recording with AirDrop, Messages, Mail…",
+                                            onclick: {
+                                                let path = uploaded_path.clone();
+                                                move |_| {
+                                                    if let Err(e) = desktop::share_file(&path) {
+                                                        eprintln!("⚠️ {}", e);
+                                                    }
+                                                }
+                                            },
+                                            "Share…"
+                                        }
+                                    }
                                     button {
                                         class: "btn btn-secondary btn-action",
                                         onclick: move |_| {
-                                            let (tx, _, _, _, _, _, _, _, _, _) = get_global_state();
+                                            let (tx, _, _, _, _, _, _, _) = get_global_state();
                                             if let Some(ref sender) = tx {
                                                 let _ = sender.send(CaptureCommand::SelectSource);
                                             }
@@ -833,155 +4097,921 @@ fn MainContent(is_capturing: bool, is_recording: bool, source_name: String, reco
                                         "New Recording"
                                     }
                                 }
+                                if !uploaded_path.is_empty() {
+                                    div { class: "url-actions-buttons",
+                                        button {
+                                            class: "btn btn-text btn-action",
+                                            onclick: {
+                                                let path = uploaded_path.clone();
+                                                move |_| {
+                                                    if let Err(e) = desktop::quick_look(&path) {
+                                                        eprintln!("⚠️ {}", e);
+                                                    }
+                                                }
+                                            },
+                                            "Quick Look"
+                                        }
+                                        button {
+                                            class: "btn btn-text btn-action",
+                                            onclick: {
+                                                let path = uploaded_path.clone();
+                                                move |_| {
+                                                    if let Err(e) = desktop::reveal_in_finder(&path) {
+                                                        eprintln!("⚠️ {}", e);
+                                                    }
+                                                }
+                                            },
+                                            "Show in Finder"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    
+                    if is_upload_error {
+                        button {
+                            class: "btn btn-secondary",
+                            onclick: move |_| {
+                                let (tx, _, _, _, _, _, _, _) = get_global_state();
+                                if let Some(ref sender) = tx {
+                                    let _ = sender.send(CaptureCommand::SelectSource);
+                                }
+                            },
+                            "New Recording"
+                        }
+                    }
+                }
+            } else if !has_source {
+                // No source state: Show select source
+                div { class: "welcome-view",
+                    h2 { class: "welcome-title", "Select Source to Record" }
+                    p { class: "welcome-subtitle", "Choose your screen or window to start recording" }
+                    button {
+                        class: "btn btn-primary btn-hero",
+                        onclick: move |_| {
+                            let (tx, _, _, _, _, _, _, _) = get_global_state();
+                            if let Some(ref sender) = tx {
+                                let _ = sender.send(CaptureCommand::SelectSource);
+                            }
+                        },
+                        "Select Source"
+                    }
+                    // Offer the source from the last session again
+                    if let Some(hint) = session::current().source {
+                        if let Some(display_id) = hint.display_id {
+                            button {
+                                class: "btn btn-secondary",
+                                onclick: move |_| {
+                                    let (tx, _, _, _, _, _, _, _) = get_global_state();
+                                    let command = match input::display_index(display_id) {
+                                        Some(index) => CaptureCommand::SelectDisplay(index),
+                                        None => CaptureCommand::SelectSource,
+                                    };
+                                    if let Some(ref sender) = tx {
+                                        let _ = sender.send(command);
+                                    }
+                                },
+                                "Capture {hint.name} again"
+                            }
+                        } else {
+                            p { class: "welcome-subtitle", "Last time: {hint.name}" }
+                        }
+                    }
+                    TodayTimeline {}
+                }
+            } else {
+                // Source selected state: Show ready to record
+                div { class: "ready-view",
+                    div { class: "source-info-card",
+                        div { class: "source-label", "Source" }
+                        div { class: "source-name-display", "{source_name}" }
+                    }
+                    ProfilePicker {}
+                    StartRecordingButton { is_capturing }
+                    button {
+                        class: "btn btn-text",
+                        onclick: move |_| {
+                            let (tx, _, _, _, _, _, _, _) = get_global_state();
+                            if let Some(ref sender) = tx {
+                                let _ = sender.send(CaptureCommand::ShowPreview);
+                            }
+                        },
+                        disabled: !is_capturing,
+                        "Open Preview"
+                    }
+                    button {
+                        class: "btn btn-text",
+                        onclick: move |_| {
+                            let (tx, _, _, _, _, _, _, _) = get_global_state();
+                            if let Some(ref sender) = tx {
+                                // Stop sharing the current source first
+                                let _ = sender.send(CaptureCommand::StopCapture);
+                            }
+                        },
+                        "Stop Sharing"
+                    }
+                    if *show_crop.read() {
+                        CropEditor { on_close: move |_| show_crop.set(false) }
+                    } else {
+                        button {
+                            class: "btn btn-text",
+                            onclick: move |_| show_crop.set(true),
+                            disabled: !is_capturing,
+                            "Crop…"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Crop editor, drawn over a frozen frame of the whole source
+#[component]
+fn CropEditor(on_close: EventHandler<()>) -> Element {
+    let mut settings = use_signal(CropSettings::load);
+    let mut frame = use_signal(|| None::<crop::FrozenFrame>);
+
+    // Freeze a frame once the stream has switched to the uncropped source
+    let refresh = move || {
+        spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            let (_, _, _, _, _, cap_state, _, _) = get_global_state();
+            let surface = cap_state.latest_surface.lock().unwrap().clone();
+            frame.set(crop::freeze_frame(surface));
+        });
+    };
+    use_hook(move || {
+        let (tx, _, _, _, _, _, _, _) = get_global_state();
+        if let Some(ref sender) = tx {
+            let _ = sender.send(CaptureCommand::EditCrop);
+        }
+        refresh();
+    });
+    // Back to the saved crop however the editor goes away, Settings closing included
+    use_drop(|| {
+        let (tx, _, _, _, _, _, _, _) = get_global_state();
+        if let Some(ref sender) = tx {
+            let _ = sender.send(CaptureCommand::ApplyCrop);
+        }
+    });
+
+    let aspect = frame.read().as_ref().map_or(16.0 / 9.0, |f| f.aspect);
+    let mut update = move |change: &dyn Fn(&mut CropSettings)| {
+        let mut updated = settings.read().clone();
+        change(&mut updated);
+        updated.fit(aspect);
+        settings.set(updated);
+    };
+    let current = settings.read().clone();
+    let frozen = frame.read().clone();
+    let (x, y, width, height) = current.normalized(aspect);
+    // Unmounting the editor applies the crop, see `use_drop` above
+    let close = move |apply: bool| {
+        if apply {
+            if let Err(e) = settings.read().save() {
+                toast::error(e);
+            }
+        }
+        on_close.call(());
+    };
+
+    rsx! {
+        div { class: "settings-section",
+            div { class: "settings-section-title", "Crop Output" }
+            div { class: "crop-frame",
+                if let Some(frozen) = frozen {
+                    img { src: "{frozen.image}" }
+                    if current.enabled {
+                        div {
+                            class: "crop-selection",
+                            style: "left: {x * 100.0}%; top: {y * 100.0}%; width: {width * 100.0}%; height: {height * 100.0}%;",
+                        }
+                    }
+                } else {
+                    div { class: "library-notes", "Waiting for a frame…" }
+                }
+            }
+            label { class: "settings-row",
+                span { "Crop recordings" }
+                input {
+                    r#type: "checkbox",
+                    checked: current.enabled,
+                    onchange: move |evt| {
+                        let enabled = evt.checked();
+                        update(&|s| s.enabled = enabled);
+                    },
+                }
+            }
+            label { class: "settings-row",
+                span { "Aspect ratio" }
+                select {
+                    class: "library-editor-input settings-select",
+                    value: "{current.aspect.as_str()}",
+                    onchange: move |evt| {
+                        if let Some(ratio) = crop::AspectRatio::from_str(&evt.value()) {
+                            update(&|s| s.aspect = ratio);
+                        }
+                    },
+                    for ratio in crop::AspectRatio::ALL {
+                        option {
+                            value: "{ratio.as_str()}",
+                            selected: current.aspect == ratio,
+                            "{ratio.label()}"
+                        }
+                    }
+                }
+            }
+            label { class: "settings-row",
+                span { "Left" }
+                input {
+                    r#type: "range",
+                    min: "0",
+                    max: "1",
+                    step: "0.01",
+                    value: "{x}",
+                    oninput: move |evt| {
+                        if let Ok(value) = evt.value().parse::<f64>() {
+                            update(&|s| s.x = value);
+                        }
+                    },
+                }
+            }
+            label { class: "settings-row",
+                span { "Top" }
+                input {
+                    r#type: "range",
+                    min: "0",
+                    max: "1",
+                    step: "0.01",
+                    value: "{y}",
+                    oninput: move |evt| {
+                        if let Ok(value) = evt.value().parse::<f64>() {
+                            update(&|s| s.y = value);
+                        }
+                    },
+                }
+            }
+            label { class: "settings-row",
+                span { "Width" }
+                input {
+                    r#type: "range",
+                    min: "0.1",
+                    max: "1",
+                    step: "0.01",
+                    value: "{width}",
+                    oninput: move |evt| {
+                        if let Ok(value) = evt.value().parse::<f64>() {
+                            update(&|s| s.width = value);
+                        }
+                    },
+                }
+            }
+            if current.aspect == crop::AspectRatio::Free {
+                label { class: "settings-row",
+                    span { "Height" }
+                    input {
+                        r#type: "range",
+                        min: "0.1",
+                        max: "1",
+                        step: "0.01",
+                        value: "{height}",
+                        oninput: move |evt| {
+                            if let Ok(value) = evt.value().parse::<f64>() {
+                                update(&|s| s.height = value);
                             }
+                        },
+                    }
+                }
+            }
+            div { class: "library-editor-actions",
+                button {
+                    class: "btn btn-text",
+                    onclick: move |_| refresh(),
+                    "Refresh frame"
+                }
+                button {
+                    class: "btn btn-text",
+                    onclick: move |_| close(false),
+                    "Cancel"
+                }
+                button {
+                    class: "btn btn-secondary",
+                    onclick: move |_| close(true),
+                    "Apply"
+                }
+            }
+        }
+    }
+}
+
+/// When a meeting starts, in the user's date and time format
+fn meeting_start_text(event: &auth::MeetingEvent) -> String {
+    event
+        .start_time()
+        .map_or_else(|| event.meeting_start_time.clone(), time_format::day_time)
+}
+
+/// Format seconds as `m:ss.s` for the trim editor
+fn format_trim_time(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).floor();
+    format!("{}:{:04.1}", minutes as u64, seconds - minutes * 60.0)
+}
+
+// Trim editor shown after a recording stops
+#[component]
+fn TrimEditor(session: TrimSession) -> Element {
+    // Shortest clip the editor allows, in seconds
+    const MIN_CLIP: f64 = 1.0;
+
+    let duration = session.duration;
+    let mut trim_start = use_signal(|| 0.0f64);
+    let mut trim_end = use_signal(|| duration);
+    let path = session.path.clone();
+    let mut attachment_list = use_signal(|| attachments::list(&path));
+    let mut note_draft = use_signal(|| {
+        session::current()
+            .pending_upload
+            .filter(|pending| pending.path == path)
+            .map(|pending| pending.note)
+            .unwrap_or_default()
+    });
+    // Keep the note being typed in the session until it is added
+    let save_note = {
+        let path = path.clone();
+        move |note: String| {
+            session::update(|s| {
+                if let Some(pending) = s.pending_upload.as_mut().filter(|pending| pending.path == path) {
+                    pending.note = note;
+                }
+            })
+        }
+    };
+    let mut attachment_status = use_signal(String::new);
+    // The title to upload with, starting from the organization's naming convention;
+    // worked out once rather than on every keystroke
+    let (saved_title, suggested_title) = use_hook({
+        let path = path.clone();
+        move || {
+            let metadata = sidecar::RecordingSidecar::load(&path).unwrap_or_default();
+            let suggested = naming::suggested(&metadata);
+            (metadata.title, suggested)
+        }
+    });
+    let title_placeholder = suggested_title.clone().unwrap_or_else(|| {
+        std::path::Path::new(&path).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+    });
+    let mut title_draft = use_signal(|| saved_title.clone().or(suggested_title.clone()).unwrap_or_default());
+
+    // Segmented recordings have to be joined before they can be trimmed
+    if !session.segments.is_empty() {
+        return rsx! {
+            div { class: "trim-view",
+                div { class: "trim-title", "Merge Recording" }
+                div { class: "trim-times",
+                    span { "This recording has {session.segments.len()} segments ({format_trim_time(duration)})" }
+                }
+                if let Some(ref error) = session.merge_error {
+                    div { class: "library-status", "❌ {error}" }
+                }
+                button {
+                    class: "btn btn-primary btn-large",
+                    disabled: session.merging,
+                    onclick: move |_| {
+                        let (tx, _, _, _, _, _, _, _) = get_global_state();
+                        if let Some(ref sender) = tx {
+                            let _ = sender.send(CaptureCommand::MergeSegments);
                         }
+                    },
+                    if session.merging { "Merging…" } else { "Merge segments" }
+                }
+            }
+        };
+    }
+
+    let start = *trim_start.read();
+    let end = *trim_end.read();
+    let selection_left = start / duration * 100.0;
+    let selection_width = (end - start) / duration * 100.0;
+    let is_trimmed = start > 0.0 || end < duration;
+    let upload_path = path.clone();
+
+    rsx! {
+        div { class: "trim-view",
+            div { class: "trim-title", "Trim Recording" }
+            div { class: "trim-scrubber",
+                div { class: "trim-thumbnails",
+                    for thumbnail in session.thumbnails.iter() {
+                        img { class: "trim-thumbnail", src: "{thumbnail}" }
                     }
-                    
-                    if is_upload_error {
+                }
+                div {
+                    class: "trim-selection",
+                    style: "left: {selection_left}%; width: {selection_width}%;",
+                }
+                input {
+                    class: "trim-handle",
+                    r#type: "range",
+                    min: "0",
+                    max: "{duration}",
+                    step: "0.1",
+                    value: "{start}",
+                    oninput: move |evt| {
+                        if let Ok(value) = evt.value().parse::<f64>() {
+                            let limit = (*trim_end.read() - MIN_CLIP).max(0.0);
+                            trim_start.set(value.min(limit));
+                        }
+                    },
+                }
+                input {
+                    class: "trim-handle",
+                    r#type: "range",
+                    min: "0",
+                    max: "{duration}",
+                    step: "0.1",
+                    value: "{end}",
+                    oninput: move |evt| {
+                        if let Ok(value) = evt.value().parse::<f64>() {
+                            let limit = (*trim_start.read() + MIN_CLIP).min(duration);
+                            trim_end.set(value.max(limit));
+                        }
+                    },
+                }
+            }
+            div { class: "trim-times",
+                span { "In {format_trim_time(start)}" }
+                span { "Length {format_trim_time(end - start)}" }
+                span { "Out {format_trim_time(end)}" }
+            }
+            div { class: "settings-section",
+                div { class: "settings-section-title", "Meeting Name" }
+                input {
+                    class: "library-editor-input",
+                    placeholder: "{title_placeholder}",
+                    value: "{title_draft}",
+                    oninput: move |evt| title_draft.set(evt.value()),
+                }
+                if suggested_title.is_some() {
+                    div { class: "library-notes", "Named by your organization's convention. Edit it if this meeting needs a different name." }
+                }
+            }
+            div { class: "settings-section",
+                div { class: "settings-section-title", "Attachments" }
+                for (index, attachment) in attachment_list.read().iter().enumerate() {
+                    div { key: "{index}", class: "settings-row",
+                        span { "{attachment.label()}" }
                         button {
-                            class: "btn btn-secondary",
-                            onclick: move |_| {
-                                let (tx, _, _, _, _, _, _, _, _, _) = get_global_state();
-                                if let Some(ref sender) = tx {
-                                    let _ = sender.send(CaptureCommand::SelectSource);
+                            class: "btn btn-text",
+                            onclick: {
+                                let path = path.clone();
+                                move |_| match attachments::remove(&path, index) {
+                                    Ok(()) => attachment_list.set(attachments::list(&path)),
+                                    Err(e) => attachment_status.set(e),
                                 }
                             },
-                            "New Recording"
+                            "Remove"
                         }
                     }
                 }
-            } else if !has_source {
-                // No source state: Show select source
-                div { class: "welcome-view",
-                    h2 { class: "welcome-title", "Select Source to Record" }
-                    p { class: "welcome-subtitle", "Choose your screen or window to start recording" }
-                    button {
-                        class: "btn btn-primary btn-hero",
-                        onclick: move |_| {
-                            let (tx, _, _, _, _, _, _, _, _, _) = get_global_state();
-                            if let Some(ref sender) = tx {
-                                let _ = sender.send(CaptureCommand::SelectSource);
-                            }
-                        },
-                        "Select Source"
-                    }
+                textarea {
+                    class: "library-editor-input",
+                    rows: "2",
+                    placeholder: "Add a note for this recording…",
+                    value: "{note_draft}",
+                    oninput: {
+                        let save_note = save_note.clone();
+                        move |evt| {
+                            save_note(evt.value());
+                            note_draft.set(evt.value());
+                        }
+                    },
                 }
-            } else {
-                // Source selected state: Show ready to record
-                div { class: "ready-view",
-                    div { class: "source-info-card",
-                        div { class: "source-label", "Source" }
-                        div { class: "source-name-display", "{source_name}" }
-                    }
+                if !attachment_status.read().is_empty() {
+                    div { class: "library-notes", "{attachment_status}" }
+                }
+                div { class: "library-editor-actions",
                     button {
-                        class: "btn btn-success btn-hero",
-                        onclick: move |_| {
-                            let (tx, _, _, _, _, _, _, _, _, _) = get_global_state();
-                            if let Some(ref sender) = tx {
-                                let _ = sender.send(CaptureCommand::StartRecording);
+                        class: "btn btn-text",
+                        onclick: {
+                            let path = path.clone();
+                            move |_| {
+                                let path = path.clone();
+                                spawn(async move {
+                                    match tokio::task::spawn_blocking(attachments::choose_files).await {
+                                        Ok(Ok(files)) if files.is_empty() => {}
+                                        Ok(Ok(files)) => match attachments::add_files(&path, &files) {
+                                            Ok(()) => {
+                                                attachment_status.set(String::new());
+                                                attachment_list.set(attachments::list(&path));
+                                            }
+                                            Err(e) => attachment_status.set(e),
+                                        },
+                                        Ok(Err(e)) => attachment_status.set(e),
+                                        Err(e) => attachment_status.set(format!("File picker failed: {}", e)),
+                                    }
+                                });
                             }
                         },
-                        disabled: !is_capturing,
-                        "Start Recording"
+                        "Attach files…"
                     }
                     button {
-                        class: "btn btn-text",
-                        onclick: move |_| {
-                            let (tx, _, _, _, _, _, _, _, _, _) = get_global_state();
-                            if let Some(ref sender) = tx {
-                                // Stop sharing the current source first
-                                let _ = sender.send(CaptureCommand::StopCapture);
+                        class: "btn btn-secondary",
+                        disabled: note_draft.read().trim().is_empty(),
+                        onclick: {
+                            let path = path.clone();
+                            move |_| {
+                                let text = note_draft.read().clone();
+                                match attachments::add_note(&path, &text) {
+                                    Ok(()) => {
+                                        note_draft.set(String::new());
+                                        save_note(String::new());
+                                        attachment_status.set(String::new());
+                                        attachment_list.set(attachments::list(&path));
+                                    }
+                                    Err(e) => attachment_status.set(e),
+                                }
                             }
                         },
-                        "Stop Sharing"
+                        "Add note"
+                    }
+                }
+            }
+            button {
+                class: "btn btn-primary btn-large",
+                onclick: move |_| {
+                    let (tx, _, _, _, _, _, _, _) = get_global_state();
+                    if let Some(ref sender) = tx {
+                        // Kept with the recording so retries and the trimmed copy use it too
+                        let title = title_draft.read().trim().to_string();
+                        if let Err(e) = sidecar::RecordingSidecar::update(&upload_path, |s| {
+                            s.title = Some(title).filter(|t| !t.is_empty());
+                        }) {
+                            eprintln!("⚠️  {}", e);
+                        }
+                        let start = *trim_start.read();
+                        let end = *trim_end.read();
+                        let trim = (start > 0.0 || end < duration).then_some((start, end));
+                        let _ = sender.send(CaptureCommand::UploadRecording { trim });
                     }
+                },
+                if is_trimmed { "Trim & Upload" } else { "Upload" }
+            }
+            if is_trimmed {
+                button {
+                    class: "btn btn-text",
+                    onclick: move |_| {
+                        trim_start.set(0.0);
+                        trim_end.set(duration);
+                    },
+                    "Reset"
                 }
             }
         }
     }
 }
 
-
 // Capture backend thread
 fn run_capture_backend(
     cmd_rx: Receiver<CaptureCommand>,
     is_capturing: Arc<AtomicBool>,
     is_recording: Arc<AtomicBool>,
     source_name: Arc<Mutex<String>>,
-    upload_status: Arc<Mutex<String>>,
-    uploaded_file_id: Arc<Mutex<String>>,
+    upload_state: Arc<watch::Sender<UploadState>>,
+    trim_session: Arc<Mutex<Option<TrimSession>>>,
+    recording_path: Arc<Mutex<String>>,
+    uploaded_path: Arc<Mutex<String>>,
     runtime: tokio::runtime::Handle,
     capture_state: Arc<CaptureState>,
     auth_tokens: Arc<Mutex<Option<auth::AuthTokens>>>,
+    meeting_events: Arc<Mutex<Vec<auth::MeetingEvent>>>,
 ) {
     let mut stream: Option<SCStream> = None;
     let mut current_filter: Option<SCContentFilter> = None;
-    let stream_config = default_stream_config();
+    let options = CaptureSettings::load();
+    // Stream options from Settings, before any crop
+    let mut base_config = options.stream_config();
+    // Capture stops on its own after running this long without a recording
+    let mut idle_timeout = options.idle_timeout();
+    // Base configuration, cropped to the window when a window is the source and
+    // then to the output crop
+    let mut stream_config = base_config.clone();
     let mut capture_size = (1280u32, 720u32);
-    let pending_picker: Arc<Mutex<PickerResult>> = Arc::new(Mutex::new(None));
+    // Uncropped source: its area within the filter's content and its size in pixels
+    let mut source_region: Option<screencapturekit::cg::CGRect> = None;
+    // Keeps the crop on a window source that is moved or resized
+    let mut window_tracker: Option<app_audio::WindowTracker> = None;
+    let mut source_size = capture_size;
+    // Display being captured, if the source is a whole display
+    let mut picked_display: Option<u32> = None;
+    let (picker_tx, picker_rx) = std::sync::mpsc::channel::<PickerEvent>();
+    let mut previews = PreviewManager::new();
+    // Held while capturing (and so while recording) to stop the display sleeping;
+    // `Some(None)` means capturing but IOKit refused the assertion
+    let mut capture_awake: Option<Option<power::Assertion>> = None;
+    // Capture runs lighter while the battery is low or the Mac is running hot
+    let mut low_battery = false;
+    let mut reduced_quality = false;
+    let mut last_battery_check: Option<std::time::Instant> = None;
+    let mut last_thermal_check: Option<std::time::Instant> = None;
 
     #[cfg(feature = "macos_15_0")]
     let mut recording_state = RecordingState::new();
+    // Capture settings from before the recording's profile was applied
+    #[cfg(feature = "macos_15_0")]
+    let mut unprofiled: Option<(SCStreamConfiguration, (u32, u32))> = None;
+    // Set once standby has prepared the stream for recording, with the profile applied
     #[cfg(feature = "macos_15_0")]
-    let recording_config = RecordingConfig::new();
+    let mut warmed: Option<Option<profiles::RecordingProfile>> = None;
+    // The filter from before standby hid notification banners, to go back to
+    #[cfg(feature = "macos_15_0")]
+    let mut standby_filter: Option<SCContentFilter> = None;
+    // Handled before the next queued command, e.g. a stop when a segment fails to start
+    let mut forced_command: Option<CaptureCommand> = None;
+    let mut mock = mock_capture::enabled().then(|| {
+        mock_capture::MockBackend::new(
+            &capture_state,
+            &is_capturing,
+            &is_recording,
+            &source_name,
+            &recording_path,
+            &trim_session,
+        )
+    });
 
     loop {
-        // First check for pending picker results (continuously polling)
-        if let Ok(mut pending) = pending_picker.try_lock() {
-            if let Some((filter, width, height, source)) = pending.take() {
+        // Stop sharing a source nobody is recording once it has been idle too long
+        if idle::tick(is_capturing.load(Ordering::Relaxed), is_recording.load(Ordering::Relaxed), idle_timeout) {
+            input::stop_capture(&mut stream, &is_capturing, "idle without a recording");
+            println!("💤 Capture stopped after running without a recording");
+        }
+
+        let capturing_now = is_capturing.load(Ordering::Relaxed);
+        if capturing_now != capture_awake.is_some() {
+            capture_awake = capturing_now.then(|| power::Assertion::prevent_display_sleep("Screen capture in progress"));
+        }
+
+        // Drop to a lighter capture on low battery or thermal pressure, and back after
+        if last_battery_check.map_or(true, |t| t.elapsed() >= power::BATTERY_CHECK_INTERVAL) {
+            last_battery_check = Some(std::time::Instant::now());
+            low_battery = capturing_now && power::BatterySettings::load().reduces_quality_now();
+        }
+        if last_thermal_check.map_or(true, |t| t.elapsed() >= thermal::CHECK_INTERVAL) {
+            last_thermal_check = Some(std::time::Instant::now());
+            thermal::refresh();
+        }
+        let reduce_now = capturing_now && (low_battery || thermal::under_pressure());
+        if reduce_now != reduced_quality {
+            reduced_quality = reduce_now;
+            if let Some(ref s) = stream {
+                let (config, _) = power::capture_settings(&stream_config, capture_size, reduced_quality);
+                match s.update_configuration(&config) {
+                    Ok(()) if reduced_quality => println!("🔋 Capturing at reduced quality"),
+                    Ok(()) => println!("🔌 Capturing at full quality again"),
+                    Err(e) => eprintln!("⚠️  Failed to update capture quality: {:?}", e),
+                }
+            }
+        }
+
+        // In standby, get the stream ready to record as soon as there is one; once
+        // standby ends without a recording, go back to the settings and filter the
+        // source was picked with
+        #[cfg(feature = "macos_15_0")]
+        if !is_recording.load(Ordering::Relaxed) {
+            let standing_by = capturing_now && standby::current().enabled;
+            if standing_by && warmed.is_none() {
+                if let Some(ref s) = stream {
+                    let events = meeting_events.lock().unwrap().clone();
+                    standby_filter = current_filter.clone();
+                    warmed = Some(prepare_recording(
+                        s,
+                        picked_display,
+                        sidecar::current_meeting(&events),
+                        &mut stream_config,
+                        &mut capture_size,
+                        &mut unprofiled,
+                        reduced_quality,
+                        &mut current_filter,
+                    ));
+                    standby::set_warm(true);
+                    println!("⚡ Standing by to record");
+                }
+            } else if !standing_by && warmed.take().is_some() {
+                if let Some((config, size)) = unprofiled.take() {
+                    stream_config = config;
+                    capture_size = size;
+                    if let Some(ref s) = stream {
+                        let (config, _) = power::capture_settings(&stream_config, capture_size, reduced_quality);
+                        let _ = s.update_configuration(&config);
+                    }
+                }
+                if let Some(filter) = standby_filter.take() {
+                    if let Some(ref s) = stream {
+                        let _ = s.update_content_filter(&filter);
+                    }
+                    current_filter = Some(filter);
+                }
+                standby::set_warm(false);
+            }
+        }
+
+        // First check for picker results (continuously polling)
+        match picker_rx.try_recv() {
+            Ok(PickerEvent::Picked(PickedSource { mut filter, width, height, source, whole_display, window_id })) => {
+                // A new source needs preparing afresh; its settings replace the profile's
+                #[cfg(feature = "macos_15_0")]
+                if warmed.take().is_some() {
+                    unprofiled = None;
+                    standby_filter = None;
+                    standby::set_warm(false);
+                }
                 // Update source info immediately
                 let source_display = format_picked_source(&source);
+                // Meeting apps put the meeting's title in their window titles
+                let logged_source = match source {
+                    screencapturekit::content_sharing_picker::SCPickedSource::Window(ref title) => {
+                        format!("[W] {}", calendar_privacy::redact(title))
+                    }
+                    _ => source_display.clone(),
+                };
+                journal::record(journal::JournalEvent::SourceSelected { source: logged_source.clone() });
+                input::set_current_source(Some(source.clone()), window_id);
+                source_region = None;
+                window_tracker = None;
+                picked_display = match source {
+                    screencapturekit::content_sharing_picker::SCPickedSource::Display(id) => {
+                        input::set_source_display(Some(id));
+                        source_region = crop::display_region(id);
+                        // Apps picked on a display leave Notification Center out already,
+                        // and replacing their filter would record the whole display
+                        whole_display.then_some(id)
+                    }
+                    // Keep other apps' sound out of a window recording
+                    screencapturekit::content_sharing_picker::SCPickedSource::Window(_) => {
+                        let capture = window_id
+                            .ok_or_else(|| "The picked window could not be identified".to_string())
+                            .and_then(app_audio::for_window);
+                        match capture {
+                            Ok(capture) => {
+                                println!("🔈 Capturing audio from {} only", capture.application_name);
+                                input::set_source_display(Some(capture.display_id));
+                                window_tracker = Some(app_audio::WindowTracker::start(&capture));
+                                source_region = Some(capture.source_rect);
+                                filter = capture.filter;
+                            }
+                            Err(e) => eprintln!("⚠️  Capturing all system audio: {}", e),
+                        }
+                        None
+                    }
+                    _ => None,
+                };
+                source_size = (width, height);
+                (stream_config, capture_size) =
+                    crop::configure(&base_config, source_region, source_size, &CropSettings::load());
                 *source_name.lock().unwrap() = source_display.clone();
-                println!("✅ Source selected: {}", source_display);
+                session::update(|s| {
+                    s.source = Some(session::SourceHint { name: source_display.clone(), display_id: picked_display })
+                });
+                println!("✅ Source selected: {}", logged_source);
                 
                 // If already capturing, update the filter live
                 if is_capturing.load(Ordering::Relaxed) {
                     if let Some(ref s) = stream {
+                        let (config, _) = power::capture_settings(&stream_config, capture_size, reduced_quality);
+                        let _ = s.update_configuration(&config);
                         let _ = s.update_content_filter(&filter);
                         println!("🔄 Updated capture filter to new source");
                     }
+                    current_filter = Some(filter);
                 } else {
                     // Store filter and size for future capture
                     current_filter = Some(filter.clone());
-                    capture_size = (width, height);
-                    
+
                     // Auto-start capture after picking (like original app)
+                    low_battery = power::BatterySettings::load().reduces_quality_now();
+                    reduced_quality = low_battery || thermal::under_pressure();
+                    let (config, size) = power::capture_settings(&stream_config, capture_size, reduced_quality);
                     input::start_capture(
                         &mut stream,
                         Some(&filter),
-                        capture_size,
-                        &stream_config,
+                        size,
+                        &config,
                         &capture_state,
                         &is_capturing,
                         false,
                     );
                 }
             }
+            Ok(PickerEvent::Cancelled) => toast::show("Selection cancelled"),
+            Ok(PickerEvent::Failed(e)) => toast::error(format!("Could not select a source: {e}")),
+            Err(_) => {}
+        }
+
+        // Keep a window source's crop on the window as it is moved and resized
+        let moved = window_tracker.as_ref().zip(source_region).and_then(|(tracker, region)| {
+            tracker.moved(region).map(|rect| (region, rect))
+        });
+        if let Some((previous, rect)) = moved {
+            let scale = f64::from(source_size.0) / previous.width.max(1.0);
+            source_region = Some(rect);
+            source_size = ((rect.width * scale).round() as u32, (rect.height * scale).round() as u32);
+            let (config, size) = crop::configure(&base_config, source_region, source_size, &CropSettings::load());
+            stream_config.set_source_rect(config.source_rect());
+            // A recording keeps its dimensions; the window is scaled into them
+            if !is_recording.load(Ordering::Relaxed) {
+                capture_size = size;
+            }
+            if let Some(ref s) = stream {
+                let (config, _) = power::capture_settings(&stream_config, capture_size, reduced_quality);
+                if let Err(e) = s.update_configuration(&config) {
+                    eprintln!("⚠️  Failed to follow the window: {:?}", e);
+                }
+            }
         }
         
+        // Continue segmented recordings in a new file when the current part is full
+        #[cfg(feature = "macos_15_0")]
+        if let Some(ref s) = stream {
+            match recording_state.rotate_segment_if_due(s) {
+                Ok(true) => *recording_path.lock().unwrap() = recording_state.path.clone().unwrap_or_default(),
+                Ok(false) => {}
+                Err(e) => {
+                    // End the recording with what was written, as if the user stopped it
+                    toast::error(format!("Recording stopped: could not continue in a new part: {e}"));
+                    forced_command = Some(CaptureCommand::StopRecording);
+                }
+            }
+        }
+        // The main window times the recording by what has been written to it
+        #[cfg(feature = "macos_15_0")]
+        if recording_state.is_active() {
+            capture_state.set_recorded(recording_state.recorded_seconds());
+            frame_health::record_written(
+                recording_state.recorded_seconds(),
+                capture_state.recording_paused.load(Ordering::Relaxed),
+            );
+        }
+        if let Some(ref mock) = mock {
+            mock.publish_recorded();
+        }
+
+        // Snapshot requested from the preview window
+        if capture_state.snapshot_requested.swap(false, Ordering::Relaxed) {
+            if let Some(ref filter) = current_filter {
+                let saved = screenshot::take_screenshot(filter, capture_size, &stream_config);
+                *capture_state.last_snapshot.lock().unwrap() =
+                    saved.map(|path| (path, std::time::Instant::now()));
+            }
+        }
+
+        // A start that hit a passing error tries again once its wait is over
+        if forced_command.is_none() && input::retry_due() {
+            forced_command = Some(CaptureCommand::StartCapture);
+        }
+
         // Then check for commands (with timeout to continue polling)
-        if let Ok(cmd) = cmd_rx.recv_timeout(std::time::Duration::from_millis(50)) {
+        let next = match forced_command.take() {
+            Some(cmd) => Ok(cmd),
+            None => cmd_rx.recv_timeout(std::time::Duration::from_millis(50)),
+        };
+        if let Ok(cmd) = next {
+            let cmd = match mock.as_mut() {
+                Some(mock) => mock.handle(cmd),
+                None => Some(cmd),
+            };
+            let Some(cmd) = cmd else {
+                continue;
+            };
             match cmd {
                 CaptureCommand::SelectSource => {
-                    // Clear previous upload status and file ID
-                    *upload_status.lock().unwrap() = String::new();
-                    *uploaded_file_id.lock().unwrap() = String::new();
+                    // Clear the previous upload
+                    upload_state.send_replace(UploadState::default());
+                    uploaded_path.lock().unwrap().clear();
                     
                     // Open picker (result will be handled in the polling loop above)
                     if let Some(ref s) = stream {
-                        input::open_picker_for_stream(&pending_picker, s);
+                        input::open_picker_for_stream(&picker_tx, s);
                     } else {
-                        input::open_picker(&pending_picker);
+                        input::open_picker(&picker_tx);
                     }
                     println!("📺 Opening content picker...");
                 }
+                CaptureCommand::SelectDisplay(index) => {
+                    upload_state.send_replace(UploadState::default());
+                    uploaded_path.lock().unwrap().clear();
+
+                    input::select_display(index, &picker_tx);
+                }
                 CaptureCommand::StartCapture => {
                     if current_filter.is_some() {
+                        low_battery = power::BatterySettings::load().reduces_quality_now();
+                        reduced_quality = low_battery || thermal::under_pressure();
+                        let (config, size) = power::capture_settings(&stream_config, capture_size, reduced_quality);
                         input::start_capture(
                             &mut stream,
                             current_filter.as_ref(),
-                            capture_size,
-                            &stream_config,
+                            size,
+                            &config,
                             &capture_state,
                             &is_capturing,
                             false,
@@ -992,28 +5022,94 @@ fn run_capture_backend(
                 }
                 CaptureCommand::StopCapture => {
                     // Stop capture and clear source
-                    input::stop_capture(&mut stream, &is_capturing);
+                    input::stop_capture(&mut stream, &is_capturing, "stopped by the user");
                     *source_name.lock().unwrap() = "No source selected".to_string();
+                    session::update(|s| s.source = None);
+                    input::set_current_source(None, None);
+                    window_tracker = None;
                     current_filter = None;
                     println!("🔄 Source detached, ready to select new source");
                 }
                 CaptureCommand::TakeScreenshot => {
                     if is_capturing.load(Ordering::Relaxed) {
-                        println!("📸 Taking screenshot...");
-                        // Screenshot logic would go here
+                        if let Some(ref filter) = current_filter {
+                            let saved = screenshot::take_screenshot(filter, capture_size, &stream_config);
+                            *capture_state.last_snapshot.lock().unwrap() =
+                                saved.map(|path| (path, std::time::Instant::now()));
+                        }
                     }
                 }
                 CaptureCommand::StartRecording => {
                     #[cfg(feature = "macos_15_0")]
                     if is_capturing.load(Ordering::Relaxed) {
                         if let Some(ref s) = stream {
-                            match recording_state.start(s, &recording_config) {
+                            let metadata = {
+                                let events = meeting_events.lock().unwrap();
+                                let meeting = sidecar::current_meeting(&events);
+                                let chosen = profiles::ProfileSettings::load().choose(meeting).cloned();
+                                // Standby may have prepared the stream already
+                                let profile = match warmed.take() {
+                                    Some(ready) if ready.as_ref().map(|p| &p.name) == chosen.as_ref().map(|p| &p.name) => {
+                                        println!("⚡ Recording from standby");
+                                        ready
+                                    }
+                                    _ => {
+                                        if let Some((config, size)) = unprofiled.take() {
+                                            stream_config = config;
+                                            capture_size = size;
+                                        }
+                                        prepare_recording(
+                                            s,
+                                            picked_display,
+                                            meeting,
+                                            &mut stream_config,
+                                            &mut capture_size,
+                                            &mut unprofiled,
+                                            reduced_quality,
+                                            &mut current_filter,
+                                        )
+                                    }
+                                };
+                                // The recording keeps the filter standby chose
+                                standby_filter = None;
+                                standby::set_warm(false);
+                                focus::enable(&focus::FocusSettings::load());
+                                // Record the size frames are actually captured at
+                                let (_, recorded_size) = power::capture_settings(&stream_config, capture_size, reduced_quality);
+                                let mut metadata = sidecar::RecordingSidecar::new(
+                                    source_name.lock().unwrap().clone(),
+                                    recorded_size,
+                                    meeting,
+                                );
+                                metadata.provider = call_detect::current().map(|call| call.provider.to_string());
+                                metadata.profile = profile.as_ref().map(|p| p.name.clone());
+                                profiles::set_active(profile);
+                                metadata
+                            };
+                            match recording_state.start(s, &CaptureSettings::load().recording_config().with_allowed_codec(), &metadata) {
                                 Ok(path) => {
+                                    let started_at = frame_health::host_seconds();
+                                    capture_state.set_recorded(0.0);
+                                    capture_state.recording_paused.store(false, Ordering::Relaxed);
                                     is_recording.store(true, Ordering::Relaxed);
+                                    frame_health::reset(power::capture_settings(&stream_config, capture_size, reduced_quality).0.fps());
+                                    journal::record(journal::JournalEvent::RecordingStarted { path: path.clone() });
                                     println!("⏺ Recording started: {}", path);
+                                    if CaptureSettings::load().recording_config().segment_minutes.is_none() {
+                                        audio_tracks::start(&path, started_at);
+                                    }
+                                    *recording_path.lock().unwrap() = path;
                                 }
                                 Err(e) => {
                                     eprintln!("❌ Failed to start recording: {}", e);
+                                    journal::record(journal::JournalEvent::Error { message: format!("Failed to start recording: {e}") });
+                                    focus::restore();
+                                    profiles::set_active(None);
+                                    // Standby prepares the stream again from the picked settings
+                                    if let Some((config, size)) = unprofiled.take() {
+                                        stream_config = config;
+                                        capture_size = size;
+                                    }
                                 }
                             }
                         }
@@ -1024,82 +5120,79 @@ fn run_capture_backend(
                     {
                         if let Some(ref s) = stream {
                             println!("⏹ Stopping recording...");
-                            if let Some(path) = recording_state.stop(s) {
+                            let stopped = recording_state.stop(s);
+                            capture_state.recording_paused.store(false, Ordering::Relaxed);
+                            if frame_health::late_frames() > 0 || frame_health::dropped_frames() > 0 {
+                                eprintln!(
+                                    "⚠️  {} frames arrived late and {} were dropped during the recording; it may stutter",
+                                    frame_health::late_frames(),
+                                    frame_health::dropped_frames()
+                                );
+                            }
+                            frame_health::stop();
+                            recording_path.lock().unwrap().clear();
+                            focus::restore();
+                            profiles::set_active(None);
+                            if let Some((config, size)) = unprofiled.take() {
+                                stream_config = config;
+                                capture_size = size;
+                            }
+                            if let Some(path) = stopped {
                                 is_recording.store(false, Ordering::Relaxed);
+                                journal::record(journal::JournalEvent::RecordingStopped { path: path.clone() });
                                 println!("✅ Recording stopped and saved: {}", path);
                                 
                                 // Stop capture and clear source after recording
-                                input::stop_capture(&mut stream, &is_capturing);
+                                input::stop_capture(&mut stream, &is_capturing, "recording finished");
                                 *source_name.lock().unwrap() = "No source selected".to_string();
+                                input::set_current_source(None, None);
+                                window_tracker = None;
                                 current_filter = None;
                                 println!("🔄 Source cleared, ready for next recording");
                                 
-                                // Trigger upload to Talka backend
-                                let tokens_opt = auth_tokens.lock().unwrap().clone();
-                                if let Some(tokens) = tokens_opt {
-                                    println!("🚀 Starting upload to Talka backend...");
-                                    *upload_status.lock().unwrap() = "Preparing your recording".to_string();
-                                    
-                                    let runtime_clone = runtime.clone();
-                                    let recording_state_clone = recording_state.clone();
-                                    let upload_status_clone = Arc::clone(&upload_status);
-                                    let uploaded_file_id_clone = Arc::clone(&uploaded_file_id);
-                                    
-                                    runtime.spawn(async move {
-                                        // Refresh access token if needed
-                                        let access_token = if tokens.is_expired() {
-                                            println!("🔄 Refreshing access token...");
-                                            match auth::refresh_access_token(&tokens.refresh_token).await {
-                                                Ok(new_tokens) => {
-                                                    println!("✅ Token refreshed");
-                                                    let _ = auth::save_tokens(&new_tokens);
-                                                    new_tokens.access_token
-                                                }
-                                                Err(e) => {
-                                                    println!("⚠️ Token refresh failed: {}, using old token", e);
-                                                    tokens.access_token
-                                                }
-                                            }
-                                        } else {
-                                            tokens.access_token
-                                        };
-                                        
-                                        // Start upload with status updates
-                                        println!("📤 Uploading file: {}", path);
-                                        recording_state_clone.start_upload(
-                                            path,
-                                            access_token,
-                                            runtime_clone,
-                                        );
-                                        
-                                        // Monitor upload status and update UI
-                                        loop {
-                                            tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-                                            let current_status = recording_state_clone.upload_status.lock().unwrap().clone();
-                                            
-                                            let status_text = current_status.as_display_string();
-                                            if !status_text.is_empty() {
-                                                *upload_status_clone.lock().unwrap() = status_text.clone();
-                                            }
-                                            
-                                            // Extract and store file_id when complete
-                                            if let upload::UploadStatus::Complete { ref file_id } = current_status {
-                                                *uploaded_file_id_clone.lock().unwrap() = file_id.clone();
-                                            }
-                                            
-                                            // Stop monitoring if complete or failed
-                                            if matches!(current_status, upload::UploadStatus::Complete { .. } | upload::UploadStatus::Failed(_)) {
-                                                println!("📊 Upload finished: {:?}", current_status);
-                                                // Keep status displayed until user takes action
-                                                break;
-                                            }
+                                // Let the user trim the recording before it is uploaded;
+                                // segmented recordings are merged in the editor first
+                                let parts = recording_state.take_segments();
+                                let segmented = !parts.is_empty();
+                                let mut segments: Vec<String> = parts
+                                    .into_iter()
+                                    .filter(|part| match media::verify_recording(part) {
+                                        Ok(_) => true,
+                                        Err(e) => {
+                                            // Parts that did not finalize cannot be merged
+                                            route_to_recovery(part, &e, &upload_state);
+                                            false
                                         }
-                                    });
+                                    })
+                                    .collect();
+                                let session = if segments.len() > 1 {
+                                    println!("🧩 Recording has {} segments", segments.len());
+                                    Some((path.clone(), Ok(TrimSession::segmented(segments))))
+                                } else if let Some(part) = segments.pop() {
+                                    // Nothing to merge when only one part survived
+                                    println!("🧩 Only one segment of the recording is usable");
+                                    Some((part.clone(), TrimSession::open(part)))
+                                } else if segmented {
+                                    // Every part has already been sent to recovery
+                                    None
                                 } else {
-                                    println!("⚠️ No authentication tokens available for upload");
-                                    *upload_status.lock().unwrap() = "Please log in to upload recordings".to_string();
-                                    std::thread::sleep(std::time::Duration::from_secs(3));
-                                    *upload_status.lock().unwrap() = String::new();
+                                    // Adding the microphone tracks waits on ffmpeg, so the
+                                    // editor opens from a thread of its own
+                                    let trim_session = Arc::clone(&trim_session);
+                                    let upload_state = Arc::clone(&upload_state);
+                                    std::thread::spawn(move || {
+                                        if let Err(e) = audio_tracks::finish(&path, CaptureSettings::load().captures_microphone) {
+                                            // The track files stay next to the recording
+                                            eprintln!("⚠️  {}", e);
+                                            toast::error(e);
+                                        }
+                                        let session = TrimSession::open(path.clone());
+                                        open_trim_editor(&path, session, &trim_session, &upload_state);
+                                    });
+                                    None
+                                };
+                                if let Some((opened, session)) = session {
+                                    open_trim_editor(&opened, session, &trim_session, &upload_state);
                                 }
                             } else {
                                 println!("⚠️ No recording to stop");
@@ -1110,33 +5203,165 @@ fn run_capture_backend(
                     }
                     #[cfg(not(feature = "macos_15_0"))]
                     {
+                        let _ = &recording_path;
                         println!("⚠️ Recording not available (requires macOS 15.0+)");
                     }
                 }
+                #[cfg(feature = "macos_15_0")]
+                CaptureCommand::UploadRecording { trim } => {
+                    let session = trim_session.lock().unwrap().take();
+                    if let Some(session) = session {
+                        // The recording profile's steps run in the background, then
+                        // hand the result back here to upload
+                        let profile = sidecar::RecordingSidecar::load(&session.path).and_then(|s| s.profile);
+                        let steps = profiles::pipeline_for(profile.as_deref());
+                        upload_state.send_replace(UploadState::new(UploadPhase::PostProcessing));
+                        let progress_state = Arc::clone(&upload_state);
+                        let (tx, _, _, _, _, _, _, _) = get_global_state();
+                        pipeline::enqueue(pipeline::Job {
+                            path: session.path,
+                            steps,
+                            trim,
+                            on_progress: Box::new(move |progress| {
+                                progress_state.send_replace(UploadState {
+                                    percent: progress.overall_percent(),
+                                    step: Some(progress.describe()),
+                                    ..UploadState::new(UploadPhase::PostProcessing)
+                                });
+                            }),
+                            on_done: Box::new(move |path| {
+                                if let Some(ref sender) = tx {
+                                    let _ = sender.send(CaptureCommand::UploadProcessed { path });
+                                }
+                            }),
+                        });
+                    } else {
+                        println!("⚠️ No recording waiting for upload");
+                    }
+                }
+                #[cfg(not(feature = "macos_15_0"))]
+                CaptureCommand::UploadRecording { .. } => {
+                    println!("⚠️ Recording not available (requires macOS 15.0+)");
+                }
+                CaptureCommand::UploadProcessed { path } => {
+                    #[cfg(feature = "macos_15_0")]
+                    begin_upload(path, &recording_state, &auth_tokens, &upload_state, &uploaded_path, &runtime);
+                    #[cfg(not(feature = "macos_15_0"))]
+                    let _ = path;
+                }
+                CaptureCommand::MergeSegments => {
+                    // Joining hours of video takes a while, so it runs on its own
+                    // thread and the editor shows the session as merging meanwhile
+                    let segments = trim_session
+                        .lock()
+                        .unwrap()
+                        .as_mut()
+                        .filter(|session| !session.segments.is_empty() && !session.merging)
+                        .map(|session| {
+                            session.merging = true;
+                            session.merge_error = None;
+                            session.segments.clone()
+                        });
+                    if let Some(segments) = segments {
+                        let trim_session = Arc::clone(&trim_session);
+                        std::thread::spawn(move || {
+                            let result = library::merge_segments(&segments).and_then(TrimSession::open);
+                            {
+                                let mut slot = trim_session.lock().unwrap();
+                                // Unless the editor moved on to another recording meanwhile
+                                let Some(session) = slot.as_mut().filter(|session| session.segments == segments) else {
+                                    return;
+                                };
+                                match result {
+                                    Ok(merged) => *session = merged,
+                                    Err(e) => {
+                                        eprintln!("❌ Failed to merge segments: {}", e);
+                                        session.merging = false;
+                                        session.merge_error = Some(e);
+                                    }
+                                }
+                            }
+                            media::load_thumbnails(&trim_session);
+                        });
+                    }
+                }
+                CaptureCommand::PauseRecording | CaptureCommand::ResumeRecording => {
+                    #[cfg(feature = "macos_15_0")]
+                    if let Some(ref s) = stream {
+                        let result = if matches!(cmd, CaptureCommand::ResumeRecording) {
+                            recording_state.resume(s).map(|path| *recording_path.lock().unwrap() = path)
+                        } else if audio_tracks::is_recording() {
+                            Err("recordings with extra microphone tracks cannot be paused".to_string())
+                        } else {
+                            recording_state.pause(s)
+                        };
+                        match result {
+                            Ok(()) => {
+                                let paused = recording_state.is_paused();
+                                capture_state.recording_paused.store(paused, Ordering::Relaxed);
+                                toast::show(if paused { "⏸️ Recording paused" } else { "▶️ Recording resumed" });
+                            }
+                            Err(e) => toast::error(format!("Cannot pause or resume: {e}")),
+                        }
+                    }
+                    #[cfg(not(feature = "macos_15_0"))]
+                    toast::error("Recording not available (requires macOS 15.0+)");
+                }
+                CaptureCommand::AddBookmark => {
+                    #[cfg(feature = "macos_15_0")]
+                    match recording_state.add_bookmark() {
+                        Ok(offset) => {
+                            println!("🔖 Bookmark at {:.1}s", offset);
+                            toast::show(format!("🔖 Bookmarked {}", time_format::offset(offset.round() as u64)));
+                        }
+                        Err(e) => toast::error(format!("Cannot bookmark: {e}")),
+                    }
+                    #[cfg(not(feature = "macos_15_0"))]
+                    toast::error("Recording not available (requires macOS 15.0+)");
+                }
                 CaptureCommand::CancelRecording => {
                     #[cfg(feature = "macos_15_0")]
                     {
                         if let Some(ref s) = stream {
                             println!("✖️ Canceling recording...");
-                            if let Some(path) = recording_state.stop(s) {
+                            let stopped = recording_state.stop(s);
+                            capture_state.recording_paused.store(false, Ordering::Relaxed);
+                            recording_path.lock().unwrap().clear();
+                            focus::restore();
+                            profiles::set_active(None);
+                            if let Some((config, size)) = unprofiled.take() {
+                                stream_config = config;
+                                capture_size = size;
+                            }
+                            if let Some(path) = stopped {
                                 is_recording.store(false, Ordering::Relaxed);
+                                journal::record(journal::JournalEvent::RecordingCancelled { path: path.clone() });
+                                audio_tracks::discard();
                                 println!("🗑️ Deleting recording: {}", path);
                                 
-                                // Delete the recorded file
-                                if let Err(e) = std::fs::remove_file(&path) {
-                                    eprintln!("⚠️ Failed to delete recording file: {}", e);
-                                } else {
-                                    println!("✅ Recording file deleted");
+                                // Delete the recorded file (every part of a segmented recording)
+                                let mut files = recording_state.take_segments();
+                                if files.is_empty() {
+                                    files.push(path);
+                                }
+                                for file in &files {
+                                    if let Err(e) = library::delete_recording(file) {
+                                        eprintln!("⚠️ Failed to delete recording file: {}", e);
+                                    } else {
+                                        println!("✅ Recording file deleted");
+                                    }
                                 }
                                 
                                 // Stop capture and clear source
-                                input::stop_capture(&mut stream, &is_capturing);
+                                input::stop_capture(&mut stream, &is_capturing, "recording cancelled");
                                 *source_name.lock().unwrap() = "No source selected".to_string();
+                                input::set_current_source(None, None);
+                                window_tracker = None;
                                 current_filter = None;
                                 println!("🔄 Source cleared, ready for next recording");
                                 
                                 // Clear upload status (no upload on cancel)
-                                *upload_status.lock().unwrap() = String::new();
+                                upload_state.send_replace(UploadState::default());
                             } else {
                                 println!("⚠️ No recording to cancel");
                             }
@@ -1149,6 +5374,50 @@ fn run_capture_backend(
                         println!("⚠️ Recording not available (requires macOS 15.0+)");
                     }
                 }
+                CaptureCommand::ShowPreview | CaptureCommand::ShowPictureInPicture => {
+                    let style = if matches!(cmd, CaptureCommand::ShowPreview) {
+                        PreviewStyle::Window
+                    } else {
+                        PreviewStyle::PictureInPicture
+                    };
+                    previews.open(
+                        PreviewSource {
+                            label: source_name.lock().unwrap().clone(),
+                            capture_state: Arc::clone(&capture_state),
+                            is_capturing: Arc::clone(&is_capturing),
+                        },
+                        style,
+                    );
+                }
+                CaptureCommand::EditCrop | CaptureCommand::ApplyCrop => {
+                    let settings = if matches!(cmd, CaptureCommand::EditCrop) {
+                        CropSettings::default()
+                    } else {
+                        CropSettings::load()
+                    };
+                    (stream_config, capture_size) =
+                        crop::configure(&base_config, source_region, source_size, &settings);
+                    if let Some(ref s) = stream {
+                        let (config, _) = power::capture_settings(&stream_config, capture_size, reduced_quality);
+                        if let Err(e) = s.update_configuration(&config) {
+                            eprintln!("⚠️  Failed to update crop: {:?}", e);
+                        }
+                    }
+                }
+                CaptureCommand::ApplyCaptureSettings => {
+                    let options = CaptureSettings::load();
+                    base_config = options.stream_config();
+                    idle_timeout = options.idle_timeout();
+                    (stream_config, capture_size) =
+                        crop::configure(&base_config, source_region, source_size, &CropSettings::load());
+                    if let Some(ref s) = stream {
+                        let (config, _) = power::capture_settings(&stream_config, capture_size, reduced_quality);
+                        match s.update_configuration(&config) {
+                            Ok(()) => println!("⚙️  Capture settings applied"),
+                            Err(e) => eprintln!("⚠️  Failed to apply capture settings: {:?}", e),
+                        }
+                    }
+                }
                 CaptureCommand::ToggleMicrophone => {
                     println!("🎤 Toggle microphone");
                 }
@@ -1163,24 +5432,185 @@ fn run_capture_backend(
     }
 }
 
+/// Hide notification banners from a display recording and switch the stream to the
+/// recording profile for `meeting`, returning the profile
+///
+/// Done when Start Recording is pressed, or ahead of it in standby.
+#[cfg(feature = "macos_15_0")]
+#[allow(clippy::too_many_arguments)]
+fn prepare_recording(
+    stream: &SCStream,
+    picked_display: Option<u32>,
+    meeting: Option<&auth::MeetingEvent>,
+    stream_config: &mut SCStreamConfiguration,
+    capture_size: &mut (u32, u32),
+    unprofiled: &mut Option<(SCStreamConfiguration, (u32, u32))>,
+    reduced_quality: bool,
+    current_filter: &mut Option<SCContentFilter>,
+) -> Option<profiles::RecordingProfile> {
+    if let Some(display_id) = picked_display.filter(|_| focus::FocusSettings::load().hide_notifications) {
+        match focus::filter_without_notifications(display_id) {
+            Ok(filter) => {
+                if stream.update_content_filter(&filter).is_ok() {
+                    println!("🔕 Notification banners hidden from recording");
+                    *current_filter = Some(filter);
+                }
+            }
+            Err(e) => eprintln!("⚠️  {}", e),
+        }
+    }
+    let profile = profiles::ProfileSettings::load().choose(meeting).cloned();
+    if let Some(ref profile) = profile {
+        println!("🎛️  Recording with profile \"{}\" ({})", profile.name, profile.summary());
+        let (config, size) = profile.apply(stream_config, *capture_size);
+        *unprofiled = Some((std::mem::replace(stream_config, config), *capture_size));
+        *capture_size = size;
+        let (config, _) = power::capture_settings(stream_config, *capture_size, reduced_quality);
+        if let Err(e) = stream.update_configuration(&config) {
+            eprintln!("⚠️  Failed to apply recording profile: {:?}", e);
+        }
+    }
+    profile
+}
+
+/// Hand a stopped recording to the trim editor, or to recovery when it cannot be opened
+#[cfg(feature = "macos_15_0")]
+fn open_trim_editor(
+    path: &str,
+    session: Result<TrimSession, String>,
+    trim_session: &Arc<Mutex<Option<TrimSession>>>,
+    upload_state: &watch::Sender<UploadState>,
+) {
+    match session {
+        Ok(session) => {
+            println!("✂️  Recording ready to trim ({:.1}s)", session.duration);
+            *trim_session.lock().unwrap() = Some(session);
+            media::load_thumbnails(trim_session);
+        }
+        Err(e) => route_to_recovery(path, &e, upload_state),
+    }
+}
+
+/// Keep a recording that failed verification locally and tell the user why
+#[cfg(feature = "macos_15_0")]
+fn route_to_recovery(path: &str, error: &str, upload_state: &watch::Sender<UploadState>) {
+    eprintln!("❌ Recording failed verification ({}): {}", error, path);
+    library::mark_damaged(path, error);
+    upload_state.send_replace(UploadState::failed(UploadPhase::Unverified, error));
+}
+
+/// Upload a finished recording to the Talka backend and mirror its progress into the UI
+#[cfg(feature = "macos_15_0")]
+fn begin_upload(
+    path: String,
+    recording_state: &RecordingState,
+    auth_tokens: &Arc<Mutex<Option<auth::AuthTokens>>>,
+    upload_state: &Arc<watch::Sender<UploadState>>,
+    uploaded_path: &Arc<Mutex<String>>,
+    runtime: &tokio::runtime::Handle,
+) {
+    // Never upload media that was not finalized properly
+    if let Err(e) = media::verify_recording(&path) {
+        route_to_recovery(&path, &e, upload_state);
+        return;
+    }
+
+    *uploaded_path.lock().unwrap() = path.clone();
+
+    let tokens_opt = auth_tokens.lock().unwrap().clone();
+    if let Some(tokens) = tokens_opt {
+        println!("🚀 Starting upload to Talka backend...");
+        upload_state.send_replace(UploadState::new(UploadPhase::Preparing));
+        
+        let runtime_clone = runtime.clone();
+        let recording_state_clone = recording_state.clone();
+        let upload_state_clone = Arc::clone(upload_state);
+        
+        runtime.spawn(async move {
+            // Don't drain a nearly flat battery on a large upload
+            if power::BatterySettings::load().defers_uploads_now() {
+                println!("🔋 Low battery: upload waiting for power");
+                upload_state_clone.send_replace(UploadState::new(UploadPhase::WaitingForPower));
+                while power::BatterySettings::load().defers_uploads_now() {
+                    tokio::time::sleep(power::BATTERY_CHECK_INTERVAL).await;
+                }
+                upload_state_clone.send_replace(UploadState::new(UploadPhase::Preparing));
+            }
+
+            // Refresh access token if needed
+            let access_token = if tokens.is_expired() {
+                println!("🔄 Refreshing access token...");
+                match auth::refresh_access_token(&tokens.refresh_token).await {
+                    Ok(new_tokens) => {
+                        println!("✅ Token refreshed");
+                        let _ = auth::save_tokens(&new_tokens);
+                        naming::refresh_in_background(new_tokens.access_token.clone());
+                        new_tokens.access_token
+                    }
+                    Err(e) => {
+                        println!("⚠️ Token refresh failed: {}, using old token", e);
+                        tokens.access_token
+                    }
+                }
+            } else {
+                tokens.access_token
+            };
+
+            // Ask before a long upload on a slow connection
+            if upload_queue::check_before_upload(&path, &access_token).await == upload_queue::Decision::Queue {
+                match upload_queue::park(&path) {
+                    Ok(()) => toast::show("Upload queued until the connection improves"),
+                    Err(e) => toast::error(format!("Failed to queue upload: {e}")),
+                }
+                upload_state_clone.send_replace(UploadState::default());
+                return;
+            }
+            
+            // Start upload with status updates
+            println!("📤 Uploading file: {}", path);
+            recording_state_clone.start_upload(
+                path.clone(),
+                access_token,
+                runtime_clone,
+            );
+            
+            // Monitor upload status and update UI
+            loop {
+                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+                let current_status = recording_state_clone.upload_status.lock().unwrap().clone();
+                
+                // Only wake the window when the upload has actually moved on
+                if current_status != upload::UploadStatus::Idle {
+                    let next = UploadState::from(&current_status);
+                    upload_state_clone.send_if_modified(|state| {
+                        let changed = *state != next;
+                        *state = next;
+                        changed
+                    });
+                }
+                
+                // Stop monitoring if complete or failed
+                if matches!(current_status, upload::UploadStatus::Complete { .. } | upload::UploadStatus::Failed(_)) {
+                    println!("📊 Upload finished: {:?}", current_status);
+                    // Keep status displayed until user takes action
+                    break;
+                }
+            }
+        });
+    } else {
+        println!("⚠️ No authentication tokens available for upload");
+        upload_state.send_replace(UploadState::new(UploadPhase::SignedOut));
+        std::thread::sleep(std::time::Duration::from_secs(3));
+        upload_state.send_replace(UploadState::default());
+    }
+}
+
 /// Helper to copy text to clipboard (macOS specific)
-fn copy_to_clipboard(text: &str) -> Result<(), String> {
-    use std::process::Command;
-    
-    let mut child = Command::new("pbcopy")
-        .stdin(std::process::Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to spawn pbcopy: {}", e))?;
-    
-    use std::io::Write;
-    if let Some(mut stdin) = child.stdin.take() {
-        stdin.write_all(text.as_bytes())
-            .map_err(|e| format!("Failed to write to pbcopy: {}", e))?;
+fn copy_to_clipboard(text: &str) {
+    match clipboard::copy_text(text) {
+        Ok(()) => toast::show("Copied to clipboard"),
+        Err(e) => toast::error(e),
     }
-    
-    child.wait().map_err(|e| format!("Failed to wait for pbcopy: {}", e))?;
-    println!("📋 Copied to clipboard: {}", text);
-    Ok(())
 }
 
 async fn authenticate_user_with_ui(auth_state: &Arc<Mutex<AuthState>>) -> Result<(auth::AuthTokens, auth::UserProfile), String> {
@@ -1045,13 +1045,8 @@ fn run_app() -> i32 {
                     ));
 
                     // Uniforms - pass capture texture dimensions for aspect ratio
-                    let uniforms = Uniforms {
-                        viewport_size: [width, height],
-                        texture_size: [tex_width, tex_height],
-                        time,
-                        pixel_format,
-                        _padding: [0.0; 2],
-                    };
+                    let uniforms =
+                        Uniforms::new([width, height], [tex_width, tex_height], time, pixel_format);
                     let uniforms_buffer = device.new_buffer_with_data(
                         std::ptr::addr_of!(uniforms).cast(),
                         size_of::<Uniforms>() as u64,
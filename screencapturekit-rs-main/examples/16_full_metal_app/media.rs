@@ -0,0 +1,377 @@
+//! Post-recording media operations
+//!
+//! Reads the MP4/MOV headers directly and performs edits with the system `avconvert`
+//! tool using the passthrough preset, so recordings are never re-encoded. The one
+//! exception is [`transcode`], used for deliberate archival compression.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+/// Number of thumbnails shown under the trim scrubber
+pub const THUMBNAIL_COUNT: usize = 6;
+
+/// A finished recording waiting in the trim editor before upload
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrimSession {
+    pub path: String,
+    /// Length of the recording in seconds
+    pub duration: f64,
+    /// JPEG `data:` URIs spread evenly across the recording
+    pub thumbnails: Vec<String>,
+    /// Parts of a segmented recording that must be merged before trimming
+    pub segments: Vec<String>,
+    /// Why the last merge attempt failed
+    pub merge_error: Option<String>,
+    /// The parts are being merged in the background
+    pub merging: bool,
+}
+
+impl TrimSession {
+    /// Probe a recording and prepare it for the trim editor
+    ///
+    /// Thumbnails are left to [`load_thumbnails`], once the session is shown.
+    pub fn open(path: String) -> Result<Self, String> {
+        let duration = verify_recording(&path)?;
+        Ok(Self {
+            path,
+            duration,
+            thumbnails: Vec::new(),
+            segments: Vec::new(),
+            merge_error: None,
+            merging: false,
+        })
+    }
+
+    /// A segmented recording, shown in the editor until its parts are merged
+    pub fn segmented(segments: Vec<String>) -> Self {
+        let duration = segments
+            .iter()
+            .filter_map(|part| duration_seconds(part).ok())
+            .sum();
+        Self {
+            path: segments.first().cloned().unwrap_or_default(),
+            duration,
+            thumbnails: Vec::new(),
+            segments,
+            merge_error: None,
+            merging: false,
+        }
+    }
+}
+
+/// Find the first box of type `kind` in `[start, end)`, returning its payload range
+fn find_box(
+    file: &mut File,
+    start: u64,
+    end: u64,
+    kind: &[u8; 4],
+) -> std::io::Result<Option<(u64, u64)>> {
+    let mut pos = start;
+    while pos + 8 <= end {
+        file.seek(SeekFrom::Start(pos))?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header)?;
+
+        let mut size = u64::from(u32::from_be_bytes([header[0], header[1], header[2], header[3]]));
+        let mut header_len = 8;
+        if size == 1 {
+            // 64-bit box size follows the type
+            let mut large = [0u8; 8];
+            file.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            // Box extends to the end of its parent
+            size = end - pos;
+        }
+        if size < header_len {
+            break;
+        }
+        // A corrupt size must not wrap around to boxes already read
+        let Some(next) = pos.checked_add(size) else {
+            break;
+        };
+
+        if &header[4..8] == kind {
+            return Ok(Some((pos + header_len, next.min(end))));
+        }
+        if next >= end {
+            break;
+        }
+        pos = next;
+    }
+    Ok(None)
+}
+
+/// Duration of an MP4/MOV file in seconds, read from its `moov/mvhd` header
+pub fn duration_seconds(path: &str) -> Result<f64, String> {
+    movie_duration(path)?.ok_or_else(|| "Missing moov atom".to_string())
+}
+
+/// Like [`duration_seconds`], with `None` when the file has no `moov` atom
+fn movie_duration(path: &str) -> Result<Option<f64>, String> {
+    let io_err = |e: std::io::Error| format!("Failed to read {path}: {e}");
+
+    let mut file = File::open(path).map_err(io_err)?;
+    let len = file.metadata().map_err(io_err)?.len();
+    let Some((moov_start, moov_end)) = find_box(&mut file, 0, len, b"moov").map_err(io_err)? else {
+        return Ok(None);
+    };
+    let (mvhd_start, _) = find_box(&mut file, moov_start, moov_end, b"mvhd")
+        .map_err(io_err)?
+        .ok_or_else(|| "Missing mvhd atom".to_string())?;
+
+    file.seek(SeekFrom::Start(mvhd_start)).map_err(io_err)?;
+    let mut version = [0u8; 4];
+    file.read_exact(&mut version).map_err(io_err)?;
+    let (timescale, duration) = if version[0] == 1 {
+        // creation(8) modification(8) timescale(4) duration(8)
+        let mut buf = [0u8; 28];
+        file.read_exact(&mut buf).map_err(io_err)?;
+        (
+            u32::from_be_bytes([buf[16], buf[17], buf[18], buf[19]]),
+            u64::from_be_bytes([
+                buf[20], buf[21], buf[22], buf[23], buf[24], buf[25], buf[26], buf[27],
+            ]),
+        )
+    } else {
+        // creation(4) modification(4) timescale(4) duration(4)
+        let mut buf = [0u8; 16];
+        file.read_exact(&mut buf).map_err(io_err)?;
+        (
+            u32::from_be_bytes([buf[8], buf[9], buf[10], buf[11]]),
+            u64::from(u32::from_be_bytes([buf[12], buf[13], buf[14], buf[15]])),
+        )
+    };
+
+    if timescale == 0 {
+        return Err("Invalid mvhd timescale".to_string());
+    }
+    Ok(Some(duration as f64 / f64::from(timescale)))
+}
+
+/// Check that a finished recording was finalized and is playable
+///
+/// A file is accepted when it is non-empty, has a `moov` atom and a positive duration;
+/// the duration in seconds is returned.
+pub fn verify_recording(path: &str) -> Result<f64, String> {
+    let len = std::fs::metadata(path)
+        .map_err(|e| format!("Recording file is missing: {e}"))?
+        .len();
+    if len == 0 {
+        return Err("Recording file is empty".to_string());
+    }
+    let duration = movie_duration(path)?
+        .ok_or_else(|| "Recording was not finalized (missing moov atom)".to_string())?;
+    if duration <= 0.0 {
+        return Err("Recording has no duration".to_string());
+    }
+    Ok(duration)
+}
+
+/// Path next to `path` with `_suffix` appended to the file stem
+pub fn sibling_path(path: &str, suffix: &str, extension: Option<&str>) -> String {
+    let p = Path::new(path);
+    let stem = p.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    let ext = extension
+        .or_else(|| p.extension().and_then(|e| e.to_str()))
+        .unwrap_or("mp4");
+    p.with_file_name(format!("{stem}_{suffix}.{ext}"))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Run `avconvert` with the passthrough preset
+fn run_avconvert(source: &str, output: &str, extra_args: &[&str]) -> Result<(), String> {
+    let result = Command::new("avconvert")
+        .args(["--source", source, "--output", output])
+        .args(["--preset", "PresetPassthrough", "--replace"])
+        .args(extra_args)
+        .output()
+        .map_err(|e| format!("Failed to run avconvert: {e}"))?;
+
+    if !result.status.success() {
+        return Err(format!(
+            "avconvert failed: {}",
+            String::from_utf8_lossy(&result.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Losslessly cut a recording to `[start, end]` seconds, returning the new file's path
+///
+/// Passthrough trimming cuts on keyframes, so the result may start slightly earlier
+/// than requested.
+pub fn trim_passthrough(path: &str, start: f64, end: f64) -> Result<String, String> {
+    if end <= start {
+        return Err(format!("Invalid trim range {start:.2}s - {end:.2}s"));
+    }
+    let output = sibling_path(path, "trimmed", None);
+    cut_passthrough(path, start, end, &output)?;
+    println!("✂️  Trimmed recording saved: {output}");
+    Ok(output)
+}
+
+/// Losslessly copy `[start, end]` seconds of a recording to `output`
+pub fn cut_passthrough(path: &str, start: f64, end: f64, output: &str) -> Result<(), String> {
+    if end <= start {
+        return Err(format!("Invalid cut range {start:.2}s - {end:.2}s"));
+    }
+    let start_arg = format!("{start:.3}");
+    let duration_arg = format!("{:.3}", end - start);
+    run_avconvert(
+        path,
+        output,
+        &["--start", &start_arg, "--duration", &duration_arg],
+    )
+}
+
+/// Rewrap a recording in another container (e.g. MOV to MP4) without re-encoding
+///
+/// Returns the new file's path, next to the original.
+pub fn remux_passthrough(path: &str, extension: &str) -> Result<String, String> {
+    let output = sibling_path(path, "remux", Some(extension));
+    run_avconvert(path, &output, &[])?;
+    println!("📦 Remuxed recording saved: {output}");
+    Ok(output)
+}
+
+/// Join recordings end to end without re-encoding
+///
+/// `avconvert` cannot concatenate, so this uses `ffmpeg`'s concat demuxer; all parts
+/// must share the same codec settings, which segments of one recording always do.
+pub fn concat_passthrough(parts: &[String], output: &str) -> Result<(), String> {
+    let list_path = format!("{output}.txt");
+    let list: String = parts
+        .iter()
+        .map(|part| format!("file '{}'\n", part.replace('\'', "'\\''")))
+        .collect();
+    std::fs::write(&list_path, list).map_err(|e| format!("Failed to write {list_path}: {e}"))?;
+
+    let result = Command::new("ffmpeg")
+        .args(["-v", "error", "-y", "-f", "concat", "-safe", "0", "-i", &list_path])
+        .args(["-c", "copy", "-movflags", "+faststart", output])
+        .output();
+    let _ = std::fs::remove_file(&list_path);
+
+    let result = result.map_err(|e| format!("Failed to run ffmpeg (is it installed?): {e}"))?;
+    if !result.status.success() {
+        return Err(format!(
+            "ffmpeg failed: {}",
+            String::from_utf8_lossy(&result.stderr).trim()
+        ));
+    }
+    println!("🔗 Merged {} segments into {output}", parts.len());
+    Ok(())
+}
+
+/// Re-encode a recording with `ffmpeg`, passing `args` as the output options
+pub fn transcode(source: &str, output: &str, args: &[&str]) -> Result<(), String> {
+    let result = Command::new("ffmpeg")
+        .args(["-v", "error", "-y", "-i", source])
+        .args(args)
+        .args(["-movflags", "+faststart", output])
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg (is it installed?): {e}"))?;
+    if !result.status.success() {
+        return Err(format!(
+            "ffmpeg failed: {}",
+            String::from_utf8_lossy(&result.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Open a recording in QuickTime Player and start playing `at` seconds in
+pub fn play_at(path: &str, at: f64) -> Result<(), String> {
+    let escaped = path.replace('\\', "\\\\").replace('"', "\\\"");
+    let script = format!(
+        "tell application \"QuickTime Player\"
+            activate
+            set doc to open POSIX file \"{escaped}\"
+            set current time of doc to {at:.1}
+            play doc
+        end tell"
+    );
+    let status = Command::new("osascript")
+        .args(["-e", &script])
+        .status()
+        .map_err(|e| format!("Failed to run osascript: {e}"))?;
+    if !status.success() {
+        return Err("QuickTime Player could not open the recording".to_string());
+    }
+    Ok(())
+}
+
+/// One thumbnail of `path` at `at` seconds, or `Err` when ffmpeg is missing
+fn thumbnail(path: &str, at: f64) -> Result<Option<String>, ()> {
+    let output = Command::new("ffmpeg")
+        .args(["-v", "error", "-ss", &format!("{at:.3}"), "-i", path])
+        .args(["-frames:v", "1", "-vf", "scale=120:-1"])
+        .args(["-f", "image2pipe", "-vcodec", "mjpeg", "-"])
+        .output()
+        .map_err(|_| ())?;
+    Ok((output.status.success() && !output.stdout.is_empty())
+        .then(|| format!("data:image/jpeg;base64,{}", base64_encode(&output.stdout))))
+}
+
+/// Fill in the thumbnails of the session in `slot` on a background thread
+///
+/// The thumbnails are JPEGs spread across the recording, as `data:` URIs for the
+/// trim editor. Frames are extracted with `ffmpeg` when it is installed; without it
+/// the editor simply shows the scrubber without thumbnails.
+///
+/// Stops between thumbnails once the session has been uploaded, cancelled or
+/// replaced, so it never holds up the capture backend.
+pub fn load_thumbnails(slot: &Arc<Mutex<Option<TrimSession>>>) {
+    let Some((path, duration)) = slot
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|s| s.segments.is_empty() && s.thumbnails.is_empty())
+        .map(|s| (s.path.clone(), s.duration))
+    else {
+        return;
+    };
+    let slot = Arc::clone(slot);
+    std::thread::spawn(move || {
+        for i in 0..THUMBNAIL_COUNT {
+            if !slot.lock().unwrap().as_ref().is_some_and(|s| s.path == path) {
+                return;
+            }
+            let at = duration * (i as f64 + 0.5) / THUMBNAIL_COUNT as f64;
+            match thumbnail(&path, at) {
+                Ok(Some(thumb)) => {
+                    if let Some(session) = slot.lock().unwrap().as_mut().filter(|s| s.path == path) {
+                        session.thumbnails.push(thumb);
+                    }
+                }
+                Ok(None) => {}
+                Err(()) => {
+                    println!("⚠️  ffmpeg not found, trim editor will show no thumbnails");
+                    return;
+                }
+            }
+        }
+    });
+}
+
+pub fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        out.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        out.push(ALPHABET[(n >> 12) as usize & 63] as char);
+        out.push(if chunk.len() > 1 { ALPHABET[(n >> 6) as usize & 63] as char } else { '=' });
+        out.push(if chunk.len() > 2 { ALPHABET[n as usize & 63] as char } else { '=' });
+    }
+    out
+}
@@ -0,0 +1,158 @@
+//! Meeting summary export
+//!
+//! Turns a recording's sidecar into a readable summary: title, date, attendees,
+//! bookmarks with timestamps, notes and the transcript. Offered in the library for
+//! recordings that have a transcript, as Markdown or HTML saved next to the recording
+//! (`recording_123.summary.md`), or as Markdown on the clipboard. Recordings are
+//! transcribed by default once a Whisper model is installed; see
+//! [`pipeline::default_steps`](crate::pipeline::default_steps).
+
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::library::LibraryEntry;
+use crate::time_format;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryFormat {
+    Markdown,
+    Html,
+}
+
+impl SummaryFormat {
+    pub const ALL: [Self; 2] = [Self::Markdown, Self::Html];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Markdown => "Markdown",
+            Self::Html => "HTML",
+        }
+    }
+
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "summary.md",
+            Self::Html => "summary.html",
+        }
+    }
+}
+
+/// Whether the recording has a transcript to summarize
+pub fn available(entry: &LibraryEntry) -> bool {
+    entry
+        .sidecar
+        .as_ref()
+        .and_then(|s| s.transcript.as_deref())
+        .is_some_and(|t| !t.trim().is_empty())
+}
+
+/// Offset into the recording, e.g. "4:05" or "1:02:09"
+fn timestamp(seconds: f64) -> String {
+    time_format::offset(seconds.max(0.0).round() as u64)
+}
+
+/// When the recording was made, in local time
+fn recorded_on(entry: &LibraryEntry) -> Option<String> {
+    let sidecar = entry.sidecar.as_ref()?;
+    let started = sidecar
+        .calendar_event
+        .as_ref()
+        .map_or(sidecar.recorded_at.as_str(), |e| e.start_time.as_str());
+    time_format::parse(started).map(time_format::date_time)
+}
+
+pub fn render(entry: &LibraryEntry, format: SummaryFormat) -> String {
+    match format {
+        SummaryFormat::Markdown => render_markdown(entry),
+        SummaryFormat::Html => render_html(entry),
+    }
+}
+
+fn render_markdown(entry: &LibraryEntry) -> String {
+    let mut out = format!("# {}\n\n", entry.title());
+    if let Some(date) = recorded_on(entry) {
+        let _ = writeln!(out, "*{date}*\n");
+    }
+    let Some(sidecar) = entry.sidecar.as_ref() else {
+        return out;
+    };
+
+    let participants = sidecar.calendar_event.as_ref().map(|e| e.participants.as_slice()).unwrap_or_default();
+    if !participants.is_empty() {
+        out.push_str("## Attendees\n\n");
+        for participant in participants {
+            let _ = writeln!(out, "- {participant}");
+        }
+        out.push('\n');
+    }
+    if !sidecar.bookmarks.is_empty() {
+        out.push_str("## Bookmarks\n\n");
+        for bookmark in &sidecar.bookmarks {
+            let _ = writeln!(out, "- {}", timestamp(*bookmark));
+        }
+        out.push('\n');
+    }
+    if !sidecar.notes.is_empty() {
+        let _ = writeln!(out, "## Notes\n\n{}\n", sidecar.notes);
+    }
+    if let Some(transcript) = sidecar.transcript.as_deref() {
+        let _ = writeln!(out, "## Transcript\n\n{}", transcript.trim());
+    }
+    out
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_html(entry: &LibraryEntry) -> String {
+    let title = html_escape(&entry.title());
+    let mut out = format!(
+        "<!doctype html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title></head>\n\
+         <body style=\"font-family:-apple-system,sans-serif;max-width:720px;margin:2em auto;line-height:1.5\">\n\
+         <h1>{title}</h1>\n"
+    );
+    if let Some(date) = recorded_on(entry) {
+        let _ = writeln!(out, "<p><em>{date}</em></p>");
+    }
+    if let Some(sidecar) = entry.sidecar.as_ref() {
+        let participants = sidecar.calendar_event.as_ref().map(|e| e.participants.as_slice()).unwrap_or_default();
+        if !participants.is_empty() {
+            out.push_str("<h2>Attendees</h2>\n<ul>\n");
+            for participant in participants {
+                let _ = writeln!(out, "<li>{}</li>", html_escape(participant));
+            }
+            out.push_str("</ul>\n");
+        }
+        if !sidecar.bookmarks.is_empty() {
+            out.push_str("<h2>Bookmarks</h2>\n<ul>\n");
+            for bookmark in &sidecar.bookmarks {
+                let _ = writeln!(out, "<li>{}</li>", timestamp(*bookmark));
+            }
+            out.push_str("</ul>\n");
+        }
+        if !sidecar.notes.is_empty() {
+            let _ = writeln!(out, "<h2>Notes</h2>\n<p style=\"white-space:pre-wrap\">{}</p>", html_escape(&sidecar.notes));
+        }
+        if let Some(transcript) = sidecar.transcript.as_deref() {
+            let _ = writeln!(
+                out,
+                "<h2>Transcript</h2>\n<p style=\"white-space:pre-wrap\">{}</p>",
+                html_escape(transcript.trim())
+            );
+        }
+    }
+    out.push_str("</body></html>\n");
+    out
+}
+
+/// Write the summary next to the recording and return its path
+pub fn save(entry: &LibraryEntry, format: SummaryFormat) -> Result<String, String> {
+    let output = Path::new(&entry.path).with_extension(format.extension());
+    std::fs::write(&output, render(entry, format))
+        .map_err(|e| format!("Failed to write {}: {e}", output.display()))?;
+    Ok(output.to_string_lossy().into_owned())
+}
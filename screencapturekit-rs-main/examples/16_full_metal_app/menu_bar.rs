@@ -0,0 +1,77 @@
+//! Menu bar status item
+//!
+//! Shows a live countdown to the next calendar event ("Sync in 12m") so upcoming
+//! meetings are visible while the recorder window is in the background. AppKit
+//! objects must only be touched from the main thread, where the Dioxus UI runs.
+
+use cocoa::base::{id, nil, NO, YES};
+use cocoa::foundation::NSString;
+use objc::{class, msg_send, sel, sel_impl};
+use std::cell::Cell;
+
+use crate::auth::{self, MeetingEvent};
+
+/// `NSVariableStatusItemLength`
+const VARIABLE_LENGTH: f64 = -1.0;
+
+/// Longest meeting name shown before it is cut short
+const MAX_TITLE_CHARS: usize = 18;
+
+thread_local! {
+    /// Status item, created on first use and kept for the life of the app
+    static STATUS_ITEM: Cell<id> = Cell::new(nil);
+}
+
+/// Menu bar text for the next meeting in the coming day, e.g. "Sync in 1h 5m"
+pub fn countdown_title(events: &[MeetingEvent]) -> Option<String> {
+    let meeting = auth::next_meeting(events, chrono::Duration::hours(24))?;
+    let minutes = (meeting.start_time()? - chrono::Utc::now()).num_minutes().max(0);
+    let until = match minutes {
+        0 => "now".to_string(),
+        m if m < 60 => format!("in {m}m"),
+        m => format!("in {}h {}m", m / 60, m % 60),
+    };
+
+    let name: String = meeting.event_summary.trim().chars().take(MAX_TITLE_CHARS).collect();
+    let name = if meeting.event_summary.trim().chars().count() > MAX_TITLE_CHARS {
+        format!("{}…", name.trim_end())
+    } else if name.is_empty() {
+        "Meeting".to_string()
+    } else {
+        name
+    };
+    Some(format!("{name} {until}"))
+}
+
+fn status_item() -> id {
+    STATUS_ITEM.with(|cell| {
+        if cell.get() == nil {
+            let item: id = unsafe {
+                let bar: id = msg_send![class!(NSStatusBar), systemStatusBar];
+                let item: id = msg_send![bar, statusItemWithLength: VARIABLE_LENGTH];
+                msg_send![item, retain]
+            };
+            cell.set(item);
+        }
+        cell.get()
+    })
+}
+
+/// Show `title` in the menu bar, or hide the item when there is nothing to show
+pub fn set_title(title: Option<&str>) {
+    let item = status_item();
+    unsafe {
+        match title {
+            Some(title) => {
+                let button: id = msg_send![item, button];
+                let ns_title = NSString::alloc(nil).init_str(title);
+                let _: () = msg_send![button, setTitle: ns_title];
+                let _: () = msg_send![ns_title, release];
+                let _: () = msg_send![item, setVisible: YES];
+            }
+            None => {
+                let _: () = msg_send![item, setVisible: NO];
+            }
+        }
+    }
+}
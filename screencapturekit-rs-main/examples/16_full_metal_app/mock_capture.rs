@@ -0,0 +1,404 @@
+//! Synthetic capture source for development and CI
+//!
+//! Started with `--mock-capture` (or `TALKA_MOCK_CAPTURE=1`), selecting a source
+//! skips the system picker and starts a test pattern instead: IOSurface-backed BGRA
+//! frames are handed to the regular [`CaptureHandler`] as `CMSampleBuffer`s, exactly
+//! as `SCStream` delivers them, and a 440 Hz tone feeds the system audio waveform. No
+//! display, screen recording permission or window server session is needed.
+//!
+//! `SCRecordingOutput` only attaches to a real stream, so while recording the same
+//! frames are piped to `ffmpeg`, which encodes them with the tone. The file then goes
+//! through the normal verification, trim and upload flow. `16_full_metal_app
+//! mock-selftest` records a few seconds this way without a window, for CI.
+
+use std::ffi::c_void;
+use std::io::Write;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSString;
+use objc::{class, msg_send, sel, sel_impl};
+use screencapturekit::cm::{self, CMSampleBuffer, CMTime, CVPixelBuffer};
+use screencapturekit::prelude::*;
+
+use crate::capture::{CaptureHandler, CaptureState};
+use crate::dioxus_ui::CaptureCommand;
+use crate::library;
+use crate::media::{self, TrimSession};
+use crate::sidecar::RecordingSidecar;
+
+pub const SOURCE_NAME: &str = "[M] Test pattern";
+
+/// Whether this run uses the test pattern instead of ScreenCaptureKit
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        std::env::args().any(|arg| arg == "--mock-capture")
+            || std::env::var("TALKA_MOCK_CAPTURE").is_ok_and(|value| value == "1")
+    })
+}
+
+const FPS: u32 = 30;
+const SAMPLE_RATE: f64 = 48_000.0;
+const TONE_HZ: f64 = 440.0;
+/// `kCVPixelFormatType_32BGRA`
+const PIXEL_FORMAT_BGRA: u32 = 0x4247_5241;
+
+/// SMPTE-style color bars, as BGRA
+const BARS: [[u8; 4]; 7] = [
+    [192, 192, 192, 255],
+    [0, 192, 192, 255],
+    [192, 192, 0, 255],
+    [0, 192, 0, 255],
+    [192, 0, 192, 255],
+    [0, 0, 192, 255],
+    [192, 0, 0, 255],
+];
+
+#[link(name = "IOSurface", kind = "framework")]
+extern "C" {
+    fn IOSurfaceCreate(properties: id) -> *mut c_void;
+    fn IOSurfaceLock(surface: *mut c_void, options: u32, seed: *mut u32) -> i32;
+    fn IOSurfaceUnlock(surface: *mut c_void, options: u32, seed: *mut u32) -> i32;
+    fn IOSurfaceGetBaseAddress(surface: *mut c_void) -> *mut u8;
+    fn IOSurfaceGetBytesPerRow(surface: *mut c_void) -> usize;
+}
+
+/// A new `width` x `height` BGRA surface
+fn create_surface(width: u32, height: u32) -> Option<cm::IOSurface> {
+    unsafe {
+        let properties: id = msg_send![class!(NSMutableDictionary), dictionary];
+        for (key, value) in [
+            ("IOSurfaceWidth", width),
+            ("IOSurfaceHeight", height),
+            ("IOSurfaceBytesPerElement", 4),
+            ("IOSurfacePixelFormat", PIXEL_FORMAT_BGRA),
+        ] {
+            let key = NSString::alloc(nil).init_str(key);
+            let value: id = msg_send![class!(NSNumber), numberWithUnsignedInt: value];
+            let _: () = msg_send![properties, setObject: value forKey: key];
+            let _: () = msg_send![key, release];
+        }
+        // Created at +1, released when the wrapper drops
+        cm::IOSurface::from_raw(IOSurfaceCreate(properties))
+    }
+}
+
+/// Color bars with a white block sweeping across the bottom band, as tightly packed BGRA
+fn draw_pattern(width: u32, height: u32, frame: u64) -> Vec<u8> {
+    let (width, height) = (width as usize, height as usize);
+    let bars_end = height * 3 / 4;
+    let block = (width / 12).max(1);
+    let block_x = (frame as usize * (width / 90).max(1)) % width.saturating_sub(block).max(1);
+    let mut pixels = vec![0u8; width * height * 4];
+    for (y, row) in pixels.chunks_exact_mut(width * 4).enumerate() {
+        for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+            let color = if y < bars_end {
+                BARS[x * BARS.len() / width]
+            } else if (block_x..block_x + block).contains(&x) {
+                [255, 255, 255, 255]
+            } else {
+                [24, 24, 24, 255]
+            };
+            pixel.copy_from_slice(&color);
+        }
+    }
+    pixels
+}
+
+/// Copy packed BGRA rows into `surface`, whose rows may be padded
+fn fill_surface(surface: &cm::IOSurface, width: u32, pixels: &[u8]) {
+    let row_bytes = width as usize * 4;
+    let ptr = surface.as_ptr();
+    unsafe {
+        if IOSurfaceLock(ptr, 0, std::ptr::null_mut()) != 0 {
+            return;
+        }
+        let base = IOSurfaceGetBaseAddress(ptr);
+        let stride = IOSurfaceGetBytesPerRow(ptr);
+        for (y, row) in pixels.chunks_exact(row_bytes).enumerate() {
+            std::ptr::copy_nonoverlapping(row.as_ptr(), base.add(y * stride), row_bytes);
+        }
+        IOSurfaceUnlock(ptr, 0, std::ptr::null_mut());
+    }
+}
+
+/// Where the generator writes its frames while a recording runs
+type FrameSink = Arc<Mutex<Option<ChildStdin>>>;
+
+/// Frames and tone generator standing in for a running `SCStream`
+struct Generator {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl Generator {
+    fn start(capture_state: &Arc<CaptureState>, (width, height): (u32, u32), sink: &FrameSink) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let handler = CaptureHandler {
+            state: Arc::clone(capture_state),
+        };
+        let stop_clone = Arc::clone(&stop);
+        let sink = Arc::clone(sink);
+        let thread = std::thread::spawn(move || {
+            let frame_interval = Duration::from_secs(1) / FPS;
+            let samples_per_frame = (SAMPLE_RATE / f64::from(FPS)) as u64;
+            let started = Instant::now();
+            let mut frame: u64 = 0;
+            while !stop_clone.load(Ordering::Relaxed) {
+                let pixels = draw_pattern(width, height, frame);
+                if let Some(surface) = create_surface(width, height) {
+                    fill_surface(&surface, width, &pixels);
+                    let sample = CVPixelBuffer::create_with_io_surface(&surface).and_then(|pixels| {
+                        CMSampleBuffer::create_for_image_buffer(
+                            &pixels,
+                            CMTime::new(frame as i64, FPS as i32),
+                            CMTime::new(1, FPS as i32),
+                        )
+                    });
+                    match sample {
+                        Ok(sample) => handler.did_output_sample_buffer(sample, SCStreamOutputType::Screen),
+                        Err(status) => eprintln!("⚠️  Mock frame failed: {}", status),
+                    }
+                }
+                let mut recorder = sink.lock().unwrap();
+                if let Some(Err(e)) = recorder.as_mut().map(|stdin| stdin.write_all(&pixels)) {
+                    eprintln!("⚠️  Mock recording stopped taking frames: {}", e);
+                    *recorder = None;
+                }
+                drop(recorder);
+
+                let tone: Vec<f32> = (frame * samples_per_frame..(frame + 1) * samples_per_frame)
+                    .map(|n| (0.5 * (std::f64::consts::TAU * TONE_HZ * n as f64 / SAMPLE_RATE).sin()) as f32)
+                    .collect();
+                handler.state.audio_waveform.lock().unwrap().push(&tone);
+
+                frame += 1;
+                let next = started + frame_interval * frame as u32;
+                std::thread::sleep(next.saturating_duration_since(Instant::now()));
+            }
+        });
+        Self { stop, thread }
+    }
+
+    fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.thread.join();
+    }
+}
+
+/// Recording of the generated frames, encoded by `ffmpeg`
+struct MockRecording {
+    path: String,
+    encoder: Child,
+    started: Instant,
+}
+
+impl MockRecording {
+    /// Start encoding; the generator's frames go to `sink` until the recording stops
+    fn start((width, height): (u32, u32), sink: &FrameSink) -> Result<Self, String> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = format!("{}/recording_{timestamp}.mp4", library::recordings_dir());
+        RecordingSidecar::new(SOURCE_NAME.to_string(), (width, height), None).save(&path)?;
+
+        let size = format!("{width}x{height}");
+        let audio = format!("sine=frequency={TONE_HZ}:sample_rate={SAMPLE_RATE}");
+        let mut encoder = Command::new("ffmpeg")
+            .args(["-hide_banner", "-loglevel", "error", "-y"])
+            .args(["-f", "rawvideo", "-pix_fmt", "bgra", "-video_size", &size, "-framerate", &FPS.to_string()])
+            .args(["-i", "-", "-f", "lavfi", "-i", &audio, "-shortest"])
+            .args(["-c:v", "h264_videotoolbox", "-allow_sw", "1", "-pix_fmt", "yuv420p", "-c:a", "aac"])
+            .arg(&path)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run ffmpeg (is it installed?): {e}"))?;
+        *sink.lock().unwrap() = encoder.stdin.take();
+        Ok(Self { path, encoder, started: Instant::now() })
+    }
+
+    /// End the frames so `ffmpeg` finishes the file, and wait for it
+    fn stop(mut self, sink: &FrameSink) -> Result<String, String> {
+        sink.lock().unwrap().take();
+        let status = self
+            .encoder
+            .wait()
+            .map_err(|e| format!("Failed to wait for ffmpeg: {e}"))?;
+        if !status.success() {
+            return Err(format!("ffmpeg exited with {status}"));
+        }
+        if let Ok(duration) = media::duration_seconds(&self.path) {
+            RecordingSidecar::update(&self.path, |s| s.duration_seconds = Some(duration))?;
+        }
+        Ok(self.path)
+    }
+
+    /// Stop `ffmpeg` without finishing the file, and delete what it wrote
+    fn cancel(mut self, sink: &FrameSink) {
+        sink.lock().unwrap().take();
+        let _ = self.encoder.kill();
+        let _ = self.encoder.wait();
+        if let Err(e) = library::delete_recording(&self.path) {
+            eprintln!("⚠️ Failed to delete mock recording: {}", e);
+        }
+    }
+}
+
+/// Serves capture commands from the synthetic source in place of `SCStream`
+pub struct MockBackend {
+    capture_state: Arc<CaptureState>,
+    is_capturing: Arc<AtomicBool>,
+    is_recording: Arc<AtomicBool>,
+    source_name: Arc<Mutex<String>>,
+    recording_path: Arc<Mutex<String>>,
+    trim_session: Arc<Mutex<Option<TrimSession>>>,
+    size: (u32, u32),
+    generator: Option<Generator>,
+    frames: FrameSink,
+    recording: Option<MockRecording>,
+}
+
+impl MockBackend {
+    pub fn new(
+        capture_state: &Arc<CaptureState>,
+        is_capturing: &Arc<AtomicBool>,
+        is_recording: &Arc<AtomicBool>,
+        source_name: &Arc<Mutex<String>>,
+        recording_path: &Arc<Mutex<String>>,
+        trim_session: &Arc<Mutex<Option<TrimSession>>>,
+    ) -> Self {
+        println!("🧪 Mock capture source enabled");
+        Self {
+            capture_state: Arc::clone(capture_state),
+            is_capturing: Arc::clone(is_capturing),
+            is_recording: Arc::clone(is_recording),
+            source_name: Arc::clone(source_name),
+            recording_path: Arc::clone(recording_path),
+            trim_session: Arc::clone(trim_session),
+            size: (1280, 720),
+            generator: None,
+            frames: Arc::new(Mutex::new(None)),
+            recording: None,
+        }
+    }
+
+    fn start_capture(&mut self) {
+        if self.generator.is_none() {
+            self.generator = Some(Generator::start(&self.capture_state, self.size, &self.frames));
+            self.is_capturing.store(true, Ordering::Relaxed);
+            *self.source_name.lock().unwrap() = SOURCE_NAME.to_string();
+            println!("✅ Mock capture started");
+        }
+    }
+
+    fn stop_capture(&mut self) {
+        if let Some(generator) = self.generator.take() {
+            generator.stop();
+        }
+        self.is_capturing.store(false, Ordering::Relaxed);
+        *self.source_name.lock().unwrap() = "No source selected".to_string();
+        println!("✅ Mock capture stopped");
+    }
+
+    /// Report how long the mock recording has run, standing in for the media time
+    /// `SCRecordingOutput` reports
+    pub fn publish_recorded(&self) {
+        if let Some(ref recording) = self.recording {
+            self.capture_state.set_recorded(recording.started.elapsed().as_secs_f64());
+        }
+    }
+
+    /// Handle `cmd` if it concerns the capture source, otherwise hand it back
+    pub fn handle(&mut self, cmd: CaptureCommand) -> Option<CaptureCommand> {
+        match cmd {
+            CaptureCommand::SelectSource | CaptureCommand::SelectDisplay(_) | CaptureCommand::StartCapture => {
+                self.start_capture();
+            }
+            CaptureCommand::StopCapture => self.stop_capture(),
+            CaptureCommand::StartRecording if self.generator.is_some() && self.recording.is_none() => {
+                match MockRecording::start(self.size, &self.frames) {
+                    Ok(recording) => {
+                        println!("⏺ Mock recording started: {}", recording.path);
+                        *self.recording_path.lock().unwrap() = recording.path.clone();
+                        self.capture_state.set_recorded(0.0);
+                        self.is_recording.store(true, Ordering::Relaxed);
+                        self.recording = Some(recording);
+                    }
+                    Err(e) => eprintln!("❌ Failed to start mock recording: {}", e),
+                }
+            }
+            CaptureCommand::StopRecording if self.recording.is_some() => {
+                let recording = self.recording.take().expect("checked above");
+                self.is_recording.store(false, Ordering::Relaxed);
+                self.recording_path.lock().unwrap().clear();
+                self.stop_capture();
+                match recording.stop(&self.frames).and_then(TrimSession::open) {
+                    Ok(session) => {
+                        println!("✂️  Mock recording ready to trim ({:.1}s)", session.duration);
+                        *self.trim_session.lock().unwrap() = Some(session);
+                        media::load_thumbnails(&self.trim_session);
+                    }
+                    Err(e) => eprintln!("❌ Mock recording failed: {}", e),
+                }
+            }
+            CaptureCommand::CancelRecording if self.recording.is_some() => {
+                let recording = self.recording.take().expect("checked above");
+                println!("🗑️ Deleting mock recording: {}", recording.path);
+                recording.cancel(&self.frames);
+                self.is_recording.store(false, Ordering::Relaxed);
+                self.capture_state.recording_paused.store(false, Ordering::Relaxed);
+                self.recording_path.lock().unwrap().clear();
+                self.stop_capture();
+            }
+            other => return Some(other),
+        }
+        None
+    }
+}
+
+/// Record a few seconds of the test pattern without a window and check the result
+///
+/// Run as `16_full_metal_app mock-selftest`; returns the process exit code.
+pub fn run_selftest() -> i32 {
+    let capture_state = Arc::new(CaptureState::new());
+    let is_capturing = Arc::new(AtomicBool::new(false));
+    let is_recording = Arc::new(AtomicBool::new(false));
+    let source_name = Arc::new(Mutex::new(String::new()));
+    let recording_path = Arc::new(Mutex::new(String::new()));
+    let trim_session = Arc::new(Mutex::new(None));
+    let mut mock = MockBackend::new(
+        &capture_state,
+        &is_capturing,
+        &is_recording,
+        &source_name,
+        &recording_path,
+        &trim_session,
+    );
+
+    mock.handle(CaptureCommand::StartCapture);
+    mock.handle(CaptureCommand::StartRecording);
+    if !is_recording.load(Ordering::Relaxed) {
+        eprintln!("❌ Self-test: the mock recording did not start");
+        return 1;
+    }
+    std::thread::sleep(Duration::from_secs(3));
+    mock.handle(CaptureCommand::StopRecording);
+
+    let Some(session) = trim_session.lock().unwrap().take() else {
+        eprintln!("❌ Self-test: the mock recording did not produce a file");
+        return 1;
+    };
+    let _ = library::delete_recording(&session.path);
+    if session.duration < 2.0 {
+        eprintln!("❌ Self-test: the recording is {:.1}s long, expected about 3s", session.duration);
+        return 1;
+    }
+    println!("✅ Self-test: recorded {:.1}s of generated frames", session.duration);
+    0
+}
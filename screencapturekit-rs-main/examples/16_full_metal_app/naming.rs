@@ -0,0 +1,194 @@
+//! Recording titles from the organization's naming convention
+//!
+//! An admin can set one title template for the whole organization in Talka, such
+//! as "ACME – {customer} – {date}". It is fetched after sign-in and cached in
+//! `naming_convention.json` in the data folder, so titles follow it offline too.
+//! Placeholders are filled in from the recording's calendar meeting:
+//!
+//! - `{title}`: the meeting title, or the recorded window or display without one
+//! - `{customer}`: the company of the first participant from outside the organization
+//! - `{date}` and `{time}`: when the meeting started, as "2026-01-19" and "14:30"
+//! - `{source}`: the recorded window or display
+//!
+//! Dates and times have one fixed form rather than following each member's region,
+//! so titles across the organization sort and read alike. A placeholder with nothing
+//! to fill it is dropped together with the separator next to it. The trim editor
+//! shows the title for a last edit before upload, and the edited title is kept in
+//! the recording's sidecar.
+
+use chrono::{Local, Utc};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::sidecar::RecordingSidecar;
+use crate::talka_api::{NamingConvention, TalkaClient};
+use crate::time_format;
+use crate::user_data;
+
+/// Words between placeholders that only make sense with something on both sides
+const SEPARATORS: [&str; 6] = ["–", "—", "-", "|", "·", "/"];
+/// Personal mail services, which say nothing about a participant's company
+const PERSONAL_DOMAINS: [&str; 8] =
+    ["gmail.com", "googlemail.com", "icloud.com", "me.com", "outlook.com", "hotmail.com", "live.com", "yahoo.com"];
+
+static CURRENT: Mutex<Option<NamingConvention>> = Mutex::new(None);
+
+fn cache_path() -> PathBuf {
+    user_data::file("naming_convention.json")
+}
+
+/// The organization's convention, as last fetched
+pub fn current() -> NamingConvention {
+    CURRENT
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| {
+            std::fs::read_to_string(cache_path())
+                .ok()
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default()
+        })
+        .clone()
+}
+
+/// Fetch the convention and cache it; on failure the cached one stays in use
+pub async fn refresh(access_token: &str) -> Result<(), String> {
+    let convention = TalkaClient::new(access_token)
+        .naming_convention()
+        .await
+        .map_err(|e| format!("Failed to fetch naming convention: {e}"))?
+        .unwrap_or_default();
+    let json = serde_json::to_string_pretty(&convention)
+        .map_err(|e| format!("Failed to serialize naming convention: {e}"))?;
+    if let Err(e) = user_data::write_private(&cache_path(), json) {
+        eprintln!("⚠️  Failed to cache naming convention: {}", e);
+    }
+    *CURRENT.lock().unwrap() = Some(convention);
+    Ok(())
+}
+
+/// [`refresh`] without waiting for it, whenever a sign-in or token renewal brings a
+/// new access token, so a long-running app picks up changes to the convention
+pub fn refresh_in_background(access_token: String) {
+    tokio::spawn(async move {
+        if let Err(e) = refresh(&access_token).await {
+            eprintln!("⚠️  {}", e);
+        }
+    });
+}
+
+/// Title from the convention for a recording, `None` when the organization has none
+pub fn suggested(sidecar: &RecordingSidecar) -> Option<String> {
+    let convention = current();
+    if convention.template.trim().is_empty() {
+        return None;
+    }
+    let event = sidecar.calendar_event.as_ref();
+    let started = event
+        .and_then(|e| time_format::parse(&e.start_time))
+        .or_else(|| time_format::parse(&sidecar.recorded_at))
+        .unwrap_or_else(Utc::now)
+        .with_timezone(&Local);
+    let title = event
+        .map(|e| e.title.trim())
+        .filter(|t| !t.is_empty())
+        .unwrap_or(&sidecar.source_name);
+    let customer = event
+        .and_then(|e| customer(&e.participants, &convention.internal_domains))
+        .unwrap_or_default();
+
+    let date = started.format("%Y-%m-%d").to_string();
+    let time = started.format("%H:%M").to_string();
+    let mut text = convention.template.clone();
+    for (name, value) in [
+        ("title", title),
+        ("customer", customer.as_str()),
+        ("date", date.as_str()),
+        ("time", time.as_str()),
+        ("source", sidecar.source_name.as_str()),
+    ] {
+        text = text.replace(&format!("{{{name}}}"), value);
+    }
+    Some(tidy(&text)).filter(|t| !t.is_empty())
+}
+
+/// Title to upload a recording with: the one chosen before upload, else the convention's
+pub fn title_for(sidecar: &RecordingSidecar) -> Option<String> {
+    sidecar.title.clone().or_else(|| suggested(sidecar))
+}
+
+/// Company of the first participant whose domain is not the organization's own
+fn customer(participants: &[String], internal_domains: &[String]) -> Option<String> {
+    participants
+        .iter()
+        .filter_map(|p| p.rsplit_once('@').map(|(_, domain)| domain.trim_end_matches('>').trim().to_lowercase()))
+        .filter(|domain| !PERSONAL_DOMAINS.contains(&domain.as_str()))
+        .find(|domain| {
+            !internal_domains.iter().any(|own| {
+                let own = own.trim().to_lowercase();
+                *domain == own || domain.ends_with(&format!(".{own}"))
+            })
+        })
+        .and_then(|domain| company(&domain))
+}
+
+/// "Acme" for "acme.com", "sales.acme.co.uk" and the like
+fn company(domain: &str) -> Option<String> {
+    let mut labels: Vec<&str> = domain.split('.').filter(|l| !l.is_empty()).collect();
+    labels.pop();
+    if labels.len() > 1 && matches!(labels.last(), Some(&("co" | "com" | "org" | "net" | "ac"))) {
+        labels.pop();
+    }
+    let name = labels.pop()?;
+    let mut chars = name.chars();
+    chars.next().map(|first| first.to_uppercase().chain(chars).collect())
+}
+
+/// Collapse spaces and drop separators left without anything on one side
+fn tidy(text: &str) -> String {
+    let mut words: Vec<&str> = Vec::new();
+    for word in text.split_whitespace() {
+        let separator = SEPARATORS.contains(&word);
+        if separator && words.last().map_or(true, |last| SEPARATORS.contains(last)) {
+            continue;
+        }
+        words.push(word);
+    }
+    if words.last().is_some_and(|last| SEPARATORS.contains(last)) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tidy_drops_separators_left_alone() {
+        assert_eq!(tidy("ACME –  – 2026-01-19"), "ACME – 2026-01-19");
+        assert_eq!(tidy("– Weekly sync –"), "Weekly sync");
+        assert_eq!(tidy("  Weekly   sync | "), "Weekly sync");
+        assert_eq!(tidy(" – "), "");
+    }
+
+    #[test]
+    fn company_from_domain() {
+        assert_eq!(company("acme.com").as_deref(), Some("Acme"));
+        assert_eq!(company("sales.acme.co.uk").as_deref(), Some("Acme"));
+        assert_eq!(company("globex.io").as_deref(), Some("Globex"));
+        assert_eq!(company("com"), None);
+    }
+
+    #[test]
+    fn customer_skips_own_and_personal_domains() {
+        let participants = [
+            "Jane <jane@talka.ai>".to_string(),
+            "bob@gmail.com".to_string(),
+            "Ann <ann@acme.com>".to_string(),
+        ];
+        assert_eq!(customer(&participants, &["talka.ai".to_string()]).as_deref(), Some("Acme"));
+        assert_eq!(customer(&["x@eu.talka.ai".to_string()], &["Talka.ai".to_string()]), None);
+        assert_eq!(customer(&["No address".to_string()], &[]), None);
+    }
+}
@@ -0,0 +1,108 @@
+//! Metered connection detection and what uploads do on one
+//!
+//! macOS does not say whether a connection is metered, so this goes by the route to
+//! the internet: an iPhone tethered over USB or Bluetooth, a cellular interface, or
+//! Wi-Fi whose router has the address a phone's Personal Hotspot hands out. Large
+//! uploads on such a connection ask first (or are queued straight away) until the
+//! Mac is back on ordinary Wi-Fi or Ethernet, as configured in `network.json` in the
+//! data folder.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::user_data;
+
+/// Routers of phone hotspots: iOS Personal Hotspot and older Android tethering
+const HOTSPOT_ROUTERS: [&str; 2] = ["172.20.10.1", "192.168.43.1"];
+
+/// Hardware ports that share a phone's mobile data
+const TETHERED_PORTS: [&str; 3] = ["iPhone", "Bluetooth PAN", "RNDIS"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeteredUploads {
+    /// Ask whether to upload now or wait for Wi-Fi
+    #[default]
+    Ask,
+    /// Queue until Wi-Fi without asking
+    Queue,
+    /// Upload as on any other connection
+    Upload,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    pub metered_uploads: MeteredUploads,
+}
+
+impl NetworkSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("network.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize network settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save network settings: {e}"))
+    }
+}
+
+/// Interface and router of the default route, from `route -n get default`
+fn default_route() -> Option<(String, Option<String>)> {
+    let output = Command::new("route").args(["-n", "get", "default"]).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|line| line.trim().strip_prefix(name))
+            .map(|value| value.trim().to_string())
+    };
+    Some((field("interface:")?, field("gateway:")))
+}
+
+/// Hardware port name of `device` (such as "Wi-Fi" for en0), from `networksetup`
+fn hardware_port(device: &str) -> Option<String> {
+    let output = Command::new("networksetup").arg("-listallhardwareports").output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut port = None;
+    for line in text.lines() {
+        if let Some(name) = line.strip_prefix("Hardware Port: ") {
+            port = Some(name.trim().to_string());
+        } else if line.strip_prefix("Device: ").is_some_and(|d| d.trim() == device) {
+            return port;
+        }
+    }
+    None
+}
+
+/// Why the current connection looks metered, or `None` when it does not
+///
+/// Runs `route` and `networksetup`, so it waits for them on a blocking thread.
+pub async fn metered() -> Option<String> {
+    tokio::task::spawn_blocking(detect_metered).await.ok().flatten()
+}
+
+fn detect_metered() -> Option<String> {
+    let (interface, router) = default_route()?;
+    if interface.starts_with("pdp_ip") {
+        return Some("a cellular connection".to_string());
+    }
+    if let Some(port) = hardware_port(&interface) {
+        if TETHERED_PORTS.iter().any(|tethered| port.contains(tethered)) {
+            return Some(format!("a phone ({port})"));
+        }
+    }
+    if router.is_some_and(|router| HOTSPOT_ROUTERS.contains(&router.as_str())) {
+        return Some("a phone's hotspot".to_string());
+    }
+    None
+}
@@ -1,6 +1,9 @@
 //! Menu state and configuration
 
-use screencapturekit::prelude::*;
+use crate::capture_settings;
+use crate::capture_settings::{
+    CaptureSettings, CHANNEL_OPTIONS, FPS_OPTIONS, PIXEL_FORMATS, QUEUE_OPTIONS, SAMPLE_RATE_OPTIONS,
+};
 
 /// Menu mode - determines which menu items are shown
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -82,6 +85,7 @@ impl OverlayState {
     }
 }
 
+/// Capture options in the overlay, stepping through the shared [`CaptureSettings`]
 pub struct ConfigMenu;
 
 impl ConfigMenu {
@@ -102,10 +106,6 @@ impl ConfigMenu {
         "Pixel Format",
         "Queue",
     ];
-    pub const FPS_OPTIONS: [u32; 5] = [15, 30, 60, 120, 240];
-    pub const QUEUE_OPTIONS: [u32; 4] = [3, 5, 8, 12];
-    pub const SAMPLE_RATE_OPTIONS: [i32; 3] = [44100, 48000, 96000];
-    pub const CHANNEL_OPTIONS: [i32; 2] = [1, 2];
 
     pub const fn option_count() -> usize {
         Self::OPTIONS.len()
@@ -115,226 +115,55 @@ impl ConfigMenu {
         Self::OPTIONS.get(idx).unwrap_or(&"?")
     }
 
-    pub fn option_value(
-        config: &SCStreamConfiguration,
-        mic_device_idx: Option<usize>,
-        idx: usize,
-    ) -> String {
+    pub fn option_value(settings: &CaptureSettings, idx: usize) -> String {
+        let on_off = |on: bool| if on { "On" } else { "Off" }.to_string();
         match idx {
-            0 => format!("{}", config.fps()),
-            1 => if config.shows_cursor() { "On" } else { "Off" }.to_string(),
-            2 => if config.captures_audio() { "On" } else { "Off" }.to_string(),
-            3 => if config.captures_microphone() {
-                "On"
-            } else {
-                "Off"
-            }
-            .to_string(),
-            4 => mic_device_idx.map_or_else(
-                || "Default".to_string(),
-                |idx| {
-                    AudioInputDevice::list()
-                        .get(idx)
-                        .map_or_else(|| "?".to_string(), |d| d.name.chars().take(10).collect())
-                },
-            ),
-            5 => if config.excludes_current_process_audio() {
-                "On"
-            } else {
-                "Off"
-            }
-            .to_string(),
-            6 => format!("{}Hz", config.sample_rate()),
-            7 => format!("{}ch", config.channel_count()),
-            8 => if config.scales_to_fit() { "On" } else { "Off" }.to_string(),
-            9 => if config.preserves_aspect_ratio() {
-                "On"
-            } else {
-                "Off"
-            }
-            .to_string(),
-            10 => if config.should_be_opaque() {
-                "On"
-            } else {
-                "Off"
-            }
-            .to_string(),
-            11 => if config.captures_shadows_only() {
-                "On"
-            } else {
-                "Off"
-            }
-            .to_string(),
-            12 => if config.ignores_shadows_display() {
-                "On"
-            } else {
-                "Off"
-            }
-            .to_string(),
-            13 => format!("{:?}", config.pixel_format()),
-            14 => format!("{}", config.queue_depth()),
+            0 => format!("{}", settings.fps),
+            1 => on_off(settings.shows_cursor),
+            2 => on_off(settings.captures_audio),
+            3 => on_off(settings.captures_microphone),
+            4 => settings.microphone_name().chars().take(10).collect(),
+            5 => on_off(settings.excludes_own_audio),
+            6 => format!("{}Hz", settings.sample_rate),
+            7 => format!("{}ch", settings.channel_count),
+            8 => on_off(settings.scales_to_fit),
+            9 => on_off(settings.preserves_aspect_ratio),
+            10 => on_off(settings.opaque),
+            11 => on_off(settings.shadows_only),
+            12 => on_off(settings.ignores_shadows),
+            13 => settings.pixel_format.clone(),
+            14 => format!("{}", settings.queue_depth),
             _ => "?".to_string(),
         }
     }
 
-    #[allow(clippy::too_many_lines)]
-    pub fn toggle_or_adjust(
-        config: &mut SCStreamConfiguration,
-        mic_device_idx: &mut Option<usize>,
-        idx: usize,
-        increase: bool,
-    ) {
-        use screencapturekit::stream::configuration::pixel_format::PixelFormat;
-        const PIXEL_FORMATS: [PixelFormat; 4] = [
-            PixelFormat::BGRA,
-            PixelFormat::l10r,
-            PixelFormat::YCbCr_420v,
-            PixelFormat::YCbCr_420f,
-        ];
-
+    pub fn toggle_or_adjust(settings: &mut CaptureSettings, idx: usize, increase: bool) {
         match idx {
-            0 => {
-                // FPS
-                let current_fps = config.fps();
-                let current_idx = Self::FPS_OPTIONS
-                    .iter()
-                    .position(|&f| f == current_fps)
-                    .unwrap_or(2);
-                let new_idx = if increase {
-                    (current_idx + 1) % Self::FPS_OPTIONS.len()
-                } else {
-                    (current_idx + Self::FPS_OPTIONS.len() - 1) % Self::FPS_OPTIONS.len()
-                };
-                config.set_fps(Self::FPS_OPTIONS[new_idx]);
-            }
-            1 => {
-                config.set_shows_cursor(!config.shows_cursor());
-            }
-            2 => {
-                config.set_captures_audio(!config.captures_audio());
-            }
-            3 => {
-                config.set_captures_microphone(!config.captures_microphone());
-            }
-            4 => {
-                // Mic Device
-                let devices = AudioInputDevice::list();
-                if devices.is_empty() {
-                    return;
-                }
-                match *mic_device_idx {
-                    None => *mic_device_idx = Some(if increase { 0 } else { devices.len() - 1 }),
-                    Some(idx) => {
-                        if increase {
-                            *mic_device_idx = if idx + 1 >= devices.len() {
-                                None
-                            } else {
-                                Some(idx + 1)
-                            };
-                        } else {
-                            *mic_device_idx = if idx == 0 { None } else { Some(idx - 1) };
-                        }
-                    }
-                }
-                if let Some(idx) = *mic_device_idx {
-                    if let Some(device) = devices.get(idx) {
-                        config.set_microphone_capture_device_id(&device.id);
-                    }
-                }
-            }
-            5 => {
-                config.set_excludes_current_process_audio(!config.excludes_current_process_audio());
-            }
+            0 => settings.fps = capture_settings::step(&FPS_OPTIONS, &settings.fps, increase),
+            1 => settings.shows_cursor = !settings.shows_cursor,
+            2 => settings.captures_audio = !settings.captures_audio,
+            3 => settings.captures_microphone = !settings.captures_microphone,
+            4 => settings.step_microphone(increase),
+            5 => settings.excludes_own_audio = !settings.excludes_own_audio,
             6 => {
-                // Sample Rate
-                let current = config.sample_rate();
-                let current_idx = Self::SAMPLE_RATE_OPTIONS
-                    .iter()
-                    .position(|&r| r == current)
-                    .unwrap_or(1);
-                let new_idx = if increase {
-                    (current_idx + 1) % Self::SAMPLE_RATE_OPTIONS.len()
-                } else {
-                    (current_idx + Self::SAMPLE_RATE_OPTIONS.len() - 1)
-                        % Self::SAMPLE_RATE_OPTIONS.len()
-                };
-                config.set_sample_rate(Self::SAMPLE_RATE_OPTIONS[new_idx]);
+                settings.sample_rate = capture_settings::step(&SAMPLE_RATE_OPTIONS, &settings.sample_rate, increase);
             }
             7 => {
-                // Channels
-                let current = config.channel_count();
-                let current_idx = Self::CHANNEL_OPTIONS
-                    .iter()
-                    .position(|&c| c == current)
-                    .unwrap_or(1);
-                let new_idx = if increase {
-                    (current_idx + 1) % Self::CHANNEL_OPTIONS.len()
-                } else {
-                    (current_idx + Self::CHANNEL_OPTIONS.len() - 1) % Self::CHANNEL_OPTIONS.len()
-                };
-                config.set_channel_count(Self::CHANNEL_OPTIONS[new_idx]);
-            }
-            8 => {
-                config.set_scales_to_fit(!config.scales_to_fit());
-            }
-            9 => {
-                config.set_preserves_aspect_ratio(!config.preserves_aspect_ratio());
-            }
-            10 => {
-                config.set_should_be_opaque(!config.should_be_opaque());
-            }
-            11 => {
-                config.set_captures_shadows_only(!config.captures_shadows_only());
-            }
-            12 => {
-                config.set_ignores_shadows_display(!config.ignores_shadows_display());
+                settings.channel_count = capture_settings::step(&CHANNEL_OPTIONS, &settings.channel_count, increase);
             }
+            8 => settings.scales_to_fit = !settings.scales_to_fit,
+            9 => settings.preserves_aspect_ratio = !settings.preserves_aspect_ratio,
+            10 => settings.opaque = !settings.opaque,
+            11 => settings.shadows_only = !settings.shadows_only,
+            12 => settings.ignores_shadows = !settings.ignores_shadows,
             13 => {
-                // Pixel Format
-                let current = config.pixel_format();
-                let current_idx = PIXEL_FORMATS
-                    .iter()
-                    .position(|&f| f == current)
-                    .unwrap_or(0);
-                let new_idx = if increase {
-                    (current_idx + 1) % PIXEL_FORMATS.len()
-                } else {
-                    (current_idx + PIXEL_FORMATS.len() - 1) % PIXEL_FORMATS.len()
-                };
-                config.set_pixel_format(PIXEL_FORMATS[new_idx]);
+                settings.pixel_format =
+                    capture_settings::step(&PIXEL_FORMATS, &settings.pixel_format.as_str(), increase).to_string();
             }
             14 => {
-                // Queue
-                let current_depth = config.queue_depth();
-                let current_idx = Self::QUEUE_OPTIONS
-                    .iter()
-                    .position(|&q| q == current_depth)
-                    .unwrap_or(2);
-                let new_idx = if increase {
-                    (current_idx + 1) % Self::QUEUE_OPTIONS.len()
-                } else {
-                    (current_idx + Self::QUEUE_OPTIONS.len() - 1) % Self::QUEUE_OPTIONS.len()
-                };
-                config.set_queue_depth(Self::QUEUE_OPTIONS[new_idx]);
+                settings.queue_depth = capture_settings::step(&QUEUE_OPTIONS, &settings.queue_depth, increase);
             }
             _ => {}
         }
     }
 }
-
-pub fn default_stream_config() -> SCStreamConfiguration {
-    SCStreamConfiguration::new()
-        .with_width(1920)
-        .with_height(1080)
-        .with_fps(10)
-        .with_shows_cursor(true)
-        .with_captures_audio(true)
-        .with_excludes_current_process_audio(true)
-        .with_captures_microphone(true)
-        .with_channel_count(1)
-        .with_sample_rate(48000)
-        .with_scales_to_fit(true)
-        .with_preserves_aspect_ratio(true)
-        .with_queue_depth(8)
-        .with_pixel_format(screencapturekit::stream::configuration::PixelFormat::YCbCr_420v)
-}
@@ -0,0 +1,58 @@
+//! Keeping the main window above full-screen meeting apps
+//!
+//! A meeting app in full screen gets a Space of its own, and the main window stays
+//! behind on the desktop, so Stop and Bookmark are out of reach mid-call. Pinned,
+//! the window floats above other windows and follows the user into every Space,
+//! full-screen ones included, as an auxiliary window that does not take a Space of
+//! its own. AppKit only lets a regular app's window over another app's full-screen
+//! Space unreliably, so while pinned the app runs as an accessory app, without a
+//! Dock icon, and goes back to a regular one when unpinned. Whether it is pinned is
+//! kept in the session. AppKit objects must only be touched from the main thread,
+//! where the Dioxus UI runs.
+
+use cocoa::base::{id, nil, BOOL, YES};
+use dioxus::desktop::tao::platform::macos::WindowExtMacOS;
+use dioxus::desktop::DesktopContext;
+use objc::{class, msg_send, sel, sel_impl};
+
+/// `NSNormalWindowLevel`
+const NORMAL_LEVEL: i64 = 0;
+/// `NSFloatingWindowLevel`
+const FLOATING_LEVEL: i64 = 3;
+/// `NSWindowCollectionBehaviorCanJoinAllSpaces`
+const CAN_JOIN_ALL_SPACES: u64 = 1 << 0;
+/// `NSWindowCollectionBehaviorFullScreenAuxiliary`: may be shown over a full-screen app
+const FULL_SCREEN_AUXILIARY: u64 = 1 << 8;
+/// `NSApplicationActivationPolicyRegular`: a Dock icon and a menu bar
+const REGULAR_POLICY: i64 = 0;
+/// `NSApplicationActivationPolicyAccessory`: windows only, allowed over full-screen apps
+const ACCESSORY_POLICY: i64 = 1;
+
+/// Float the main window above everything, or put it back among other windows
+pub fn apply(window: &DesktopContext, pinned: bool) {
+    let ns_window = window.window.ns_window() as id;
+    if ns_window == nil {
+        return;
+    }
+    let pinned_behavior = CAN_JOIN_ALL_SPACES | FULL_SCREEN_AUXILIARY;
+    unsafe {
+        let behavior: u64 = msg_send![ns_window, collectionBehavior];
+        let (level, behavior) = if pinned {
+            (FLOATING_LEVEL, behavior | pinned_behavior)
+        } else {
+            (NORMAL_LEVEL, behavior & !pinned_behavior)
+        };
+        let _: () = msg_send![ns_window, setCollectionBehavior: behavior];
+        let _: () = msg_send![ns_window, setLevel: level];
+
+        let app: id = msg_send![class!(NSApplication), sharedApplication];
+        let policy = if pinned { ACCESSORY_POLICY } else { REGULAR_POLICY };
+        let current: i64 = msg_send![app, activationPolicy];
+        if current != policy {
+            let _: BOOL = msg_send![app, setActivationPolicy: policy];
+            // Changing the policy can send the window behind other apps' windows
+            let _: () = msg_send![app, activateIgnoringOtherApps: YES];
+            let _: () = msg_send![ns_window, orderFrontRegardless];
+        }
+    }
+}
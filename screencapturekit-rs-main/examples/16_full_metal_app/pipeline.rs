@@ -0,0 +1,387 @@
+//! Post-processing of a finished recording before it is uploaded
+//!
+//! Each recording profile lists the steps its recordings go through: trimming to
+//! the range chosen in the trim editor, making speech equally loud throughout,
+//! re-encoding to a smaller file and transcribing locally. Steps always run in that
+//! order, whatever order they are listed in, each on the previous step's output. A
+//! range chosen in the trim editor is always cut, whether or not the profile lists
+//! the trim step, since what was cut out may be private.
+//! Jobs run one at a time on a background thread and report which step they are on
+//! and how far it has got; the main window shows that in the upload card.
+//!
+//! A step that fails is skipped with a warning and the next one works on what came
+//! before, so a recording is never held back from upload by its post-processing.
+//! Files made along the way are removed once the next step has replaced them; the
+//! original recording is always kept.
+//!
+//! The pipeline covers what happens between the trim editor and the upload. Work on
+//! recordings at other times keeps its own code: merging a segmented recording's
+//! parts before the editor opens ([`library::merge_segments`]), adding extra
+//! microphone tracks when a recording stops ([`crate::audio_tracks`]), archiving old
+//! recordings in the library ([`crate::archive`]) and exporting highlight clips once
+//! the file to upload is known ([`highlights`]).
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::OnceLock;
+
+use crate::archive::ArchivePreset;
+use crate::highlights;
+use crate::library;
+use crate::media;
+use crate::sidecar::RecordingSidecar;
+use crate::sidecar::TranscriptCue;
+use crate::toast;
+use crate::user_data;
+
+/// Loudness target for speech: -16 LUFS, as podcast and meeting platforms use
+const LOUDNORM_FILTER: &str = "loudnorm=I=-16:TP=-1.5:LRA=11";
+
+/// One thing done to a recording after it stops
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum Step {
+    /// Cut to the range chosen in the trim editor, without re-encoding
+    Trim,
+    /// Even out the loudness of the audio; the video is copied as is
+    Normalize,
+    /// Re-encode with an archive preset
+    Transcode { preset: ArchivePreset },
+    /// Transcribe locally with whisper.cpp into the sidecar's transcript
+    Transcribe {
+        /// Path of the Whisper model (`ggml-*.bin`)
+        model: String,
+    },
+}
+
+impl Step {
+    /// Steps in the order they run in, as offered in Settings
+    pub fn all() -> [Self; 4] {
+        [
+            Self::Trim,
+            Self::Normalize,
+            Self::Transcode { preset: ArchivePreset::Standard },
+            Self::Transcribe { model: String::new() },
+        ]
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Trim => 0,
+            Self::Normalize => 1,
+            Self::Transcode { .. } => 2,
+            Self::Transcribe { .. } => 3,
+        }
+    }
+
+    /// Whether `other` is the same kind of step, whatever its options
+    pub fn same_kind(&self, other: &Self) -> bool {
+        self.rank() == other.rank()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Trim => "Trim",
+            Self::Normalize => "Normalize loudness",
+            Self::Transcode { .. } => "Transcode",
+            Self::Transcribe { .. } => "Transcribe",
+        }
+    }
+
+    /// What the main window says while the step runs
+    pub fn activity(&self) -> &'static str {
+        match self {
+            Self::Trim => "Trimming",
+            Self::Normalize => "Normalizing loudness",
+            Self::Transcode { .. } => "Transcoding",
+            Self::Transcribe { .. } => "Transcribing",
+        }
+    }
+}
+
+/// Steps for recordings made without a profile
+///
+/// Recordings are transcribed, which the meeting summary needs, once a Whisper model
+/// has been put in the models folder.
+pub fn default_steps() -> Vec<Step> {
+    let mut steps = vec![Step::Trim];
+    if let Some(model) = installed_model() {
+        steps.push(Step::Transcribe { model });
+    }
+    steps
+}
+
+/// First `ggml-*.bin` model in [`user_data::models_dir`], by name
+fn installed_model() -> Option<String> {
+    std::fs::read_dir(user_data::models_dir())
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("ggml-") && name.ends_with(".bin"))
+        })
+        .min()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+/// How far a job has got
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    pub step: Step,
+    /// Counting from 1
+    pub number: usize,
+    pub total: usize,
+    /// Of the current step
+    pub percent: u8,
+}
+
+impl Progress {
+    /// Of the whole job
+    pub fn overall_percent(&self) -> u8 {
+        let done = (self.number - 1) * 100 + usize::from(self.percent);
+        (done / self.total.max(1)).min(100) as u8
+    }
+
+    /// "Normalizing loudness (2 of 3)"
+    pub fn describe(&self) -> String {
+        format!("{} ({} of {})", self.step.activity(), self.number, self.total)
+    }
+}
+
+/// A recording to post-process, and who to tell how it goes
+pub struct Job {
+    pub path: String,
+    pub steps: Vec<Step>,
+    /// Range chosen in the trim editor, in seconds
+    pub trim: Option<(f64, f64)>,
+    pub on_progress: Box<dyn Fn(&Progress) + Send>,
+    /// Called with the file to upload
+    pub on_done: Box<dyn FnOnce(String) + Send>,
+}
+
+static QUEUE: OnceLock<Sender<Job>> = OnceLock::new();
+
+/// Queue `job` behind any that are already running
+pub fn enqueue(job: Job) {
+    let queue = QUEUE.get_or_init(|| {
+        let (tx, rx) = channel::<Job>();
+        std::thread::spawn(move || {
+            for job in rx {
+                let output = run(&job.path, &job.steps, job.trim, &*job.on_progress);
+                // Bookmarks are offsets into the trimmed file by now
+                highlights::export_if_enabled(&output);
+                (job.on_done)(output);
+            }
+        });
+        tx
+    });
+    if let Err(failed) = queue.send(job) {
+        eprintln!("⚠️  Post-processing worker stopped; uploading without post-processing");
+        let job = failed.0;
+        (job.on_done)(job.path);
+    }
+}
+
+/// Run `steps` on `path` in order, returning the file to upload
+fn run(path: &str, steps: &[Step], trim: Option<(f64, f64)>, on_progress: &dyn Fn(&Progress)) -> String {
+    let mut steps: Vec<Step> = steps.iter().filter(|step| **step != Step::Trim || trim.is_some()).cloned().collect();
+    // Never upload what the user cut out, whatever the profile lists
+    if trim.is_some() && !steps.contains(&Step::Trim) {
+        steps.push(Step::Trim);
+    }
+    steps.sort_by_key(|step| step.rank());
+    steps.dedup_by(|a, b| a.same_kind(b));
+
+    let mut current = path.to_string();
+    for (index, step) in steps.iter().enumerate() {
+        let report = |percent: u8| {
+            on_progress(&Progress { step: step.clone(), number: index + 1, total: steps.len(), percent });
+        };
+        report(0);
+        println!("⚙️  {} {}", step.activity(), current);
+        let result = match step {
+            Step::Trim => trim.map_or(Ok(None), |(start, end)| trimmed(&current, start, end).map(Some)),
+            Step::Normalize => normalized(&current, &report).map(Some),
+            Step::Transcode { preset } => transcoded(&current, *preset, &report).map(Some),
+            Step::Transcribe { model } => transcribe(&current, model, &report).map(|()| None),
+        };
+        match result {
+            Ok(Some(output)) => {
+                // Only files this job made are removed, never the recording itself
+                if current != path {
+                    let _ = library::delete_recording(&current);
+                }
+                current = output;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("⚠️  {} failed, skipping it: {}", step.label(), e);
+                toast::error(format!("{} failed: {e}", step.label()));
+            }
+        }
+        report(100);
+    }
+    current
+}
+
+/// Carry the sidecar of `source` over to `output`, with the output's duration
+///
+/// The output remembers the original recording, past any files made in between,
+/// which are removed.
+fn copy_sidecar(source: &str, output: &str, adjust: impl FnOnce(&mut RecordingSidecar)) {
+    if let Some(mut metadata) = RecordingSidecar::load(source) {
+        metadata.duration_seconds = media::duration_seconds(output).ok();
+        metadata.derived_from = Some(metadata.derived_from.take().unwrap_or_else(|| source.to_string()));
+        adjust(&mut metadata);
+        let _ = metadata.save(output);
+    }
+}
+
+fn trimmed(path: &str, start: f64, end: f64) -> Result<String, String> {
+    println!("✂️  Trimming recording to {:.1}s - {:.1}s", start, end);
+    let output = media::trim_passthrough(path, start, end)?;
+    // Bookmarks move with the new start
+    copy_sidecar(path, &output, |metadata| {
+        metadata.bookmarks = metadata
+            .bookmarks
+            .iter()
+            .filter(|&&t| t >= start && t <= end)
+            .map(|t| t - start)
+            .collect();
+    });
+    Ok(output)
+}
+
+fn normalized(path: &str, report: &dyn Fn(u8)) -> Result<String, String> {
+    let output = media::sibling_path(path, "normalized", None);
+    let args = ["-map", "0", "-c", "copy", "-c:a", "aac", "-b:a", "160k", "-af", LOUDNORM_FILTER];
+    ffmpeg_with_progress(path, &args, &output, report)?;
+    copy_sidecar(path, &output, |_| {});
+    Ok(output)
+}
+
+fn transcoded(path: &str, preset: ArchivePreset, report: &dyn Fn(u8)) -> Result<String, String> {
+    let output = media::sibling_path(path, "transcoded", None);
+    ffmpeg_with_progress(path, preset.ffmpeg_args(), &output, report)?;
+    // The presets mix down to one audio track
+    copy_sidecar(path, &output, |metadata| metadata.audio_tracks.clear());
+    Ok(output)
+}
+
+/// Run `ffmpeg` on `source`, reporting how much of it has been written
+fn ffmpeg_with_progress(source: &str, args: &[&str], output: &str, report: &dyn Fn(u8)) -> Result<(), String> {
+    let duration = media::duration_seconds(source).unwrap_or(0.0);
+    let mut child = Command::new("ffmpeg")
+        .args(["-v", "error", "-y", "-nostats", "-progress", "pipe:1", "-i", source])
+        .args(args)
+        .args(["-movflags", "+faststart", output])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run ffmpeg (is it installed?): {e}"))?;
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let Some(written) = line.strip_prefix("out_time_us=").and_then(|us| us.trim().parse::<f64>().ok()) else {
+                continue;
+            };
+            if duration > 0.0 {
+                report((written / 1_000_000.0 / duration * 100.0).clamp(0.0, 99.0) as u8);
+            }
+        }
+    }
+    let result = child.wait_with_output().map_err(|e| format!("Failed to run ffmpeg: {e}"))?;
+    if !result.status.success() {
+        let _ = std::fs::remove_file(output);
+        return Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&result.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// Transcribe `path` with `whisper-cli` and keep the text in its sidecar
+fn transcribe(path: &str, model: &str, report: &dyn Fn(u8)) -> Result<(), String> {
+    let installed = installed_model();
+    let model = match model.trim() {
+        "" => installed.as_deref().unwrap_or_default(),
+        chosen => chosen,
+    };
+    if model.is_empty() || !Path::new(model).exists() {
+        return Err("Choose a Whisper model for the profile in Settings".to_string());
+    }
+    // whisper.cpp reads 16 kHz mono WAV
+    let audio = media::sibling_path(path, "speech", Some("wav"));
+    let extracted = Command::new("ffmpeg")
+        .args(["-v", "error", "-y", "-i", path, "-vn", "-ac", "1", "-ar", "16000", &audio])
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg (is it installed?): {e}"))?;
+    if !extracted.status.success() {
+        let _ = std::fs::remove_file(&audio);
+        return Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&extracted.stderr).trim()));
+    }
+
+    let text_base = audio.trim_end_matches(".wav").to_string();
+    let spawned = Command::new("whisper-cli")
+        .args(["-m", model, "-f", &audio, "-l", "auto", "-pp", "-otxt", "-osrt", "-of", &text_base])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = std::fs::remove_file(&audio);
+            return Err(format!("Failed to run whisper-cli (is whisper.cpp installed?): {e}"));
+        }
+    };
+    // Progress lines look like "whisper_print_progress_callback: progress =  45%"
+    if let Some(stderr) = child.stderr.take() {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            let percent = line
+                .split_once("progress =")
+                .and_then(|(_, rest)| rest.trim().trim_end_matches('%').parse::<u8>().ok());
+            if let Some(percent) = percent {
+                report(percent.min(99));
+            }
+        }
+    }
+    let status = child.wait().map_err(|e| format!("Failed to run whisper-cli: {e}"));
+    let _ = std::fs::remove_file(&audio);
+    if !status?.success() {
+        return Err("whisper-cli failed".to_string());
+    }
+
+    let text_path = format!("{text_base}.txt");
+    let transcript = std::fs::read_to_string(&text_path).map_err(|e| format!("Failed to read the transcript: {e}"))?;
+    let _ = std::fs::remove_file(&text_path);
+    let subtitles_path = format!("{text_base}.srt");
+    let cues = std::fs::read_to_string(&subtitles_path).map(|srt| parse_srt(&srt)).unwrap_or_default();
+    let _ = std::fs::remove_file(&subtitles_path);
+    println!("📝 Transcribed {} ({} characters)", path, transcript.len());
+    RecordingSidecar::update(path, |s| {
+        s.transcript = Some(transcript.trim().to_string());
+        s.transcript_cues = cues;
+    })
+}
+
+/// Cues of a SubRip file, whose blocks look like "1\n00:01:02,500 --> 00:01:05,000\ntext"
+fn parse_srt(srt: &str) -> Vec<TranscriptCue> {
+    let seconds = |stamp: &str| {
+        let (clock, millis) = stamp.trim().split_once(',')?;
+        let mut parts = clock.split(':').map(|part| part.parse::<f64>().ok());
+        let (hours, minutes, secs) = (parts.next()??, parts.next()??, parts.next()??);
+        Some(hours * 3600.0 + minutes * 60.0 + secs + millis.parse::<f64>().ok()? / 1000.0)
+    };
+    srt.replace("\r\n", "\n")
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+            let (start, _) = lines.next()?.split_once("-->")?;
+            let start_seconds = seconds(start)?;
+            let text = lines.map(str::trim).collect::<Vec<_>>().join(" ");
+            (!text.is_empty()).then_some(TranscriptCue { start_seconds, text })
+        })
+        .collect()
+}
@@ -0,0 +1,206 @@
+//! Power management: staying awake and going easy on the battery
+//!
+//! Wraps IOKit power assertions: while an [`Assertion`] is alive macOS will not idle
+//! sleep, and dropping it hands control back. Capture and recording keep the display
+//! on as well; uploads only need the system awake.
+//!
+//! On a low battery the recorder can also capture at a lower frame rate and
+//! resolution and hold uploads until the charger is connected, as configured in
+//! `battery.json` in the data folder.
+
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSString;
+use objc::{msg_send, sel, sel_impl};
+use screencapturekit::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ffi::c_void;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::user_data;
+
+/// How often the battery level is checked while capturing or waiting to upload
+pub const BATTERY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Frame rate used while saving battery
+const BATTERY_SAVING_FPS: u32 = 5;
+
+type IOPMAssertionID = u32;
+
+/// `kIOPMAssertionLevelOn`
+const ASSERTION_LEVEL_ON: u32 = 255;
+/// `kIOReturnSuccess`
+const IO_RETURN_SUCCESS: i32 = 0;
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOPMAssertionCreateWithName(
+        assertion_type: *const c_void,
+        level: u32,
+        name: *const c_void,
+        assertion_id: *mut IOPMAssertionID,
+    ) -> i32;
+    fn IOPMAssertionRelease(assertion_id: IOPMAssertionID) -> i32;
+}
+
+/// A held power assertion, released on drop
+#[derive(Debug)]
+pub struct Assertion {
+    id: IOPMAssertionID,
+    reason: &'static str,
+}
+
+impl Assertion {
+    fn create(assertion_type: &str, reason: &'static str) -> Option<Self> {
+        let mut assertion_id: IOPMAssertionID = 0;
+        // NSString is toll-free bridged to the CFStringRef IOKit expects
+        let result = unsafe {
+            let ns_type: id = NSString::alloc(nil).init_str(assertion_type);
+            let ns_reason: id = NSString::alloc(nil).init_str(reason);
+            let result = IOPMAssertionCreateWithName(
+                ns_type as *const c_void,
+                ASSERTION_LEVEL_ON,
+                ns_reason as *const c_void,
+                &mut assertion_id,
+            );
+            let _: () = msg_send![ns_type, release];
+            let _: () = msg_send![ns_reason, release];
+            result
+        };
+
+        if result == IO_RETURN_SUCCESS {
+            println!("☕ Preventing sleep: {}", reason);
+            Some(Self {
+                id: assertion_id,
+                reason,
+            })
+        } else {
+            eprintln!("⚠️  Failed to prevent sleep ({}): IOKit error {:#x}", reason, result);
+            None
+        }
+    }
+
+    /// Keep both the display and the system awake
+    pub fn prevent_display_sleep(reason: &'static str) -> Option<Self> {
+        Self::create("PreventUserIdleDisplaySleep", reason)
+    }
+
+    /// Keep the system awake; the display may still turn off
+    pub fn prevent_system_sleep(reason: &'static str) -> Option<Self> {
+        Self::create("PreventUserIdleSystemSleep", reason)
+    }
+}
+
+impl Drop for Assertion {
+    fn drop(&mut self) {
+        unsafe {
+            IOPMAssertionRelease(self.id);
+        }
+        println!("😴 Sleep allowed again: {}", self.reason);
+    }
+}
+
+/// Battery-saving behaviour
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatterySettings {
+    /// Battery percentage at or below which saving kicks in
+    pub threshold_percent: u8,
+    /// Halve the capture resolution and drop the frame rate
+    pub reduce_quality: bool,
+    /// Hold uploads until the Mac is plugged in
+    pub defer_uploads: bool,
+}
+
+impl Default for BatterySettings {
+    fn default() -> Self {
+        Self {
+            threshold_percent: 20,
+            reduce_quality: false,
+            defer_uploads: false,
+        }
+    }
+}
+
+impl BatterySettings {
+    fn config_path() -> PathBuf {
+        user_data::file("battery.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize battery settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save battery settings: {e}"))
+    }
+
+    fn is_low(&self) -> bool {
+        battery_status().is_some_and(|b| b.on_battery && b.percent <= self.threshold_percent)
+    }
+
+    /// Whether capture should currently run at reduced quality
+    pub fn reduces_quality_now(&self) -> bool {
+        self.reduce_quality && self.is_low()
+    }
+
+    /// Whether uploads should currently wait for the charger
+    pub fn defers_uploads_now(&self) -> bool {
+        self.defer_uploads && self.is_low()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatteryStatus {
+    pub on_battery: bool,
+    pub percent: u8,
+}
+
+/// Read the battery state from `pmset`; `None` on Macs without a battery
+pub fn battery_status() -> Option<BatteryStatus> {
+    let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    parse_pmset(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse output such as:
+///
+/// ```text
+/// Now drawing from 'Battery Power'
+///  -InternalBattery-0 (id=4653155)	45%; discharging; 3:12 remaining present: true
+/// ```
+fn parse_pmset(output: &str) -> Option<BatteryStatus> {
+    let percent = output
+        .split_whitespace()
+        .find_map(|word| word.strip_suffix("%;"))
+        .and_then(|p| p.parse().ok())?;
+    Some(BatteryStatus {
+        on_battery: output.contains("'Battery Power'"),
+        percent,
+    })
+}
+
+/// Stream configuration and capture size to use, reduced while saving battery
+pub fn capture_settings(
+    base: &SCStreamConfiguration,
+    (width, height): (u32, u32),
+    saving_battery: bool,
+) -> (SCStreamConfiguration, (u32, u32)) {
+    let mut config = base.clone();
+    let size = if saving_battery {
+        config.set_fps(BATTERY_SAVING_FPS);
+        // Keep dimensions even, as the encoder requires
+        ((width / 4).max(1) * 2, (height / 4).max(1) * 2)
+    } else {
+        (width, height)
+    };
+    config.set_width(size.0);
+    config.set_height(size.1);
+    (config, size)
+}
@@ -0,0 +1,168 @@
+//! Checks run before a recording starts
+//!
+//! Catches what spoils a recording without showing until afterwards: a muted or
+//! missing microphone, a disk about to fill up, a sign-in that will block the upload,
+//! a source window that has been closed or minimized, and a low battery. Each check
+//! passes or warns; the main window starts recording when everything passes and
+//! otherwise lists the warnings, with the option to record anyway. The results use
+//! the same [`Report`] as the diagnostics in Settings.
+
+use screencapturekit::content_sharing_picker::SCPickedSource;
+use screencapturekit::shareable_content::SCShareableContent;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::auth;
+use crate::capture::CaptureState;
+use crate::capture_settings::CaptureSettings;
+use crate::doctor;
+use crate::doctor::{Check, CheckStatus, Report};
+use crate::input;
+use crate::naming;
+use crate::power;
+
+/// How long to listen to the microphone for
+const MIC_LISTEN: Duration = Duration::from_secs(1);
+/// Peak level below which the microphone is taken to be muted
+const MIC_SILENCE: f32 = 0.001;
+
+async fn microphone(capture_state: &CaptureState) -> Check {
+    let before = capture_state.mic_waveform.lock().unwrap().sample_count();
+    if CaptureSettings::load().captures_microphone {
+        tokio::time::sleep(MIC_LISTEN).await;
+    }
+    microphone_since(capture_state, before)
+}
+
+/// The microphone, judged by what it picked up since it had taken `before` samples
+fn microphone_since(capture_state: &CaptureState, before: u64) -> Check {
+    if !CaptureSettings::load().captures_microphone {
+        return Check::new("Microphone", CheckStatus::Warn, "Turned off in Settings; only system audio will be recorded");
+    }
+    let waveform = capture_state.mic_waveform.lock().unwrap();
+    if waveform.sample_count() == before {
+        Check::new("Microphone", CheckStatus::Warn, "No audio is arriving from the microphone")
+    } else if waveform.peak(4096) < MIC_SILENCE {
+        Check::new("Microphone", CheckStatus::Warn, "Silent; check the microphone is not muted")
+    } else {
+        Check::new("Microphone", CheckStatus::Pass, "Picking up sound")
+    }
+}
+
+async fn sign_in(auth_tokens: &Mutex<Option<auth::AuthTokens>>) -> Check {
+    let tokens = auth_tokens.lock().unwrap().clone();
+    match tokens {
+        None => Check::new("Sign-in", CheckStatus::Warn, "Not signed in; the recording can only be kept on this Mac"),
+        Some(tokens) if tokens.is_expired() => match auth::refresh_access_token(&tokens.refresh_token).await {
+            Ok(new_tokens) => {
+                let _ = auth::save_tokens(&new_tokens);
+                naming::refresh_in_background(new_tokens.access_token.clone());
+                *auth_tokens.lock().unwrap() = Some(new_tokens);
+                Check::new("Sign-in", CheckStatus::Pass, "Renewed")
+            }
+            Err(e) => Check::new(
+                "Sign-in",
+                CheckStatus::Warn,
+                format!("Expired and could not be renewed ({e}); sign in again before uploading"),
+            ),
+        },
+        Some(_) => Check::new("Sign-in", CheckStatus::Pass, "Signed in"),
+    }
+}
+
+fn source() -> Check {
+    let Some(picked) = input::current_source() else {
+        return Check::new("Source", CheckStatus::Warn, "No source selected");
+    };
+    let content = match SCShareableContent::get() {
+        Ok(content) => content,
+        Err(e) => return Check::new("Source", CheckStatus::Warn, format!("Could not check the source: {e}")),
+    };
+    match picked {
+        SCPickedSource::Window(title) => {
+            // Several windows can share a title; the picked one is known by its id
+            let window = match input::current_window_id() {
+                Some(id) => content.windows().into_iter().find(|w| w.window_id() == id),
+                None => content.windows().into_iter().find(|w| w.title().as_deref() == Some(title.as_str())),
+            };
+            match window {
+                Some(window) if window.is_on_screen() => Check::new("Source", CheckStatus::Pass, format!("\"{title}\" is on screen")),
+                Some(_) => Check::new("Source", CheckStatus::Warn, format!("\"{title}\" is minimized or hidden")),
+                None => Check::new("Source", CheckStatus::Warn, format!("\"{title}\" has been closed")),
+            }
+        }
+        SCPickedSource::Display(id) => {
+            if content.displays().iter().any(|d| d.display_id() == id) {
+                Check::new("Source", CheckStatus::Pass, format!("Display {id} is connected"))
+            } else {
+                Check::new("Source", CheckStatus::Warn, format!("Display {id} has been disconnected"))
+            }
+        }
+        SCPickedSource::Application(name) => {
+            if content.applications().iter().any(|a| a.application_name() == name) {
+                Check::new("Source", CheckStatus::Pass, format!("{name} is running"))
+            } else {
+                Check::new("Source", CheckStatus::Warn, format!("{name} is no longer running"))
+            }
+        }
+        SCPickedSource::Unknown => Check::new("Source", CheckStatus::Pass, "Selected"),
+    }
+}
+
+fn battery() -> Check {
+    let Some(status) = power::battery_status() else {
+        return Check::new("Power", CheckStatus::Pass, "No battery");
+    };
+    if !status.on_battery {
+        Check::new("Power", CheckStatus::Pass, format!("Connected to power ({}%)", status.percent))
+    } else if status.percent < power::BatterySettings::load().threshold_percent {
+        Check::new(
+            "Power",
+            CheckStatus::Warn,
+            format!("{}% battery; connect power for a long recording", status.percent),
+        )
+    } else {
+        Check::new("Power", CheckStatus::Pass, format!("On battery ({}%)", status.percent))
+    }
+}
+
+/// Run a check that blocks on ScreenCaptureKit or a child process (`df`, `pmset`) off the UI thread
+async fn blocking(name: &str, check: fn() -> Check) -> Check {
+    tokio::task::spawn_blocking(check)
+        .await
+        .unwrap_or_else(|e| Check::new(name, CheckStatus::Warn, format!("Could not be checked: {e}")))
+}
+
+/// Repeat the checks of `previous`, a [`run`] result, that need no listening, child
+/// processes or ScreenCaptureKit queries, keeping its other results
+///
+/// The microphone is judged by what it picked up since it had taken `mic_samples`
+/// samples, when `previous` was taken.
+pub async fn recheck(
+    previous: &Report,
+    mic_samples: u64,
+    capture_state: &CaptureState,
+    auth_tokens: &Mutex<Option<auth::AuthTokens>>,
+) -> Report {
+    let microphone = microphone_since(capture_state, mic_samples);
+    let sign_in = sign_in(auth_tokens).await;
+    let checks = previous
+        .checks
+        .iter()
+        .map(|check| match check.name.as_str() {
+            "Microphone" => microphone.clone(),
+            "Sign-in" => sign_in.clone(),
+            _ => check.clone(),
+        })
+        .collect();
+    Report { checks }
+}
+
+/// Run every check against the current capture
+pub async fn run(capture_state: Arc<CaptureState>, auth_tokens: Arc<Mutex<Option<auth::AuthTokens>>>) -> Report {
+    let mut checks = vec![microphone(&capture_state).await, blocking("Disk space", doctor::disk_space).await];
+    checks.push(sign_in(&auth_tokens).await);
+    checks.push(blocking("Source", source).await);
+    checks.push(blocking("Power", battery).await);
+    Report { checks }
+}
@@ -0,0 +1,187 @@
+//! On-screen webcam bubble and watermark for recording profiles
+//!
+//! ScreenCaptureKit cannot capture cameras, so the webcam is shown in a round,
+//! always-on-top window in the corner of the recorded display and recorded along
+//! with the rest of it, as is the watermark label in the opposite corner. Both
+//! only appear in display recordings. AppKit objects must only be touched from the
+//! main thread, where the Dioxus UI runs.
+
+use cocoa::base::{id, nil, NO, YES};
+use cocoa::foundation::{NSPoint, NSRect, NSSize, NSString};
+use objc::{class, msg_send, sel, sel_impl};
+use std::cell::{Cell, RefCell};
+
+use crate::profiles::RecordingProfile;
+use crate::screens;
+
+/// Diameter of the webcam bubble, in points
+const CAMERA_SIZE: f64 = 180.0;
+/// Distance kept from the screen edges
+const MARGIN: f64 = 24.0;
+const WATERMARK_FONT_SIZE: f64 = 18.0;
+
+/// `NSWindowStyleMaskBorderless`
+const BORDERLESS: u64 = 0;
+/// `NSBackingStoreBuffered`
+const BUFFERED: u64 = 2;
+/// `NSFloatingWindowLevel`
+const FLOATING_LEVEL: i64 = 3;
+
+#[link(name = "AVFoundation", kind = "framework")]
+extern "C" {
+    static AVMediaTypeVideo: id;
+    static AVLayerVideoGravityResizeAspectFill: id;
+}
+
+thread_local! {
+    static CAMERA_WINDOW: Cell<id> = Cell::new(nil);
+    static CAMERA_SESSION: Cell<id> = Cell::new(nil);
+    static WATERMARK_WINDOW: Cell<id> = Cell::new(nil);
+    /// What is currently on screen and on which display, to skip redundant updates
+    static SHOWN: RefCell<Option<(bool, String, u32)>> = RefCell::new(None);
+}
+
+unsafe fn overlay_window(frame: NSRect) -> id {
+    let window: id = msg_send![class!(NSWindow), alloc];
+    let window: id = msg_send![window,
+        initWithContentRect: frame
+        styleMask: BORDERLESS
+        backing: BUFFERED
+        defer: NO
+    ];
+    let clear: id = msg_send![class!(NSColor), clearColor];
+    let _: () = msg_send![window, setReleasedWhenClosed: NO];
+    let _: () = msg_send![window, setOpaque: NO];
+    let _: () = msg_send![window, setBackgroundColor: clear];
+    let _: () = msg_send![window, setHasShadow: NO];
+    let _: () = msg_send![window, setLevel: FLOATING_LEVEL];
+    window
+}
+
+unsafe fn visible_frame(display_id: u32) -> NSRect {
+    let screen = screens::screen_for(Some(display_id));
+    msg_send![screen, visibleFrame]
+}
+
+/// Round window showing the default camera, bottom-left; `nil` without a camera
+unsafe fn show_camera(display_id: u32) -> id {
+    let device: id = msg_send![class!(AVCaptureDevice), defaultDeviceWithMediaType: AVMediaTypeVideo];
+    if device == nil {
+        eprintln!("⚠️  No camera found for the webcam bubble");
+        return nil;
+    }
+    let mut error: id = nil;
+    let input: id = msg_send![class!(AVCaptureDeviceInput), deviceInputWithDevice: device error: &mut error];
+    if input == nil {
+        eprintln!("⚠️  Camera unavailable (camera access may be denied in System Settings)");
+        return nil;
+    }
+    let session: id = msg_send![class!(AVCaptureSession), new];
+    let _: () = msg_send![session, addInput: input];
+
+    let screen = visible_frame(display_id);
+    let frame = NSRect::new(
+        NSPoint::new(screen.origin.x + MARGIN, screen.origin.y + MARGIN),
+        NSSize::new(CAMERA_SIZE, CAMERA_SIZE),
+    );
+    let window = overlay_window(frame);
+    let _: () = msg_send![window, setMovableByWindowBackground: YES];
+    let view: id = msg_send![window, contentView];
+    let _: () = msg_send![view, setWantsLayer: YES];
+    let layer: id = msg_send![view, layer];
+    let _: () = msg_send![layer, setCornerRadius: CAMERA_SIZE / 2.0];
+    let _: () = msg_send![layer, setMasksToBounds: YES];
+
+    let preview: id = msg_send![class!(AVCaptureVideoPreviewLayer), layerWithSession: session];
+    let bounds: NSRect = msg_send![view, bounds];
+    let _: () = msg_send![preview, setFrame: bounds];
+    let _: () = msg_send![preview, setVideoGravity: AVLayerVideoGravityResizeAspectFill];
+    let _: () = msg_send![layer, addSublayer: preview];
+
+    let _: () = msg_send![session, startRunning];
+    let _: () = msg_send![window, orderFrontRegardless];
+    CAMERA_SESSION.with(|cell| cell.set(session));
+    window
+}
+
+/// Label with `text`, bottom-right, that clicks pass through
+unsafe fn show_watermark(text: &str, display_id: u32) -> id {
+    let ns_text = NSString::alloc(nil).init_str(text);
+    let label: id = msg_send![class!(NSTextField), labelWithString: ns_text];
+    let _: () = msg_send![ns_text, release];
+    let font: id = msg_send![class!(NSFont), boldSystemFontOfSize: WATERMARK_FONT_SIZE];
+    let white: id = msg_send![class!(NSColor), whiteColor];
+    let color: id = msg_send![white, colorWithAlphaComponent: 0.6f64];
+    let _: () = msg_send![label, setFont: font];
+    let _: () = msg_send![label, setTextColor: color];
+    let _: () = msg_send![label, sizeToFit];
+    let label_frame: NSRect = msg_send![label, frame];
+
+    let screen = visible_frame(display_id);
+    let frame = NSRect::new(
+        NSPoint::new(
+            screen.origin.x + screen.size.width - label_frame.size.width - MARGIN,
+            screen.origin.y + MARGIN,
+        ),
+        label_frame.size,
+    );
+    let window = overlay_window(frame);
+    let _: () = msg_send![window, setIgnoresMouseEvents: YES];
+    let _: () = msg_send![window, setContentView: label];
+    let _: () = msg_send![window, orderFrontRegardless];
+    window
+}
+
+fn close(window: &'static std::thread::LocalKey<Cell<id>>) {
+    window.with(|cell| {
+        let window = cell.replace(nil);
+        if window != nil {
+            unsafe {
+                let _: () = msg_send![window, close];
+                let _: () = msg_send![window, release];
+            }
+        }
+    });
+}
+
+fn hide() {
+    CAMERA_SESSION.with(|cell| {
+        let session = cell.replace(nil);
+        if session != nil {
+            unsafe {
+                let _: () = msg_send![session, stopRunning];
+                let _: () = msg_send![session, release];
+            }
+        }
+    });
+    close(&CAMERA_WINDOW);
+    close(&WATERMARK_WINDOW);
+}
+
+/// Show what `profile` asks for on `display` while it is recorded, and nothing otherwise
+///
+/// Called from the UI poll loop.
+pub fn sync(profile: Option<&RecordingProfile>, display: Option<u32>) {
+    let wanted = profile
+        .zip(display)
+        .map(|(p, display_id)| (p.camera, p.watermark.trim().to_string(), display_id))
+        .filter(|(camera, watermark, _)| *camera || !watermark.is_empty());
+    if SHOWN.with(|shown| *shown.borrow() == wanted) {
+        return;
+    }
+    hide();
+    if let Some((camera, ref watermark, display_id)) = wanted {
+        unsafe {
+            if camera {
+                let window = show_camera(display_id);
+                CAMERA_WINDOW.with(|cell| cell.set(window));
+            }
+            if !watermark.is_empty() {
+                let window = show_watermark(watermark, display_id);
+                WATERMARK_WINDOW.with(|cell| cell.set(window));
+            }
+        }
+        println!("🎥 Showing recording overlays for this profile");
+    }
+    SHOWN.with(|shown| *shown.borrow_mut() = wanted);
+}
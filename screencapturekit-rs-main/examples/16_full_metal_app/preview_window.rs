@@ -1,75 +1,185 @@
-//! Metal preview window for captured frames
+//! Metal preview windows for captured frames
+//!
+//! All preview windows share one event loop thread (winit allows a single event loop per
+//! process) and are tracked in a registry keyed by `WindowId`. Each entry owns its own
+//! renderer state, so any number of windows can show the same or different streams.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::mem::size_of;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::{Duration, Instant};
 
 use cocoa::appkit::NSView;
 use cocoa::base::id as cocoa_id;
 use core_graphics_types::geometry::CGSize;
 use metal::{
-    Device, MTLClearColor, MTLLoadAction, MTLPixelFormat, MTLPrimitiveType,
-    MTLResourceOptions, MTLStoreAction, MetalLayer, RenderPassDescriptor,
+    CommandQueue, Device, MTLClearColor, MTLLoadAction, MTLPixelFormat, MTLPrimitiveType,
+    MTLStoreAction, MetalLayer, RenderPassDescriptor, RenderPipelineState,
 };
 use objc::rc::autoreleasepool;
 use objc::runtime::YES;
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
-use winit::event::{Event, WindowEvent};
-use winit::event_loop::{ControlFlow, EventLoop};
+use winit::event::{ElementState, Event, MouseButton, VirtualKeyCode, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget};
+use winit::monitor::MonitorHandle;
+use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
+use winit::window::{Fullscreen, Window, WindowId, WindowLevel};
 
 use crate::capture::CaptureState;
-use crate::renderer::{create_textures_from_iosurface, CaptureTextures, 
+use crate::font::BitmapFont;
+use crate::gpu;
+use crate::thermal;
+use crate::toast;
+use crate::renderer::{create_pipeline, create_textures_from_iosurface, CaptureTextures,
     PIXEL_FORMAT_420F, PIXEL_FORMAT_420V, SHADER_SOURCE};
-use crate::vertex::Uniforms;
-
-pub fn run_preview_window(
-    capture_state: Arc<CaptureState>,
-    is_capturing: Arc<AtomicBool>,
-) {
-    std::thread::spawn(move || {
-        let event_loop = EventLoop::new();
-        let window = winit::window::WindowBuilder::new()
-            .with_inner_size(winit::dpi::LogicalSize::new(960, 540))
-            .with_title("Screen Capture Preview")
-            .build(&event_loop)
-            .unwrap();
+use crate::vertex::{FrameBuffers, Uniforms, VertexBufferBuilder};
 
-        // Initialize Metal
-        let device = Device::system_default().expect("No Metal device found");
+/// Maximum gap between two clicks to count as a double-click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
 
-        let mut layer = MetalLayer::new();
-        layer.set_device(&device);
-        layer.set_pixel_format(MTLPixelFormat::BGRA8Unorm);
-        layer.set_presents_with_transaction(false);
+/// How long the snapshot confirmation stays on screen
+const SNAPSHOT_BANNER_DURATION: Duration = Duration::from_secs(2);
 
-        // Attach layer to window
-        unsafe {
-            match window.raw_window_handle() {
-                RawWindowHandle::AppKit(handle) => {
-                    let view = handle.ns_view as cocoa_id;
-                    view.setWantsLayer(YES);
-                    view.setLayer(std::mem::transmute(layer.as_mut()));
-                }
-                _ => panic!("Unsupported window handle"),
+/// How long the PiP window must be still after a drag before it snaps to a corner
+const PIP_SNAP_DELAY: Duration = Duration::from_millis(250);
+
+/// Distance (in points) kept between the PiP window and the screen edges
+const PIP_MARGIN: f64 = 16.0;
+
+/// How often the registry checks for new requests while no window is open
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How often open windows are checked for new frames (about 60 fps)
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Redraw interval while the Mac is under thermal pressure (about 5 fps)
+const THROTTLED_FRAME_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Redraw interval in low-power preview mode (about 12 fps)
+const LOW_POWER_FRAME_INTERVAL: Duration = Duration::from_millis(83);
+
+/// Which kind of preview window to open
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PreviewStyle {
+    /// Regular resizable, decorated window
+    Window,
+    /// Small borderless always-on-top window that snaps to screen corners
+    PictureInPicture,
+}
+
+impl PreviewStyle {
+    const fn inner_size(self) -> LogicalSize<u32> {
+        match self {
+            Self::Window => LogicalSize::new(960, 540),
+            Self::PictureInPicture => LogicalSize::new(256, 144),
+        }
+    }
+}
+
+/// A capture stream that can be shown in a preview window
+#[derive(Clone)]
+pub struct PreviewSource {
+    /// Shown in the window title
+    pub label: String,
+    pub capture_state: Arc<CaptureState>,
+    pub is_capturing: Arc<AtomicBool>,
+}
+
+enum PreviewRequest {
+    Open {
+        source: PreviewSource,
+        style: PreviewStyle,
+    },
+}
+
+/// Handle for opening preview windows
+///
+/// The registry thread starts on the first [`PreviewManager::open`] call and keeps running
+/// after its windows close, so later windows reuse the same event loop.
+pub struct PreviewManager {
+    requests: Option<Sender<PreviewRequest>>,
+}
+
+impl PreviewManager {
+    pub const fn new() -> Self {
+        Self { requests: None }
+    }
+
+    /// Open another preview window for `source`
+    pub fn open(&mut self, source: PreviewSource, style: PreviewStyle) {
+        let mut request = PreviewRequest::Open { source, style };
+        if let Some(ref tx) = self.requests {
+            match tx.send(request) {
+                Ok(()) => return,
+                // Registry thread is gone (e.g. it panicked) - start a fresh one
+                Err(err) => request = err.0,
             }
         }
 
-        let draw_size = window.inner_size();
-        layer.set_drawable_size(CGSize::new(
-            f64::from(draw_size.width),
-            f64::from(draw_size.height),
-        ));
+        let (tx, rx) = channel();
+        let _ = tx.send(request);
+        self.requests = Some(tx);
+        std::thread::spawn(move || run_registry(rx));
+    }
+}
+
+/// Toggle borderless fullscreen on the given display (or the window's current one)
+fn toggle_fullscreen(window: &Window, monitor: Option<MonitorHandle>) {
+    if window.fullscreen().is_some() {
+        window.set_fullscreen(None);
+    } else {
+        let target = monitor.or_else(|| window.current_monitor());
+        window.set_fullscreen(Some(Fullscreen::Borderless(target)));
+    }
+}
+
+/// Position of the screen corner closest to the window's center
+fn nearest_corner(window: &Window) -> Option<PhysicalPosition<i32>> {
+    let monitor = window.current_monitor()?;
+    let window_pos = window.outer_position().ok()?;
+    let window_size = window.outer_size();
+    let screen_pos = monitor.position();
+    let screen_size = monitor.size();
+    let margin = (PIP_MARGIN * monitor.scale_factor()) as i32;
+
+    let center_x = window_pos.x + window_size.width as i32 / 2;
+    let center_y = window_pos.y + window_size.height as i32 / 2;
+    let left = center_x < screen_pos.x + screen_size.width as i32 / 2;
+    let top = center_y < screen_pos.y + screen_size.height as i32 / 2;
+
+    let x = if left {
+        screen_pos.x + margin
+    } else {
+        screen_pos.x + screen_size.width as i32 - window_size.width as i32 - margin
+    };
+    let y = if top {
+        screen_pos.y + margin
+    } else {
+        screen_pos.y + screen_size.height as i32 - window_size.height as i32 - margin
+    };
+    Some(PhysicalPosition::new(x, y))
+}
 
+/// Render pipelines shared by every preview window
+struct PreviewPipelines {
+    fullscreen: RenderPipelineState,
+    ycbcr: RenderPipelineState,
+    overlay: RenderPipelineState,
+}
+
+impl PreviewPipelines {
+    fn new(device: &Device) -> Self {
         // Compile shaders
         let compile_options = metal::CompileOptions::new();
         let library = device
             .new_library_with_source(SHADER_SOURCE, &compile_options)
             .expect("Failed to compile shaders");
 
-        // Create fullscreen textured pipeline
-        let fullscreen_pipeline = {
+        // Captured frames cover the letterboxed area opaquely, so no blending here
+        let frame_pipeline = |fragment_fn: &str| {
             let vert = library.get_function("vertex_fullscreen", None).unwrap();
-            let frag = library.get_function("fragment_textured", None).unwrap();
+            let frag = library.get_function(fragment_fn, None).unwrap();
             let desc = metal::RenderPipelineDescriptor::new();
             desc.set_vertex_function(Some(&vert));
             desc.set_fragment_function(Some(&frag));
@@ -80,121 +190,466 @@ pub fn run_preview_window(
             device.new_render_pipeline_state(&desc).unwrap()
         };
 
-        // Create YCbCr pipeline
-        let ycbcr_pipeline = {
-            let vert = library.get_function("vertex_fullscreen", None).unwrap();
-            let frag = library.get_function("fragment_ycbcr", None).unwrap();
-            let desc = metal::RenderPipelineDescriptor::new();
-            desc.set_vertex_function(Some(&vert));
-            desc.set_fragment_function(Some(&frag));
-            desc.color_attachments()
-                .object_at(0)
-                .unwrap()
-                .set_pixel_format(MTLPixelFormat::BGRA8Unorm);
-            device.new_render_pipeline_state(&desc).unwrap()
-        };
+        Self {
+            fullscreen: frame_pipeline("fragment_textured"),
+            ycbcr: frame_pipeline("fragment_ycbcr"),
+            overlay: create_pipeline(device, &library, "vertex_colored", "fragment_colored"),
+        }
+    }
+}
+
+/// Metal objects shared by every preview window
+struct PreviewGpu {
+    device: Device,
+    command_queue: CommandQueue,
+    pipelines: PreviewPipelines,
+    font: BitmapFont,
+}
+
+impl PreviewGpu {
+    /// Set up on the GPU chosen in Settings
+    ///
+    /// Fails on Macs without a Metal device, e.g. some virtual machines.
+    fn new() -> Result<Self, String> {
+        let device = gpu::choose(&gpu::current()).ok_or_else(|| "No Metal device found".to_string())?;
+        println!("🖥️  Preview rendering on {}", device.name());
+        Ok(Self {
+            command_queue: device.new_command_queue(),
+            pipelines: PreviewPipelines::new(&device),
+            font: BitmapFont::new(),
+            device,
+        })
+    }
+}
+
+/// One registered preview window and its renderer state
+struct PreviewWindow {
+    window: Window,
+    layer: MetalLayer,
+    source: PreviewSource,
+    style: PreviewStyle,
+    viewport_size: PhysicalSize<u32>,
+    vertex_builder: VertexBufferBuilder,
+    frame_buffers: FrameBuffers,
+    last_click: Option<Instant>,
+    last_moved: Option<Instant>,
+    fullscreen_monitor: Option<MonitorHandle>,
+    /// Set when the window must be drawn even without a new frame, e.g. after a resize
+    needs_redraw: bool,
+    /// Whether the last frame drawn showed the capture, to redraw once it stops
+    drew_capture: bool,
+    /// Whether the last frame drawn showed the snapshot banner, to redraw once it expires
+    drew_banner: bool,
+}
+
+impl PreviewWindow {
+    fn new(
+        target: &EventLoopWindowTarget<()>,
+        device: &Device,
+        source: PreviewSource,
+        style: PreviewStyle,
+    ) -> Self {
+        let is_pip = style == PreviewStyle::PictureInPicture;
+        let window = winit::window::WindowBuilder::new()
+            .with_inner_size(style.inner_size())
+            .with_title(format!("Screen Capture Preview - {}", source.label))
+            .with_decorations(!is_pip)
+            .with_resizable(!is_pip)
+            .with_window_level(if is_pip {
+                WindowLevel::AlwaysOnTop
+            } else {
+                WindowLevel::Normal
+            })
+            .build(target)
+            .unwrap();
+        if is_pip {
+            if let Some(corner) = nearest_corner(&window) {
+                window.set_outer_position(corner);
+            }
+        }
+
+        let mut layer = MetalLayer::new();
+        layer.set_device(device);
+        layer.set_pixel_format(MTLPixelFormat::BGRA8Unorm);
+        layer.set_presents_with_transaction(false);
+
+        // Attach layer to window
+        unsafe {
+            match window.raw_window_handle() {
+                RawWindowHandle::AppKit(handle) => {
+                    let view = handle.ns_view as cocoa_id;
+                    view.setWantsLayer(YES);
+                    view.setLayer(std::mem::transmute(layer.as_mut()));
+                }
+                _ => panic!("Unsupported window handle"),
+            }
+        }
 
-        let command_queue = device.new_command_queue();
-        let mut time = 0.0f32;
+        // Match the layer to the window's backing scale so Retina output stays sharp
+        layer.set_contents_scale(window.scale_factor());
+        let viewport_size = window.inner_size();
+        layer.set_drawable_size(CGSize::new(
+            f64::from(viewport_size.width),
+            f64::from(viewport_size.height),
+        ));
+
+        Self {
+            window,
+            layer,
+            source,
+            style,
+            viewport_size,
+            vertex_builder: VertexBufferBuilder::new(),
+            frame_buffers: FrameBuffers::new(device),
+            last_click: None,
+            last_moved: None,
+            fullscreen_monitor: None,
+            needs_redraw: true,
+            drew_capture: false,
+            drew_banner: false,
+        }
+    }
 
-        event_loop.run(move |event, _, control_flow| {
-            *control_flow = ControlFlow::Poll;
+    fn is_pip(&self) -> bool {
+        self.style == PreviewStyle::PictureInPicture
+    }
 
-            match event {
-                Event::WindowEvent {
-                    event: WindowEvent::CloseRequested,
-                    ..
-                } => {
-                    *control_flow = ControlFlow::Exit;
+    fn resize(&mut self, size: PhysicalSize<u32>) {
+        self.viewport_size = size;
+        self.needs_redraw = true;
+        self.layer.set_drawable_size(CGSize::new(
+            f64::from(size.width),
+            f64::from(size.height),
+        ));
+    }
+
+    /// Handle a window event, returning `false` once the window should close
+    ///
+    /// Preview window controls:
+    /// - Double-click / `F` - Toggle fullscreen on the chosen display
+    /// - `1`-`9` - Choose the display used for fullscreen (moves the window if already fullscreen)
+    /// - `Esc` - Leave fullscreen
+    /// - `S` - Save the current frame as a PNG snapshot
+    ///
+    /// In [`PreviewStyle::PictureInPicture`] the window is dragged with the mouse instead,
+    /// snaps to the nearest screen corner once released, and `Esc` closes it.
+    fn handle_event(&mut self, event: WindowEvent<'_>) -> bool {
+        match event {
+            WindowEvent::CloseRequested => return false,
+            WindowEvent::Resized(size) => self.resize(size),
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => {
+                // Moving between Retina and non-Retina displays changes the backing scale
+                self.layer.set_contents_scale(scale_factor);
+                self.resize(*new_inner_size);
+            }
+            WindowEvent::Moved(_) if self.is_pip() => {
+                self.last_moved = Some(Instant::now());
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } => {
+                if self.is_pip() {
+                    let _ = self.window.drag_window();
+                } else {
+                    let now = Instant::now();
+                    if self
+                        .last_click
+                        .is_some_and(|t| now.duration_since(t) <= DOUBLE_CLICK_INTERVAL)
+                    {
+                        toggle_fullscreen(&self.window, self.fullscreen_monitor.clone());
+                        self.last_click = None;
+                    } else {
+                        self.last_click = Some(now);
+                    }
                 }
-                Event::MainEventsCleared => {
-                    window.request_redraw();
+            }
+            WindowEvent::KeyboardInput {
+                input:
+                    winit::event::KeyboardInput {
+                        virtual_keycode: Some(keycode),
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                ..
+            } => match keycode {
+                VirtualKeyCode::S => {
+                    if self.source.is_capturing.load(Ordering::Relaxed) {
+                        println!("📸 Snapshot requested from preview");
+                        self.source
+                            .capture_state
+                            .snapshot_requested
+                            .store(true, Ordering::Relaxed);
+                    }
+                }
+                VirtualKeyCode::F if !self.is_pip() => {
+                    toggle_fullscreen(&self.window, self.fullscreen_monitor.clone());
+                }
+                VirtualKeyCode::Escape if self.is_pip() => return false,
+                VirtualKeyCode::Escape => {
+                    if self.window.fullscreen().is_some() {
+                        self.window.set_fullscreen(None);
+                    }
                 }
-                Event::RedrawRequested(_) => {
-                    autoreleasepool(|| {
-                        time += 0.016;
-
-                        let size = window.inner_size();
-                        let width = size.width as f32;
-                        let height = size.height as f32;
-
-                        // Try to get captured frame
-                        let mut capture_textures: Option<CaptureTextures> = None;
-                        let mut tex_width = 1280.0f32;
-                        let mut tex_height = 720.0f32;
-                        let mut pixel_format: u32 = 0;
-
-                        if is_capturing.load(Ordering::Relaxed) {
-                            if let Ok(guard) = capture_state.latest_surface.try_lock() {
-                                if let Some(ref surface) = *guard {
-                                    tex_width = surface.width() as f32;
-                                    tex_height = surface.height() as f32;
-                                    capture_textures = unsafe {
-                                        create_textures_from_iosurface(&device, surface.as_ptr())
-                                    };
-                                    if let Some(ref ct) = capture_textures {
-                                        pixel_format = ct.pixel_format;
-                                    }
-                                }
-                            }
+                VirtualKeyCode::Key1
+                | VirtualKeyCode::Key2
+                | VirtualKeyCode::Key3
+                | VirtualKeyCode::Key4
+                | VirtualKeyCode::Key5
+                | VirtualKeyCode::Key6
+                | VirtualKeyCode::Key7
+                | VirtualKeyCode::Key8
+                | VirtualKeyCode::Key9 => {
+                    let idx = keycode as usize - VirtualKeyCode::Key1 as usize;
+                    if let Some(monitor) = self.window.available_monitors().nth(idx) {
+                        println!(
+                            "🖥️  Preview display: {}",
+                            monitor.name().unwrap_or_else(|| format!("Display {}", idx + 1))
+                        );
+                        if self.window.fullscreen().is_some() {
+                            self.window.set_fullscreen(Some(Fullscreen::Borderless(Some(
+                                monitor.clone(),
+                            ))));
                         }
+                        self.fullscreen_monitor = Some(monitor);
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        true
+    }
 
-                        // Uniforms
-                        let uniforms = Uniforms {
-                            viewport_size: [width, height],
-                            texture_size: [tex_width, tex_height],
-                            time,
-                            pixel_format,
-                            _padding: [0.0; 2],
-                        };
-                        let uniforms_buffer = device.new_buffer_with_data(
-                            std::ptr::addr_of!(uniforms).cast(),
-                            size_of::<Uniforms>() as u64,
-                            MTLResourceOptions::CPUCacheModeDefaultCache,
-                        );
+    /// Snap the PiP window into a corner once the user stops dragging it
+    fn snap_if_settled(&mut self) {
+        if !self.last_moved.is_some_and(|t| t.elapsed() >= PIP_SNAP_DELAY) {
+            return;
+        }
+        self.last_moved = None;
+        if let (Some(corner), Ok(current)) =
+            (nearest_corner(&self.window), self.window.outer_position())
+        {
+            if corner != current {
+                self.window.set_outer_position(corner);
+            }
+        }
+    }
+
+    fn banner_visible(&self) -> bool {
+        self.source
+            .capture_state
+            .last_snapshot
+            .try_lock()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|(_, saved_at)| *saved_at))
+            .is_some_and(|saved_at| saved_at.elapsed() < SNAPSHOT_BANNER_DURATION)
+    }
+
+    /// Whether anything on screen would change; `fresh` when a new frame arrived
+    fn wants_redraw(&self, fresh: bool) -> bool {
+        let capturing = self.source.is_capturing.load(Ordering::Relaxed);
+        self.needs_redraw
+            || (fresh && capturing)
+            || capturing != self.drew_capture
+            || self.drew_banner
+            || self.banner_visible()
+    }
+
+    fn render(&mut self, gpu: &PreviewGpu, time: f32) {
+        // Minimized or zero-sized windows have no drawable to render into
+        if self.viewport_size.width == 0 || self.viewport_size.height == 0 {
+            return;
+        }
+        let width = self.viewport_size.width as f32;
+        let height = self.viewport_size.height as f32;
+        let capture_state = &self.source.capture_state;
+
+        // Try to get captured frame
+        let mut capture_textures: Option<CaptureTextures> = None;
+        let mut tex_width = 1280.0f32;
+        let mut tex_height = 720.0f32;
+        let mut pixel_format: u32 = 0;
+
+        if self.source.is_capturing.load(Ordering::Relaxed) {
+            if let Ok(guard) = capture_state.latest_surface.try_lock() {
+                if let Some(ref surface) = *guard {
+                    tex_width = surface.width() as f32;
+                    tex_height = surface.height() as f32;
+                    capture_textures =
+                        unsafe { create_textures_from_iosurface(&gpu.device, surface.as_ptr()) };
+                    if let Some(ref ct) = capture_textures {
+                        pixel_format = ct.pixel_format;
+                    }
+                }
+            }
+        }
+
+        // Uniforms
+        let uniforms = Uniforms::new([width, height], [tex_width, tex_height], time, pixel_format);
+
+        // Overlay: snapshot confirmation
+        self.vertex_builder.clear();
+        let show_banner = self.banner_visible();
+        if show_banner {
+            self.vertex_builder.snapshot_banner(&gpu.font, width, height);
+        }
+
+        // Render
+        let Some(drawable) = self.layer.next_drawable() else {
+            return;
+        };
+
+        let render_pass = RenderPassDescriptor::new();
+        let attachment = render_pass.color_attachments().object_at(0).unwrap();
+        attachment.set_texture(Some(drawable.texture()));
+        attachment.set_load_action(MTLLoadAction::Clear);
+        attachment.set_clear_color(MTLClearColor::new(0.08, 0.08, 0.1, 1.0));
+        attachment.set_store_action(MTLStoreAction::Store);
+
+        let (uniforms_buffer, vertex_buffer) =
+            self.frame_buffers
+                .next_frame(&gpu.device, &uniforms, &self.vertex_builder);
+
+        let cmd_buffer = gpu.command_queue.new_command_buffer();
+        let encoder = cmd_buffer.new_render_command_encoder(render_pass);
+
+        // Draw captured frame if available
+        if let Some(ref textures) = capture_textures {
+            let is_ycbcr = textures.pixel_format == PIXEL_FORMAT_420V
+                || textures.pixel_format == PIXEL_FORMAT_420F;
+
+            if is_ycbcr && textures.plane1.is_some() {
+                encoder.set_render_pipeline_state(&gpu.pipelines.ycbcr);
+                encoder.set_vertex_buffer(0, Some(uniforms_buffer), 0);
+                encoder.set_fragment_texture(0, Some(&textures.plane0));
+                encoder.set_fragment_texture(1, Some(textures.plane1.as_ref().unwrap()));
+                encoder.set_fragment_buffer(0, Some(uniforms_buffer), 0);
+            } else {
+                encoder.set_render_pipeline_state(&gpu.pipelines.fullscreen);
+                encoder.set_vertex_buffer(0, Some(uniforms_buffer), 0);
+                encoder.set_fragment_texture(0, Some(&textures.plane0));
+            }
+            encoder.draw_primitives(MTLPrimitiveType::TriangleStrip, 0, 4);
+        }
+
+        if let Some(vertex_buffer) = vertex_buffer {
+            encoder.set_render_pipeline_state(&gpu.pipelines.overlay);
+            encoder.set_vertex_buffer(0, Some(vertex_buffer), 0);
+            encoder.set_vertex_buffer(1, Some(uniforms_buffer), 0);
+            encoder.draw_primitives(
+                MTLPrimitiveType::Triangle,
+                0,
+                self.vertex_builder.vertex_count() as u64,
+            );
+        }
+
+        encoder.end_encoding();
+        cmd_buffer.present_drawable(drawable);
+        cmd_buffer.commit();
+        self.frame_buffers.track(cmd_buffer);
+
+        self.needs_redraw = false;
+        self.drew_capture = self.source.is_capturing.load(Ordering::Relaxed);
+        self.drew_banner = show_banner;
+    }
+}
+
+/// Event loop owning every open preview window
+fn run_registry(requests: Receiver<PreviewRequest>) {
+    // Checked before the event loop exists, since only one can ever be created
+    let mut gpu = match PreviewGpu::new() {
+        Ok(gpu) => gpu,
+        Err(e) => {
+            toast::error(format!("Preview unavailable: {e}"));
+            return;
+        }
+    };
+    let event_loop = EventLoop::new();
 
-                        // Render
-                        let Some(drawable) = layer.next_drawable() else {
-                            return;
-                        };
-
-                        let render_pass = RenderPassDescriptor::new();
-                        let attachment = render_pass.color_attachments().object_at(0).unwrap();
-                        attachment.set_texture(Some(drawable.texture()));
-                        attachment.set_load_action(MTLLoadAction::Clear);
-                        attachment.set_clear_color(MTLClearColor::new(0.08, 0.08, 0.1, 1.0));
-                        attachment.set_store_action(MTLStoreAction::Store);
-
-                        let cmd_buffer = command_queue.new_command_buffer();
-                        let encoder = cmd_buffer.new_render_command_encoder(render_pass);
-
-                        // Draw captured frame if available
-                        if let Some(ref textures) = capture_textures {
-                            let is_ycbcr = textures.pixel_format == PIXEL_FORMAT_420V
-                                || textures.pixel_format == PIXEL_FORMAT_420F;
-
-                            if is_ycbcr && textures.plane1.is_some() {
-                                encoder.set_render_pipeline_state(&ycbcr_pipeline);
-                                encoder.set_vertex_buffer(0, Some(&uniforms_buffer), 0);
-                                encoder.set_fragment_texture(0, Some(&textures.plane0));
-                                encoder.set_fragment_texture(1, Some(textures.plane1.as_ref().unwrap()));
-                                encoder.set_fragment_buffer(0, Some(&uniforms_buffer), 0);
-                            } else {
-                                encoder.set_render_pipeline_state(&fullscreen_pipeline);
-                                encoder.set_vertex_buffer(0, Some(&uniforms_buffer), 0);
-                                encoder.set_fragment_texture(0, Some(&textures.plane0));
-                            }
-                            encoder.draw_primitives(MTLPrimitiveType::TriangleStrip, 0, 4);
+    let mut windows: HashMap<WindowId, PreviewWindow> = HashMap::new();
+    let mut time = 0.0f32;
+    let mut last_frame = Instant::now();
+
+    event_loop.run(move |event, target, control_flow| {
+        match event {
+            Event::WindowEvent { window_id, event } => {
+                if let Some(preview) = windows.get_mut(&window_id) {
+                    if !preview.handle_event(event) {
+                        // Dropping the entry closes the window; the loop stays alive
+                        windows.remove(&window_id);
+                    }
+                }
+            }
+            Event::MainEventsCleared => {
+                // Open windows keep their layers on the old GPU, so switch once all are closed
+                if windows.is_empty() && gpu::take_changed() {
+                    match PreviewGpu::new() {
+                        Ok(changed) => gpu = changed,
+                        Err(e) => eprintln!("⚠️  Keeping the current GPU for previews: {}", e),
+                    }
+                }
+                while let Ok(request) = requests.try_recv() {
+                    match request {
+                        PreviewRequest::Open { source, style } => {
+                            let preview = PreviewWindow::new(target, &gpu.device, source, style);
+                            windows.insert(preview.window.id(), preview);
                         }
+                    }
+                }
 
-                        encoder.end_encoding();
-                        cmd_buffer.present_drawable(drawable);
-                        cmd_buffer.commit();
-                    });
+                time += 0.016;
+                let low_power = gpu::low_power_preview();
+                let frame_due = !low_power || last_frame.elapsed() >= LOW_POWER_FRAME_INTERVAL;
+                // Each stream's flag is taken once, however many windows show it
+                let mut checked: Vec<(Arc<CaptureState>, bool)> = Vec::new();
+                for preview in windows.values_mut() {
+                    preview.snap_if_settled();
+                    if !frame_due {
+                        // A resized window is drawn right away so it never shows stretched content
+                        if preview.needs_redraw {
+                            preview.window.request_redraw();
+                        }
+                        continue;
+                    }
+                    let state = &preview.source.capture_state;
+                    let fresh = match checked.iter().find(|(seen, _)| Arc::ptr_eq(seen, state)) {
+                        Some(&(_, fresh)) => fresh,
+                        None => {
+                            let fresh = state.surface_dirty.swap(false, Ordering::Acquire);
+                            checked.push((Arc::clone(state), fresh));
+                            fresh
+                        }
+                    };
+                    if preview.wants_redraw(fresh) {
+                        preview.window.request_redraw();
+                    }
+                }
+                if frame_due {
+                    last_frame = Instant::now();
+                }
+            }
+            Event::RedrawRequested(window_id) => {
+                if let Some(preview) = windows.get_mut(&window_id) {
+                    autoreleasepool(|| preview.render(&gpu, time));
                 }
-                _ => {}
             }
-        });
+            _ => {}
+        }
+
+        *control_flow = if windows.is_empty() {
+            ControlFlow::WaitUntil(Instant::now() + IDLE_POLL_INTERVAL)
+        } else if thermal::under_pressure() {
+            ControlFlow::WaitUntil(Instant::now() + THROTTLED_FRAME_INTERVAL)
+        } else if gpu::low_power_preview() {
+            ControlFlow::WaitUntil(last_frame + LOW_POWER_FRAME_INTERVAL)
+        } else {
+            // Waiting rather than polling, so an idle window does not keep a core busy
+            ControlFlow::WaitUntil(last_frame + FRAME_INTERVAL)
+        };
     });
 }
-
@@ -0,0 +1,202 @@
+//! Named recording profiles
+//!
+//! A profile bundles how a kind of meeting is recorded: "Customer demo" at 1080p60
+//! with the webcam bubble and a watermark, "Internal sync" at 720p30 with the focus
+//! on sound. One is picked before recording, or, left on automatic, chosen from the
+//! current calendar event when its title contains one of the profile's keywords.
+//!
+//! A profile also lists the post-processing its recordings go through before upload
+//! (see `pipeline`). Profiles are stored in `profiles.json` in the data folder;
+//! without a profile the capture settings are used unchanged and recordings are
+//! only trimmed.
+
+use screencapturekit::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::auth::MeetingEvent;
+use crate::pipeline::{self, Step};
+use crate::user_data;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingProfile {
+    pub name: String,
+    /// Output height in pixels, keeping the source's aspect ratio; 0 keeps the source size
+    pub max_height: u32,
+    pub fps: u32,
+    /// Show the webcam in a bubble on screen while recording
+    pub camera: bool,
+    /// Text shown in the corner of the screen while recording
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub watermark: String,
+    pub system_audio: bool,
+    pub microphone: bool,
+    /// Calendar event titles containing one of these pick the profile automatically
+    pub keywords: Vec<String>,
+    /// Post-processing before upload
+    pub pipeline: Vec<Step>,
+}
+
+impl Default for RecordingProfile {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            max_height: 0,
+            fps: 30,
+            camera: false,
+            watermark: String::new(),
+            system_audio: true,
+            microphone: true,
+            keywords: Vec::new(),
+            pipeline: pipeline::default_steps(),
+        }
+    }
+}
+
+impl RecordingProfile {
+    /// "1080p60 · camera · watermark"
+    pub fn summary(&self) -> String {
+        let mut parts = vec![if self.max_height == 0 {
+            format!("source size, {} fps", self.fps)
+        } else {
+            format!("{}p{}", self.max_height, self.fps)
+        }];
+        if self.camera {
+            parts.push("camera".to_string());
+        }
+        if !self.watermark.is_empty() {
+            parts.push("watermark".to_string());
+        }
+        if !self.system_audio {
+            parts.push("no system audio".to_string());
+        }
+        if !self.microphone {
+            parts.push("no microphone".to_string());
+        }
+        parts.join(" · ")
+    }
+
+    /// The profile's post-processing step of the same kind as `kind`
+    pub fn step(&self, kind: &Step) -> Option<&Step> {
+        self.pipeline.iter().find(|step| step.same_kind(kind))
+    }
+
+    /// Turn the step of `step`'s kind on with its options, or off
+    pub fn set_step(&mut self, step: Step, on: bool) {
+        self.pipeline.retain(|existing| !existing.same_kind(&step));
+        if on {
+            self.pipeline.push(step);
+        }
+    }
+
+    fn matches(&self, title: &str) -> bool {
+        let title = title.to_lowercase();
+        self.keywords
+            .iter()
+            .map(|keyword| keyword.trim().to_lowercase())
+            .any(|keyword| !keyword.is_empty() && title.contains(&keyword))
+    }
+
+    /// `base` and `size` adjusted to the profile
+    pub fn apply(&self, base: &SCStreamConfiguration, (width, height): (u32, u32)) -> (SCStreamConfiguration, (u32, u32)) {
+        let mut config = base.clone();
+        config.set_fps(self.fps.max(1));
+        config.set_captures_audio(self.system_audio);
+        config.set_captures_microphone(self.microphone);
+        let size = if self.max_height == 0 || height <= self.max_height {
+            (width, height)
+        } else {
+            let scaled_width = u64::from(width) * u64::from(self.max_height) / u64::from(height);
+            // Keep dimensions even, as the encoder requires
+            (((scaled_width / 2).max(1) * 2) as u32, (self.max_height / 2).max(1) * 2)
+        };
+        (config, size)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileSettings {
+    pub profiles: Vec<RecordingProfile>,
+    /// Name of the profile to record with; empty picks one from the calendar
+    pub selected: String,
+}
+
+impl Default for ProfileSettings {
+    fn default() -> Self {
+        Self {
+            profiles: vec![
+                RecordingProfile {
+                    name: "Customer demo".to_string(),
+                    max_height: 1080,
+                    fps: 60,
+                    camera: true,
+                    watermark: "Talka".to_string(),
+                    keywords: vec!["demo".to_string(), "customer".to_string()],
+                    ..RecordingProfile::default()
+                },
+                RecordingProfile {
+                    name: "Internal sync".to_string(),
+                    max_height: 720,
+                    fps: 30,
+                    keywords: vec!["sync".to_string(), "standup".to_string(), "1:1".to_string()],
+                    ..RecordingProfile::default()
+                },
+            ],
+            selected: String::new(),
+        }
+    }
+}
+
+impl ProfileSettings {
+    pub fn config_path() -> PathBuf {
+        user_data::file("profiles.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize recording profiles: {e}"))?;
+        user_data::write_private(&Self::config_path(), json).map_err(|e| format!("Failed to save recording profiles: {e}"))
+    }
+
+    /// The profile picked by keyword for `event`
+    pub fn matching(&self, event: Option<&MeetingEvent>) -> Option<&RecordingProfile> {
+        let event = event?;
+        self.profiles.iter().find(|profile| profile.matches(&event.event_summary))
+    }
+
+    /// The profile to record `event` with: the selected one, or else a keyword match
+    pub fn choose(&self, event: Option<&MeetingEvent>) -> Option<&RecordingProfile> {
+        if self.selected.is_empty() {
+            self.matching(event)
+        } else {
+            self.profiles.iter().find(|profile| profile.name == self.selected)
+        }
+    }
+}
+
+/// Post-processing for a recording made with the profile called `name`
+pub fn pipeline_for(name: Option<&str>) -> Vec<Step> {
+    name.and_then(|name| ProfileSettings::load().profiles.into_iter().find(|p| p.name == name))
+        .map_or_else(pipeline::default_steps, |profile| profile.pipeline)
+}
+
+/// Profile of the recording in progress
+static ACTIVE: Mutex<Option<RecordingProfile>> = Mutex::new(None);
+
+pub fn active() -> Option<RecordingProfile> {
+    ACTIVE.lock().unwrap().clone()
+}
+
+pub fn set_active(profile: Option<RecordingProfile>) {
+    *ACTIVE.lock().unwrap() = profile;
+}
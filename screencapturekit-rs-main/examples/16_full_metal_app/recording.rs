@@ -6,12 +6,26 @@ use screencapturekit::recording_output::{
     SCRecordingOutputFileType,
 };
 #[cfg(feature = "macos_15_0")]
+use screencapturekit::recording_sink::{FileSink, RecordingSink};
+#[cfg(feature = "macos_15_0")]
 use screencapturekit::stream::sc_stream::SCStream;
 #[cfg(feature = "macos_15_0")]
 use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(feature = "macos_15_0")]
 use std::sync::{Arc, Condvar, Mutex};
 #[cfg(feature = "macos_15_0")]
+use crate::capture_settings;
+#[cfg(feature = "macos_15_0")]
+use crate::capture_settings::{CaptureSettings, CODECS, FILE_TYPES, SEGMENT_OPTIONS};
+#[cfg(feature = "macos_15_0")]
+use crate::library;
+#[cfg(feature = "macos_15_0")]
+use crate::remote_config;
+#[cfg(feature = "macos_15_0")]
+use crate::sidecar::{RecordingSidecar, SegmentInfo};
+#[cfg(feature = "macos_15_0")]
+use crate::time_format;
+#[cfg(feature = "macos_15_0")]
 use crate::upload::UploadStatus;
 
 /// Recording configuration state
@@ -20,6 +34,8 @@ use crate::upload::UploadStatus;
 pub struct RecordingConfig {
     pub codec: SCRecordingOutputCodec,
     pub file_type: SCRecordingOutputFileType,
+    /// Split the recording into parts of this many minutes (`None` records one file)
+    pub segment_minutes: Option<u32>,
 }
 
 #[cfg(feature = "macos_15_0")]
@@ -28,14 +44,41 @@ impl Default for RecordingConfig {
         Self {
             codec: SCRecordingOutputCodec::H264,
             file_type: SCRecordingOutputFileType::MP4,
+            segment_minutes: None,
         }
     }
 }
 
 #[cfg(feature = "macos_15_0")]
 impl RecordingConfig {
-    pub fn new() -> Self {
-        Self::default()
+    fn segment_length(&self) -> Option<std::time::Duration> {
+        self.segment_minutes
+            .map(|minutes| std::time::Duration::from_secs(u64::from(minutes) * 60))
+    }
+
+    /// Codec name as used in the remote config
+    pub const fn codec_name(&self) -> &'static str {
+        match self.codec {
+            SCRecordingOutputCodec::H264 => "h264",
+            SCRecordingOutputCodec::HEVC => "hevc",
+        }
+    }
+
+    /// This config, with its codec replaced if the remote config no longer allows it
+    pub fn with_allowed_codec(&self) -> Self {
+        let mut config = self.clone();
+        if !remote_config::codec_allowed(config.codec_name()) {
+            for codec in [SCRecordingOutputCodec::H264, SCRecordingOutputCodec::HEVC] {
+                config.codec = codec;
+                if remote_config::codec_allowed(config.codec_name()) {
+                    println!("🎞️  Recording with {} as the server requires", config.codec_name());
+                    return config;
+                }
+            }
+            eprintln!("⚠️  Remote config allows no known codec; keeping {}", self.codec_name());
+            config.codec = self.codec;
+        }
+        config
     }
 
     /// Apply this config to a recording output configuration
@@ -57,28 +100,74 @@ impl RecordingConfig {
     }
 }
 
+/// First free `recording_<date>_<time>` name, adding `_2`, `_3`, ... when recordings
+/// start within the same second or a file with that name already exists
+#[cfg(feature = "macos_15_0")]
+fn unique_base_name(stamp: &str) -> String {
+    let existing: Vec<String> = std::fs::read_dir(library::recordings_dir())
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    // A name is taken if any recording, part, sidecar or derived copy already uses it
+    let in_use = |name: &str| {
+        existing
+            .iter()
+            .any(|f| f.strip_prefix(name).is_some_and(|rest| rest.starts_with(['.', '_'])))
+    };
+
+    let base = format!("recording_{stamp}");
+    if !in_use(&base) {
+        return base;
+    }
+    (2u32..)
+        .map(|n| format!("{base}_{n}"))
+        .find(|name| !in_use(name))
+        .unwrap_or(base)
+}
+
 /// Recording state manager
 #[cfg(feature = "macos_15_0")]
 #[derive(Clone)]
 pub struct RecordingState {
-    pub output: Option<SCRecordingOutput>,
+    /// Sink writing the current file
+    pub sink: Option<FileSink>,
     pub path: Option<String>,
     pub is_recording: Arc<AtomicBool>,
     /// Signal when recording finishes (for waiting before opening file)
     finish_signal: Arc<(Mutex<bool>, Condvar)>,
     /// Upload status
     pub upload_status: Arc<Mutex<UploadStatus>>,
+    /// Settings and metadata of the current recording, reused for each new segment
+    session: Option<(RecordingConfig, RecordingSidecar)>,
+    /// `recording_<timestamp>`, shared by every segment of the current recording
+    base_name: String,
+    /// Finished and in-progress segment files, oldest first (segmented recordings, and
+    /// ones that were paused and resumed)
+    segments: Vec<String>,
+    /// Media time of the parts of the current recording already finished, in seconds
+    finished_seconds: f64,
+    /// Last file finished by [`RecordingState::pause`], while the recording is paused
+    paused_at: Option<String>,
 }
 
 #[cfg(feature = "macos_15_0")]
 impl RecordingState {
     pub fn new() -> Self {
         Self {
-            output: None,
+            sink: None,
             path: None,
             is_recording: Arc::new(AtomicBool::new(false)),
             finish_signal: Arc::new((Mutex::new(false), Condvar::new())),
             upload_status: Arc::new(Mutex::new(UploadStatus::Idle)),
+            session: None,
+            base_name: String::new(),
+            segments: Vec::new(),
+            finished_seconds: 0.0,
+            paused_at: None,
         }
     }
 
@@ -87,17 +176,54 @@ impl RecordingState {
         self.is_recording.load(Ordering::Relaxed)
     }
 
-    /// Start recording to a file
-    pub fn start(&mut self, stream: &SCStream, config: &RecordingConfig) -> Result<String, String> {
+    /// Start recording to a file, writing `metadata` as its sidecar
+    ///
+    /// Returns the path of the first file; segmented recordings continue in further
+    /// `_partN` files via [`RecordingState::rotate_segment_if_due`].
+    pub fn start(
+        &mut self,
+        stream: &SCStream,
+        config: &RecordingConfig,
+        metadata: &RecordingSidecar,
+    ) -> Result<String, String> {
         if self.is_active() {
             return Err("Already recording".to_string());
         }
 
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
-        let path = format!("/tmp/recording_{}.{}", timestamp, config.file_extension());
+        self.base_name = unique_base_name(&time_format::file_stamp(chrono::Utc::now()));
+        self.segments.clear();
+        self.finished_seconds = 0.0;
+        self.paused_at = None;
+        self.session = Some((config.clone(), metadata.clone()));
+
+        let path = self.start_output(stream)?;
+        self.is_recording.store(true, Ordering::Relaxed);
+        Ok(path)
+    }
+
+    /// Add a recording output for the next file of the current recording
+    fn start_output(&mut self, stream: &SCStream) -> Result<String, String> {
+        let Some((config, metadata)) = self.session.clone() else {
+            return Err("No recording in progress".to_string());
+        };
+
+        // A resumed recording continues in parts too
+        let segmented = config.segment_minutes.is_some() || !self.segments.is_empty();
+        let path = if segmented {
+            format!(
+                "{}/{}_part{}.{}",
+                library::recordings_dir(),
+                self.base_name,
+                self.segments.len() + 1,
+                config.file_extension()
+            )
+        } else {
+            format!("{}/{}.{}", library::recordings_dir(), self.base_name, config.file_extension())
+        };
+
+        if std::path::Path::new(&path).exists() {
+            return Err(format!("Refusing to overwrite existing file {path}"));
+        }
 
         let rec_config = config.apply_to(
             SCRecordingOutputConfiguration::new().with_output_url(std::path::Path::new(&path)),
@@ -127,34 +253,39 @@ impl RecordingState {
                 eprintln!("❌ Recording failed: {}", error);
             });
 
-        match SCRecordingOutput::new_with_delegate(&rec_config, delegate) {
-            Some(rec) => match stream.add_recording_output(&rec) {
-                Ok(()) => {
-                    println!("🔴 Recording to: {path}");
-                    self.is_recording.store(true, Ordering::Relaxed);
-                    self.output = Some(rec);
-                    self.path = Some(path.clone());
-                    Ok(path)
+        let Some(rec) = SCRecordingOutput::new_with_delegate(&rec_config, delegate) else {
+            return Err("Failed to create recording output".to_string());
+        };
+        let mut sink = FileSink::new(rec, path.clone());
+        match sink.start(stream) {
+            Ok(()) => {
+                println!("🔴 Recording to: {path}");
+                let mut metadata = metadata;
+                if segmented {
+                    metadata.segment = Some(SegmentInfo {
+                        group: self.base_name.clone(),
+                        index: self.segments.len() as u32 + 1,
+                    });
+                    self.segments.push(path.clone());
+                }
+                if let Err(e) = metadata.save(&path) {
+                    println!("⚠️  {e}");
                 }
-                Err(e) => Err(format!("Failed to start recording: {e:?}")),
-            },
-            None => Err("Failed to create recording output".to_string()),
+                self.sink = Some(sink);
+                self.path = Some(path.clone());
+                Ok(path)
+            }
+            Err(e) => Err(format!("Failed to start recording: {e:?}")),
         }
     }
 
-    /// Stop recording and return the file path
-    pub fn stop(&mut self, stream: &SCStream) -> Option<String> {
-        if !self.is_active() {
-            return None;
+    /// Remove the current recording output and wait for its file to be finalized
+    fn finish_output(&mut self, stream: &SCStream) -> Option<String> {
+        let written = self.output_seconds();
+        if let Some(ref mut sink) = self.sink {
+            let _ = sink.stop(stream);
         }
 
-        if let Some(ref rec) = self.output {
-            println!("⏹️  Stopping recording...");
-            let _ = stream.remove_recording_output(rec);
-        }
-
-        self.is_recording.store(false, Ordering::Relaxed);
-
         // Wait for recording to finish (with timeout)
         {
             let (lock, cvar) = &*self.finish_signal;
@@ -170,7 +301,7 @@ impl RecordingState {
             }
         }
 
-        self.output = None;
+        self.sink = None;
 
         let path = self.path.take();
         if let Some(ref p) = path {
@@ -179,6 +310,11 @@ impl RecordingState {
 
             if std::path::Path::new(p).exists() {
                 println!("✅ Recording saved: {p}");
+                // The finished file has the last word; until then, what the output
+                // reported last
+                let duration = crate::media::duration_seconds(p).unwrap_or(written);
+                self.finished_seconds += duration;
+                let _ = RecordingSidecar::update(p, |s| s.duration_seconds = Some(duration));
                 // Note: File is NOT automatically opened - UI handles the flow
             } else {
                 println!("⚠️  Recording file not found: {p}");
@@ -187,6 +323,155 @@ impl RecordingState {
         path
     }
 
+    /// Media time written to the current file, in seconds, from the timestamps of the
+    /// samples the output has taken in
+    fn output_seconds(&self) -> f64 {
+        let Some(ref sink) = self.sink else {
+            return 0.0;
+        };
+        // Reported without the valid flag, so `as_seconds` would refuse it
+        let recorded = sink.output().recorded_duration();
+        if recorded.timescale > 0 {
+            recorded.value as f64 / f64::from(recorded.timescale)
+        } else {
+            0.0
+        }
+    }
+
+    /// Media time of the whole current recording so far, in seconds, across all its
+    /// parts
+    ///
+    /// Unlike the wall clock this leaves out time not written to the file, such as
+    /// pauses and the gaps between segments, so it matches the finished recording.
+    pub fn recorded_seconds(&self) -> f64 {
+        self.finished_seconds + self.output_seconds()
+    }
+
+    /// Stop recording and return the file path
+    ///
+    /// For segmented recordings this is the last part; see [`RecordingState::take_segments`].
+    pub fn stop(&mut self, stream: &SCStream) -> Option<String> {
+        if !self.is_active() {
+            return None;
+        }
+
+        println!("⏹️  Stopping recording...");
+        self.is_recording.store(false, Ordering::Relaxed);
+        let path = match self.paused_at.take() {
+            Some(path) => Some(path),
+            None => self.finish_output(stream),
+        };
+        self.session = None;
+        path
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Stop writing until [`RecordingState::resume`], without ending the recording
+    ///
+    /// The file written so far is finished; resuming continues in a new part, and
+    /// the parts are merged in the trim editor like those of a segmented recording.
+    pub fn pause(&mut self, stream: &SCStream) -> Result<(), String> {
+        if !self.is_active() || self.is_paused() {
+            return Err("Not recording".to_string());
+        }
+        println!("⏸️  Pausing recording...");
+        let path = self.finish_output(stream).ok_or_else(|| "Not recording".to_string())?;
+        self.paused_at = Some(path);
+        Ok(())
+    }
+
+    /// Continue a paused recording in a new part, returning its path
+    pub fn resume(&mut self, stream: &SCStream) -> Result<String, String> {
+        let Some(paused_at) = self.paused_at.clone() else {
+            return Err("Recording is not paused".to_string());
+        };
+        if self.segments.is_empty() {
+            self.make_first_part(&paused_at)?;
+        }
+        let path = self.start_output(stream)?;
+        self.paused_at = None;
+        println!("▶️  Recording resumed in {path}");
+        Ok(path)
+    }
+
+    /// Rename the only file of a recording to its first part, so more can follow
+    fn make_first_part(&mut self, path: &str) -> Result<(), String> {
+        let part = std::path::Path::new(path)
+            .with_file_name(format!(
+                "{}_part1.{}",
+                self.base_name,
+                std::path::Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("mp4")
+            ))
+            .to_string_lossy()
+            .into_owned();
+        std::fs::rename(path, &part).map_err(|e| format!("Failed to rename {path}: {e}"))?;
+        if let Some(mut metadata) = RecordingSidecar::load(path) {
+            metadata.segment = Some(SegmentInfo { group: self.base_name.clone(), index: 1 });
+            metadata.save(&part)?;
+            let _ = std::fs::remove_file(RecordingSidecar::path_for(path));
+            let _ = crate::library_index::remove(path);
+        }
+        self.paused_at = Some(part.clone());
+        self.segments.push(part);
+        Ok(())
+    }
+
+    /// Bookmark the current moment in the file being written, returning its offset
+    ///
+    /// In segmented recordings the offset is into the current part; merging shifts
+    /// it to the whole recording.
+    pub fn add_bookmark(&self) -> Result<f64, String> {
+        let Some(path) = self.path.as_deref() else {
+            return Err("Not recording".to_string());
+        };
+        let offset = self.output_seconds();
+        RecordingSidecar::update(path, |s| s.bookmarks.push(offset))?;
+        Ok(offset)
+    }
+
+    /// Close the current segment and continue in a new file once it reaches the
+    /// configured length
+    ///
+    /// Switching outputs leaves a short gap (well under a second) between parts.
+    /// Returns `Ok(true)` when a new segment file was started. When the next part
+    /// cannot be started the recording is left as if paused, so [`RecordingState::stop`]
+    /// still hands back the parts written so far.
+    pub fn rotate_segment_if_due(&mut self, stream: &SCStream) -> Result<bool, String> {
+        let Some(length) = self.session.as_ref().and_then(|(config, _)| config.segment_length())
+        else {
+            return Ok(false);
+        };
+        if !self.is_active() || self.is_paused() || self.output_seconds() < length.as_secs_f64() {
+            return Ok(false);
+        }
+
+        println!("✂️  Starting recording segment {}", self.segments.len() + 1);
+        let finished = self.finish_output(stream);
+        match self.start_output(stream) {
+            Ok(_) => Ok(true),
+            Err(e) => {
+                eprintln!("❌ Failed to start next segment: {}", e);
+                self.paused_at = finished;
+                Err(e)
+            }
+        }
+    }
+
+    /// Segment files of the last recording, oldest first
+    ///
+    /// Empty unless the recording was split into more than one part.
+    pub fn take_segments(&mut self) -> Vec<String> {
+        let segments = std::mem::take(&mut self.segments);
+        if segments.len() > 1 {
+            segments
+        } else {
+            Vec::new()
+        }
+    }
+
     /// Get the recording flag for UI display
     pub fn recording_flag(&self) -> Arc<AtomicBool> {
         Arc::clone(&self.is_recording)
@@ -250,13 +535,13 @@ impl Default for RecordingState {
     }
 }
 
-/// Recording config menu
+/// Recording options in the overlay, stepping through the shared [`CaptureSettings`]
 #[cfg(feature = "macos_15_0")]
 pub struct RecordingConfigMenu;
 
 #[cfg(feature = "macos_15_0")]
 impl RecordingConfigMenu {
-    pub const OPTIONS: &'static [&'static str] = &["Video Codec", "File Type"];
+    pub const OPTIONS: &'static [&'static str] = &["Video Codec", "File Type", "Segments"];
 
     pub const fn option_count() -> usize {
         Self::OPTIONS.len()
@@ -266,36 +551,20 @@ impl RecordingConfigMenu {
         Self::OPTIONS.get(idx).unwrap_or(&"?")
     }
 
-    pub fn option_value(config: &RecordingConfig, idx: usize) -> String {
+    pub fn option_value(settings: &CaptureSettings, idx: usize) -> String {
         match idx {
-            0 => match config.codec {
-                SCRecordingOutputCodec::H264 => "H.264".to_string(),
-                SCRecordingOutputCodec::HEVC => "HEVC".to_string(),
-            },
-            1 => match config.file_type {
-                SCRecordingOutputFileType::MP4 => "MP4".to_string(),
-                SCRecordingOutputFileType::MOV => "MOV".to_string(),
-            },
+            0 => capture_settings::choice_label(&CODECS, &settings.codec).to_string(),
+            1 => capture_settings::choice_label(&FILE_TYPES, &settings.file_type).to_string(),
+            2 => capture_settings::segment_label(settings.segment_minutes),
             _ => "?".to_string(),
         }
     }
 
-    pub fn toggle_or_adjust(config: &mut RecordingConfig, idx: usize, _increase: bool) {
+    pub fn toggle_or_adjust(settings: &mut CaptureSettings, idx: usize, increase: bool) {
         match idx {
-            0 => {
-                // Toggle codec
-                config.codec = match config.codec {
-                    SCRecordingOutputCodec::H264 => SCRecordingOutputCodec::HEVC,
-                    SCRecordingOutputCodec::HEVC => SCRecordingOutputCodec::H264,
-                };
-            }
-            1 => {
-                // Toggle file type
-                config.file_type = match config.file_type {
-                    SCRecordingOutputFileType::MP4 => SCRecordingOutputFileType::MOV,
-                    SCRecordingOutputFileType::MOV => SCRecordingOutputFileType::MP4,
-                };
-            }
+            0 => settings.codec = capture_settings::step_choice(&CODECS, &settings.codec, increase),
+            1 => settings.file_type = capture_settings::step_choice(&FILE_TYPES, &settings.file_type, increase),
+            2 => settings.segment_minutes = capture_settings::step(&SEGMENT_OPTIONS, &settings.segment_minutes, increase),
             _ => {}
         }
     }
@@ -0,0 +1,269 @@
+//! On-screen recording timer and the hourly "still recording" reminder
+//!
+//! With the main window hidden it is easy to forget a recording is running, and
+//! the menu bar indicator is small. Optionally a small timer with a pulsing red dot
+//! sits in the top-right corner of the recorded display while recording; its window is
+//! marked as not shared, so it stays out of the capture. Separately, every full hour
+//! of recording posts a notification and a toast, so a call that ended hours ago
+//! does not go on recording. Both are configured in `recording_timer.json` in the
+//! data folder. AppKit objects must only be touched from the main thread, where the
+//! Dioxus UI runs.
+
+use cocoa::base::{id, nil, NO, YES};
+use cocoa::foundation::{NSPoint, NSRect, NSSize, NSString};
+use objc::{class, msg_send, sel, sel_impl};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::screens;
+use crate::time_format;
+use crate::toast;
+use crate::user_data;
+
+/// Distance kept from the screen edges
+const MARGIN: f64 = 16.0;
+const WIDTH: f64 = 96.0;
+const HEIGHT: f64 = 28.0;
+const DOT_WIDTH: f64 = 22.0;
+const FONT_SIZE: f64 = 14.0;
+/// One fade out and back in of the dot
+const PULSE_PERIOD: Duration = Duration::from_millis(1600);
+const REMINDER_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// `NSWindowStyleMaskBorderless`
+const BORDERLESS: u64 = 0;
+/// `NSBackingStoreBuffered`
+const BUFFERED: u64 = 2;
+/// `NSFloatingWindowLevel`
+const FLOATING_LEVEL: i64 = 3;
+/// `NSWindowSharingNone`: left out of screen captures
+const SHARING_NONE: u64 = 0;
+/// `NSTextAlignmentCenter`
+const ALIGN_CENTER: i64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimerSettings {
+    /// Show the timer on screen while recording
+    pub shows_timer: bool,
+    /// Remind every hour that a recording is still running
+    pub hourly_reminder: bool,
+}
+
+impl Default for TimerSettings {
+    fn default() -> Self {
+        Self {
+            shows_timer: false,
+            hourly_reminder: true,
+        }
+    }
+}
+
+/// Settings in effect, loaded on first use
+static CURRENT: Mutex<Option<TimerSettings>> = Mutex::new(None);
+
+impl TimerSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("recording_timer.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save, and use these settings from now on
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize recording timer settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save recording timer settings: {e}"))?;
+        *CURRENT.lock().unwrap() = Some(self.clone());
+        Ok(())
+    }
+}
+
+fn current() -> TimerSettings {
+    CURRENT.lock().unwrap().get_or_insert_with(TimerSettings::load).clone()
+}
+
+/// Pick up settings written to `recording_timer.json` by something other than
+/// [`TimerSettings::save`]
+pub fn reload() {
+    *CURRENT.lock().unwrap() = Some(TimerSettings::load());
+}
+
+thread_local! {
+    static WINDOW: Cell<id> = Cell::new(nil);
+    static TIME_LABEL: Cell<id> = Cell::new(nil);
+    static DOT_LABEL: Cell<id> = Cell::new(nil);
+    /// Display the timer window was placed on
+    static SHOWN_ON: Cell<Option<u32>> = Cell::new(None);
+    /// Full hours already reminded about for the current recording
+    static REMINDED_HOURS: Cell<u64> = Cell::new(0);
+}
+
+unsafe fn label(text: &str, frame: NSRect, color: id) -> id {
+    let ns_text = NSString::alloc(nil).init_str(text);
+    let label: id = msg_send![class!(NSTextField), labelWithString: ns_text];
+    let _: () = msg_send![ns_text, release];
+    let font: id = msg_send![class!(NSFont), monospacedDigitSystemFontOfSize: FONT_SIZE weight: 0.3f64];
+    let _: () = msg_send![label, setFont: font];
+    let _: () = msg_send![label, setTextColor: color];
+    let _: () = msg_send![label, setAlignment: ALIGN_CENTER];
+    let _: () = msg_send![label, setFrame: frame];
+    label
+}
+
+/// Timer window in the top-right corner of `display_id`'s screen, that clicks pass through
+unsafe fn show(display_id: Option<u32>) {
+    let screen = screens::screen_for(display_id);
+    let screen: NSRect = msg_send![screen, visibleFrame];
+    let frame = NSRect::new(
+        NSPoint::new(
+            screen.origin.x + screen.size.width - WIDTH - MARGIN,
+            screen.origin.y + screen.size.height - HEIGHT - MARGIN,
+        ),
+        NSSize::new(WIDTH, HEIGHT),
+    );
+    let window: id = msg_send![class!(NSWindow), alloc];
+    let window: id = msg_send![window,
+        initWithContentRect: frame
+        styleMask: BORDERLESS
+        backing: BUFFERED
+        defer: NO
+    ];
+    let clear: id = msg_send![class!(NSColor), clearColor];
+    let _: () = msg_send![window, setReleasedWhenClosed: NO];
+    let _: () = msg_send![window, setOpaque: NO];
+    let _: () = msg_send![window, setBackgroundColor: clear];
+    let _: () = msg_send![window, setHasShadow: NO];
+    let _: () = msg_send![window, setLevel: FLOATING_LEVEL];
+    let _: () = msg_send![window, setIgnoresMouseEvents: YES];
+    let _: () = msg_send![window, setSharingType: SHARING_NONE];
+
+    let view: id = msg_send![window, contentView];
+    let _: () = msg_send![view, setWantsLayer: YES];
+    let layer: id = msg_send![view, layer];
+    let black: id = msg_send![class!(NSColor), blackColor];
+    let background: id = msg_send![black, colorWithAlphaComponent: 0.6f64];
+    let background: id = msg_send![background, CGColor];
+    let _: () = msg_send![layer, setBackgroundColor: background];
+    let _: () = msg_send![layer, setCornerRadius: HEIGHT / 2.0];
+
+    let text_y = (HEIGHT - FONT_SIZE * 1.4) / 2.0;
+    let red: id = msg_send![class!(NSColor), systemRedColor];
+    let dot = label(
+        "●",
+        NSRect::new(NSPoint::new(4.0, text_y), NSSize::new(DOT_WIDTH, FONT_SIZE * 1.4)),
+        red,
+    );
+    let white: id = msg_send![class!(NSColor), whiteColor];
+    let time = label(
+        &time_format::clock(Duration::ZERO),
+        NSRect::new(
+            NSPoint::new(DOT_WIDTH, text_y),
+            NSSize::new(WIDTH - DOT_WIDTH - 8.0, FONT_SIZE * 1.4),
+        ),
+        white,
+    );
+    let _: () = msg_send![view, addSubview: dot];
+    let _: () = msg_send![view, addSubview: time];
+    let _: () = msg_send![window, orderFrontRegardless];
+
+    WINDOW.with(|cell| cell.set(window));
+    SHOWN_ON.with(|cell| cell.set(display_id));
+    DOT_LABEL.with(|cell| cell.set(dot));
+    TIME_LABEL.with(|cell| cell.set(time));
+}
+
+fn hide() {
+    TIME_LABEL.with(|cell| cell.set(nil));
+    DOT_LABEL.with(|cell| cell.set(nil));
+    WINDOW.with(|cell| {
+        let window = cell.replace(nil);
+        if window != nil {
+            unsafe {
+                let _: () = msg_send![window, close];
+                let _: () = msg_send![window, release];
+            }
+        }
+    });
+}
+
+unsafe fn update(elapsed: Duration) {
+    let time = TIME_LABEL.with(Cell::get);
+    let ns_text = NSString::alloc(nil).init_str(&time_format::clock(elapsed));
+    let _: () = msg_send![time, setStringValue: ns_text];
+    let _: () = msg_send![ns_text, release];
+
+    // Fade between fully lit and dim, and back, once per period
+    let phase = (elapsed.as_secs_f64() / PULSE_PERIOD.as_secs_f64()).fract();
+    let alpha = 0.25 + 0.75 * (0.5 + 0.5 * (phase * std::f64::consts::TAU).cos());
+    let dot = DOT_LABEL.with(Cell::get);
+    let _: () = msg_send![dot, setAlphaValue: alpha];
+}
+
+/// Post a Notification Center notification; failures are only logged
+fn notify(title: &str, message: &str) {
+    let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+    let script = format!(
+        "display notification \"{}\" with title \"{}\"",
+        escape(message),
+        escape(title)
+    );
+    if let Err(e) = Command::new("osascript").args(["-e", &script]).spawn() {
+        eprintln!("⚠️  Failed to post notification: {}", e);
+    }
+}
+
+fn remind(elapsed: Duration) {
+    let hours = elapsed.as_secs() / REMINDER_INTERVAL.as_secs();
+    if hours <= REMINDED_HOURS.with(Cell::get) {
+        return;
+    }
+    REMINDED_HOURS.with(|cell| cell.set(hours));
+    let message = if hours == 1 {
+        "Recording for 1 hour. Stop it if the call has ended.".to_string()
+    } else {
+        format!("Recording for {hours} hours. Stop it if the call has ended.")
+    };
+    println!("⏰ Still recording after {} h", hours);
+    notify("Still recording", &message);
+    toast::show(message);
+}
+
+/// Show the timer and send reminders for a recording `recorded` long so far, and
+/// nothing when not recording
+///
+/// The timer goes on `display_id`, the display being recorded, or the main screen
+/// when that is not known. Called from the UI poll loop.
+pub fn sync(recorded: Option<Duration>, display_id: Option<u32>) {
+    let Some(elapsed) = recorded else {
+        REMINDED_HOURS.with(|cell| cell.set(0));
+        hide();
+        return;
+    };
+    let settings = current();
+    if settings.hourly_reminder {
+        remind(elapsed);
+    }
+    let mut shown = WINDOW.with(Cell::get) != nil;
+    // Moved to the other display when the recording switches to it
+    if shown && (!settings.shows_timer || SHOWN_ON.with(Cell::get) != display_id) {
+        hide();
+        shown = false;
+    }
+    if settings.shows_timer && !shown {
+        unsafe { show(display_id) };
+    }
+    if settings.shows_timer {
+        unsafe { update(elapsed) };
+    }
+}
@@ -0,0 +1,284 @@
+//! Remote control from a phone or watch on the same network
+//!
+//! For presenters who step away from the desk: with remote control turned on, a small
+//! web page on this Mac shows whether a recording is running and for how long, with
+//! buttons to pause, resume and stop it. Any phone browser can open it; nothing needs
+//! to be installed. The page's address carries a random token, which Settings shows
+//! to open on the phone (pairing), so only devices given the link can control the
+//! recording; making a new link unpairs every device that had the old one. While the
+//! server runs it is advertised over Bonjour as an `_http._tcp` service, without the
+//! token, so the Mac shows up in network browsers. Configured in `remote.json` in the
+//! data folder.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::capture::CaptureState;
+use crate::dioxus_ui::CaptureCommand;
+use crate::lan_server;
+use crate::time_format;
+use crate::user_data;
+
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Name the server is advertised under over Bonjour
+const SERVICE_NAME: &str = "Talka Recall Remote";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Secret path component of every URL
+    pub token: String,
+}
+
+impl Default for RemoteSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8090,
+            token: String::new(),
+        }
+    }
+}
+
+impl RemoteSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("remote.json")
+    }
+
+    /// Load the settings, generating the access token on first use
+    pub fn load() -> Self {
+        let mut settings: Self = std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        if settings.token.is_empty() {
+            match lan_server::new_token() {
+                Ok(token) => {
+                    settings.token = token;
+                    let _ = settings.save();
+                }
+                Err(e) => eprintln!("⚠️  {}", e),
+            }
+        }
+        settings
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize remote control settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save remote control settings: {e}"))
+    }
+
+    /// Replace the token, so links handed out before stop working
+    pub fn new_link(&mut self) -> Result<(), String> {
+        self.token = lan_server::new_token()?;
+        Ok(())
+    }
+
+    /// Address to open on the phone
+    pub fn remote_url(&self) -> String {
+        let host = lan_server::local_host_name().map_or_else(|| "localhost".to_string(), |name| format!("{name}.local"));
+        format!("http://{host}:{}/{}/", self.port, self.token)
+    }
+}
+
+/// What the remote needs from the rest of the app
+#[derive(Clone)]
+pub struct Controls {
+    pub commands: Sender<CaptureCommand>,
+    pub is_recording: Arc<AtomicBool>,
+    pub capture_state: Arc<CaptureState>,
+}
+
+struct Server {
+    settings: RemoteSettings,
+    stop: Arc<AtomicBool>,
+    /// Accept loop, which owns the listening socket
+    thread: JoinHandle<()>,
+    /// `dns-sd` advertising the server, while it runs
+    advertiser: Option<Child>,
+}
+
+impl Server {
+    /// Stop accepting, and wait until the port is free to listen on again
+    fn shut_down(mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.thread.join();
+        if let Some(mut advertiser) = self.advertiser.take() {
+            let _ = advertiser.kill();
+            let _ = advertiser.wait();
+        }
+    }
+}
+
+static SERVER: Mutex<Option<Server>> = Mutex::new(None);
+
+/// Start, restart or stop the server to match `settings`
+pub fn apply(settings: &RemoteSettings, controls: &Controls) -> Result<(), String> {
+    let mut server = SERVER.lock().unwrap();
+    if server.as_ref().is_some_and(|s| s.settings == *settings) && settings.enabled {
+        return Ok(());
+    }
+    if let Some(previous) = server.take() {
+        previous.shut_down();
+    }
+    if !settings.enabled {
+        return Ok(());
+    }
+    if settings.token.is_empty() {
+        return Err("Remote control has no access token; make a new link".to_string());
+    }
+
+    // Phones reach the Mac over the network, so this listens on every interface
+    let addr = SocketAddr::from(([0, 0, 0, 0], settings.port));
+    let listener = TcpListener::bind(addr).map_err(|e| format!("Cannot listen on port {}: {e}", settings.port))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure remote control server: {e}"))?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_clone = Arc::clone(&stop);
+    let settings_clone = settings.clone();
+    let controls = controls.clone();
+    let thread = std::thread::spawn(move || accept_loop(&listener, &settings_clone, &controls, &stop_clone));
+
+    // Advertising is a convenience; the link works without it
+    let advertiser = match Command::new("dns-sd")
+        .args(["-R", SERVICE_NAME, "_http._tcp", "local", &settings.port.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => Some(child),
+        Err(e) => {
+            eprintln!("⚠️  Failed to advertise remote control over Bonjour: {}", e);
+            None
+        }
+    };
+
+    println!("📱 Remote control on port {}", settings.port);
+    *server = Some(Server {
+        settings: settings.clone(),
+        stop,
+        thread,
+        advertiser,
+    });
+    Ok(())
+}
+
+fn accept_loop(listener: &TcpListener, settings: &RemoteSettings, controls: &Controls, stop: &Arc<AtomicBool>) {
+    let clients = lan_server::Clients::default();
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((mut stream, peer)) => {
+                let Some(slot) = clients.admit() else {
+                    let _ = stream.write_all(lan_server::BUSY_RESPONSE.as_bytes());
+                    continue;
+                };
+                let settings = settings.clone();
+                let controls = controls.clone();
+                std::thread::spawn(move || {
+                    let _slot = slot;
+                    if let Err(e) = handle_client(stream, &settings, &controls) {
+                        println!("📱 Remote control request from {} failed: {}", peer, e);
+                    }
+                });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+            Err(e) => eprintln!("⚠️  Remote control server accept failed: {}", e),
+        }
+    }
+    println!("📱 Remote control stopped");
+}
+
+/// Where the recording stands, as the page shows it
+fn status(controls: &Controls) -> (&'static str, String) {
+    if !controls.is_recording.load(Ordering::Relaxed) {
+        return ("Not recording", String::new());
+    }
+    let recorded = time_format::clock(controls.capture_state.recorded());
+    if controls.capture_state.recording_paused.load(Ordering::Relaxed) {
+        ("Paused", recorded)
+    } else {
+        ("Recording", recorded)
+    }
+}
+
+fn page(base: &str, controls: &Controls) -> String {
+    let (state, recorded) = status(controls);
+    let button = |action: &str, label: &str, color: &str, confirm: Option<&str>| {
+        let onsubmit = confirm.map_or_else(String::new, |question| format!(" onsubmit=\"return confirm('{question}')\""));
+        format!(
+            "<form method=\"post\" action=\"{base}{action}\"{onsubmit}>\
+             <button style=\"width:100%;padding:1.2rem;margin-top:1rem;font-size:1.4rem;border:0;border-radius:1rem;color:#fff;background:{color}\">{label}</button></form>"
+        )
+    };
+    let buttons = match state {
+        "Recording" => button("pause", "Pause", "#555", None) + &button("stop", "Stop", "#d33", Some("Stop the recording?")),
+        "Paused" => button("resume", "Resume", "#2a7", None) + &button("stop", "Stop", "#d33", Some("Stop the recording?")),
+        _ => String::new(),
+    };
+    format!(
+        "<!doctype html><meta name=\"viewport\" content=\"width=device-width,initial-scale=1\">\
+         <meta http-equiv=\"refresh\" content=\"2\"><title>Talka Recall</title>\
+         <body style=\"margin:0;padding:1.5rem;font-family:-apple-system,sans-serif;background:#111;color:#eee;text-align:center\">\
+         <div style=\"font-size:1.2rem;opacity:0.7\">{state}</div>\
+         <div style=\"font-size:3rem;font-variant-numeric:tabular-nums;margin:0.5rem 0\">{recorded}</div>\
+         {buttons}</body>"
+    )
+}
+
+fn handle_client(mut stream: TcpStream, settings: &RemoteSettings, controls: &Controls) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let Some((method, path)) = lan_server::read_request(&stream)? else {
+        return Ok(());
+    };
+    let (method, path) = (method.as_str(), path.as_str());
+    let base = format!("/{}/", settings.token);
+
+    if method == "GET" && path == base {
+        let page = page(&base, controls);
+        return write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nCache-Control: no-store\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{page}",
+            page.len()
+        );
+    }
+    let command = match path.strip_prefix(base.as_str()) {
+        Some("stop") => Some(CaptureCommand::StopRecording),
+        Some("pause") => Some(CaptureCommand::PauseRecording),
+        Some("resume") => Some(CaptureCommand::ResumeRecording),
+        _ => None,
+    };
+    match command {
+        Some(command) if method == "POST" => {
+            if controls.is_recording.load(Ordering::Relaxed) {
+                println!("📱 Remote control: {:?}", command);
+                let _ = controls.commands.send(command);
+            }
+            // Back to the page, where the new state shows on the next refresh
+            write!(
+                stream,
+                "HTTP/1.1 303 See Other\r\nLocation: {base}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+        }
+        // Same answer for wrong tokens and unknown paths
+        _ => write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"),
+    }
+}
@@ -0,0 +1,195 @@
+//! Server-driven configuration
+//!
+//! At startup the recorder fetches a small JSON document from the Talka API with
+//! feature flags, endpoint URLs and limits, so the backend team can adjust them
+//! without shipping a new build. The last document fetched is cached in
+//! `remote_config.json` in the data folder and used until the next fetch succeeds;
+//! without either, the built-in defaults apply.
+//!
+//! Values in `remote_config_overrides.json` next to it (same format) win over the
+//! server's, e.g. to point a development build at staging; they are read at launch
+//! and when settings are imported. Endpoint URLs from the
+//! server or an imported settings file are only accepted on `talka.ai` over HTTPS,
+//! since they receive the user's access token.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::updater;
+use crate::user_data;
+
+const CONFIG_URL: &str = "https://insights.talka.ai/recorder/config.json";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Host suffix endpoint URLs from the server must have
+const TRUSTED_HOST_SUFFIX: &str = ".talka.ai";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteConfig {
+    /// Feature flags by name; flags not listed keep their built-in default
+    pub flags: HashMap<String, bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_feed_url: Option<String>,
+    /// Largest recording the storage service accepts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_upload_bytes: Option<u64>,
+    /// Video codecs recordings may use ("h264", "hevc"); empty allows all
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_codecs: Vec<String>,
+}
+
+impl RemoteConfig {
+    fn cache_path() -> PathBuf {
+        user_data::file("remote_config.json")
+    }
+
+    pub fn overrides_path() -> PathBuf {
+        user_data::file("remote_config_overrides.json")
+    }
+
+    fn read(path: &PathBuf) -> Option<Self> {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+    }
+
+    /// Drop endpoint URLs that should not be sent the access token
+    fn without_untrusted_urls(mut self) -> Self {
+        for url in [&mut self.storage_url, &mut self.update_feed_url] {
+            if url.as_deref().is_some_and(|u| !is_trusted_url(u)) {
+                eprintln!("⚠️  Ignoring untrusted endpoint in remote config: {}", url.take().unwrap_or_default());
+            }
+        }
+        self
+    }
+
+    /// Refuse endpoint URLs that should not be sent the access token
+    pub fn check_endpoints(&self) -> Result<(), String> {
+        match [&self.storage_url, &self.update_feed_url].into_iter().flatten().find(|u| !is_trusted_url(u)) {
+            Some(url) => Err(format!("{url} is not a talka.ai HTTPS address")),
+            None => Ok(()),
+        }
+    }
+
+    /// `self` with everything set in `overrides` replaced
+    fn merged(mut self, overrides: Self) -> Self {
+        self.flags.extend(overrides.flags);
+        self.storage_url = overrides.storage_url.or(self.storage_url);
+        self.update_feed_url = overrides.update_feed_url.or(self.update_feed_url);
+        self.max_upload_bytes = overrides.max_upload_bytes.or(self.max_upload_bytes);
+        if !overrides.allowed_codecs.is_empty() {
+            self.allowed_codecs = overrides.allowed_codecs;
+        }
+        self
+    }
+}
+
+fn is_trusted_url(url: &str) -> bool {
+    let Some(rest) = url.strip_prefix("https://") else {
+        return false;
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+    host == &TRUSTED_HOST_SUFFIX[1..] || host.ends_with(TRUSTED_HOST_SUFFIX)
+}
+
+/// Server document in use (cached or fetched), before local overrides
+static SERVER: Mutex<Option<RemoteConfig>> = Mutex::new(None);
+/// [`current`], worked out on first use after a change
+static EFFECTIVE: Mutex<Option<RemoteConfig>> = Mutex::new(None);
+
+/// Effective configuration: the server's document with local overrides applied
+///
+/// Flags are read on hot paths, so the files are only read again after [`refresh`]
+/// or [`reload`].
+pub fn current() -> RemoteConfig {
+    EFFECTIVE
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| {
+            let server = SERVER
+                .lock()
+                .unwrap()
+                .get_or_insert_with(|| RemoteConfig::read(&RemoteConfig::cache_path()).unwrap_or_default())
+                .clone();
+            match RemoteConfig::read(&RemoteConfig::overrides_path()) {
+                Some(overrides) => server.merged(overrides),
+                None => server,
+            }
+        })
+        .clone()
+}
+
+/// Pick up overrides written to `remote_config_overrides.json`, e.g. by a settings import
+pub fn reload() {
+    *EFFECTIVE.lock().unwrap() = None;
+}
+
+/// Fetch the latest document and cache it; on failure the cached one stays in use
+pub async fn refresh() -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let response = client
+        .get(CONFIG_URL)
+        .query(&[("version", updater::CURRENT_VERSION)])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach remote config: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Remote config returned HTTP {}", response.status()));
+    }
+    let config: RemoteConfig = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid remote config: {e}"))?;
+    let config = config.without_untrusted_urls();
+
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize remote config: {e}"))?;
+    if let Err(e) = user_data::write_private(&RemoteConfig::cache_path(), json) {
+        eprintln!("⚠️  Failed to cache remote config: {}", e);
+    }
+    *SERVER.lock().unwrap() = Some(config);
+    reload();
+    Ok(())
+}
+
+/// Whether feature `name` is on, `default` unless the config says otherwise
+pub fn flag(name: &str, default: bool) -> bool {
+    current().flags.get(name).copied().unwrap_or(default)
+}
+
+/// `configured` when set, otherwise `default`
+fn endpoint(configured: Option<String>, default: &str) -> String {
+    configured
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| default.to_string())
+}
+
+#[cfg(feature = "macos_15_0")]
+pub fn storage_url(default: &str) -> String {
+    endpoint(current().storage_url, default)
+}
+
+pub fn update_feed_url(default: &str) -> String {
+    endpoint(current().update_feed_url, default)
+}
+
+#[cfg(feature = "macos_15_0")]
+pub fn max_upload_bytes() -> Option<u64> {
+    current().max_upload_bytes
+}
+
+#[cfg(feature = "macos_15_0")]
+/// Whether recordings may use `codec` ("h264", "hevc")
+pub fn codec_allowed(codec: &str) -> bool {
+    let allowed = current().allowed_codecs;
+    allowed.is_empty() || allowed.iter().any(|c| c.eq_ignore_ascii_case(codec))
+}
@@ -213,7 +213,7 @@ pub const SHADER_SOURCE: &str = r"
 #include <metal_stdlib>
 using namespace metal;
 struct Vertex { packed_float2 position; packed_float4 color; };
-struct Uniforms { float2 viewport_size; float2 texture_size; float time; uint pixel_format; float padding[2]; };
+struct Uniforms { float2 viewport_size; float2 texture_size; float time; uint pixel_format; float2 content_scale; };
 struct VertexOut { float4 position [[position]]; float4 color; };
 struct TexturedVertexOut { float4 position [[position]]; float2 texcoord; };
 vertex VertexOut vertex_colored(const device Vertex* vertices [[buffer(0)]], constant Uniforms& uniforms [[buffer(1)]], uint vid [[vertex_id]]) {
@@ -222,8 +222,8 @@ vertex VertexOut vertex_colored(const device Vertex* vertices [[buffer(0)]], con
 }
 fragment float4 fragment_colored(VertexOut in [[stage_in]]) { return in.color; }
 vertex TexturedVertexOut vertex_fullscreen(uint vid [[vertex_id]], constant Uniforms& uniforms [[buffer(0)]]) {
-    TexturedVertexOut out; float va = uniforms.viewport_size.x / uniforms.viewport_size.y; float ta = uniforms.texture_size.x / uniforms.texture_size.y;
-    float sx = ta > va ? 1.0 : ta / va; float sy = ta > va ? va / ta : 1.0;
+    // Letterbox/pillarbox scale is computed on the CPU (see vertex::letterbox_rect)
+    TexturedVertexOut out; float sx = uniforms.content_scale.x; float sy = uniforms.content_scale.y;
     float2 positions[4] = { float2(-sx, -sy), float2(sx, -sy), float2(-sx, sy), float2(sx, sy) };
     float2 texcoords[4] = { float2(0.0, 1.0), float2(1.0, 1.0), float2(0.0, 0.0), float2(1.0, 0.0) };
     out.position = float4(positions[vid], 0.0, 1.0); out.texcoord = texcoords[vid]; return out;
@@ -0,0 +1,71 @@
+//! Screen Recording permission, checked before it is needed
+//!
+//! Without the permission the content picker opens but then fails without saying
+//! why, and a permission revoked while the app was closed looks no different. So at
+//! startup the app asks ScreenCaptureKit for the shareable content, which fails when
+//! the permission is missing, and the main window blocks everything else with steps
+//! to fix it and a button to check again. `CGPreflightScreenCaptureAccess` alone is
+//! not enough: it can still report access that has since been revoked.
+
+use screencapturekit::shareable_content::SCShareableContent;
+use std::process::Command;
+use std::sync::Mutex;
+
+use crate::mock_capture;
+
+/// The Screen Recording pane of System Settings
+const SETTINGS_URL: &str = "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScreenAccess {
+    /// Not checked yet, or being checked
+    Checking,
+    Granted,
+    /// Missing or revoked, with what ScreenCaptureKit said
+    Denied(String),
+}
+
+static STATE: Mutex<ScreenAccess> = Mutex::new(ScreenAccess::Checking);
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+    fn CGRequestScreenCaptureAccess() -> bool;
+}
+
+pub fn state() -> ScreenAccess {
+    STATE.lock().unwrap().clone()
+}
+
+/// Find out whether capture is allowed; blocks for as long as ScreenCaptureKit takes
+pub fn check() -> ScreenAccess {
+    *STATE.lock().unwrap() = ScreenAccess::Checking;
+    let access = if mock_capture::enabled() {
+        ScreenAccess::Granted
+    } else if !unsafe { CGPreflightScreenCaptureAccess() } {
+        ScreenAccess::Denied("Screen Recording is not allowed for this app".to_string())
+    } else {
+        match SCShareableContent::get() {
+            Ok(_) => ScreenAccess::Granted,
+            Err(e) => ScreenAccess::Denied(e.to_string()),
+        }
+    };
+    match access {
+        ScreenAccess::Granted => println!("✅ Screen Recording permission granted"),
+        ScreenAccess::Denied(ref reason) => eprintln!("🚫 Screen Recording unavailable: {}", reason),
+        ScreenAccess::Checking => {}
+    }
+    *STATE.lock().unwrap() = access.clone();
+    access
+}
+
+/// Ask macOS for the permission and open its pane in System Settings
+///
+/// macOS only shows its own prompt the first time; after that the user has to
+/// switch the app on in System Settings, which may require reopening the app.
+pub fn request() {
+    unsafe { CGRequestScreenCaptureAccess() };
+    if let Err(e) = Command::new("open").arg(SETTINGS_URL).spawn() {
+        eprintln!("⚠️  Failed to open System Settings: {}", e);
+    }
+}
@@ -0,0 +1,41 @@
+//! Finding the screen a capture is on
+//!
+//! Windows the app puts over a recording (drawings, the webcam bubble, the recording
+//! timer) belong on the display being recorded, which is not necessarily the main
+//! screen. AppKit objects must only be touched from the main thread.
+
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSString;
+use objc::{class, msg_send, sel, sel_impl};
+
+/// The `NSScreen` showing `display_id`, or the main screen
+///
+/// # Safety
+///
+/// Must be called on the main thread.
+pub unsafe fn screen_for(display_id: Option<u32>) -> id {
+    let main_screen: id = msg_send![class!(NSScreen), mainScreen];
+    let Some(display_id) = display_id else {
+        return main_screen;
+    };
+    let screens: id = msg_send![class!(NSScreen), screens];
+    let count: usize = msg_send![screens, count];
+    let key = NSString::alloc(nil).init_str("NSScreenNumber");
+    let mut found = nil;
+    for i in 0..count {
+        let screen: id = msg_send![screens, objectAtIndex: i];
+        let description: id = msg_send![screen, deviceDescription];
+        let number: id = msg_send![description, objectForKey: key];
+        let number: u32 = msg_send![number, unsignedIntValue];
+        if number == display_id {
+            found = screen;
+            break;
+        }
+    }
+    let _: () = msg_send![key, release];
+    if found == nil {
+        main_screen
+    } else {
+        found
+    }
+}
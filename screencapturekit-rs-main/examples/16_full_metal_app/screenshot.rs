@@ -4,20 +4,22 @@ use screencapturekit::prelude::*;
 use screencapturekit::screenshot_manager::SCScreenshotManager;
 use screencapturekit::stream::content_filter::SCContentFilter;
 
+use crate::time_format;
+use crate::user_data;
+
 /// Take a screenshot using the best available API
 /// - macOS 26.0+: Uses `SCScreenshotConfiguration` with native file saving
 /// - macOS 14.0+: Uses `SCStreamConfiguration` and `CGImage::save_png()`
+///
+/// Returns the path of the saved PNG on success
 pub fn take_screenshot(
     filter: &SCContentFilter,
     capture_size: (u32, u32),
     stream_config: &SCStreamConfiguration,
-) {
+) -> Option<String> {
     println!("📸 Taking screenshot...");
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-    let path = format!("/tmp/screenshot_{timestamp}.png");
+    let stamp = time_format::file_stamp(chrono::Utc::now());
+    let path = format!("{}/screenshot_{stamp}.png", user_data::screenshots_dir().display());
 
     #[cfg(feature = "macos_26_0")]
     {
@@ -35,6 +37,7 @@ pub fn take_screenshot(
                 if let Some(url) = output.file_url() {
                     println!("✅ Screenshot saved to {url}");
                     let _ = std::process::Command::new("open").arg(&url).spawn();
+                    return Some(path);
                 } else if let Some(image) = output.sdr_image() {
                     println!(
                         "✅ Screenshot captured: {}x{}",
@@ -45,6 +48,7 @@ pub fn take_screenshot(
                         Ok(()) => {
                             println!("📁 Saved to {path}");
                             let _ = std::process::Command::new("open").arg(&path).spawn();
+                            return Some(path);
                         }
                         Err(e) => eprintln!("❌ Failed to save: {e:?}"),
                     }
@@ -52,6 +56,7 @@ pub fn take_screenshot(
             }
             Err(e) => eprintln!("❌ Screenshot failed: {e:?}"),
         }
+        None
     }
 
     #[cfg(not(feature = "macos_26_0"))]
@@ -73,11 +78,18 @@ pub fn take_screenshot(
                     Ok(()) => {
                         println!("📁 Saved to {}", path);
                         let _ = std::process::Command::new("open").arg(&path).spawn();
+                        Some(path)
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Failed to save: {:?}", e);
+                        None
                     }
-                    Err(e) => eprintln!("❌ Failed to save: {:?}", e),
                 }
             }
-            Err(e) => eprintln!("❌ Screenshot failed: {:?}", e),
+            Err(e) => {
+                eprintln!("❌ Screenshot failed: {:?}", e);
+                None
+            }
         }
     }
 }
@@ -0,0 +1,179 @@
+//! Session state kept across restarts
+//!
+//! What the user was in the middle of (the open view, whether the window was pinned
+//! on top, the source last captured, a recording still waiting to be uploaded, and
+//! tags, notes or an upload note being typed) is written to `session.json` in the
+//! data folder shortly after it changes, and once more when the app quits, so
+//! quitting by accident, a crash or an update restart loses next to nothing; waiting
+//! for changes to settle keeps typing a draft from writing the file on every
+//! keystroke. On the next launch the view, the pin and drafts come back as they were;
+//! the source is only offered again, since capturing starts with the user choosing to.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::sidecar::{RecordingSidecar, SidecarUploadState};
+use crate::user_data;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenView {
+    #[default]
+    Main,
+    Calendar,
+    Library,
+    Settings,
+}
+
+/// Source captured when the app last ran
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceHint {
+    pub name: String,
+    /// Set for displays, which can be captured again without the picker
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_id: Option<u32>,
+}
+
+/// Recording that was in the trim editor or uploading
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingUpload {
+    pub path: String,
+    /// Note being typed in the trim editor
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+/// Unsaved tags and notes from the library
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataDraft {
+    pub path: String,
+    pub tags: String,
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionState {
+    pub view: OpenView,
+    /// Main window kept above other windows, full-screen apps included
+    pub pinned: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceHint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_upload: Option<PendingUpload>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_draft: Option<MetadataDraft>,
+}
+
+impl SessionState {
+    fn path() -> PathBuf {
+        user_data::file("session.json")
+    }
+
+    fn load() -> Self {
+        let mut state: Self = std::fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        // Recordings from before the data folder were in /tmp
+        if let Some(pending) = &mut state.pending_upload {
+            pending.path = user_data::relocated(&pending.path);
+        }
+        if let Some(draft) = &mut state.metadata_draft {
+            draft.path = user_data::relocated(&draft.path);
+        }
+        state
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize session: {e}"))?;
+        user_data::write_private(&Self::path(), json).map_err(|e| format!("Failed to save session: {e}"))
+    }
+}
+
+/// How long changes settle before the session is written
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Session as last changed, loaded on first use
+static CURRENT: Mutex<Option<SessionState>> = Mutex::new(None);
+/// Changed since last written
+static DIRTY: AtomicBool = AtomicBool::new(false);
+static SAVES: OnceLock<Sender<()>> = OnceLock::new();
+/// Set while the pending upload is reopened in the background
+static RESTORING: AtomicBool = AtomicBool::new(false);
+
+/// Write the session once changes have settled
+fn schedule_save() {
+    let saves = SAVES.get_or_init(|| {
+        let (tx, rx) = channel::<()>();
+        std::thread::spawn(move || {
+            while rx.recv().is_ok() {
+                // Changes made within the delay are written together
+                while rx.recv_timeout(SAVE_DELAY).is_ok() {}
+                flush();
+            }
+        });
+        tx
+    });
+    let _ = saves.send(());
+}
+
+/// Write any unsaved changes now; called when the app quits
+pub fn flush() {
+    if !DIRTY.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    let state = CURRENT.lock().unwrap().clone();
+    if let Some(Err(e)) = state.map(|state| state.save()) {
+        eprintln!("⚠️  {}", e);
+    }
+}
+
+pub fn current() -> SessionState {
+    CURRENT.lock().unwrap().get_or_insert_with(SessionState::load).clone()
+}
+
+/// Change the session, saving it shortly when anything actually changed
+pub fn update(change: impl FnOnce(&mut SessionState)) {
+    let mut current = CURRENT.lock().unwrap();
+    let state = current.get_or_insert_with(SessionState::load);
+    let before = state.clone();
+    change(state);
+    if *state != before {
+        DIRTY.store(true, Ordering::SeqCst);
+        drop(current);
+        schedule_save();
+    }
+}
+
+/// Mark the pending upload as being reopened, so the trim editor being empty
+/// meanwhile does not clear it from the session
+pub fn set_restoring(restoring: bool) {
+    RESTORING.store(restoring, Ordering::SeqCst);
+}
+
+pub fn is_restoring() -> bool {
+    RESTORING.load(Ordering::SeqCst)
+}
+
+/// Recording from the last session that still needs uploading
+///
+/// Recordings that were uploaded, queued for later or found damaged since are left
+/// to the library and the upload queue.
+pub fn pending_upload() -> Option<PendingUpload> {
+    let pending = current().pending_upload?;
+    if !std::path::Path::new(&pending.path).exists() {
+        return None;
+    }
+    let state = RecordingSidecar::load(&pending.path).map(|s| s.upload_state).unwrap_or_default();
+    matches!(
+        state,
+        SidecarUploadState::Pending | SidecarUploadState::Uploading | SidecarUploadState::Failed
+    )
+    .then_some(pending)
+}
@@ -0,0 +1,257 @@
+//! Settings import and export
+//!
+//! Everything the user has set up (update channel, capture options, preview GPU,
+//! recording profiles, crop, battery, Focus shortcuts, recording timer and reminders,
+//! screenshot copying, drawing, highlights, backup folder, live preview, webhook, mobile data uploads, cleanup policy and the endpoint overrides)
+//! can be written to one `.json` file and read back on another Mac, so IT can
+//! pre-provision new machines and users can move between laptops. Sign-in tokens, the
+//! access tokens in the live preview and remote control links, the library index and
+//! the session are never part of it.
+//! The bookmark hotkey is built in, so there is nothing to carry over for it.
+//!
+//! Available from Settings, and as `16_full_metal_app export-settings <file>` and
+//! `16_full_metal_app import-settings <file>` from a terminal or a provisioning
+//! script. Importing only replaces the settings the file has; each is checked
+//! before anything is written, so a bad file changes nothing.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::annotations;
+use crate::audio_tracks;
+use crate::backup;
+use crate::calendar_privacy;
+use crate::capture_settings;
+use crate::clipboard;
+#[cfg(feature = "macos_15_0")]
+use crate::cleanup;
+use crate::crop;
+use crate::focus;
+use crate::gpu;
+use crate::highlights;
+use crate::live_preview;
+#[cfg(feature = "macos_15_0")]
+use crate::network;
+use crate::power;
+#[cfg(feature = "macos_15_0")]
+use crate::profiles;
+use crate::recording_timer;
+use crate::remote;
+use crate::remote_config;
+use crate::standby;
+use crate::updater;
+use crate::user_data;
+use crate::webhook;
+
+/// Identifies a settings file, so other JSON is not imported by mistake
+const FORMAT: &str = "talka-recorder-settings";
+const VERSION: u32 = 1;
+
+/// Checks a setting from a bundle and returns it as it is stored
+type Normalize = fn(serde_json::Value) -> Result<String, String>;
+
+/// Files in the data folder that are exported, with the settings each holds
+const SETTINGS: &[(&str, Normalize)] = &[
+    ("capture.json", normalize::<capture_settings::CaptureSettings>),
+    ("updates.json", normalize::<updater::UpdateSettings>),
+    ("gpu.json", normalize::<gpu::GpuSettings>),
+    #[cfg(feature = "macos_15_0")]
+    ("profiles.json", normalize::<profiles::ProfileSettings>),
+    ("crop.json", normalize::<crop::CropSettings>),
+    ("battery.json", normalize::<power::BatterySettings>),
+    ("focus.json", normalize::<focus::FocusSettings>),
+    ("recording_timer.json", normalize::<recording_timer::TimerSettings>),
+    ("standby.json", normalize::<standby::StandbySettings>),
+    ("clipboard.json", normalize::<clipboard::ClipboardSettings>),
+    ("audio_tracks.json", normalize::<audio_tracks::AudioTrackSettings>),
+    ("calendar_privacy.json", normalize::<calendar_privacy::CalendarPrivacy>),
+    ("annotations.json", normalize::<annotations::AnnotationSettings>),
+    ("highlights.json", normalize::<highlights::HighlightSettings>),
+    ("backup.json", normalize::<backup::BackupSettings>),
+    ("live_preview.json", normalize::<live_preview::LivePreviewSettings>),
+    ("remote.json", normalize::<remote::RemoteSettings>),
+    #[cfg(feature = "macos_15_0")]
+    ("network.json", normalize::<network::NetworkSettings>),
+    ("webhook.json", normalize::<webhook::WebhookSettings>),
+    #[cfg(feature = "macos_15_0")]
+    ("cleanup.json", normalize::<cleanup::CleanupPolicy>),
+    ("remote_config_overrides.json", normalize_overrides),
+];
+
+/// Fields that stay on this Mac: left out of exports, and kept as they are on import
+///
+/// The install ID is among them so every Mac set up from one file still gets its
+/// own place in staged rollouts.
+const SECRETS: &[(&str, &str)] = &[
+    ("live_preview.json", "token"),
+    ("remote.json", "token"),
+    ("updates.json", "install_id"),
+];
+
+fn secrets_in(name: &str) -> impl Iterator<Item = &'static str> + '_ {
+    SECRETS.iter().filter(move |(file, _)| *file == name).map(|(_, field)| *field)
+}
+
+/// Set the secret fields of setting `name` to their values on this Mac
+fn keep_local_secrets(name: &str, value: &mut serde_json::Value) {
+    let local: Option<serde_json::Value> = std::fs::read_to_string(user_data::file(name))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok());
+    let Some(fields) = value.as_object_mut() else {
+        return;
+    };
+    for field in secrets_in(name) {
+        match local.as_ref().and_then(|l| l.get(field)) {
+            Some(kept) => fields.insert(field.to_string(), kept.clone()),
+            None => fields.remove(field),
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsBundle {
+    pub format: String,
+    pub version: u32,
+    /// App version that wrote the file
+    pub app_version: String,
+    /// Settings by file name; settings never changed from their defaults are left out
+    pub settings: BTreeMap<String, serde_json::Value>,
+}
+
+fn normalize<T: Serialize + DeserializeOwned>(value: serde_json::Value) -> Result<String, String> {
+    let settings: T = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())
+}
+
+/// Endpoint overrides, which a settings file may not point outside `talka.ai`
+fn normalize_overrides(value: serde_json::Value) -> Result<String, String> {
+    let overrides: remote_config::RemoteConfig = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    overrides.check_endpoints()?;
+    serde_json::to_string_pretty(&overrides).map_err(|e| e.to_string())
+}
+
+/// Collect the current settings into a bundle
+pub fn collect() -> SettingsBundle {
+    let mut settings = BTreeMap::new();
+    for (name, _) in SETTINGS.iter().copied() {
+        let Ok(json) = std::fs::read_to_string(user_data::file(name)) else {
+            continue;
+        };
+        match serde_json::from_str::<serde_json::Value>(&json) {
+            Ok(mut value) => {
+                if let Some(fields) = value.as_object_mut() {
+                    for field in secrets_in(name) {
+                        fields.remove(field);
+                    }
+                }
+                settings.insert(name.to_string(), value);
+            }
+            Err(e) => eprintln!("⚠️  Leaving {} out of the export: {}", name, e),
+        }
+    }
+    SettingsBundle {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        app_version: updater::CURRENT_VERSION.to_string(),
+        settings,
+    }
+}
+
+/// Write the current settings to `path`, returning how many were written
+pub fn export(path: &Path) -> Result<usize, String> {
+    let bundle = collect();
+    let json =
+        serde_json::to_string_pretty(&bundle).map_err(|e| format!("Failed to serialize settings: {e}"))?;
+    user_data::write_private(path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    println!("📤 Exported {} settings to {}", bundle.settings.len(), path.display());
+    Ok(bundle.settings.len())
+}
+
+/// Replace the settings in the bundle at `path`, returning how many were imported
+pub fn import(path: &Path) -> Result<usize, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let bundle: SettingsBundle =
+        serde_json::from_str(&json).map_err(|_| "This is not a Talka Recorder settings file".to_string())?;
+    if bundle.format != FORMAT {
+        return Err("This is not a Talka Recorder settings file".to_string());
+    }
+    if bundle.version > VERSION {
+        return Err(format!(
+            "This settings file is from a newer version ({}); update Talka Recorder first",
+            bundle.app_version
+        ));
+    }
+
+    let mut files = Vec::new();
+    for (name, mut value) in bundle.settings {
+        let Some((_, normalize)) = SETTINGS.iter().find(|(known, _)| *known == name) else {
+            eprintln!("⚠️  Skipping unknown setting {} in {}", name, path.display());
+            continue;
+        };
+        // Links paired on this Mac keep working, and its install ID stays its own,
+        // whatever the file holds
+        keep_local_secrets(&name, &mut value);
+        let contents = normalize(value).map_err(|e| format!("Invalid {name} in settings file: {e}"))?;
+        files.push((name, contents));
+    }
+    for (name, contents) in &files {
+        user_data::write_private(&user_data::file(name), contents)
+            .map_err(|e| format!("Failed to save {name}: {e}"))?;
+    }
+    gpu::reload();
+    recording_timer::reload();
+    standby::reload();
+    calendar_privacy::reload();
+    annotations::reload();
+    remote_config::reload();
+    println!("📥 Imported {} settings from {}", files.len(), path.display());
+    Ok(files.len())
+}
+
+/// Ask where to save an export; `None` when cancelled
+pub fn choose_export_file() -> Result<Option<String>, String> {
+    choose(
+        "POSIX path of (choose file name with prompt \"Export settings to\" default name \"Talka Recorder Settings.json\")",
+    )
+}
+
+/// Ask for a settings file to import; `None` when cancelled
+pub fn choose_import_file() -> Result<Option<String>, String> {
+    choose("POSIX path of (choose file with prompt \"Choose a settings file to import\" of type {\"public.json\"})")
+}
+
+fn choose(script: &str) -> Result<Option<String>, String> {
+    let output = Command::new("osascript")
+        .args(["-e", script])
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {e}"))?;
+    if !output.status.success() {
+        // The picker reports cancellation as an error
+        return Ok(None);
+    }
+    let file = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok((!file.is_empty()).then_some(file))
+}
+
+/// `export-settings <file>` and `import-settings <file>` from the command line
+pub fn run_cli(command: &str, file: Option<&str>) -> i32 {
+    let Some(file) = file else {
+        eprintln!("Usage: 16_full_metal_app {} <file>", command);
+        return 2;
+    };
+    let result = if command == "export-settings" {
+        export(Path::new(file))
+    } else {
+        import(Path::new(file))
+    };
+    match result {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            1
+        }
+    }
+}
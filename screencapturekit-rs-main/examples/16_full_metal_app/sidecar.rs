@@ -0,0 +1,219 @@
+//! Metadata sidecar stored next to each recording
+//!
+//! `recording_123.mp4` is described by `recording_123.json` beside it. The recording
+//! module creates it, the upload module records progress and the resulting file ID, and
+//! recovery and library features read it back. Every save is mirrored into the library
+//! index.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::archive::ArchivePreset;
+use crate::attachments::Attachment;
+use crate::audio_tracks::TrackLabel;
+use crate::auth::MeetingEvent;
+use crate::user_data;
+
+/// Upload progress as persisted in the sidecar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SidecarUploadState {
+    #[default]
+    Pending,
+    Uploading,
+    /// Deferred until the connection is fast enough
+    Queued,
+    Uploaded,
+    Failed,
+    /// Failed integrity checks after recording; kept locally and never uploaded
+    Damaged,
+}
+
+/// Calendar meeting a recording was made during
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarEventInfo {
+    pub event_id: String,
+    pub title: String,
+    pub start_time: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub participants: Vec<String>,
+}
+
+impl From<&MeetingEvent> for CalendarEventInfo {
+    fn from(event: &MeetingEvent) -> Self {
+        Self {
+            event_id: event.event_id.clone(),
+            title: event.event_summary.clone(),
+            start_time: event.meeting_start_time.clone(),
+            participants: event.meeting_participants.clone(),
+        }
+    }
+}
+
+/// Where a recording was last mirrored to by folder-sync backup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupCopy {
+    pub path: String,
+    /// Size of the recording when it was copied; a different size means it changed
+    pub size_bytes: u64,
+    pub backed_up_at: String,
+}
+
+/// A line of the transcript and where in the recording it is spoken
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptCue {
+    pub start_seconds: f64,
+    pub text: String,
+}
+
+/// Position of a file within a segmented recording
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentInfo {
+    /// `recording_<timestamp>` shared by all parts
+    pub group: String,
+    /// 1-based part number
+    pub index: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordingSidecar {
+    pub source_name: String,
+    pub width: u32,
+    pub height: u32,
+    /// RFC 3339 timestamp of when recording started
+    pub recorded_at: String,
+    #[serde(default)]
+    pub duration_seconds: Option<f64>,
+    #[serde(default)]
+    pub calendar_event: Option<CalendarEventInfo>,
+    /// Offsets into the recording, in seconds
+    #[serde(default)]
+    pub bookmarks: Vec<f64>,
+    #[serde(default)]
+    pub upload_state: SidecarUploadState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload_error: Option<String>,
+    #[serde(default)]
+    pub file_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segment: Option<SegmentInfo>,
+    /// User-assigned tags from the library
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Free-form notes from the library
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+    /// Calling service detected when recording started, e.g. "Zoom"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Set once the recording has been re-encoded for archival storage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_preset: Option<ArchivePreset>,
+    /// Transcript text, once one has been produced for the recording
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<String>,
+    /// The transcript line by line with timestamps, so search results open where
+    /// the words are spoken
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transcript_cues: Vec<TranscriptCue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<BackupCopy>,
+    /// Notes and files sent along with the upload
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// Attachments that could not be sent after the recording was uploaded, to retry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unsent_attachments: Vec<Attachment>,
+    /// Recording profile the recording was made with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Meeting title chosen in the trim editor, uploaded instead of the file name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Labels of the labeled microphone tracks, in the order they are in the file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub audio_tracks: Vec<TrackLabel>,
+    /// Recording this file was trimmed or post-processed from; uploading this file
+    /// counts as uploading that one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derived_from: Option<String>,
+}
+
+impl RecordingSidecar {
+    pub fn new(
+        source_name: String,
+        (width, height): (u32, u32),
+        calendar_event: Option<&MeetingEvent>,
+    ) -> Self {
+        Self {
+            source_name,
+            width,
+            height,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            calendar_event: calendar_event.map(CalendarEventInfo::from),
+            ..Self::default()
+        }
+    }
+
+    /// Sidecar location for a recording file
+    pub fn path_for(recording_path: &str) -> PathBuf {
+        Path::new(recording_path).with_extension("json")
+    }
+
+    /// Read the sidecar for a recording, if one exists and parses
+    pub fn load(recording_path: &str) -> Option<Self> {
+        let contents = std::fs::read_to_string(Self::path_for(recording_path)).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Write the sidecar for a recording
+    ///
+    /// Written to a temporary file first so a crash never leaves a truncated sidecar.
+    pub fn save(&self, recording_path: &str) -> Result<(), String> {
+        let path = Self::path_for(recording_path);
+        let tmp_path = path.with_extension("json.tmp");
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize sidecar: {e}"))?;
+        user_data::write_private(&tmp_path, json)
+            .and_then(|()| std::fs::rename(&tmp_path, &path))
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+
+        // The sidecar is written either way; a stale index is fixed by the next refresh
+        if let Err(e) = crate::library_index::upsert(recording_path, self) {
+            eprintln!("⚠️  {e}");
+        }
+        Ok(())
+    }
+
+    /// Load, modify and save the sidecar for a recording
+    pub fn update(recording_path: &str, f: impl FnOnce(&mut Self)) -> Result<(), String> {
+        let mut sidecar = Self::load(recording_path)
+            .ok_or_else(|| format!("No sidecar for {recording_path}"))?;
+        f(&mut sidecar);
+        sidecar.save(recording_path)
+    }
+}
+
+/// Meeting that is most likely being recorded right now
+///
+/// Picks the event starting closest to now, from two hours ago up to fifteen minutes
+/// ahead (people often start recording just before a meeting begins).
+pub fn current_meeting(events: &[MeetingEvent]) -> Option<&MeetingEvent> {
+    use chrono::{DateTime, Duration as ChronoDuration, Utc};
+
+    let now = Utc::now();
+    let earliest = now - ChronoDuration::hours(2);
+    let latest = now + ChronoDuration::minutes(15);
+
+    events
+        .iter()
+        .filter_map(|event| {
+            let start = DateTime::parse_from_rfc3339(&event.meeting_start_time)
+                .ok()?
+                .with_timezone(&Utc);
+            (start >= earliest && start <= latest)
+                .then(|| ((start - now).num_seconds().abs(), event))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, event)| event)
+}
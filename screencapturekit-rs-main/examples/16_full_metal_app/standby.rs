@@ -0,0 +1,118 @@
+//! Recording standby: the checks and stream settings Start Recording needs, done
+//! ahead of time
+//!
+//! Once a source is picked the stream is already running, so starting a recording
+//! adds a recording output to it. What used to take seconds is the work around that:
+//! the pre-flight checks (which listen to the microphone for a second and may renew
+//! the sign-in), applying the recording profile's stream settings and looking up the
+//! display to hide notification banners from. In standby the capture backend applies
+//! the profile and the filter as soon as a source is picked, and the main window runs
+//! the checks once and then keeps the cheap ones (microphone and sign-in) fresh in the
+//! background, so pressing Start skips that work; the recording output itself is
+//! still created and added at Start. Nothing is written to disk before Start, and when
+//! standby ends without a recording the stream goes back to the source's own settings
+//! and filter. Off unless turned on in `standby.json` in the data folder.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::auth;
+use crate::capture::CaptureState;
+use crate::doctor::Report;
+use crate::preflight;
+use crate::user_data;
+
+/// How often the cheap pre-flight checks are repeated while waiting to record
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(20);
+/// How long a pre-flight result stands in for running the checks again
+const FRESH_FOR: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StandbySettings {
+    /// Prepare the checks and stream settings as soon as a source is picked
+    pub enabled: bool,
+}
+
+impl Default for StandbySettings {
+    fn default() -> Self {
+        Self { enabled: false }
+    }
+}
+
+/// Settings in effect, loaded on first use
+static CURRENT: Mutex<Option<StandbySettings>> = Mutex::new(None);
+/// Latest pre-flight result, when it was taken, and the microphone's sample count then
+static CHECKS: Mutex<Option<(Instant, Report, u64)>> = Mutex::new(None);
+/// The backend has applied the profile and filter for the picked source
+static WARM: AtomicBool = AtomicBool::new(false);
+
+impl StandbySettings {
+    fn config_path() -> PathBuf {
+        user_data::file("standby.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save, and use these settings from now on
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize standby settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save standby settings: {e}"))?;
+        *CURRENT.lock().unwrap() = Some(self.clone());
+        Ok(())
+    }
+}
+
+pub fn current() -> StandbySettings {
+    CURRENT.lock().unwrap().get_or_insert_with(StandbySettings::load).clone()
+}
+
+/// Pick up settings written to `standby.json` by something other than
+/// [`StandbySettings::save`]
+pub fn reload() {
+    *CURRENT.lock().unwrap() = Some(StandbySettings::load());
+}
+
+/// Keep the pre-flight result for Start Recording up to date: all checks the first
+/// time after standby starts, and afterwards only those that cost nothing to repeat
+pub async fn refresh_checks(capture_state: Arc<CaptureState>, auth_tokens: Arc<Mutex<Option<auth::AuthTokens>>>) {
+    let previous = CHECKS.lock().unwrap().as_ref().map(|(_, report, mic_samples)| (report.clone(), *mic_samples));
+    let report = match previous {
+        Some((report, mic_samples)) => preflight::recheck(&report, mic_samples, &capture_state, &auth_tokens).await,
+        None => preflight::run(Arc::clone(&capture_state), auth_tokens).await,
+    };
+    let mic_samples = capture_state.mic_waveform.lock().unwrap().sample_count();
+    *CHECKS.lock().unwrap() = Some((Instant::now(), report, mic_samples));
+}
+
+/// The latest pre-flight result, unless it is too old to go by
+pub fn fresh_checks() -> Option<Report> {
+    CHECKS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|(checked, _, _)| checked.elapsed() < FRESH_FOR)
+        .map(|(_, report, _)| report.clone())
+}
+
+pub fn set_warm(warm: bool) {
+    WARM.store(warm, Ordering::Relaxed);
+    if !warm {
+        *CHECKS.lock().unwrap() = None;
+    }
+}
+
+/// Whether pressing Start Recording can skip the checks and stream changes
+pub fn is_warm() -> bool {
+    WARM.load(Ordering::Relaxed) && fresh_checks().is_some_and(|report| report.problems().next().is_none())
+}
@@ -0,0 +1,452 @@
+//! Typed client for the Talka backend
+//!
+//! Every authenticated call the recorder makes (the user profile, meeting events,
+//! the naming convention, storage file creation, call metadata and attachments)
+//! goes through [`TalkaClient`], which carries the access token and one shared HTTP
+//! connection pool. Requests and responses are plain serde structs, so new
+//! endpoints are added here as another method rather than as another hand-built
+//! request elsewhere.
+//!
+//! The OAuth device flow in `auth.rs` talks to the identity provider, not the
+//! backend, and is not part of this client. The module only depends on serde,
+//! reqwest and chrono so the standalone auth test binary can include it too.
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const USERINFO_URL: &str = "https://login.talka.ai/userinfo";
+const MEETING_EVENTS_URL: &str = "https://meeting-bot-scheduler.talka.ai/meeting-events";
+const STORAGE_URL: &str = "https://storage.talka.ai";
+
+#[derive(Debug)]
+pub enum ApiError {
+    Network(String),
+    /// The server answered with a non-success status
+    Status { status: u16, body: String },
+    InvalidResponse(String),
+    /// HTTP 429, with how long the server asked to wait when it said so
+    RateLimited { retry_after: Option<Duration> },
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Network(msg) => write!(f, "{msg}"),
+            Self::Status { status, body } => write!(f, "HTTP {status}: {body}"),
+            Self::InvalidResponse(msg) => write!(f, "{msg}"),
+            Self::RateLimited { .. } => write!(f, "Rate limited by the server"),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Where each service lives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoints {
+    pub userinfo: String,
+    pub meeting_events: String,
+    /// Base URL of the storage service, without a trailing slash
+    pub storage: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            userinfo: USERINFO_URL.to_string(),
+            meeting_events: MEETING_EVENTS_URL.to_string(),
+            storage: STORAGE_URL.to_string(),
+        }
+    }
+}
+
+/// User profile information from Auth0
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UserProfile {
+    pub sub: String, // User ID
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub picture: String,
+    #[serde(default)]
+    pub nickname: String,
+}
+
+impl UserProfile {
+    /// Get user initials for avatar
+    pub fn initials(&self) -> String {
+        if !self.name.is_empty() {
+            self.name
+                .split_whitespace()
+                .take(2)
+                .filter_map(|s| s.chars().next())
+                .collect::<String>()
+                .to_uppercase()
+        } else if !self.email.is_empty() {
+            self.email.chars().next().unwrap().to_uppercase().to_string()
+        } else {
+            "U".to_string()
+        }
+    }
+
+    /// Get display name
+    pub fn display_name(&self) -> String {
+        if !self.name.is_empty() {
+            self.name.clone()
+        } else if !self.nickname.is_empty() {
+            self.nickname.clone()
+        } else if !self.email.is_empty() {
+            self.email.split('@').next().unwrap_or("User").to_string()
+        } else {
+            "User".to_string()
+        }
+    }
+}
+
+/// Meeting event information from the calendar API
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MeetingEvent {
+    pub bot_meeting_id: String,
+    pub event_id: String,
+    pub event_status: String,
+    pub event_summary: String,
+    pub join: bool,
+    pub join_toggle: Option<bool>,
+    pub meeting_participants: Vec<String>,
+    pub meeting_start_time: String, // ISO 8601 format: "2026-01-19T14:30:00"
+    pub meeting_url: String,
+    pub platform: String,
+    pub user_id: String,
+}
+
+impl MeetingEvent {
+    /// Start time in UTC, if the timestamp parses
+    pub fn start_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::parse_from_rfc3339(&self.meeting_start_time)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+    }
+}
+
+/// Query for one page of meeting events
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingEventsQuery {
+    /// RFC 3339 start of the window of meeting start times
+    pub start: String,
+    pub end: String,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// One page of the meeting events response
+///
+/// Older servers return every event as a bare array, without paging.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum MeetingEventsPage {
+    Paged {
+        events: Vec<MeetingEvent>,
+        #[serde(default)]
+        next_cursor: Option<String>,
+    },
+    Unpaged(Vec<MeetingEvent>),
+}
+
+/// Validators from an earlier response, sent back to make a request conditional
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let value = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        Self {
+            etag: value(reqwest::header::ETAG),
+            last_modified: value(reqwest::header::LAST_MODIFIED),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Response to a conditional request
+#[derive(Debug)]
+pub enum Conditional<T> {
+    /// HTTP 304: what was fetched with the validators is still current
+    NotModified,
+    Modified { value: T, validators: CacheValidators },
+}
+
+/// Seconds or an HTTP date, as `Retry-After` allows
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().ok()
+}
+
+/// Sent as a multipart form
+#[derive(Debug, Clone)]
+pub struct CreateFileRequest {
+    pub name: String,
+    /// "mp4", "mp3", …
+    pub file_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateFileResponse {
+    pub file_id: String,
+    pub upload_url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CallMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorded_datetime: Option<String>,
+    /// Length of the uploaded media, pauses not included
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webcam_primary_user: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_private: Option<bool>,
+    #[serde(default)]
+    pub speakers: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Labeled microphone tracks, such as an interpreter's
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub audio_tracks: Vec<AudioTrackMetadata>,
+    pub file_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AudioTrackMetadata {
+    pub title: String,
+    /// ISO 639-2 code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// How the organization names its recordings, set by an admin in Talka
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NamingConvention {
+    /// Title with placeholders, e.g. "ACME – {customer} – {date}"
+    pub template: String,
+    /// Email domains of the organization itself; other participants are customers
+    pub internal_domains: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NoteAttachmentRequest<'a> {
+    pub text: &'a str,
+}
+
+/// A file attached to an uploaded recording
+#[derive(Debug, Clone)]
+pub struct FileAttachmentRequest {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// The app's HTTP client, so every request shares one connection pool
+///
+/// Also used for the identity provider in `auth.rs`.
+pub fn http() -> reqwest::Client {
+    static HTTP: OnceLock<reqwest::Client> = OnceLock::new();
+    HTTP.get_or_init(reqwest::Client::new).clone()
+}
+
+/// Authenticated client for the Talka backend
+#[derive(Debug, Clone)]
+pub struct TalkaClient {
+    http: reqwest::Client,
+    access_token: String,
+    endpoints: Endpoints,
+}
+
+impl TalkaClient {
+    pub fn new(access_token: &str) -> Self {
+        Self::with_endpoints(access_token, Endpoints::default())
+    }
+
+    pub fn with_endpoints(access_token: &str, endpoints: Endpoints) -> Self {
+        Self {
+            http: http(),
+            access_token: access_token.to_string(),
+            endpoints,
+        }
+    }
+
+    /// Fail with the response body unless the request succeeded
+    async fn check(response: reqwest::Response) -> Result<reqwest::Response, ApiError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(ApiError::RateLimited { retry_after: retry_after(response.headers()) });
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(ApiError::Status { status: status.as_u16(), body })
+    }
+
+    async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response, ApiError> {
+        let response = request.send().await.map_err(|e| ApiError::Network(e.to_string()))?;
+        Self::check(response).await
+    }
+
+    async fn json<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T, ApiError> {
+        let text = response
+            .text()
+            .await
+            .map_err(|e| ApiError::Network(format!("Failed to read response: {e}")))?;
+        serde_json::from_str(&text).map_err(|e| ApiError::InvalidResponse(e.to_string()))
+    }
+
+    /// `GET /userinfo` (Bearer token)
+    pub async fn user_profile(&self) -> Result<UserProfile, ApiError> {
+        let request = self.http.get(&self.endpoints.userinfo).bearer_auth(&self.access_token);
+        Self::json(Self::send(request).await?).await
+    }
+
+    /// `GET /meeting-events`, conditional on `validators` from an earlier response
+    ///
+    /// The body is only read and parsed when the server reports a change.
+    pub async fn meeting_events(
+        &self,
+        query: &MeetingEventsQuery,
+        validators: &CacheValidators,
+    ) -> Result<Conditional<MeetingEventsPage>, ApiError> {
+        let mut request = self
+            .http
+            .get(&self.endpoints.meeting_events)
+            .header("authorization", &self.access_token)
+            .query(query);
+        if let Some(ref etag) = validators.etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(ref last_modified) = validators.last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+        let response = request.send().await.map_err(|e| ApiError::Network(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(Conditional::NotModified);
+        }
+        let response = Self::check(response).await?;
+        let validators = CacheValidators::from_headers(response.headers());
+        let value = Self::json(response).await?;
+        Ok(Conditional::Modified { value, validators })
+    }
+
+    /// `POST /files/v2`: a storage entry and the URL to upload its content to
+    pub async fn create_file(&self, request: &CreateFileRequest) -> Result<CreateFileResponse, ApiError> {
+        let form = reqwest::multipart::Form::new()
+            .text("name", request.name.clone())
+            .text("file-type", request.file_type.clone());
+        let request = self
+            .http
+            .post(format!("{}/files/v2", self.endpoints.storage))
+            .header("Authorization", &self.access_token)
+            .header("Accept", "application/json")
+            .multipart(form);
+        Self::json(Self::send(request).await?).await
+    }
+
+    /// `PUT` the file content to the presigned URL from [`Self::create_file`]
+    ///
+    /// The URL carries its own authorization, so no access token is sent.
+    pub async fn upload_content(upload_url: &str, data: Vec<u8>) -> Result<(), ApiError> {
+        let request = http()
+            .put(upload_url)
+            .header("Content-Type", "application/octet-stream")
+            .header("Content-Length", data.len())
+            .body(data);
+        Self::send(request).await.map(drop)
+    }
+
+    /// `POST /files/v2/{id}/call`
+    pub async fn create_call_metadata(&self, file_id: &str, metadata: &CallMetadata) -> Result<(), ApiError> {
+        let request = self
+            .http
+            .post(format!("{}/files/v2/{}/call", self.endpoints.storage, file_id))
+            .header("Authorization", &self.access_token)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json")
+            .json(metadata);
+        Self::send(request).await.map(drop)
+    }
+
+    /// `GET /organization/naming-convention`; `None` when the organization has none
+    pub async fn naming_convention(&self) -> Result<Option<NamingConvention>, ApiError> {
+        let response = self
+            .http
+            .get(format!("{}/organization/naming-convention", self.endpoints.storage))
+            .header("Authorization", &self.access_token)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        if matches!(response.status(), reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::NO_CONTENT) {
+            return Ok(None);
+        }
+        Self::json(Self::check(response).await?).await.map(Some)
+    }
+
+    /// `POST /files/v2/{id}/attachments/notes`
+    pub async fn attach_note(&self, file_id: &str, note: &NoteAttachmentRequest<'_>) -> Result<(), ApiError> {
+        let request = self
+            .http
+            .post(format!("{}/files/v2/{}/attachments/notes", self.endpoints.storage, file_id))
+            .header("Authorization", &self.access_token)
+            .header("Accept", "application/json")
+            .json(note);
+        Self::send(request).await.map(drop)
+    }
+
+    /// `POST /files/v2/{id}/attachments/files`
+    pub async fn attach_file(&self, file_id: &str, file: FileAttachmentRequest) -> Result<(), ApiError> {
+        let form = reqwest::multipart::Form::new()
+            .text("name", file.name.clone())
+            .part("file", reqwest::multipart::Part::bytes(file.data).file_name(file.name));
+        let request = self
+            .http
+            .post(format!("{}/files/v2/{}/attachments/files", self.endpoints.storage, file_id))
+            .header("Authorization", &self.access_token)
+            .header("Accept", "application/json")
+            .multipart(form);
+        Self::send(request).await.map(drop)
+    }
+
+    /// `POST /files/v2/speed-probe` with `payload`, which the server discards
+    ///
+    /// Like an upload, it has to be accepted: a rejected probe says nothing about the
+    /// connection's speed.
+    pub async fn speed_probe(&self, payload: Vec<u8>, timeout: Duration) -> Result<(), ApiError> {
+        let request = self
+            .http
+            .post(format!("{}/files/v2/speed-probe", self.endpoints.storage))
+            .header("Authorization", &self.access_token)
+            .header("Content-Type", "application/octet-stream")
+            .timeout(timeout)
+            .body(payload);
+        Self::send(request).await.map(drop)
+    }
+}
@@ -4,6 +4,9 @@
 //! Or directly: rustc test_auth.rs && ./test_auth
 
 mod auth;
+mod calendar_privacy;
+mod talka_api;
+mod user_data;
 
 #[tokio::main]
 async fn main() {
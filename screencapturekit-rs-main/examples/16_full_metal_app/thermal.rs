@@ -0,0 +1,62 @@
+//! Thermal pressure tracking
+//!
+//! Long recordings on a fanless or hot laptop can push macOS into serious thermal
+//! pressure, at which point it throttles the CPU anyway. Backing off first (slower
+//! preview redraws, a lighter capture) keeps the recording itself smooth.
+//!
+//! The state is sampled from `NSProcessInfo` by the capture loop rather than observed
+//! through `NSProcessInfoThermalStateDidChangeNotification`, which needs an
+//! Objective-C block; the state changes over minutes, so a few seconds of lag is fine.
+
+use cocoa::base::id;
+use objc::{class, msg_send, sel, sel_impl};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// How often the capture loop samples the thermal state
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// `NSProcessInfoThermalState`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThermalState {
+    Nominal,
+    Fair,
+    Serious,
+    Critical,
+}
+
+impl ThermalState {
+    const fn from_raw(raw: i64) -> Self {
+        match raw {
+            0 => Self::Nominal,
+            1 => Self::Fair,
+            2 => Self::Serious,
+            _ => Self::Critical,
+        }
+    }
+}
+
+static UNDER_PRESSURE: AtomicBool = AtomicBool::new(false);
+
+/// Read the current thermal state and remember whether it calls for throttling
+pub fn refresh() -> ThermalState {
+    let raw: i64 = unsafe {
+        let info: id = msg_send![class!(NSProcessInfo), processInfo];
+        msg_send![info, thermalState]
+    };
+    let state = ThermalState::from_raw(raw);
+    let pressure = state >= ThermalState::Serious;
+    if UNDER_PRESSURE.swap(pressure, Ordering::Relaxed) != pressure {
+        if pressure {
+            eprintln!("🌡️  Thermal state {:?}: scaling back preview and capture", state);
+        } else {
+            println!("🌡️  Thermal state back to {:?}", state);
+        }
+    }
+    state
+}
+
+/// Whether the last sample showed serious or critical pressure
+pub fn under_pressure() -> bool {
+    UNDER_PRESSURE.load(Ordering::Relaxed)
+}
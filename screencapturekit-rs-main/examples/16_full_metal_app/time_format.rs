@@ -0,0 +1,103 @@
+//! Dates, times and durations as the app shows them
+//!
+//! Dates and times go through `NSDateFormatter` with skeleton templates, so month and
+//! weekday names, the order of the parts and the separators follow the user's
+//! region, and the hour follows the 12/24-hour choice in System Settings (the `j`
+//! skeleton picks whichever the user has set). Durations are shown as clock digits,
+//! which read the same everywhere. File names use a fixed, sortable form instead,
+//! so they do not change when the region does.
+
+use chrono::{DateTime, Local, Utc};
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSString;
+use objc::{class, msg_send, sel, sel_impl};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::time::Duration;
+
+/// Time of day: "9:30 AM", or "09:30" with 24-hour time
+const TIME: &str = "jmm";
+/// Day and time, for meetings: "Mon 19 Jan, 9:30 AM"
+const DAY_TIME: &str = "EEEdMMMjmm";
+/// Full date and time: "19 January 2026 at 9:30 AM"
+const DATE_TIME: &str = "dMMMMyjmm";
+
+thread_local! {
+    /// Formatters by template; creating one is slow
+    static FORMATTERS: RefCell<HashMap<&'static str, id>> = RefCell::new(HashMap::new());
+}
+
+unsafe fn formatter(template: &'static str) -> id {
+    FORMATTERS.with(|formatters| {
+        *formatters.borrow_mut().entry(template).or_insert_with(|| {
+            let formatter: id = msg_send![class!(NSDateFormatter), new];
+            let locale: id = msg_send![class!(NSLocale), autoupdatingCurrentLocale];
+            let _: () = msg_send![formatter, setLocale: locale];
+            let ns_template = NSString::alloc(nil).init_str(template);
+            let _: () = msg_send![formatter, setLocalizedDateFormatFromTemplate: ns_template];
+            let _: () = msg_send![ns_template, release];
+            formatter
+        })
+    })
+}
+
+/// `at` in local time, laid out from `template` for the user's region
+fn localized(at: DateTime<Utc>, template: &'static str) -> Option<String> {
+    objc::rc::autoreleasepool(|| unsafe {
+        let seconds = at.timestamp_millis() as f64 / 1000.0;
+        let date: id = msg_send![class!(NSDate), dateWithTimeIntervalSince1970: seconds];
+        let text: id = msg_send![formatter(template), stringFromDate: date];
+        if text == nil {
+            return None;
+        }
+        let utf8: *const std::os::raw::c_char = msg_send![text, UTF8String];
+        (!utf8.is_null()).then(|| CStr::from_ptr(utf8).to_string_lossy().into_owned())
+    })
+}
+
+/// Time of day, such as "9:30 AM" or "09:30"
+pub fn time(at: DateTime<Utc>) -> String {
+    localized(at, TIME).unwrap_or_else(|| at.with_timezone(&Local).format("%H:%M").to_string())
+}
+
+/// Weekday, date and time, such as "Mon 19 Jan, 9:30 AM"
+pub fn day_time(at: DateTime<Utc>) -> String {
+    localized(at, DAY_TIME).unwrap_or_else(|| at.with_timezone(&Local).format("%a %-d %b, %H:%M").to_string())
+}
+
+/// Full date and time, such as "19 January 2026 at 9:30 AM"
+pub fn date_time(at: DateTime<Utc>) -> String {
+    localized(at, DATE_TIME).unwrap_or_else(|| at.with_timezone(&Local).format("%-d %B %Y, %H:%M").to_string())
+}
+
+/// Parse an RFC 3339 timestamp, as the API and sidecars store them
+pub fn parse(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// Running time of a recording: "04:05", then "1:04:05" from the first hour
+pub fn clock(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// A moment in, or the length of, a recording: "4:05" or "1:04:05"
+pub fn offset(seconds: u64) -> String {
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// Local time for file names, such as "2026-01-19_09-30-05", that sorts by date
+pub fn file_stamp(at: DateTime<Utc>) -> String {
+    at.with_timezone(&Local).format("%Y-%m-%d_%H-%M-%S").to_string()
+}
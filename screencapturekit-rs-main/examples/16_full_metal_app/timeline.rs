@@ -0,0 +1,302 @@
+//! Today's sessions, read back from the session journal
+//!
+//! The main window shows a strip of the day so far: when capture ran, which parts
+//! of it were recorded and what became of each recording, to answer "did I actually
+//! record the 2 pm call?" at a glance. Everything comes from today's journal, so
+//! it also covers sessions from before the app was last restarted. A session the
+//! journal never saw end (the app quit or crashed) ends where the next capture
+//! started.
+
+use chrono::{DateTime, Duration, DurationRound, Local, Utc};
+use std::path::Path;
+
+use crate::journal::{self, JournalEntry, JournalEvent};
+use crate::time_format;
+
+/// What became of a recording
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Recording,
+    Cancelled,
+    /// Stopped and kept, but not uploaded (yet)
+    Saved,
+    Uploading,
+    Uploaded,
+    UploadFailed(String),
+}
+
+impl Outcome {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Recording => "Recording",
+            Self::Cancelled => "Cancelled",
+            Self::Saved => "Not uploaded",
+            Self::Uploading => "Uploading",
+            Self::Uploaded => "Uploaded",
+            Self::UploadFailed(_) => "Upload failed",
+        }
+    }
+
+    /// Class for the recording's block on the strip
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Recording | Self::Uploading => "timeline-recording active",
+            Self::Cancelled => "timeline-recording cancelled",
+            Self::Saved => "timeline-recording",
+            Self::Uploaded => "timeline-recording uploaded",
+            Self::UploadFailed(_) => "timeline-recording failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub started: DateTime<Utc>,
+    /// `None` while it is still going
+    pub ended: Option<DateTime<Utc>>,
+}
+
+impl Span {
+    fn end_or(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.ended.unwrap_or(now)
+    }
+}
+
+/// A period of capture, and what was shared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureSession {
+    pub span: Span,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordingSession {
+    pub span: Span,
+    pub path: String,
+    pub source: Option<String>,
+    pub outcome: Outcome,
+}
+
+impl RecordingSession {
+    pub fn file_name(&self) -> String {
+        Path::new(&self.path)
+            .file_name()
+            .map_or_else(|| self.path.clone(), |n| n.to_string_lossy().into_owned())
+    }
+
+    /// "2:03 PM – 2:47 PM", or "2:03 PM – now"
+    pub fn time_range(&self) -> String {
+        let end = self.span.ended.map_or_else(|| "now".to_string(), time_format::time);
+        format!("{} – {}", time_format::time(self.span.started), end)
+    }
+
+    /// Whether an uploaded file came from this recording: trimmed copies and
+    /// merged segments keep the recording's name in front
+    fn produced(&self, upload_path: &str) -> bool {
+        let stem = |path: &str| {
+            Path::new(path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        let own = stem(&self.path);
+        let base = own.rsplit_once("_part").map_or(own.as_str(), |(base, _)| base);
+        !base.is_empty() && stem(upload_path).starts_with(base)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timeline {
+    pub captures: Vec<CaptureSession>,
+    pub recordings: Vec<RecordingSession>,
+    /// Errors with when they happened
+    pub errors: Vec<(DateTime<Utc>, String)>,
+    /// Start and end of the strip, on the local hour
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl Timeline {
+    /// Where `at` falls along the strip, in percent
+    pub fn position(&self, at: DateTime<Utc>) -> f64 {
+        let (Some(from), Some(to)) = (self.from, self.to) else {
+            return 0.0;
+        };
+        let total = (to - from).num_seconds().max(1) as f64;
+        ((at - from).num_seconds() as f64 / total * 100.0).clamp(0.0, 100.0)
+    }
+
+    /// Left edge and width of `span` along the strip, in percent
+    pub fn extent(&self, span: &Span, now: DateTime<Utc>) -> (f64, f64) {
+        let left = self.position(span.started);
+        // Wide enough to see, however short
+        let width = (self.position(span.end_or(now)) - left).max(0.8);
+        (left, width.min(100.0 - left))
+    }
+}
+
+/// Today's timeline, from today's journal
+pub fn today() -> Timeline {
+    build(&journal::today(), Utc::now())
+}
+
+fn build(entries: &[JournalEntry], now: DateTime<Utc>) -> Timeline {
+    let mut timeline = Timeline::default();
+    let mut source: Option<String> = None;
+
+    for entry in entries {
+        let Some(at) = time_format::parse(&entry.at) else {
+            continue;
+        };
+        match &entry.event {
+            JournalEvent::CaptureStarted => {
+                // Whatever was still open did not get to say it ended
+                close_open(&mut timeline, at);
+                source = None;
+                timeline.captures.push(CaptureSession { span: Span { started: at, ended: None }, source: None });
+            }
+            JournalEvent::SourceSelected { source: name } => {
+                source = Some(name.clone());
+                if let Some(capture) = timeline.captures.last_mut().filter(|c| c.span.ended.is_none()) {
+                    capture.source = Some(name.clone());
+                }
+            }
+            JournalEvent::CaptureStopped { .. } => {
+                if let Some(capture) = timeline.captures.last_mut().filter(|c| c.span.ended.is_none()) {
+                    capture.span.ended = Some(at);
+                }
+            }
+            JournalEvent::RecordingStarted { path } => timeline.recordings.push(RecordingSession {
+                span: Span { started: at, ended: None },
+                path: path.clone(),
+                source: source.clone(),
+                outcome: Outcome::Recording,
+            }),
+            JournalEvent::RecordingStopped { path } | JournalEvent::RecordingCancelled { path } => {
+                let cancelled = matches!(entry.event, JournalEvent::RecordingCancelled { .. });
+                let recording = timeline
+                    .recordings
+                    .iter_mut()
+                    .rev()
+                    .find(|r| r.span.ended.is_none() && (r.path == *path || r.produced(path)));
+                if let Some(recording) = recording {
+                    recording.span.ended = Some(at);
+                    recording.outcome = if cancelled { Outcome::Cancelled } else { Outcome::Saved };
+                }
+            }
+            JournalEvent::UploadStarted { path } => set_outcome(&mut timeline, path, Outcome::Uploading),
+            JournalEvent::UploadFinished { path, .. } => set_outcome(&mut timeline, path, Outcome::Uploaded),
+            JournalEvent::UploadFailed { path, error } => {
+                set_outcome(&mut timeline, path, Outcome::UploadFailed(error.clone()))
+            }
+            JournalEvent::Error { message } => timeline.errors.push((at, message.clone())),
+        }
+    }
+
+    let first = [
+        timeline.captures.first().map(|c| c.span.started),
+        timeline.recordings.first().map(|r| r.span.started),
+        timeline.errors.first().map(|(at, _)| *at),
+    ]
+    .into_iter()
+    .flatten()
+    .min();
+    if let Some(first) = first {
+        let hour = Duration::hours(1);
+        let on_the_hour = |at: DateTime<Utc>| at.with_timezone(&Local).duration_trunc(hour).map(|t| t.with_timezone(&Utc));
+        timeline.from = Some(on_the_hour(first).unwrap_or(first));
+        timeline.to = Some(on_the_hour(now).map_or(now, |t| t + hour));
+    }
+    timeline
+}
+
+/// End captures and recordings still open at `at`
+fn close_open(timeline: &mut Timeline, at: DateTime<Utc>) {
+    for capture in timeline.captures.iter_mut().filter(|c| c.span.ended.is_none()) {
+        capture.span.ended = Some(at);
+    }
+    for recording in timeline.recordings.iter_mut().filter(|r| r.span.ended.is_none()) {
+        recording.span.ended = Some(at);
+        recording.outcome = Outcome::Saved;
+    }
+}
+
+/// Record an upload's progress on the latest recording it came from
+fn set_outcome(timeline: &mut Timeline, upload_path: &str, outcome: Outcome) {
+    if let Some(recording) = timeline.recordings.iter_mut().rev().find(|r| r.produced(upload_path)) {
+        // A retry that fails does not undo an earlier successful upload
+        if recording.outcome != Outcome::Uploaded || outcome == Outcome::Uploaded {
+            recording.outcome = outcome;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(time: &str, event: JournalEvent) -> JournalEntry {
+        JournalEntry { at: format!("2026-01-19T{time}:00+00:00"), event }
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time_format::parse(&format!("2026-01-19T{time}:00+00:00")).unwrap()
+    }
+
+    fn path(name: &str) -> String {
+        format!("/recordings/{name}")
+    }
+
+    #[test]
+    fn unclosed_session_ends_at_next_capture() {
+        let entries = [
+            entry("10:00", JournalEvent::CaptureStarted),
+            entry("10:01", JournalEvent::SourceSelected { source: "Zoom".to_string() }),
+            entry("10:05", JournalEvent::RecordingStarted { path: path("recording_1000.mp4") }),
+            // The app quit without the journal hearing of it
+            entry("11:00", JournalEvent::CaptureStarted),
+        ];
+        let timeline = build(&entries, at("11:30"));
+
+        assert_eq!(timeline.captures.len(), 2);
+        assert_eq!(timeline.captures[0].span.ended, Some(at("11:00")));
+        assert_eq!(timeline.captures[0].source.as_deref(), Some("Zoom"));
+        assert_eq!(timeline.captures[1].span.ended, None);
+        let recording = &timeline.recordings[0];
+        assert_eq!(recording.span.ended, Some(at("11:00")));
+        assert_eq!(recording.outcome, Outcome::Saved);
+        assert_eq!(recording.source.as_deref(), Some("Zoom"));
+    }
+
+    #[test]
+    fn trimmed_and_merged_uploads_count_for_their_recording() {
+        let entries = [
+            entry("09:00", JournalEvent::RecordingStarted { path: path("recording_0900.mp4") }),
+            entry("09:30", JournalEvent::RecordingStopped { path: path("recording_0900.mp4") }),
+            entry("09:31", JournalEvent::UploadStarted { path: path("recording_0900_trimmed.mp4") }),
+            entry("09:40", JournalEvent::RecordingStarted { path: path("recording_0940_part1.mp4") }),
+            // A segmented recording stops on its last part
+            entry("10:40", JournalEvent::RecordingStopped { path: path("recording_0940_part3.mp4") }),
+            entry("10:45", JournalEvent::UploadFinished { path: path("recording_0940_merged.mp4"), file_id: "f1".to_string() }),
+        ];
+        let timeline = build(&entries, at("11:00"));
+
+        assert_eq!(timeline.recordings[0].outcome, Outcome::Uploading);
+        assert_eq!(timeline.recordings[1].span.ended, Some(at("10:40")));
+        assert_eq!(timeline.recordings[1].outcome, Outcome::Uploaded);
+        assert!(!timeline.recordings[0].produced(&path("recording_0940_merged.mp4")));
+    }
+
+    #[test]
+    fn failed_retry_keeps_earlier_upload() {
+        let entries = [
+            entry("09:00", JournalEvent::RecordingStarted { path: path("recording_0900.mp4") }),
+            entry("09:30", JournalEvent::RecordingStopped { path: path("recording_0900.mp4") }),
+            entry("09:40", JournalEvent::UploadFinished { path: path("recording_0900.mp4"), file_id: "f1".to_string() }),
+            entry("09:50", JournalEvent::UploadFailed { path: path("recording_0900.mp4"), error: "Timed out".to_string() }),
+        ];
+        let timeline = build(&entries, at("10:00"));
+
+        assert_eq!(timeline.recordings[0].outcome, Outcome::Uploaded);
+    }
+}
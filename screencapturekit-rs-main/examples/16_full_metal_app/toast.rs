@@ -0,0 +1,47 @@
+//! Toasts: short-lived messages shown at the bottom of the main window
+//!
+//! Anything can post one from any thread; the UI poll loop picks up the current toast
+//! and it disappears on its own once it has been visible long enough.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const INFO_DURATION: Duration = Duration::from_secs(3);
+/// Errors stay longer so they can be read
+const ERROR_DURATION: Duration = Duration::from_secs(6);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    pub message: String,
+    pub is_error: bool,
+}
+
+static CURRENT: Mutex<Option<(Toast, Instant)>> = Mutex::new(None);
+
+fn post(message: String, is_error: bool) {
+    *CURRENT.lock().unwrap() = Some((Toast { message, is_error }, Instant::now()));
+}
+
+/// Show a confirmation, replacing any toast already on screen
+pub fn show(message: impl Into<String>) {
+    post(message.into(), false);
+}
+
+/// Show a failure, replacing any toast already on screen
+pub fn error(message: impl Into<String>) {
+    let message = message.into();
+    eprintln!("❌ {}", message);
+    post(message, true);
+}
+
+/// The toast that should be on screen now, if any
+pub fn current() -> Option<Toast> {
+    let mut current = CURRENT.lock().unwrap();
+    let expired = current.as_ref().is_some_and(|(toast, posted)| {
+        posted.elapsed() >= if toast.is_error { ERROR_DURATION } else { INFO_DURATION }
+    });
+    if expired {
+        *current = None;
+    }
+    current.as_ref().map(|(toast, _)| toast.clone())
+}
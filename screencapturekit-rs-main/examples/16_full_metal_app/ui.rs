@@ -7,8 +7,7 @@
     clippy::too_many_lines
 )]
 
-use screencapturekit::prelude::*;
-
+use crate::capture_settings::CaptureSettings;
 use crate::font::BitmapFont;
 use crate::overlay::ConfigMenu;
 use crate::vertex::VertexBufferBuilder;
@@ -359,8 +358,7 @@ impl VertexBufferBuilder {
         font: &BitmapFont,
         vw: f32,
         vh: f32,
-        config: &SCStreamConfiguration,
-        mic_device_idx: Option<usize>,
+        settings: &CaptureSettings,
         selection: usize,
         is_capturing: bool,
         source_name: &str,
@@ -443,7 +441,7 @@ impl VertexBufferBuilder {
             }
 
             let name = ConfigMenu::option_name(i);
-            let value = ConfigMenu::option_value(config, mic_device_idx, i);
+            let value = ConfigMenu::option_value(settings, i);
 
             let name_color = if is_selected {
                 [1.0, 1.0, 1.0, 1.0]
@@ -502,7 +500,7 @@ impl VertexBufferBuilder {
         font: &BitmapFont,
         vw: f32,
         vh: f32,
-        config: &crate::recording::RecordingConfig,
+        settings: &CaptureSettings,
         selection: usize,
     ) {
         use crate::recording::RecordingConfigMenu;
@@ -553,7 +551,7 @@ impl VertexBufferBuilder {
             let is_selected = i == selection;
             let text_y = ly + (line_h - text_h) / 2.0;
             let item = RecordingConfigMenu::option_name(i);
-            let value = RecordingConfigMenu::option_value(config, i);
+            let value = RecordingConfigMenu::option_value(settings, i);
 
             if is_selected {
                 // Selection highlight
@@ -600,6 +598,26 @@ impl VertexBufferBuilder {
         );
     }
 
+    /// Confirmation banner shown after a snapshot is saved (bottom-center)
+    pub fn snapshot_banner(&mut self, font: &BitmapFont, vw: f32, vh: f32) {
+        let base_scale = (vw.min(vh) / 800.0).clamp(0.8, 2.0);
+        let scale = 1.5 * base_scale;
+        let padding = 10.0 * base_scale;
+        let actual_scale = (scale as i32) as f32;
+
+        let text = "SNAPSHOT SAVED";
+        let text_w = text.len() as f32 * 8.0 * actual_scale;
+        let text_h = 8.0 * actual_scale;
+        let box_w = text_w + padding * 2.0;
+        let box_h = text_h + padding * 2.0;
+        let x = (vw - box_w) / 2.0;
+        let y = vh - box_h - 24.0 * base_scale;
+
+        self.rect(x, y, box_w, box_h, DARK_BG);
+        self.rect_outline(x, y, box_w, box_h, 2.0, NEON_CYAN);
+        self.text(font, text, x + padding, y + padding, scale, NEON_CYAN);
+    }
+
     /// Upload status overlay (bottom-right corner)
     #[cfg(feature = "macos_15_0")]
     pub fn upload_status_overlay(
@@ -0,0 +1,390 @@
+//! Self-update from Talka's signed release feed
+//!
+//! Each channel has a JSON feed describing the latest release. The feed's Ed25519
+//! signature covers the release's version, download URL and SHA-256 hash, so none of
+//! them can be swapped out; an update is only installed after its archive matches
+//! that hash and the extracted app passes `codesign` verification. The public key is baked in at build
+//! time from `TALKA_UPDATE_PUBLIC_KEY`; builds without it never update themselves.
+//!
+//! Stable releases can be rolled out gradually: each install falls into a fixed
+//! bucket from 0 to 99 and only sees a release once `rollout_percent` exceeds it.
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::remote_config;
+use crate::user_data;
+
+/// Version of this build, compared against the feed
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// How often the background task looks for updates
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Used unless the remote config names another feed
+const FEED_BASE_URL: &str = "https://insights.talka.ai/recorder/releases";
+
+/// Hex-encoded Ed25519 key that release archives are signed with
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("TALKA_UPDATE_PUBLIC_KEY");
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    /// Early releases, delivered to everyone on the channel immediately
+    Beta,
+}
+
+impl UpdateChannel {
+    pub const ALL: [Self; 2] = [Self::Stable, Self::Beta];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Stable => "Stable",
+            Self::Beta => "Beta (early access)",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == value)
+    }
+}
+
+/// Update preferences, stored in `updates.json` in the data folder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateSettings {
+    pub channel: UpdateChannel,
+    /// Check and download in the background
+    pub automatic: bool,
+    /// Random per-install ID that decides the staged rollout bucket
+    pub install_id: String,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::Stable,
+            automatic: true,
+            install_id: String::new(),
+        }
+    }
+}
+
+impl UpdateSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("updates.json")
+    }
+
+    /// Load the settings, assigning an install ID on first use
+    pub fn load() -> Self {
+        let mut settings: Self = std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        if settings.install_id.is_empty() {
+            let mut hasher = DefaultHasher::new();
+            std::time::SystemTime::now().hash(&mut hasher);
+            std::process::id().hash(&mut hasher);
+            settings.install_id = format!("{:016x}", hasher.finish());
+            let _ = settings.save();
+        }
+        settings
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize update settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save update settings: {e}"))
+    }
+
+    /// This install's rollout bucket, 0-99
+    ///
+    /// Hashed with SHA-256 so the bucket stays the same across builds.
+    fn rollout_bucket(&self) -> u8 {
+        let digest = Sha256::digest(self.install_id.as_bytes());
+        let value = u64::from_be_bytes(digest[..8].try_into().expect("digest is 32 bytes"));
+        (value % 100) as u8
+    }
+}
+
+/// Latest release on a channel, as published in its feed
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Release {
+    pub version: String,
+    /// Zipped `.app` bundle
+    pub url: String,
+    /// Hex-encoded SHA-256 of the zip file
+    pub sha256: String,
+    /// Hex-encoded Ed25519 signature of [`Release::manifest`]
+    pub signature: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default = "Release::full_rollout")]
+    pub rollout_percent: u8,
+}
+
+impl Release {
+    const fn full_rollout() -> u8 {
+        100
+    }
+
+    /// What the release signature covers: version, URL and archive hash, one per line
+    fn manifest(&self) -> String {
+        format!("{}\n{}\n{}", self.version, self.url, self.sha256)
+    }
+}
+
+/// Where the updater currently stands, for the settings view
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum UpdateState {
+    #[default]
+    Idle,
+    Checking,
+    UpToDate,
+    Downloading { version: String },
+    /// Verified and waiting for the user to restart
+    Ready { version: String, app_path: PathBuf },
+    Failed(String),
+}
+
+impl std::fmt::Display for UpdateState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Idle => Ok(()),
+            Self::Checking => write!(f, "Checking for updates…"),
+            Self::UpToDate => write!(f, "Talka Recorder is up to date"),
+            Self::Downloading { version } => write!(f, "Downloading version {version}…"),
+            Self::Ready { version, .. } => write!(f, "Version {version} is ready to install"),
+            Self::Failed(e) => write!(f, "Update failed: {e}"),
+        }
+    }
+}
+
+static STATE: Mutex<UpdateState> = Mutex::new(UpdateState::Idle);
+
+pub fn state() -> UpdateState {
+    STATE.lock().unwrap().clone()
+}
+
+fn set_state(state: UpdateState) {
+    *STATE.lock().unwrap() = state;
+}
+
+/// `1.2.10` style version as comparable numbers
+///
+/// Only plain `MAJOR.MINOR.PATCH` is accepted, which also keeps the version safe to
+/// use in file names.
+fn parse_version(version: &str) -> Option<[u64; 3]> {
+    let mut parts = version.split('.').map(|part| {
+        let is_number = !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        is_number.then(|| part.parse().ok()).flatten()
+    });
+    let parsed = [parts.next()??, parts.next()??, parts.next()??];
+    parts.next().is_none().then_some(parsed)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn verify_signature(data: &[u8], signature_hex: &str) -> Result<(), String> {
+    let key_hex = RELEASE_PUBLIC_KEY.ok_or("This build has no update signing key")?;
+    let key_bytes: [u8; 32] = decode_hex(key_hex)
+        .and_then(|b| b.try_into().ok())
+        .ok_or("Invalid update signing key")?;
+    let signature_bytes: [u8; 64] = decode_hex(signature_hex)
+        .and_then(|b| b.try_into().ok())
+        .ok_or("Malformed release signature")?;
+
+    let key = VerifyingKey::from_bytes(&key_bytes).map_err(|e| format!("Invalid key: {e}"))?;
+    key.verify_strict(data, &Signature::from_bytes(&signature_bytes))
+        .map_err(|_| "Release signature does not match".to_string())
+}
+
+/// Fetch the feed and return the release to install, if this install should get one
+pub async fn check(settings: &UpdateSettings) -> Result<Option<Release>, String> {
+    let url = format!(
+        "{}/{}.json",
+        remote_config::update_feed_url(FEED_BASE_URL),
+        settings.channel.as_str()
+    );
+    let response = reqwest::get(&url)
+        .await
+        .map_err(|e| format!("Failed to reach update feed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Update feed returned HTTP {}", response.status()));
+    }
+    let release: Release = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid update feed: {e}"))?;
+    verify_signature(release.manifest().as_bytes(), &release.signature)?;
+
+    let version = parse_version(&release.version)
+        .ok_or_else(|| format!("Update feed lists an invalid version {:?}", release.version))?;
+    let current = parse_version(CURRENT_VERSION).ok_or("This build has an invalid version")?;
+    let in_rollout = settings.channel == UpdateChannel::Beta
+        || settings.rollout_bucket() < release.rollout_percent;
+    Ok((version > current && in_rollout).then_some(release))
+}
+
+/// Download a release, check its signature and unpack it to a staging directory
+///
+/// Returns the path of the verified `.app` bundle.
+pub async fn download(release: &Release) -> Result<PathBuf, String> {
+    let bytes = reqwest::get(&release.url)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| format!("Download failed: {e}"))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Download failed: {e}"))?;
+    let hash: String = Sha256::digest(&bytes).iter().map(|b| format!("{b:02x}")).collect();
+    if !hash.eq_ignore_ascii_case(&release.sha256) {
+        return Err("Downloaded update does not match the signed release".to_string());
+    }
+
+    // Built from the parsed version, so the feed cannot point it outside the temp dir
+    let [major, minor, patch] = parse_version(&release.version).ok_or("Invalid release version")?;
+    let staging = std::env::temp_dir().join(format!("talka-update-{major}.{minor}.{patch}"));
+    tokio::task::spawn_blocking(move || unpack(&bytes, &staging))
+        .await
+        .map_err(|e| format!("Failed to unpack update: {e}"))?
+}
+
+fn run(program: &str, args: &[&std::ffi::OsStr]) -> Result<(), String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {program}: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn unpack(archive: &[u8], staging: &Path) -> Result<PathBuf, String> {
+    let _ = std::fs::remove_dir_all(staging);
+    std::fs::create_dir_all(staging).map_err(|e| format!("Failed to create {staging:?}: {e}"))?;
+    let zip_path = staging.join("update.zip");
+    std::fs::write(&zip_path, archive).map_err(|e| format!("Failed to save update: {e}"))?;
+
+    run("ditto", &["-x".as_ref(), "-k".as_ref(), zip_path.as_os_str(), staging.as_os_str()])?;
+    let app = std::fs::read_dir(staging)
+        .map_err(|e| format!("Failed to read update: {e}"))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+        .ok_or("Update archive contains no app")?;
+
+    // The archive signature proves where it came from; this proves Apple will run it
+    run(
+        "codesign",
+        &["--verify".as_ref(), "--deep".as_ref(), "--strict".as_ref(), app.as_os_str()],
+    )?;
+    Ok(app)
+}
+
+/// `.app` bundle the running binary belongs to
+fn current_bundle() -> Result<PathBuf, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Cannot locate app: {e}"))?;
+    exe.ancestors()
+        .find(|p| p.extension().is_some_and(|ext| ext == "app"))
+        .map(Path::to_path_buf)
+        .ok_or_else(|| "Not running from an app bundle".to_string())
+}
+
+/// Replace the running app with a verified update and relaunch it
+///
+/// Only returns on failure; the old bundle is restored if the swap fails halfway.
+pub fn install_and_relaunch(app_path: &Path) -> Result<(), String> {
+    let bundle = current_bundle()?;
+    let incoming = bundle.with_extension("app.new");
+    let outgoing = bundle.with_extension("app.old");
+    let _ = std::fs::remove_dir_all(&incoming);
+    let _ = std::fs::remove_dir_all(&outgoing);
+
+    // Copy next to the bundle first so the final swap is two same-volume renames
+    run("ditto", &[app_path.as_os_str(), incoming.as_os_str()])?;
+    std::fs::rename(&bundle, &outgoing).map_err(|e| format!("Failed to replace app: {e}"))?;
+    if let Err(e) = std::fs::rename(&incoming, &bundle) {
+        let _ = std::fs::rename(&outgoing, &bundle);
+        return Err(format!("Failed to replace app: {e}"));
+    }
+    let _ = std::fs::remove_dir_all(&outgoing);
+
+    println!("🔄 Update installed, relaunching");
+    Command::new("open")
+        .arg("-n")
+        .arg(&bundle)
+        .spawn()
+        .map_err(|e| format!("Update installed but relaunch failed: {e}"))?;
+    std::process::exit(0);
+}
+
+/// Check the configured channel and stage any available update
+pub async fn check_and_download() {
+    let settings = UpdateSettings::load();
+    if RELEASE_PUBLIC_KEY.is_none() {
+        set_state(UpdateState::Failed("Updates are not available in this build".to_string()));
+        return;
+    }
+
+    set_state(UpdateState::Checking);
+    let release = match check(&settings).await {
+        Ok(Some(release)) => release,
+        Ok(None) => {
+            set_state(UpdateState::UpToDate);
+            return;
+        }
+        Err(e) => {
+            eprintln!("⚠️  Update check failed: {}", e);
+            set_state(UpdateState::Failed(e));
+            return;
+        }
+    };
+
+    println!("⬇️  Downloading update {}", release.version);
+    set_state(UpdateState::Downloading {
+        version: release.version.clone(),
+    });
+    match download(&release).await {
+        Ok(app_path) => {
+            println!("✅ Update {} verified and ready", release.version);
+            set_state(UpdateState::Ready {
+                version: release.version,
+                app_path,
+            });
+        }
+        Err(e) => {
+            eprintln!("❌ Update {} rejected: {}", release.version, e);
+            set_state(UpdateState::Failed(e));
+        }
+    }
+}
@@ -2,12 +2,42 @@
 //!
 //! Handles uploading recordings to the Talka storage service
 
-use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
-const STORAGE_BASE_URL: &str = "https://storage.talka.ai";
+use crate::attachments::Attachment;
+use crate::highlights;
+use crate::journal;
+use crate::journal::JournalEvent;
+use crate::media;
+use crate::naming;
+use crate::power;
+use crate::remote_config;
+use crate::toast;
+use crate::upload_queue;
+use crate::upload_state::{UploadPhase, UploadState};
+use crate::webhook;
+use crate::sidecar::{RecordingSidecar, SidecarUploadState};
+use crate::talka_api::{
+    ApiError, AudioTrackMetadata, CallMetadata, CreateFileRequest, CreateFileResponse, Endpoints,
+    FileAttachmentRequest, NoteAttachmentRequest, TalkaClient,
+};
+
+/// Payload sent by the speed probe
+const PROBE_BYTES: usize = 1024 * 1024;
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Endpoints with the storage URL from the remote config
+fn endpoints() -> Endpoints {
+    let mut endpoints = Endpoints::default();
+    endpoints.storage = remote_config::storage_url(&endpoints.storage);
+    endpoints
+}
+
+fn client(access_token: &str) -> TalkaClient {
+    TalkaClient::with_endpoints(access_token, endpoints())
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum UploadStatus {
@@ -27,59 +57,25 @@ impl Default for UploadStatus {
 
 impl UploadStatus {
     pub fn as_display_string(&self) -> String {
-        match self {
-            Self::Idle => String::new(),
-            Self::CreatingFile => "Preparing your recording".to_string(),
-            Self::UploadingFile { percent } => {
-                if *percent < 100 {
-                    format!("{}% uploaded", percent)
-                } else {
-                    "Finalizing upload".to_string()
-                }
-            },
-            Self::CreatingMetadata => "Processing recording".to_string(),
-            Self::Complete { .. } => "Your recording is ready".to_string(),
-            Self::Failed(err) => {
-                if err.contains("network") || err.contains("connection") {
-                    "Connection lost. Please try again.".to_string()
-                } else if err.contains("auth") || err.contains("token") {
-                    "Authentication failed. Please log in again.".to_string()
-                } else {
-                    "Something went wrong. Please try again.".to_string()
-                }
-            },
-        }
+        UploadState::from(self).message()
     }
 }
 
-#[derive(Debug, Serialize)]
-struct CreateFileRequest {
-    name: String,
-    #[serde(rename = "file-type")]
-    file_type: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct CreateFileResponse {
-    pub file_id: String,
-    pub upload_url: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct CallMetadata {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub title: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub recorded_datetime: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub provider: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub webcam_primary_user: Option<i32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub is_private: Option<bool>,
-    #[serde(default)]
-    pub speakers: Vec<String>,
-    pub file_id: String,
+impl From<&UploadStatus> for UploadState {
+    fn from(status: &UploadStatus) -> Self {
+        match status {
+            UploadStatus::Idle => Self::default(),
+            UploadStatus::CreatingFile => Self::new(UploadPhase::Preparing),
+            UploadStatus::UploadingFile { percent } => Self { percent: *percent, ..Self::new(UploadPhase::Uploading) },
+            UploadStatus::CreatingMetadata => Self { percent: 100, ..Self::new(UploadPhase::Processing) },
+            UploadStatus::Complete { file_id } => Self {
+                percent: 100,
+                file_id: Some(file_id.clone()),
+                ..Self::new(UploadPhase::Complete)
+            },
+            UploadStatus::Failed(error) => Self::failed(UploadPhase::Failed, error.clone()),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -88,6 +84,8 @@ pub enum UploadError {
     Io(String),
     InvalidToken,
     InvalidResponse(String),
+    /// Larger than the storage service's upload limit
+    TooLarge { size_bytes: u64, limit_bytes: u64 },
 }
 
 impl std::fmt::Display for UploadError {
@@ -97,12 +95,27 @@ impl std::fmt::Display for UploadError {
             Self::Io(msg) => write!(f, "I/O error: {}", msg),
             Self::InvalidToken => write!(f, "Invalid or expired access token"),
             Self::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
+            Self::TooLarge { size_bytes, limit_bytes } => write!(
+                f,
+                "Recording is {} MB, over the {} MB upload limit",
+                size_bytes / (1024 * 1024),
+                limit_bytes / (1024 * 1024)
+            ),
         }
     }
 }
 
 impl std::error::Error for UploadError {}
 
+impl From<ApiError> for UploadError {
+    fn from(e: ApiError) -> Self {
+        match e {
+            ApiError::InvalidResponse(msg) => Self::InvalidResponse(msg),
+            e => Self::Network(e.to_string()),
+        }
+    }
+}
+
 /// Infer file type from file extension
 fn infer_file_type(file_name: &str) -> String {
     let lower = file_name.to_lowercase();
@@ -125,6 +138,21 @@ fn infer_file_type(file_name: &str) -> String {
     "mp4".to_string()
 }
 
+/// Measure how fast the storage endpoint accepts data, in bytes per second
+///
+/// Sends a short throwaway payload that the server discards.
+pub async fn probe_upload_speed(access_token: &str) -> Result<f64, UploadError> {
+    let started = std::time::Instant::now();
+    client(access_token)
+        .speed_probe(vec![0u8; PROBE_BYTES], PROBE_TIMEOUT)
+        .await?;
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let speed = PROBE_BYTES as f64 / elapsed.max(0.001);
+    println!("[UPLOAD] Probe: {:.1} MB/s", speed / (1024.0 * 1024.0));
+    Ok(speed)
+}
+
 /// Step 1: Create a file entry in the storage system
 pub async fn create_file(
     access_token: &str,
@@ -135,33 +163,11 @@ pub async fn create_file(
     let file_type = infer_file_type(file_name);
     println!("[UPLOAD] File type: {}", file_type);
     
-    let client = reqwest::Client::new();
-    let url = format!("{}/files/v2", STORAGE_BASE_URL);
-    
-    // Create multipart form
-    let form = reqwest::multipart::Form::new()
-        .text("name", file_name.to_string())
-        .text("file-type", file_type);
-    
-    let response = client
-        .post(&url)
-        .header("Authorization", access_token)
-        .header("Accept", "application/json")
-        .multipart(form)
-        .send()
-        .await
-        .map_err(|e| UploadError::Network(e.to_string()))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(UploadError::Network(format!("HTTP {}: {}", status, text)));
-    }
-    
-    let create_response: CreateFileResponse = response
-        .json()
-        .await
-        .map_err(|e| UploadError::InvalidResponse(e.to_string()))?;
+    let request = CreateFileRequest {
+        name: file_name.to_string(),
+        file_type,
+    };
+    let create_response = client(access_token).create_file(&request).await?;
     
     println!("[UPLOAD] File entry created: {}", create_response.file_id);
     Ok(create_response)
@@ -180,26 +186,9 @@ pub async fn upload_file(
         .await
         .map_err(|e| UploadError::Io(e.to_string()))?;
     
-    let file_size = file_data.len();
-    println!("[UPLOAD] File size: {} bytes", file_size);
-    
-    let client = reqwest::Client::new();
+    println!("[UPLOAD] File size: {} bytes", file_data.len());
     
-    // Upload
-    let response = client
-        .put(upload_url)
-        .header("Content-Type", "application/octet-stream")
-        .header("Content-Length", file_size)
-        .body(file_data)
-        .send()
-        .await
-        .map_err(|e| UploadError::Network(e.to_string()))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(UploadError::Network(format!("HTTP {}: {}", status, text)));
-    }
+    TalkaClient::upload_content(upload_url, file_data).await?;
     
     // Update progress to 100%
     if let Some(tracker) = progress_tracker {
@@ -218,40 +207,168 @@ pub async fn create_call_metadata(
 ) -> Result<(), UploadError> {
     println!("[UPLOAD] Creating call metadata for file: {}", file_id);
     
-    let client = reqwest::Client::new();
-    let url = format!("{}/files/v2/{}/call", STORAGE_BASE_URL, file_id);
-    
-    let response = client
-        .post(&url)
-        .header("Authorization", access_token)
-        .header("Content-Type", "application/json")
-        .header("Accept", "application/json")
-        .json(&metadata)
-        .send()
-        .await
-        .map_err(|e| UploadError::Network(e.to_string()))?;
-    
-    if !response.status().is_success() {
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
-        return Err(UploadError::Network(format!("HTTP {}: {}", status, text)));
-    }
+    client(access_token).create_call_metadata(file_id, &metadata).await?;
     
     println!("[UPLOAD] Call metadata created successfully");
     Ok(())
 }
 
-/// Complete upload workflow: create file, upload, and create metadata
+/// Attach a text note to an uploaded file
+pub async fn upload_note_attachment(access_token: &str, file_id: &str, text: &str) -> Result<(), UploadError> {
+    println!("[UPLOAD] Attaching note to file: {}", file_id);
+
+    client(access_token).attach_note(file_id, &NoteAttachmentRequest { text }).await?;
+    Ok(())
+}
+
+/// Attach a small file (agenda, slides) to an uploaded file
+pub async fn upload_file_attachment(access_token: &str, file_id: &str, path: &Path) -> Result<(), UploadError> {
+    println!("[UPLOAD] Attaching {} to file: {}", path.display(), file_id);
+
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| UploadError::Io("Invalid file name".to_string()))?
+        .to_string();
+    let data = tokio::fs::read(path)
+        .await
+        .map_err(|e| UploadError::Io(format!("{}: {}", path.display(), e)))?;
+
+    client(access_token).attach_file(file_id, FileAttachmentRequest { name, data }).await?;
+    Ok(())
+}
+
+/// Send the recording's attachments, returning each that failed with the reason
+///
+/// The recording itself is already stored, so failures are reported rather than
+/// failing the upload.
+async fn upload_attachments(
+    access_token: &str,
+    file_id: &str,
+    attachments: &[Attachment],
+) -> Vec<(Attachment, String)> {
+    let mut failures = Vec::new();
+    for attachment in attachments {
+        let result = match attachment {
+            Attachment::Note { text } => upload_note_attachment(access_token, file_id, text).await,
+            Attachment::File { path } => upload_file_attachment(access_token, file_id, Path::new(path)).await,
+        };
+        if let Err(e) = result {
+            failures.push((attachment.clone(), format!("{}: {}", attachment.label(), e)));
+        }
+    }
+    failures
+}
+
+/// "Attachments not sent: …", for the sidecar's upload error
+fn describe_unsent(failures: &[(Attachment, String)]) -> String {
+    let reasons: Vec<&str> = failures.iter().map(|(_, reason)| reason.as_str()).collect();
+    format!("Attachments not sent: {}", reasons.join("; "))
+}
+
+/// Send the attachments that failed when `recording_path` was uploaded
+pub async fn retry_attachments(access_token: &str, recording_path: &str) -> Result<(), String> {
+    let sidecar = RecordingSidecar::load(recording_path).unwrap_or_default();
+    let file_id = sidecar
+        .file_id
+        .ok_or_else(|| "The recording has not been uploaded".to_string())?;
+    let failures = upload_attachments(access_token, &file_id, &sidecar.unsent_attachments).await;
+    RecordingSidecar::update(recording_path, |s| {
+        s.upload_error = (!failures.is_empty()).then(|| describe_unsent(&failures));
+        s.unsent_attachments = failures.iter().map(|(attachment, _)| attachment.clone()).collect();
+    })?;
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(describe_unsent(&failures))
+    }
+}
+
+/// Complete upload workflow: create file, upload, create metadata, send the
+/// attachments and announce the upload to the webhook
+///
+/// Every upload goes through here, from the trim editor or the queue. Progress and
+/// the resulting file ID are mirrored into the recording's sidecar.
 pub async fn upload_recording(
     access_token: &str,
     file_path: &Path,
     title: Option<String>,
     status_callback: Option<Box<dyn Fn(UploadStatus) + Send + Sync>>,
+) -> Result<String, UploadError> {
+    let recording_path = file_path.to_string_lossy();
+    let _ = RecordingSidecar::update(&recording_path, |s| {
+        s.upload_state = SidecarUploadState::Uploading;
+    });
+    // A queued recording leaves the queue once its upload starts
+    upload_queue::changed();
+    // A sleeping laptop drops the connection and the upload with it
+    let _awake = power::Assertion::prevent_system_sleep("Uploading recording");
+    journal::record(JournalEvent::UploadStarted { path: recording_path.to_string() });
+
+    let result = upload_recording_steps(access_token, file_path, title, status_callback).await;
+
+    let mut failures = Vec::new();
+    if let Ok(ref file_id) = result {
+        let attachments = RecordingSidecar::load(&recording_path).map(|s| s.attachments).unwrap_or_default();
+        failures = upload_attachments(access_token, file_id, &attachments).await;
+        if !failures.is_empty() {
+            eprintln!("⚠️  {}", describe_unsent(&failures));
+            toast::error(format!("{} attachment(s) could not be sent; retry them from the library", failures.len()));
+        }
+    }
+
+    let mut original = None;
+    let _ = RecordingSidecar::update(&recording_path, |s| match &result {
+        Ok(file_id) => {
+            original = s.derived_from.clone();
+            if let Err(e) = highlights::set_file_id(&recording_path, file_id) {
+                eprintln!("⚠️  {}", e);
+            }
+            s.upload_state = SidecarUploadState::Uploaded;
+            s.file_id = Some(file_id.clone());
+            s.upload_error = (!failures.is_empty()).then(|| describe_unsent(&failures));
+            s.unsent_attachments = failures.iter().map(|(attachment, _)| attachment.clone()).collect();
+        }
+        Err(e) => {
+            s.upload_state = SidecarUploadState::Failed;
+            s.upload_error = Some(e.to_string());
+        }
+    });
+    // The recording a trimmed or processed file came from is uploaded with it, so
+    // the library shows it as uploaded and cleanup can reclaim it
+    if let (Ok(file_id), Some(original)) = (&result, original) {
+        let _ = RecordingSidecar::update(&original, |s| {
+            s.upload_state = SidecarUploadState::Uploaded;
+            s.file_id = Some(file_id.clone());
+            s.upload_error = None;
+        });
+    }
+    journal::record(match &result {
+        Ok(file_id) => JournalEvent::UploadFinished { path: recording_path.to_string(), file_id: file_id.clone() },
+        Err(e) => JournalEvent::UploadFailed { path: recording_path.to_string(), error: e.to_string() },
+    });
+    if let Ok(ref file_id) = result {
+        webhook::notify_upload_complete(&recording_path, file_id).await;
+    }
+    result
+}
+
+async fn upload_recording_steps(
+    access_token: &str,
+    file_path: &Path,
+    title: Option<String>,
+    status_callback: Option<Box<dyn Fn(UploadStatus) + Send + Sync>>,
 ) -> Result<String, UploadError> {
     let file_name = file_path
         .file_name()
         .and_then(|n| n.to_str())
         .ok_or_else(|| UploadError::Io("Invalid file name".to_string()))?;
+    if let Some(limit_bytes) = remote_config::max_upload_bytes() {
+        let size_bytes = std::fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
+        if size_bytes > limit_bytes {
+            return Err(UploadError::TooLarge { size_bytes, limit_bytes });
+        }
+    }
     
     // Step 1: Create file entry
     if let Some(ref cb) = status_callback {
@@ -291,13 +408,31 @@ pub async fn upload_recording(
         cb(UploadStatus::CreatingMetadata);
     }
     
+    // Tags and notes added in the library travel with the upload, and the title
+    // follows the organization's naming convention unless one was chosen. The
+    // duration is the media's, as written when the recording stopped, or read from
+    // the file for trimmed and merged ones
+    let path = file_path.to_string_lossy();
+    let sidecar = RecordingSidecar::load(&path).unwrap_or_default();
+    let duration_seconds = sidecar.duration_seconds.or_else(|| media::duration_seconds(&path).ok());
     let metadata = CallMetadata {
-        title,
+        title: naming::title_for(&sidecar).or(title),
         recorded_datetime: Some(chrono::Utc::now().to_rfc3339()),
-        provider: Some("Talka Recall".to_string()),
+        duration_seconds,
+        provider: Some(sidecar.provider.clone().unwrap_or_else(|| "Talka Recall".to_string())),
         webcam_primary_user: None,
         is_private: Some(false),
         speakers: vec![],
+        tags: sidecar.tags,
+        notes: (!sidecar.notes.is_empty()).then_some(sidecar.notes),
+        audio_tracks: sidecar
+            .audio_tracks
+            .into_iter()
+            .map(|track| AudioTrackMetadata {
+                title: track.label,
+                language: (!track.language.is_empty()).then_some(track.language),
+            })
+            .collect(),
         file_id: create_response.file_id.clone(),
     };
     
@@ -0,0 +1,395 @@
+//! Pre-upload speed check and the queue of deferred uploads
+//!
+//! Before a large recording is uploaded, a short probe measures how fast the storage
+//! endpoint accepts data. When the estimate is long ("Estimated 45 min on current
+//! connection") the user chooses between uploading now and queueing the recording.
+//! Queued recordings are marked in their sidecar and uploaded in the background once
+//! a later probe shows the connection has improved.
+//!
+//! On a metered connection (see [`crate::network`]) large uploads ask, or are queued
+//! straight away, and the queue waits for Wi-Fi. Any queued recording can still be
+//! uploaded right away from the library.
+//!
+//! The library also lets the queue be rearranged. Recordings upload in the order
+//! set there, oldest first unless moved. One marked high priority (the customer call
+//! that has to be shared now) goes ahead of the rest and does not wait for a better
+//! connection, nor for Wi-Fi unless the user chose to always queue on metered
+//! connections; paused ones are skipped until resumed. The arrangement is kept in
+//! `upload_queue.json` in the data folder.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::{watch, Notify};
+
+use crate::auth;
+use crate::library;
+use crate::naming;
+use crate::network;
+use crate::network::{MeteredUploads, NetworkSettings};
+use crate::remote_config;
+use crate::sidecar::{RecordingSidecar, SidecarUploadState};
+use crate::upload;
+use crate::user_data;
+
+/// Files smaller than this upload without a speed check
+pub const CHECK_ABOVE_BYTES: u64 = 100 * 1024 * 1024;
+/// Estimates longer than this ask before uploading
+pub const SLOW_UPLOAD: Duration = Duration::from_secs(15 * 60);
+/// How often queued uploads are retried
+pub const QUEUE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    UploadNow,
+    Queue,
+}
+
+/// Upload waiting for the user to decide
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowUploadPrompt {
+    pub path: String,
+    pub message: String,
+}
+
+static PROMPT: Mutex<Option<SlowUploadPrompt>> = Mutex::new(None);
+static DECISION: Mutex<Option<Decision>> = Mutex::new(None);
+/// Held while a prompt is up, so a second upload waits its turn to ask instead of
+/// replacing the question or taking its answer
+static ASKING: OnceLock<tokio::sync::Mutex<()>> = OnceLock::new();
+
+/// The question to show, if an upload is waiting on one
+pub fn prompt() -> Option<SlowUploadPrompt> {
+    PROMPT.lock().unwrap().clone()
+}
+
+/// Answer the current prompt
+pub fn decide(decision: Decision) {
+    *DECISION.lock().unwrap() = Some(decision);
+    *PROMPT.lock().unwrap() = None;
+}
+
+/// "45 min", "2 h 10 min"
+fn format_estimate(estimate: Duration) -> String {
+    let minutes = (estimate.as_secs() + 59) / 60;
+    if minutes >= 60 {
+        format!("{} h {} min", minutes / 60, minutes % 60)
+    } else {
+        format!("{minutes} min")
+    }
+}
+
+fn file_size(path: &str) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Time to upload `size_bytes`, or why it could not be estimated
+async fn estimate(access_token: &str, size_bytes: u64) -> Result<Duration, String> {
+    let bytes_per_second = upload::probe_upload_speed(access_token).await.map_err(|e| e.to_string())?;
+    Ok(Duration::from_secs_f64(size_bytes as f64 / bytes_per_second.max(1.0)))
+}
+
+/// Probe the connection before uploading `path`, asking the user when it looks slow
+///
+/// Waits for the answer; returns straight away for small files and fast connections.
+pub async fn check_before_upload(path: &str, access_token: &str) -> Decision {
+    let size = file_size(path);
+    if size < CHECK_ABOVE_BYTES {
+        return Decision::UploadNow;
+    }
+    if let Some(reason) = network::metered().await {
+        match NetworkSettings::load().metered_uploads {
+            MeteredUploads::Upload => {}
+            MeteredUploads::Queue => {
+                println!("📶 On {}: queueing upload until Wi-Fi", reason);
+                return Decision::Queue;
+            }
+            MeteredUploads::Ask => {
+                return ask(path, format!("Connected through {reason} — upload now or wait for Wi-Fi?")).await;
+            }
+        }
+    }
+    if !remote_config::flag("upload_speed_check", true) {
+        return Decision::UploadNow;
+    }
+    let message = match estimate(access_token, size).await {
+        Ok(estimate) if estimate <= SLOW_UPLOAD => return Decision::UploadNow,
+        Ok(estimate) => {
+            println!("🐢 Upload estimated at {:.0}s", estimate.as_secs_f64());
+            format!(
+                "Estimated {} on current connection — upload now or queue for later?",
+                format_estimate(estimate)
+            )
+        }
+        Err(e) => {
+            eprintln!("⚠️  Storage speed check failed: {}", e);
+            "Storage server is not responding — upload now or queue for later?".to_string()
+        }
+    };
+    ask(path, message).await
+}
+
+/// Show `message` about uploading `path` and wait for the answer
+async fn ask(path: &str, message: String) -> Decision {
+    let _asking = ASKING.get_or_init(|| tokio::sync::Mutex::new(())).lock().await;
+    *DECISION.lock().unwrap() = None;
+    *PROMPT.lock().unwrap() = Some(SlowUploadPrompt {
+        path: path.to_string(),
+        message,
+    });
+    loop {
+        if let Some(decision) = DECISION.lock().unwrap().take() {
+            return decision;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+/// Park a recording in the queue
+pub fn park(path: &str) -> Result<(), String> {
+    println!("🅿️  Upload queued: {}", path);
+    RecordingSidecar::update(path, |s| s.upload_state = SidecarUploadState::Queued)?;
+    changed();
+    Ok(())
+}
+
+/// A queued recording and how it was arranged
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueEntry {
+    pub path: String,
+    /// Uploads ahead of the rest, whatever the connection
+    pub high_priority: bool,
+    /// Held back until resumed
+    pub paused: bool,
+}
+
+impl QueueEntry {
+    pub fn file_name(&self) -> String {
+        Path::new(&self.path)
+            .file_name()
+            .map_or_else(|| self.path.clone(), |n| n.to_string_lossy().into_owned())
+    }
+}
+
+/// The queue as last arranged
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+struct QueueOrder {
+    entries: Vec<QueueEntry>,
+}
+
+impl QueueOrder {
+    fn config_path() -> PathBuf {
+        user_data::file("upload_queue.json")
+    }
+
+    fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize the upload queue: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save the upload queue: {e}"))?;
+        changed();
+        Ok(())
+    }
+}
+
+/// Bumped whenever what [`entries`] returns may have changed
+static CHANGES: OnceLock<watch::Sender<u64>> = OnceLock::new();
+
+fn changes() -> &'static watch::Sender<u64> {
+    CHANGES.get_or_init(|| watch::Sender::new(0))
+}
+
+/// Tell whoever shows the queue to read it again
+///
+/// Called when the queue is rearranged, and when a recording enters, leaves or
+/// is deleted from it.
+pub fn changed() {
+    changes().send_modify(|count| *count += 1);
+}
+
+/// Notified after each [`changed`]
+pub fn subscribe() -> watch::Receiver<u64> {
+    changes().subscribe()
+}
+
+/// Wakes the queue runner before its next scheduled run
+static WAKE: OnceLock<Notify> = OnceLock::new();
+
+fn wake() -> &'static Notify {
+    WAKE.get_or_init(Notify::new)
+}
+
+/// Recordings waiting in the queue, in the order they upload
+///
+/// High priority ones come first, then the rest as arranged; recordings queued
+/// since the queue was last arranged follow, oldest first.
+pub fn entries() -> Vec<QueueEntry> {
+    let mut queued: Vec<String> = library::list_recordings()
+        .into_iter()
+        .filter(|e| e.sidecar.as_ref().is_some_and(|s| s.upload_state == SidecarUploadState::Queued))
+        .map(|e| e.path)
+        .collect();
+    queued.reverse();
+
+    let mut entries: Vec<QueueEntry> = QueueOrder::load()
+        .entries
+        .into_iter()
+        .filter(|entry| queued.contains(&entry.path))
+        .collect();
+    for path in queued {
+        if !entries.iter().any(|entry| entry.path == path) {
+            entries.push(QueueEntry { path, ..QueueEntry::default() });
+        }
+    }
+    entries.sort_by_key(|entry| !entry.high_priority);
+    entries
+}
+
+/// Change the arrangement of the queue and keep it
+fn arrange(change: impl FnOnce(&mut Vec<QueueEntry>)) -> Result<(), String> {
+    let mut entries = entries();
+    change(&mut entries);
+    entries.sort_by_key(|entry| !entry.high_priority);
+    QueueOrder { entries }.save()
+}
+
+/// Swap `path` with the recording before it (`up`) or after it
+pub fn move_entry(path: &str, up: bool) -> Result<(), String> {
+    arrange(|entries| {
+        let Some(index) = entries.iter().position(|entry| entry.path == path) else {
+            return;
+        };
+        let other = if up { index.checked_sub(1) } else { Some(index + 1) };
+        if let Some(other) = other.filter(|&other| other < entries.len()) {
+            entries.swap(index, other);
+        }
+    })
+}
+
+/// Mark `path` to upload ahead of the rest; it is tried straight away
+pub fn set_high_priority(path: &str, high_priority: bool) -> Result<(), String> {
+    arrange(|entries| {
+        if let Some(entry) = entries.iter_mut().find(|entry| entry.path == path) {
+            entry.high_priority = high_priority;
+            if high_priority {
+                entry.paused = false;
+            }
+        }
+    })?;
+    if high_priority {
+        println!("⏫ Upload prioritized: {}", path);
+        wake().notify_one();
+    }
+    Ok(())
+}
+
+/// Hold back `path`, or let it upload again
+pub fn set_paused(path: &str, paused: bool) -> Result<(), String> {
+    arrange(|entries| {
+        if let Some(entry) = entries.iter_mut().find(|entry| entry.path == path) {
+            entry.paused = paused;
+        }
+    })?;
+    if !paused {
+        wake().notify_one();
+    }
+    Ok(())
+}
+
+/// Hold back everything queued except `path`
+pub fn pause_others(path: &str) -> Result<(), String> {
+    arrange(|entries| {
+        for entry in entries.iter_mut() {
+            entry.paused = entry.path != path;
+        }
+    })
+}
+
+/// Wait until the queue is due to run again, or something was prioritized or resumed
+pub async fn wait_for_next_run() {
+    let _ = tokio::time::timeout(QUEUE_INTERVAL, wake().notified()).await;
+}
+
+/// Upload queued recordings in order while the connection is fast enough for them
+///
+/// High priority recordings upload whatever the connection, except that on a
+/// metered one they wait for Wi-Fi too when the user chose to always queue there;
+/// paused ones are skipped.
+pub async fn drain(access_token: &str) {
+    let metered_uploads = if network::metered().await.is_some() {
+        NetworkSettings::load().metered_uploads
+    } else {
+        MeteredUploads::Upload
+    };
+    let waits_for_wifi = metered_uploads != MeteredUploads::Upload;
+    let priority_waits = metered_uploads == MeteredUploads::Queue;
+    let mut tried: Vec<String> = Vec::new();
+    // The queue is read again after each upload, so changes made meanwhile count
+    loop {
+        let next = entries()
+            .into_iter()
+            .filter(|entry| !entry.paused && !tried.contains(&entry.path))
+            .find(|entry| if entry.high_priority { !priority_waits } else { !waits_for_wifi });
+        let Some(entry) = next else {
+            if waits_for_wifi && entries().iter().any(|entry| !entry.paused && !tried.contains(&entry.path)) {
+                println!("📶 Queued uploads waiting for Wi-Fi");
+            }
+            return;
+        };
+        tried.push(entry.path.clone());
+        if !Path::new(&entry.path).exists() {
+            continue;
+        }
+        if !entry.high_priority {
+            match estimate(access_token, file_size(&entry.path)).await {
+                Ok(estimate) if estimate <= SLOW_UPLOAD => {}
+                // Still slow or unreachable; try again later
+                _ => return,
+            }
+        }
+        if let Err(e) = upload_now(access_token, &entry.path).await {
+            eprintln!("❌ {}", e);
+        }
+    }
+}
+
+/// Upload a queued recording straight away, whatever the connection
+pub async fn upload_now(access_token: &str, path: &str) -> Result<String, String> {
+    println!("📤 Uploading queued recording: {}", path);
+    let title = Path::new(path).file_name().and_then(|n| n.to_str()).map(str::to_string);
+    let file_id = upload::upload_recording(access_token, Path::new(path), title, None)
+        .await
+        .map_err(|e| format!("Queued upload failed: {e}"))?;
+    println!("✅ Queued upload complete! File ID: {}", file_id);
+    Ok(file_id)
+}
+
+/// Access token for queued uploads, renewed first if it has expired
+pub async fn access_token(auth_tokens: &Mutex<Option<auth::AuthTokens>>) -> Option<String> {
+    let tokens = auth_tokens.lock().unwrap().clone()?;
+    if !tokens.is_expired() {
+        return Some(tokens.access_token);
+    }
+    match auth::refresh_access_token(&tokens.refresh_token).await {
+        Ok(new_tokens) => {
+            let _ = auth::save_tokens(&new_tokens);
+            naming::refresh_in_background(new_tokens.access_token.clone());
+            *auth_tokens.lock().unwrap() = Some(new_tokens.clone());
+            Some(new_tokens.access_token)
+        }
+        Err(e) => {
+            eprintln!("⚠️  Token refresh for queued uploads failed: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! The upload shown in the main window
+//!
+//! The capture backend publishes an `UploadState` through a watch channel whenever
+//! the upload moves on, and the status card renders it directly: whether to spin,
+//! succeed or fail comes from the phase, not from the wording shown to the user.
+
+/// Where the upload shown in the main window has got to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UploadPhase {
+    /// Nothing to show
+    #[default]
+    Idle,
+    /// Going through the recording profile's post-processing steps
+    PostProcessing,
+    Preparing,
+    /// Held back until the Mac is plugged in
+    WaitingForPower,
+    Uploading,
+    /// Uploaded; the backend is attaching the meeting details
+    Processing,
+    Complete,
+    Failed,
+    /// The recording did not pass verification and was kept for recovery
+    Unverified,
+    /// Not uploaded because nobody is logged in
+    SignedOut,
+}
+
+/// The upload shown in the main window
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UploadState {
+    pub phase: UploadPhase,
+    /// Share of the file sent while uploading, or of the post-processing done
+    pub percent: u8,
+    /// Post-processing step being run, e.g. "Transcoding (2 of 3)"
+    pub step: Option<String>,
+    /// Storage file of the recording, once complete
+    pub file_id: Option<String>,
+    /// Why the upload failed or the recording was kept back
+    pub error: Option<String>,
+}
+
+impl UploadState {
+    pub fn new(phase: UploadPhase) -> Self {
+        Self { phase, ..Self::default() }
+    }
+
+    /// Failed with `phase` because of `error`
+    pub fn failed(phase: UploadPhase, error: impl Into<String>) -> Self {
+        Self { phase, error: Some(error.into()), ..Self::default() }
+    }
+
+    /// Still on its way: post-processed, preparing, sending or being processed
+    pub fn in_progress(&self) -> bool {
+        matches!(
+            self.phase,
+            UploadPhase::PostProcessing | UploadPhase::Preparing | UploadPhase::Uploading | UploadPhase::Processing
+        )
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self.phase, UploadPhase::Failed | UploadPhase::Unverified | UploadPhase::SignedOut)
+    }
+
+    /// What to tell the user
+    pub fn message(&self) -> String {
+        let error = self.error.as_deref().unwrap_or_default();
+        match self.phase {
+            UploadPhase::Idle => String::new(),
+            UploadPhase::PostProcessing => match self.step {
+                Some(ref step) => format!("{step}: {}% done", self.percent),
+                None => "Processing your recording".to_string(),
+            },
+            UploadPhase::Preparing => "Preparing your recording".to_string(),
+            UploadPhase::WaitingForPower => "Waiting for power to upload (battery low)".to_string(),
+            UploadPhase::Uploading if self.percent < 100 => format!("{}% uploaded", self.percent),
+            UploadPhase::Uploading => "Finalizing upload".to_string(),
+            UploadPhase::Processing => "Processing recording".to_string(),
+            UploadPhase::Complete => "Your recording is ready".to_string(),
+            UploadPhase::Failed => {
+                if error.contains("network") || error.contains("connection") {
+                    "Connection lost. Please try again.".to_string()
+                } else if error.contains("auth") || error.contains("token") {
+                    "Authentication failed. Please log in again.".to_string()
+                } else {
+                    "Something went wrong. Please try again.".to_string()
+                }
+            }
+            UploadPhase::Unverified => format!(
+                "Recording failed verification: {}. The file was kept in Recordings for recovery.",
+                error
+            ),
+            UploadPhase::SignedOut => "Please log in to upload recordings".to_string(),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Per-user data locations
+//!
+//! Everything the recorder keeps for a user (sign-in tokens, settings, the library
+//! index, and the recordings whose sidecars also hold the upload queue) lives in
+//! `~/Library/Application Support/Talka Recorder`, which only its owner can open, and
+//! is written readable by its owner only. The home folder comes from the account
+//! database rather than `$HOME`, so a process started with another user's
+//! environment still reads and writes its own user's files.
+//!
+//! Files left by older versions as `~/.talka_*` and recordings in the shared `/tmp`
+//! are moved in the first time they are asked for; [`relocated`] maps the old
+//! recording paths saved in the session and the library index to the new ones. These
+//! only need the system frameworks, so the standalone auth test builds without the
+//! Objective-C crates. With fast user switching each
+//! logged-in user runs their own copy of the app; [`on_console`] tells whether this
+//! one's session is the one on screen.
+
+use std::ffi::{c_void, CStr};
+use std::fs;
+use std::io::Write;
+use std::os::raw::c_char;
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const APP_FOLDER: &str = "Talka Recorder";
+/// Where recordings were written before they moved to the data folder
+const LEGACY_RECORDINGS_DIR: &str = "/tmp";
+const PRIVATE_DIR: u32 = 0o700;
+const PRIVATE_FILE: u32 = 0o600;
+/// How often the UI checks whether another user has been switched in
+pub const CONSOLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGSessionCopyCurrentDictionary() -> *const c_void;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFStringCreateWithCString(alloc: *const c_void, string: *const c_char, encoding: u32) -> *const c_void;
+    fn CFDictionaryGetValue(dict: *const c_void, key: *const c_void) -> *const c_void;
+    fn CFBooleanGetValue(boolean: *const c_void) -> u8;
+    fn CFRelease(cf: *const c_void);
+}
+
+/// `struct passwd` from `<pwd.h>`
+#[repr(C)]
+struct Passwd {
+    pw_name: *mut c_char,
+    pw_passwd: *mut c_char,
+    pw_uid: u32,
+    pw_gid: u32,
+    pw_change: i64,
+    pw_class: *mut c_char,
+    pw_gecos: *mut c_char,
+    pw_dir: *mut c_char,
+    pw_shell: *mut c_char,
+    pw_expire: i64,
+}
+
+extern "C" {
+    fn getuid() -> u32;
+    fn getpwuid_r(uid: u32, pwd: *mut Passwd, buf: *mut c_char, len: usize, result: *mut *mut Passwd) -> i32;
+}
+
+/// Home folder of the user this process runs as
+fn home_dir() -> PathBuf {
+    let mut pwd = std::mem::MaybeUninit::<Passwd>::uninit();
+    let mut buf = vec![0 as c_char; 4096];
+    let mut result = std::ptr::null_mut();
+    unsafe {
+        let status = getpwuid_r(getuid(), pwd.as_mut_ptr(), buf.as_mut_ptr(), buf.len(), &mut result);
+        if status == 0 && !result.is_null() && !(*result).pw_dir.is_null() {
+            return PathBuf::from(CStr::from_ptr((*result).pw_dir).to_string_lossy().into_owned());
+        }
+    }
+    PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".to_string()))
+}
+
+/// `path` as a directory only its owner can open, created if needed
+fn private_dir(path: PathBuf) -> PathBuf {
+    if let Err(e) = fs::create_dir_all(&path) {
+        eprintln!("⚠️  Failed to create {}: {}", path.display(), e);
+        return path;
+    }
+    match fs::metadata(&path) {
+        Ok(metadata) if metadata.uid() != unsafe { getuid() } => {
+            eprintln!("⚠️  {} belongs to another user", path.display());
+        }
+        Ok(metadata) if metadata.permissions().mode() & 0o777 != PRIVATE_DIR => {
+            if let Err(e) = fs::set_permissions(&path, fs::Permissions::from_mode(PRIVATE_DIR)) {
+                eprintln!("⚠️  Failed to restrict {}: {}", path.display(), e);
+            }
+        }
+        _ => {}
+    }
+    path
+}
+
+/// `~/Library/Application Support/Talka Recorder`
+pub fn data_dir() -> PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        private_dir(home_dir().join("Library").join("Application Support").join(APP_FOLDER))
+    })
+    .clone()
+}
+
+/// File `name` in the data folder, e.g. `tokens.json`
+///
+/// The first time, a `~/.talka_<name>` left by an older version is moved there.
+pub fn file(name: &str) -> PathBuf {
+    let path = data_dir().join(name);
+    let legacy = home_dir().join(format!(".talka_{name}"));
+    if !path.exists() && legacy.exists() {
+        match fs::rename(&legacy, &path) {
+            Ok(()) => {
+                restrict(&path);
+                println!("📦 Moved {} to {}", legacy.display(), path.display());
+            }
+            Err(e) => eprintln!("⚠️  Failed to move {}: {}", legacy.display(), e),
+        }
+    }
+    path
+}
+
+/// Make an existing file readable by its owner only
+pub fn restrict(path: &Path) {
+    if let Err(e) = fs::set_permissions(path, fs::Permissions::from_mode(PRIVATE_FILE)) {
+        eprintln!("⚠️  Failed to restrict {}: {}", path.display(), e);
+    }
+}
+
+/// Write `contents` to `path`, readable by its owner only
+pub fn write_private(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(PRIVATE_FILE)
+        .open(path)?;
+    // `mode` only applies to new files
+    file.set_permissions(fs::Permissions::from_mode(PRIVATE_FILE))?;
+    file.write_all(contents.as_ref())
+}
+
+/// Append `contents` to `path`, creating it readable by its owner only
+pub fn append_private(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(PRIVATE_FILE)
+        .open(path)?;
+    file.write_all(contents.as_ref())
+}
+
+/// Folder recordings, their sidecars and derived files are written to
+pub fn recordings_dir() -> PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = private_dir(data_dir().join("Recordings"));
+        move_legacy_recordings(&dir);
+        dir
+    })
+    .clone()
+}
+
+/// Folder screenshots are saved to
+pub fn screenshots_dir() -> PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| private_dir(data_dir().join("Screenshots"))).clone()
+}
+
+/// Folder Whisper models (`ggml-*.bin`) are picked up from when no profile names one
+pub fn models_dir() -> PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| private_dir(data_dir().join("Models"))).clone()
+}
+
+/// Folder the daily session journals are written to
+pub fn journal_dir() -> PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| private_dir(data_dir().join("Journal"))).clone()
+}
+
+/// Where a recording saved by an older version as `path` lives now
+///
+/// Paths that were not moved out of `/tmp` come back unchanged.
+pub fn relocated(path: &str) -> String {
+    let name = match Path::new(path).strip_prefix(LEGACY_RECORDINGS_DIR) {
+        Ok(name) if name.to_str().is_some_and(|n| n.starts_with("recording_")) => name,
+        _ => return path.to_string(),
+    };
+    let moved = recordings_dir().join(name);
+    if moved.exists() {
+        moved.to_string_lossy().into_owned()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Move this user's `recording_*` files and folders out of the shared `/tmp`
+fn move_legacy_recordings(dir: &Path) {
+    let Ok(entries) = fs::read_dir(LEGACY_RECORDINGS_DIR) else {
+        return;
+    };
+    let uid = unsafe { getuid() };
+    let mut moved = 0;
+    for entry in entries.filter_map(Result::ok) {
+        let name = entry.file_name();
+        let owned = entry.metadata().is_ok_and(|m| m.uid() == uid);
+        if !owned || !name.to_str().is_some_and(|n| n.starts_with("recording_")) {
+            continue;
+        }
+        match fs::rename(entry.path(), dir.join(&name)) {
+            Ok(()) => moved += 1,
+            Err(e) => eprintln!("⚠️  Failed to move {}: {}", entry.path().display(), e),
+        }
+    }
+    if moved > 0 {
+        println!("📦 Moved {} recording files from {} to {}", moved, LEGACY_RECORDINGS_DIR, dir.display());
+    }
+}
+
+/// Whether this user's login session is the one on screen
+///
+/// False while another user is switched in.
+pub fn on_console() -> bool {
+    unsafe {
+        let session = CGSessionCopyCurrentDictionary();
+        if session.is_null() {
+            // Without session information, don't get in the way of capturing
+            return true;
+        }
+        let key = CFStringCreateWithCString(
+            std::ptr::null(),
+            b"kCGSSessionOnConsoleKey\0".as_ptr().cast(),
+            CF_STRING_ENCODING_UTF8,
+        );
+        let value = if key.is_null() { std::ptr::null() } else { CFDictionaryGetValue(session, key) };
+        let on_console = value.is_null() || CFBooleanGetValue(value) != 0;
+        if !key.is_null() {
+            CFRelease(key);
+        }
+        CFRelease(session);
+        on_console
+    }
+}
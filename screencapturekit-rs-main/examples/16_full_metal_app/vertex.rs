@@ -10,7 +10,7 @@
 
 use std::mem::size_of;
 
-use metal::{Buffer, Device, MTLResourceOptions};
+use metal::{Buffer, BufferRef, CommandBuffer, CommandBufferRef, Device, MTLResourceOptions, NSRange};
 
 use crate::font::BitmapFont;
 
@@ -38,7 +38,45 @@ pub struct Uniforms {
     pub texture_size: [f32; 2],
     pub time: f32,
     pub pixel_format: u32,
-    pub _padding: [f32; 2],
+    /// Half-extent of the captured frame's quad in NDC (see [`letterbox_rect`])
+    pub content_scale: [f32; 2],
+}
+
+impl Uniforms {
+    pub fn new(viewport_size: [f32; 2], texture_size: [f32; 2], time: f32, pixel_format: u32) -> Self {
+        let [_, _, w, h] = letterbox_rect(viewport_size, texture_size);
+        let content_scale = if viewport_size[0] > 0.0 && viewport_size[1] > 0.0 {
+            [w / viewport_size[0], h / viewport_size[1]]
+        } else {
+            [1.0, 1.0]
+        };
+        Self {
+            viewport_size,
+            texture_size,
+            time,
+            pixel_format,
+            content_scale,
+        }
+    }
+}
+
+/// Largest rectangle with the texture's aspect ratio that fits in the viewport
+///
+/// Returns `[x, y, width, height]` in viewport pixels, centered and snapped to whole
+/// pixels, leaving letterbox (top/bottom) or pillarbox (left/right) bars as needed.
+pub fn letterbox_rect(viewport_size: [f32; 2], texture_size: [f32; 2]) -> [f32; 4] {
+    let [vw, vh] = viewport_size;
+    let [tw, th] = texture_size;
+    if vw <= 0.0 || vh <= 0.0 || tw <= 0.0 || th <= 0.0 {
+        return [0.0, 0.0, vw.max(0.0), vh.max(0.0)];
+    }
+
+    let scale = (vw / tw).min(vh / th);
+    let w = (tw * scale).round().min(vw);
+    let h = (th * scale).round().min(vh);
+    let x = ((vw - w) / 2.0).floor();
+    let y = ((vh - h) / 2.0).floor();
+    [x, y, w, h]
 }
 
 pub struct VertexBufferBuilder {
@@ -219,4 +257,99 @@ impl VertexBufferBuilder {
     pub fn vertex_count(&self) -> usize {
         self.vertices.len()
     }
+
+    /// Copy the vertices into `buffer`, reallocating only when it is too small
+    pub fn write_into(&self, device: &Device, buffer: &mut Option<Buffer>) {
+        let byte_len = (self.vertices.len() * size_of::<Vertex>()) as u64;
+        if byte_len == 0 {
+            return;
+        }
+        if buffer.as_ref().map_or(true, |b| b.length() < byte_len) {
+            // Grow geometrically so a slowly growing overlay doesn't reallocate every frame
+            *buffer = Some(device.new_buffer(
+                byte_len.next_power_of_two(),
+                MTLResourceOptions::CPUCacheModeDefaultCache | MTLResourceOptions::StorageModeManaged,
+            ));
+        }
+        let target = buffer.as_ref().unwrap();
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                self.vertices.as_ptr().cast::<u8>(),
+                target.contents().cast::<u8>(),
+                byte_len as usize,
+            );
+        }
+        target.did_modify_range(NSRange::new(0, byte_len));
+    }
+}
+
+/// Number of frames the CPU may encode ahead of the GPU
+pub const FRAMES_IN_FLIGHT: usize = 3;
+
+struct FrameSlot {
+    uniforms: Buffer,
+    vertices: Option<Buffer>,
+    in_flight: Option<CommandBuffer>,
+}
+
+/// Ring of per-frame uniform and vertex buffers reused across redraws
+///
+/// Before a slot is rewritten the command buffer that last read it is waited on, so the
+/// CPU never overwrites data the GPU is still using.
+pub struct FrameBuffers {
+    slots: Vec<FrameSlot>,
+    current: usize,
+}
+
+impl FrameBuffers {
+    pub fn new(device: &Device) -> Self {
+        let slots = (0..FRAMES_IN_FLIGHT)
+            .map(|_| FrameSlot {
+                uniforms: device.new_buffer(
+                    size_of::<Uniforms>() as u64,
+                    MTLResourceOptions::CPUCacheModeDefaultCache | MTLResourceOptions::StorageModeShared,
+                ),
+                vertices: None,
+                in_flight: None,
+            })
+            .collect();
+        Self { slots, current: 0 }
+    }
+
+    /// Advance to the next slot and fill it with this frame's data
+    ///
+    /// Returns the uniforms buffer and, if the builder has any vertices, the vertex buffer.
+    pub fn next_frame(
+        &mut self,
+        device: &Device,
+        uniforms: &Uniforms,
+        vertices: &VertexBufferBuilder,
+    ) -> (&BufferRef, Option<&BufferRef>) {
+        self.current = (self.current + 1) % self.slots.len();
+        let slot = &mut self.slots[self.current];
+        if let Some(cmd) = slot.in_flight.take() {
+            cmd.wait_until_completed();
+        }
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                std::ptr::addr_of!(*uniforms),
+                slot.uniforms.contents().cast::<Uniforms>(),
+                1,
+            );
+        }
+        vertices.write_into(device, &mut slot.vertices);
+
+        let vertex_buffer = if vertices.vertex_count() > 0 {
+            slot.vertices.as_deref()
+        } else {
+            None
+        };
+        (&slot.uniforms, vertex_buffer)
+    }
+
+    /// Remember the command buffer reading the current slot
+    pub fn track(&mut self, cmd_buffer: &CommandBufferRef) {
+        self.slots[self.current].in_flight = Some(cmd_buffer.to_owned());
+    }
 }
@@ -0,0 +1,185 @@
+//! Outbound webhook fired when an upload completes
+//!
+//! Posts a JSON payload built from a user-editable template, so a Slack incoming
+//! webhook (or any other endpoint) can announce new recordings in a team channel.
+//! Settings live in `webhook.json` in the data folder; with no URL configured nothing
+//! is sent.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::sidecar::RecordingSidecar;
+use crate::talka_api;
+use crate::time_format;
+use crate::user_data;
+
+/// Slack-compatible default payload
+pub const DEFAULT_TEMPLATE: &str = r#"{"text": "🎬 New recording: {{title}} ({{duration}})\n{{url}}"}"#;
+
+/// Placeholders recognised in templates
+pub const PLACEHOLDERS: [&str; 5] = ["{{url}}", "{{file_id}}", "{{title}}", "{{file_name}}", "{{duration}}"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    /// Endpoint to POST to; empty disables the webhook
+    pub url: String,
+    /// JSON body, with `{{placeholder}}`s filled in per upload
+    pub template: String,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            template: DEFAULT_TEMPLATE.to_string(),
+        }
+    }
+}
+
+impl WebhookSettings {
+    fn config_path() -> PathBuf {
+        user_data::file("webhook.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::config_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save after checking that the URL and template are usable
+    pub fn save(&self) -> Result<(), String> {
+        let url = self.url.trim();
+        // The payload describes a meeting, so it is never sent in the clear
+        if !url.is_empty() && !url.starts_with("https://") {
+            return Err("Webhook URL must start with https://".to_string());
+        }
+        render(&self.template, &Recording::example())?;
+
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize webhook settings: {e}"))?;
+        user_data::write_private(&Self::config_path(), json)
+            .map_err(|e| format!("Failed to save webhook settings: {e}"))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.url.trim().is_empty()
+    }
+}
+
+/// An uploaded recording, as described to the webhook
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub file_id: String,
+    pub file_name: String,
+    pub title: String,
+    pub duration_seconds: Option<f64>,
+}
+
+impl Recording {
+    /// Describe the recording at `path`, using its sidecar for the meeting title
+    pub fn uploaded(path: &str, file_id: &str) -> Self {
+        let sidecar = RecordingSidecar::load(path);
+        let file_name = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let title = sidecar
+            .as_ref()
+            .and_then(|s| s.calendar_event.as_ref())
+            .map(|e| e.title.clone())
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| file_name.clone());
+        Self {
+            file_id: file_id.to_string(),
+            file_name,
+            title,
+            duration_seconds: sidecar.and_then(|s| s.duration_seconds),
+        }
+    }
+
+    fn example() -> Self {
+        Self {
+            file_id: "example".to_string(),
+            file_name: "recording_example.mp4".to_string(),
+            title: "Weekly sync".to_string(),
+            duration_seconds: Some(1800.0),
+        }
+    }
+
+    pub fn insights_url(&self) -> String {
+        format!("https://insights.talka.ai/activity/meeting?fileId={}", self.file_id)
+    }
+
+    fn duration_text(&self) -> String {
+        match self.duration_seconds {
+            Some(secs) => time_format::offset(secs.round() as u64),
+            None => "unknown length".to_string(),
+        }
+    }
+}
+
+/// Escape `value` for use inside a JSON string literal
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Fill in the template, failing if the result is not valid JSON
+fn render(template: &str, recording: &Recording) -> Result<String, String> {
+    let values = [
+        recording.insights_url(),
+        recording.file_id.clone(),
+        recording.title.clone(),
+        recording.file_name.clone(),
+        recording.duration_text(),
+    ];
+    let body = PLACEHOLDERS
+        .iter()
+        .zip(values.iter())
+        .fold(template.to_string(), |body, (placeholder, value)| {
+            body.replace(placeholder, &json_escape(value))
+        });
+    serde_json::from_str::<serde_json::Value>(&body)
+        .map_err(|e| format!("Webhook template is not valid JSON: {e}"))?;
+    Ok(body)
+}
+
+/// POST the payload for `recording`
+pub async fn send(settings: &WebhookSettings, recording: &Recording) -> Result<(), String> {
+    if !settings.url.trim().starts_with("https://") {
+        return Err("Webhook URL must start with https://".to_string());
+    }
+    let body = render(&settings.template, recording)?;
+    let response = talka_api::http()
+        .post(settings.url.trim())
+        .header("Content-Type", "application/json")
+        .body(body)
+        .timeout(std::time::Duration::from_secs(15))
+        .send()
+        .await
+        .map_err(|e| format!("Webhook request failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Webhook returned HTTP {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Send an example payload so the user can check their channel
+pub async fn send_test(settings: &WebhookSettings) -> Result<(), String> {
+    send(settings, &Recording::example()).await
+}
+
+/// Announce a finished upload if a webhook is configured
+pub async fn notify_upload_complete(path: &str, file_id: &str) {
+    let settings = WebhookSettings::load();
+    if !settings.is_enabled() {
+        return;
+    }
+    match send(&settings, &Recording::uploaded(path, file_id)).await {
+        Ok(()) => println!("📣 Webhook notified for {}", file_id),
+        Err(e) => eprintln!("⚠️  {}", e),
+    }
+}
@@ -296,6 +296,16 @@ extern "C" {
         sample_buffer_out: *mut *mut std::ffi::c_void,
     ) -> i32;
 
+    pub fn cm_sample_buffer_create_for_pcm_audio(
+        samples: *const f32,
+        frame_count: isize,
+        channel_count: u32,
+        sample_rate: f64,
+        presentation_time_value: i64,
+        presentation_time_scale: i32,
+        sample_buffer_out: *mut *mut std::ffi::c_void,
+    ) -> i32;
+
     pub fn io_surface_get_width(surface: *mut std::ffi::c_void) -> usize;
     pub fn io_surface_get_height(surface: *mut std::ffi::c_void) -> usize;
     pub fn io_surface_get_bytes_per_row(surface: *mut std::ffi::c_void) -> usize;
@@ -108,6 +108,68 @@ impl CMSampleBuffer {
         }
     }
 
+    /// Create a sample buffer of interleaved 32-bit float PCM audio
+    ///
+    /// The format matches the system audio `ScreenCaptureKit` delivers, so audio
+    /// handlers can be tested without capturing.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Interleaved samples, `channel_count` per frame
+    /// * `channel_count` - Number of channels
+    /// * `sample_rate` - Frames per second
+    /// * `presentation_time` - When the first frame should be presented
+    ///
+    /// # Errors
+    ///
+    /// Returns `-50` (`kAudio_ParamError`) if `samples` is empty or does not hold
+    /// whole frames, or a Core Media error code if the sample buffer creation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use screencapturekit::cm::{CMSampleBuffer, CMTime};
+    ///
+    /// // 10 ms of stereo silence at 48 kHz
+    /// let samples = vec![0.0f32; 480 * 2];
+    /// let sample = CMSampleBuffer::create_for_pcm_audio(&samples, 2, 48_000.0, CMTime::new(0, 48_000))
+    ///     .expect("Failed to create sample buffer");
+    ///
+    /// assert_eq!(sample.num_samples(), 480);
+    /// assert!(sample.audio_buffer_list().is_some());
+    /// ```
+    pub fn create_for_pcm_audio(
+        samples: &[f32],
+        channel_count: u32,
+        sample_rate: f64,
+        presentation_time: CMTime,
+    ) -> Result<Self, i32> {
+        const PARAM_ERROR: i32 = -50;
+        let channels = channel_count as usize;
+        if channels == 0 || samples.is_empty() || samples.len() % channels != 0 {
+            return Err(PARAM_ERROR);
+        }
+        let frame_count = isize::try_from(samples.len() / channels).map_err(|_| PARAM_ERROR)?;
+        unsafe {
+            let mut sample_buffer_ptr: *mut std::ffi::c_void = std::ptr::null_mut();
+            let status = ffi::cm_sample_buffer_create_for_pcm_audio(
+                samples.as_ptr(),
+                frame_count,
+                channel_count,
+                sample_rate,
+                presentation_time.value,
+                presentation_time.timescale,
+                &mut sample_buffer_ptr,
+            );
+
+            if status == 0 && !sample_buffer_ptr.is_null() {
+                Ok(Self(sample_buffer_ptr))
+            } else {
+                Err(status)
+            }
+        }
+    }
+
     /// Get the image buffer (pixel buffer) from this sample
     pub fn image_buffer(&self) -> Option<CVPixelBuffer> {
         unsafe {
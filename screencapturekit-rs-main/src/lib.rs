@@ -11,6 +11,7 @@
 //! - **Screen and window capture** - Capture displays, windows, or specific applications
 //! - **Audio capture** - System audio and microphone input (macOS 13.0+)
 //! - **Real-time frame processing** - High-performance callbacks with custom dispatch queues
+//! - **Frame and audio subscribers** - Attach any number of callbacks and [recording sinks](recording_sink) to a capture with [`recorder::Recorder`]
 //! - **Async support** - Runtime-agnostic async API (Tokio, async-std, smol, etc.)
 //! - **Zero-copy GPU access** - Direct `IOSurface` access for Metal/OpenGL integration
 //! - **Screenshots** - Single-frame capture without streaming (macOS 14.0+)
@@ -336,8 +337,10 @@ pub mod dispatch_queue;
 pub mod error;
 pub mod ffi;
 pub mod output;
+pub mod recorder;
 #[cfg(feature = "macos_15_0")]
 pub mod recording_output;
+pub mod recording_sink;
 pub mod screenshot_manager;
 pub mod shareable_content;
 pub mod stream;
@@ -0,0 +1,456 @@
+//! Frame and audio subscriptions on top of a capture stream
+//!
+//! [`Recorder`] owns an [`SCStream`] and fans its output out to any number of
+//! subscribers, so custom processing (ML, OCR, streaming) can be attached to a
+//! capture session without writing an [`SCStreamOutputTrait`] implementation:
+//!
+//! - [`Recorder::on_frame`] is called with the `IOSurface` and presentation time of
+//!   every new video frame
+//! - [`Recorder::on_audio`] is called with the audio buffers, presentation time and
+//!   source (system audio or microphone) of every audio sample
+//!
+//! Subscribers can be added and removed at any time, including while capturing.
+//! They are called on the stream's output queue, so they should return quickly and
+//! must not panic.
+//!
+//! [`Subscribers`] is the dispatching part on its own, for use as the output handler
+//! of a stream set up by hand. Outputs with a lifecycle of their own, such as a file
+//! or a live stream, are attached as [sinks](crate::recording_sink).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use screencapturekit::prelude::*;
+//! use screencapturekit::recorder::Recorder;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let content = SCShareableContent::get()?;
+//! let display = &content.displays()[0];
+//! let filter = SCContentFilter::builder().display(display).exclude_windows(&[]).build();
+//! let config = SCStreamConfiguration::new()
+//!     .with_width(1920)
+//!     .with_height(1080)
+//!     .with_captures_audio(true);
+//!
+//! let recorder = Recorder::new(&filter, &config);
+//! recorder.on_frame(|surface, timestamp| {
+//!     println!("{}x{} frame at {:?}", surface.width(), surface.height(), timestamp);
+//! });
+//! recorder.on_audio(|buffers, _timestamp, source| {
+//!     println!("{} {} buffer(s)", buffers.num_buffers(), source);
+//! });
+//!
+//! recorder.start_capture()?;
+//! // ... capture runs in background ...
+//! recorder.stop_capture()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::cm::{AudioBufferList, CMSampleBuffer, CMTime};
+use crate::error::{SCError, SCResult};
+use crate::output::{CVPixelBufferIOSurface, IOSurface};
+use crate::recording_sink::{RecordingSink, SinkId, SinkSlot, SinkStatus};
+use crate::stream::{
+    configuration::SCStreamConfiguration, content_filter::SCContentFilter,
+    output_trait::SCStreamOutputTrait, output_type::SCStreamOutputType, sc_stream::SCStream,
+};
+
+/// Callback for new video frames: the frame's surface and presentation time
+pub type FrameCallback = dyn Fn(&IOSurface, CMTime) + Send + Sync;
+
+/// Callback for audio samples: the buffers, presentation time and source
+pub type AudioCallback = dyn Fn(&AudioBufferList, CMTime, SCStreamOutputType) + Send + Sync;
+
+/// Identifies a subscription, to remove it with [`Subscribers::unsubscribe`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type List<T> = RwLock<Vec<(SubscriptionId, Arc<T>)>>;
+
+#[derive(Default)]
+struct Inner {
+    next_id: AtomicU64,
+    frames: List<FrameCallback>,
+    audio: List<AudioCallback>,
+}
+
+/// Frame and audio subscribers of a capture stream
+///
+/// Cloning gives another handle to the same subscribers. As an
+/// [`SCStreamOutputTrait`] it passes each sample on to the matching subscribers;
+/// video samples without an image (idle or blank frames) are not passed on.
+///
+/// # Examples
+///
+/// ```
+/// use screencapturekit::recorder::Subscribers;
+///
+/// let subscribers = Subscribers::new();
+/// let id = subscribers.on_frame(|_surface, _timestamp| {});
+/// assert_eq!(subscribers.frame_subscriber_count(), 1);
+///
+/// assert!(subscribers.unsubscribe(id));
+/// assert_eq!(subscribers.frame_subscriber_count(), 0);
+/// ```
+#[derive(Clone, Default)]
+pub struct Subscribers {
+    inner: Arc<Inner>,
+}
+
+impl Subscribers {
+    /// Create an empty set of subscribers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn next_id(&self) -> SubscriptionId {
+        SubscriptionId(self.inner.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Call `callback` with every new video frame
+    ///
+    /// # Panics
+    ///
+    /// Panics if the subscriber list lock is poisoned.
+    pub fn on_frame(
+        &self,
+        callback: impl Fn(&IOSurface, CMTime) + Send + Sync + 'static,
+    ) -> SubscriptionId {
+        let id = self.next_id();
+        self.inner
+            .frames
+            .write()
+            .unwrap()
+            .push((id, Arc::new(callback)));
+        id
+    }
+
+    /// Call `callback` with every audio sample, from system audio and the microphone
+    ///
+    /// # Panics
+    ///
+    /// Panics if the subscriber list lock is poisoned.
+    pub fn on_audio(
+        &self,
+        callback: impl Fn(&AudioBufferList, CMTime, SCStreamOutputType) + Send + Sync + 'static,
+    ) -> SubscriptionId {
+        let id = self.next_id();
+        self.inner
+            .audio
+            .write()
+            .unwrap()
+            .push((id, Arc::new(callback)));
+        id
+    }
+
+    /// Remove a subscription, returning whether it was still subscribed
+    ///
+    /// A callback already running on the output queue finishes first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a subscriber list lock is poisoned.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        fn remove<T: ?Sized>(list: &List<T>, id: SubscriptionId) -> bool {
+            let mut list = list.write().unwrap();
+            let before = list.len();
+            list.retain(|(subscribed, _)| *subscribed != id);
+            list.len() != before
+        }
+        remove(&self.inner.frames, id) || remove(&self.inner.audio, id)
+    }
+
+    /// Number of frame subscribers
+    ///
+    /// # Panics
+    ///
+    /// Panics if the subscriber list lock is poisoned.
+    pub fn frame_subscriber_count(&self) -> usize {
+        self.inner.frames.read().unwrap().len()
+    }
+
+    /// Number of audio subscribers
+    ///
+    /// # Panics
+    ///
+    /// Panics if the subscriber list lock is poisoned.
+    pub fn audio_subscriber_count(&self) -> usize {
+        self.inner.audio.read().unwrap().len()
+    }
+
+    /// Current callbacks, so none is called with the lock held and callbacks may
+    /// subscribe or unsubscribe
+    fn snapshot<T: ?Sized>(list: &List<T>) -> Vec<Arc<T>> {
+        list.read()
+            .unwrap()
+            .iter()
+            .map(|(_, callback)| Arc::clone(callback))
+            .collect()
+    }
+}
+
+impl SCStreamOutputTrait for Subscribers {
+    fn did_output_sample_buffer(&self, sample_buffer: CMSampleBuffer, of_type: SCStreamOutputType) {
+        match of_type {
+            SCStreamOutputType::Screen => {
+                let callbacks = Self::snapshot(&self.inner.frames);
+                if callbacks.is_empty() {
+                    return;
+                }
+                let Some(surface) = sample_buffer
+                    .image_buffer()
+                    .and_then(|pixel_buffer| pixel_buffer.iosurface())
+                else {
+                    return;
+                };
+                let timestamp = sample_buffer.presentation_timestamp();
+                for callback in callbacks {
+                    callback(&surface, timestamp);
+                }
+            }
+            SCStreamOutputType::Audio | SCStreamOutputType::Microphone => {
+                let callbacks = Self::snapshot(&self.inner.audio);
+                if callbacks.is_empty() {
+                    return;
+                }
+                let Some(buffers) = sample_buffer.audio_buffer_list() else {
+                    return;
+                };
+                let timestamp = sample_buffer.presentation_timestamp();
+                for callback in callbacks {
+                    callback(&buffers, timestamp, of_type);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscribers")
+            .field("frames", &self.frame_subscriber_count())
+            .field("audio", &self.audio_subscriber_count())
+            .finish()
+    }
+}
+
+/// A sink added to a recorder, with the subscriptions feeding it
+struct AttachedSink {
+    id: SinkId,
+    slot: Arc<Mutex<SinkSlot>>,
+    subscriptions: Vec<SubscriptionId>,
+}
+
+/// A capture stream with frame and audio subscribers and recording sinks
+///
+/// See the [module documentation](self) for an example.
+pub struct Recorder {
+    stream: SCStream,
+    subscribers: Subscribers,
+    sinks: Mutex<Vec<AttachedSink>>,
+    next_sink_id: AtomicU64,
+}
+
+impl Recorder {
+    /// Create a recorder for `filter` with `configuration`, ready to start
+    ///
+    /// Audio subscribers only receive samples when `configuration` captures audio
+    /// or the microphone.
+    pub fn new(filter: &SCContentFilter, configuration: &SCStreamConfiguration) -> Self {
+        let subscribers = Subscribers::new();
+        let mut stream = SCStream::new(filter, configuration);
+        stream.add_output_handler(subscribers.clone(), SCStreamOutputType::Screen);
+        stream.add_output_handler(subscribers.clone(), SCStreamOutputType::Audio);
+        #[cfg(feature = "macos_15_0")]
+        stream.add_output_handler(subscribers.clone(), SCStreamOutputType::Microphone);
+        Self {
+            stream,
+            subscribers,
+            sinks: Mutex::new(Vec::new()),
+            next_sink_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Call `callback` with every new video frame
+    ///
+    /// See [`Subscribers::on_frame`].
+    pub fn on_frame(
+        &self,
+        callback: impl Fn(&IOSurface, CMTime) + Send + Sync + 'static,
+    ) -> SubscriptionId {
+        self.subscribers.on_frame(callback)
+    }
+
+    /// Call `callback` with every audio sample
+    ///
+    /// See [`Subscribers::on_audio`].
+    pub fn on_audio(
+        &self,
+        callback: impl Fn(&AudioBufferList, CMTime, SCStreamOutputType) + Send + Sync + 'static,
+    ) -> SubscriptionId {
+        self.subscribers.on_audio(callback)
+    }
+
+    /// Remove a subscription, returning whether it was still subscribed
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        self.subscribers.unsubscribe(id)
+    }
+
+    /// The subscribers, e.g. to hand to code that only adds and removes callbacks
+    pub fn subscribers(&self) -> &Subscribers {
+        &self.subscribers
+    }
+
+    /// The underlying stream, e.g. to update its configuration or filter
+    pub fn stream(&self) -> &SCStream {
+        &self.stream
+    }
+
+    /// Start capturing
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from [`SCStream::start_capture`] if the capture fails to start.
+    pub fn start_capture(&self) -> Result<(), SCError> {
+        self.stream.start_capture()
+    }
+
+    /// Stop capturing; subscribers and sinks stay attached for the next start
+    ///
+    /// # Errors
+    ///
+    /// Returns `SCError::CaptureStopFailed` if the capture fails to stop.
+    pub fn stop_capture(&self) -> Result<(), SCError> {
+        self.stream.stop_capture()
+    }
+
+    /// Start `sink` and send it this recorder's output until it is removed
+    ///
+    /// Sinks can be added before or during capture.
+    ///
+    /// # Errors
+    ///
+    /// Returns the sink's error if it fails to start; it is not added then.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sink list lock is poisoned.
+    pub fn add_sink(&self, mut sink: impl RecordingSink + 'static) -> SCResult<SinkId> {
+        sink.start(&self.stream)?;
+        let receives_samples = sink.receives_samples();
+        let slot = Arc::new(Mutex::new(SinkSlot::new(Box::new(sink))));
+
+        let mut subscriptions = Vec::new();
+        if receives_samples {
+            let frames = Arc::clone(&slot);
+            subscriptions.push(self.subscribers.on_frame(move |surface, timestamp| {
+                if let Ok(mut slot) = frames.lock() {
+                    slot.write_frame(surface, timestamp);
+                }
+            }));
+            let audio = Arc::clone(&slot);
+            subscriptions.push(
+                self.subscribers
+                    .on_audio(move |buffers, timestamp, source| {
+                        if let Ok(mut slot) = audio.lock() {
+                            slot.write_audio(buffers, timestamp, source);
+                        }
+                    }),
+            );
+        }
+
+        let id = SinkId(self.next_sink_id.fetch_add(1, Ordering::Relaxed));
+        self.sinks.lock().unwrap().push(AttachedSink {
+            id,
+            slot,
+            subscriptions,
+        });
+        Ok(id)
+    }
+
+    /// Stop a sink and detach it; the other sinks are not affected
+    ///
+    /// # Errors
+    ///
+    /// Returns `SCError::InvalidConfiguration` if the sink is not attached, or the
+    /// sink's error if it fails to stop cleanly. Either way it is detached.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sink list lock is poisoned.
+    pub fn remove_sink(&self, id: SinkId) -> SCResult<()> {
+        let mut sinks = self.sinks.lock().unwrap();
+        let index = sinks.iter().position(|attached| attached.id == id);
+        let attached = index.map(|index| sinks.remove(index));
+        drop(sinks);
+        let attached = attached
+            .ok_or_else(|| SCError::InvalidConfiguration(format!("No sink {id:?} attached")))?;
+        self.detach(attached)
+    }
+
+    fn detach(&self, attached: AttachedSink) -> SCResult<()> {
+        for subscription in attached.subscriptions {
+            self.subscribers.unsubscribe(subscription);
+        }
+        let mut slot = attached
+            .slot
+            .lock()
+            .map_err(|_| SCError::InternalError("Sink panicked".to_string()))?;
+        slot.stop(&self.stream)
+    }
+
+    /// Status of an attached sink, `None` if it is not attached
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sink list lock is poisoned.
+    pub fn sink_status(&self, id: SinkId) -> Option<SinkStatus> {
+        let sinks = self.sinks.lock().unwrap();
+        let slot = Arc::clone(&sinks.iter().find(|attached| attached.id == id)?.slot);
+        drop(sinks);
+        let status = slot.lock().map_or_else(
+            |_| SinkStatus::Failed(SCError::InternalError("Sink panicked".to_string())),
+            |slot| slot.status(),
+        );
+        Some(status)
+    }
+
+    /// Ids of the attached sinks, in the order they were added
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sink list lock is poisoned.
+    pub fn sink_ids(&self) -> Vec<SinkId> {
+        self.sinks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|attached| attached.id)
+            .collect()
+    }
+}
+
+impl Drop for Recorder {
+    /// Stops the sinks still attached, so files are finished
+    fn drop(&mut self) {
+        let sinks = std::mem::take(self.sinks.get_mut().unwrap_or_else(|e| e.into_inner()));
+        for attached in sinks {
+            let _ = self.detach(attached);
+        }
+    }
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder")
+            .field("stream", &self.stream)
+            .field("subscribers", &self.subscribers)
+            .field("sinks", &self.sink_ids().len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,342 @@
+//! Outputs a capture session records to
+//!
+//! A [`RecordingSink`] is one destination for a capture: a local file, a live
+//! stream to a server, or custom processing of the frames. Any number of sinks can be
+//! attached to one [`Recorder`](crate::recorder::Recorder) at the same time, each
+//! with its own lifecycle: a sink is started when it is added and stopped when it is
+//! removed (or the recorder is dropped), whether or not the stream is capturing.
+//!
+//! Errors stay with the sink that caused them. A sink whose `write_frame` or
+//! `write_audio` fails stops receiving samples and reports
+//! [`SinkStatus::Failed`]; the capture and the other sinks carry on.
+//!
+//! Two sinks come with the crate:
+//!
+//! - [`CallbackSink`] passes frames and audio to closures
+//! - `FileSink` records to a file with `SCRecordingOutput` (macOS 15.0+)
+//!
+//! The crate has no streaming sink; other destinations, such as RTMP, implement the
+//! trait themselves.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use screencapturekit::prelude::*;
+//! use screencapturekit::recorder::Recorder;
+//! use screencapturekit::recording_sink::CallbackSink;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let content = SCShareableContent::get()?;
+//! # let display = &content.displays()[0];
+//! # let filter = SCContentFilter::builder().display(display).build();
+//! # let config = SCStreamConfiguration::default();
+//! let recorder = Recorder::new(&filter, &config);
+//! let sink = recorder.add_sink(CallbackSink::new().on_frame(|surface, _timestamp| {
+//!     println!("{}x{} frame", surface.width(), surface.height());
+//! }))?;
+//!
+//! recorder.start_capture()?;
+//! // ... capture runs in background ...
+//! recorder.stop_capture()?;
+//! recorder.remove_sink(sink)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use crate::cm::{AudioBufferList, CMTime};
+use crate::error::{SCError, SCResult};
+use crate::output::IOSurface;
+use crate::stream::{output_type::SCStreamOutputType, sc_stream::SCStream};
+
+/// One destination for a capture session
+///
+/// Every method has a default that does nothing, so a sink only implements what it
+/// needs. `write_frame` and `write_audio` are called on the stream's output queue
+/// and should return quickly.
+pub trait RecordingSink: Send {
+    /// Name used in errors and logs, e.g. a file path or server URL
+    fn name(&self) -> String;
+
+    /// Whether the sink wants [`write_frame`](Self::write_frame) and
+    /// [`write_audio`](Self::write_audio) calls
+    ///
+    /// Sinks that attach to the stream themselves (such as `FileSink`) return
+    /// `false`, which saves extracting samples for them.
+    fn receives_samples(&self) -> bool {
+        true
+    }
+
+    /// Get ready to record from `stream`; called once, when the sink is added
+    ///
+    /// # Errors
+    ///
+    /// An error here keeps the sink from being added.
+    fn start(&mut self, _stream: &SCStream) -> SCResult<()> {
+        Ok(())
+    }
+
+    /// Record a video frame
+    ///
+    /// # Errors
+    ///
+    /// An error stops the sink from receiving further samples.
+    fn write_frame(&mut self, _surface: &IOSurface, _timestamp: CMTime) -> SCResult<()> {
+        Ok(())
+    }
+
+    /// Record an audio sample from system audio or the microphone
+    ///
+    /// # Errors
+    ///
+    /// An error stops the sink from receiving further samples.
+    fn write_audio(
+        &mut self,
+        _buffers: &AudioBufferList,
+        _timestamp: CMTime,
+        _source: SCStreamOutputType,
+    ) -> SCResult<()> {
+        Ok(())
+    }
+
+    /// Finish recording from `stream`; called once, when the sink is removed
+    ///
+    /// Also called for sinks that failed, so they can release what they hold.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recording could not be finished cleanly.
+    fn stop(&mut self, _stream: &SCStream) -> SCResult<()> {
+        Ok(())
+    }
+}
+
+/// Identifies a sink added to a recorder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SinkId(pub(crate) u64);
+
+/// State of a sink attached to a recorder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinkStatus {
+    /// Receiving samples
+    Running,
+    /// Stopped receiving samples after this error
+    Failed(SCError),
+}
+
+impl fmt::Display for SinkStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Running => write!(f, "running"),
+            Self::Failed(error) => write!(f, "failed: {error}"),
+        }
+    }
+}
+
+/// A sink and its status, shared between the recorder and its subscriptions
+pub(crate) struct SinkSlot {
+    sink: Box<dyn RecordingSink>,
+    error: Option<SCError>,
+}
+
+impl SinkSlot {
+    pub(crate) fn new(sink: Box<dyn RecordingSink>) -> Self {
+        Self { sink, error: None }
+    }
+
+    pub(crate) fn status(&self) -> SinkStatus {
+        self.error
+            .clone()
+            .map_or(SinkStatus::Running, SinkStatus::Failed)
+    }
+
+    pub(crate) fn write_frame(&mut self, surface: &IOSurface, timestamp: CMTime) {
+        if self.error.is_none() {
+            if let Err(error) = self.sink.write_frame(surface, timestamp) {
+                self.error = Some(error);
+            }
+        }
+    }
+
+    pub(crate) fn write_audio(
+        &mut self,
+        buffers: &AudioBufferList,
+        timestamp: CMTime,
+        source: SCStreamOutputType,
+    ) {
+        if self.error.is_none() {
+            if let Err(error) = self.sink.write_audio(buffers, timestamp, source) {
+                self.error = Some(error);
+            }
+        }
+    }
+
+    pub(crate) fn stop(&mut self, stream: &SCStream) -> SCResult<()> {
+        self.sink.stop(stream)
+    }
+}
+
+type FrameFn = Box<dyn FnMut(&IOSurface, CMTime) + Send>;
+type AudioFn = Box<dyn FnMut(&AudioBufferList, CMTime, SCStreamOutputType) + Send>;
+
+/// Sink that passes frames and audio to closures
+///
+/// # Examples
+///
+/// ```
+/// use screencapturekit::recording_sink::{CallbackSink, RecordingSink};
+///
+/// let mut frames = 0;
+/// let sink = CallbackSink::new()
+///     .with_name("frame counter")
+///     .on_frame(move |_surface, _timestamp| frames += 1);
+/// assert_eq!(sink.name(), "frame counter");
+/// ```
+#[derive(Default)]
+pub struct CallbackSink {
+    name: Option<String>,
+    on_frame: Option<FrameFn>,
+    on_audio: Option<AudioFn>,
+}
+
+impl CallbackSink {
+    /// Create a sink that does nothing until given callbacks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name the sink, for errors and logs
+    #[must_use]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Call `callback` with every new video frame
+    #[must_use]
+    pub fn on_frame(mut self, callback: impl FnMut(&IOSurface, CMTime) + Send + 'static) -> Self {
+        self.on_frame = Some(Box::new(callback));
+        self
+    }
+
+    /// Call `callback` with every audio sample
+    #[must_use]
+    pub fn on_audio(
+        mut self,
+        callback: impl FnMut(&AudioBufferList, CMTime, SCStreamOutputType) + Send + 'static,
+    ) -> Self {
+        self.on_audio = Some(Box::new(callback));
+        self
+    }
+}
+
+impl RecordingSink for CallbackSink {
+    fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| "callback".to_string())
+    }
+
+    fn receives_samples(&self) -> bool {
+        self.on_frame.is_some() || self.on_audio.is_some()
+    }
+
+    fn write_frame(&mut self, surface: &IOSurface, timestamp: CMTime) -> SCResult<()> {
+        if let Some(callback) = self.on_frame.as_mut() {
+            callback(surface, timestamp);
+        }
+        Ok(())
+    }
+
+    fn write_audio(
+        &mut self,
+        buffers: &AudioBufferList,
+        timestamp: CMTime,
+        source: SCStreamOutputType,
+    ) -> SCResult<()> {
+        if let Some(callback) = self.on_audio.as_mut() {
+            callback(buffers, timestamp, source);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CallbackSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackSink")
+            .field("name", &self.name())
+            .field("on_frame", &self.on_frame.is_some())
+            .field("on_audio", &self.on_audio.is_some())
+            .finish()
+    }
+}
+
+/// Sink that records to a file with an `SCRecordingOutput` (macOS 15.0+)
+///
+/// The recording output encodes the stream itself, so this sink receives no
+/// samples. Its file is finished when the sink is removed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use screencapturekit::recording_output::{SCRecordingOutput, SCRecordingOutputConfiguration};
+/// use screencapturekit::recording_sink::FileSink;
+/// use std::path::Path;
+///
+/// let config = SCRecordingOutputConfiguration::new()
+///     .with_output_url(Path::new("/tmp/recording.mp4"));
+/// let output = SCRecordingOutput::new(&config).expect("macOS 15.0+ required");
+/// let sink = FileSink::new(output, "/tmp/recording.mp4");
+/// ```
+#[cfg(feature = "macos_15_0")]
+#[derive(Clone)]
+pub struct FileSink {
+    output: crate::recording_output::SCRecordingOutput,
+    path: String,
+}
+
+#[cfg(feature = "macos_15_0")]
+impl FileSink {
+    /// Record with `output`, configured to write to `path`
+    pub fn new(
+        output: crate::recording_output::SCRecordingOutput,
+        path: impl Into<String>,
+    ) -> Self {
+        Self {
+            output,
+            path: path.into(),
+        }
+    }
+
+    /// The recording output, e.g. for its duration and file size so far
+    pub fn output(&self) -> &crate::recording_output::SCRecordingOutput {
+        &self.output
+    }
+}
+
+#[cfg(feature = "macos_15_0")]
+impl RecordingSink for FileSink {
+    fn name(&self) -> String {
+        self.path.clone()
+    }
+
+    fn receives_samples(&self) -> bool {
+        false
+    }
+
+    fn start(&mut self, stream: &SCStream) -> SCResult<()> {
+        stream.add_recording_output(&self.output)
+    }
+
+    fn stop(&mut self, stream: &SCStream) -> SCResult<()> {
+        stream.remove_recording_output(&self.output)
+    }
+}
+
+#[cfg(feature = "macos_15_0")]
+impl fmt::Debug for FileSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileSink")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
@@ -19,58 +19,90 @@ use crate::{
     },
 };
 
+/// An output handler and the stream and output type it was added for
+struct RegisteredHandler {
+    /// Address of the Swift stream, which the bridge passes back with each sample
+    stream: usize,
+    of_type: SCStreamOutputType,
+    handler: Box<dyn SCStreamOutputTrait>,
+}
+
 // Global registry for output handlers
-static HANDLER_REGISTRY: Mutex<Option<HashMap<usize, Box<dyn SCStreamOutputTrait>>>> =
-    Mutex::new(None);
+static HANDLER_REGISTRY: Mutex<Option<HashMap<usize, RegisteredHandler>>> = Mutex::new(None);
 static NEXT_HANDLER_ID: Mutex<usize> = Mutex::new(1);
 
-// C callback that retrieves handler from registry
+/// Drop handler `id` from the registry
+///
+/// Returns `true` when no handler of its stream and output type is left, so the
+/// Swift output they shared can be removed too.
+fn unregister(id: usize) -> bool {
+    let mut registry = HANDLER_REGISTRY.lock().unwrap();
+    let Some(handlers) = registry.as_mut() else {
+        return false;
+    };
+    let Some(removed) = handlers.remove(&id) else {
+        return false;
+    };
+    let last_of_its_kind = !handlers
+        .values()
+        .any(|entry| entry.stream == removed.stream && entry.of_type == removed.of_type);
+    drop(registry);
+    last_of_its_kind
+}
+
+// C callback that passes each sample to the handlers of its stream and output type
 extern "C" fn sample_handler(
-    _stream: *const c_void,
+    stream: *const c_void,
     sample_buffer: *const c_void,
     output_type: i32,
 ) {
-    // Mutex poisoning is unrecoverable in C callback context; unwrap is appropriate
-    let registry = HANDLER_REGISTRY.lock().unwrap();
-    if let Some(handlers) = registry.as_ref() {
-        if handlers.is_empty() {
-            // No handlers registered - release the buffer that Swift passed us
+    let output_type_enum = match output_type {
+        0 => SCStreamOutputType::Screen,
+        1 => SCStreamOutputType::Audio,
+        2 => SCStreamOutputType::Microphone,
+        _ => {
+            eprintln!("Unknown output type: {output_type}");
+            // Unknown type - release the buffer
             unsafe { crate::cm::ffi::cm_sample_buffer_release(sample_buffer.cast_mut()) };
             return;
         }
+    };
 
-        let output_type_enum = match output_type {
-            0 => SCStreamOutputType::Screen,
-            1 => SCStreamOutputType::Audio,
-            2 => SCStreamOutputType::Microphone,
-            _ => {
-                eprintln!("Unknown output type: {output_type}");
-                // Unknown type - release the buffer
-                unsafe { crate::cm::ffi::cm_sample_buffer_release(sample_buffer.cast_mut()) };
-                return;
-            }
-        };
-
-        let handler_count = handlers.len();
+    // Mutex poisoning is unrecoverable in C callback context; unwrap is appropriate.
+    // Handlers are only `Send`, so the registry stays locked while they run; that
+    // keeps outputs of different types from calling into one handler at once.
+    let registry = HANDLER_REGISTRY.lock().unwrap();
+    let handlers: Vec<&dyn SCStreamOutputTrait> = registry
+        .as_ref()
+        .into_iter()
+        .flat_map(HashMap::values)
+        .filter(|entry| entry.stream == stream as usize && entry.of_type == output_type_enum)
+        .map(|entry| entry.handler.as_ref())
+        .collect();
+    if handlers.is_empty() {
+        drop(registry);
+        // No handlers for this stream and type - release the buffer that Swift passed us
+        unsafe { crate::cm::ffi::cm_sample_buffer_release(sample_buffer.cast_mut()) };
+        return;
+    }
 
-        // Call all registered handlers
-        for (idx, (_id, handler)) in handlers.iter().enumerate() {
-            // Convert raw pointer to CMSampleBuffer
-            let buffer = unsafe { crate::cm::CMSampleBuffer::from_ptr(sample_buffer.cast_mut()) };
+    let handler_count = handlers.len();
 
-            // For all handlers except the last, we need to retain the buffer
-            if idx < handler_count - 1 {
-                // Retain the buffer so it's not released when this handler's buffer is dropped
-                unsafe { crate::cm::ffi::cm_sample_buffer_retain(sample_buffer.cast_mut()) };
-            }
-            // The last handler will release the original retained reference from Swift
+    // Call every handler of this stream and type
+    for (idx, handler) in handlers.into_iter().enumerate() {
+        // Convert raw pointer to CMSampleBuffer
+        let buffer = unsafe { crate::cm::CMSampleBuffer::from_ptr(sample_buffer.cast_mut()) };
 
-            handler.did_output_sample_buffer(buffer, output_type_enum);
+        // For all handlers except the last, we need to retain the buffer
+        if idx < handler_count - 1 {
+            // Retain the buffer so it's not released when this handler's buffer is dropped
+            unsafe { crate::cm::ffi::cm_sample_buffer_retain(sample_buffer.cast_mut()) };
         }
-    } else {
-        // No registry - release the buffer
-        unsafe { crate::cm::ffi::cm_sample_buffer_release(sample_buffer.cast_mut()) };
+        // The last handler will release the original retained reference from Swift
+
+        handler.did_output_sample_buffer(buffer, output_type_enum);
     }
+    drop(registry);
 }
 
 /// `SCStream` is a lightweight wrapper around the Swift `SCStream` instance.
@@ -248,7 +280,8 @@ impl SCStream {
 
     /// Add an output handler with a custom dispatch queue
     ///
-    /// This allows controlling which thread/queue the handler is called on.
+    /// This allows controlling which thread/queue the handler is called on. Handlers of
+    /// the same output type on one stream share its first handler's queue.
     ///
     /// # Arguments
     ///
@@ -297,19 +330,27 @@ impl SCStream {
             id
         };
 
-        // Store handler in registry
-        {
+        // Store handler in registry; each output type of a stream has one Swift
+        // output, shared by all of that type's handlers
+        let first_of_type = {
             // Mutex poisoning is unrecoverable; unwrap is appropriate
             let mut registry = HANDLER_REGISTRY.lock().unwrap();
-            if registry.is_none() {
-                *registry = Some(HashMap::new());
-            }
-            // We just ensured registry is Some above
-            registry
-                .as_mut()
-                .unwrap()
-                .insert(handler_id, Box::new(handler));
-        }
+            let handlers = registry.get_or_insert_with(HashMap::new);
+            let stream = self.ptr as usize;
+            let first_of_type = !handlers
+                .values()
+                .any(|entry| entry.stream == stream && entry.of_type == of_type);
+            handlers.insert(
+                handler_id,
+                RegisteredHandler {
+                    stream,
+                    of_type,
+                    handler: Box::new(handler),
+                },
+            );
+            drop(registry);
+            first_of_type
+        };
 
         // Convert output type to int for Swift
         let output_type_int = match of_type {
@@ -318,7 +359,9 @@ impl SCStream {
             SCStreamOutputType::Microphone => 2,
         };
 
-        let ok = if let Some(q) = queue {
+        let ok = if !first_of_type {
+            true
+        } else if let Some(q) = queue {
             unsafe {
                 ffi::sc_stream_add_stream_output_with_queue(
                     self.ptr,
@@ -336,11 +379,7 @@ impl SCStream {
             Some(handler_id)
         } else {
             // Remove from registry since Swift rejected it
-            HANDLER_REGISTRY
-                .lock()
-                .unwrap()
-                .as_mut()
-                .map(|handlers| handlers.remove(&handler_id));
+            unregister(handler_id);
             None
         }
     }
@@ -367,12 +406,9 @@ impl SCStream {
         }
         self.handler_ids.remove(pos.unwrap());
 
-        // Remove from global registry
-        {
-            let mut registry = HANDLER_REGISTRY.lock().unwrap();
-            if let Some(handlers) = registry.as_mut() {
-                handlers.remove(&id);
-            }
+        // Remove from global registry, keeping the Swift output while other handlers use it
+        if !unregister(id) {
+            return true;
         }
 
         // Tell Swift to remove the output
@@ -384,17 +420,61 @@ impl SCStream {
         unsafe { ffi::sc_stream_remove_stream_output(self.ptr, output_type_int) }
     }
 
+    /// Pass `sample` to this stream's handlers of `of_type`, as if it had been captured
+    ///
+    /// The sample takes the same path as captured ones, so code built on top of a
+    /// stream can be tested without capturing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal handler registry mutex is poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use screencapturekit::prelude::*;
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let content = SCShareableContent::get()?;
+    /// # let display = &content.displays()[0];
+    /// # let filter = SCContentFilter::builder().display(display).exclude_windows(&[]).build();
+    /// # let config = SCStreamConfiguration::default();
+    /// let mut stream = SCStream::new(&filter, &config);
+    /// stream.add_output_handler(|_sample, _type| println!("Got audio!"), SCStreamOutputType::Audio);
+    ///
+    /// let samples = vec![0.0f32; 480 * 2];
+    /// let sample = CMSampleBuffer::create_for_pcm_audio(&samples, 2, 48_000.0, CMTime::new(0, 48_000))
+    ///     .expect("Failed to create sample buffer");
+    /// stream.dispatch_sample(sample, SCStreamOutputType::Audio);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dispatch_sample(&self, sample: crate::cm::CMSampleBuffer, of_type: SCStreamOutputType) {
+        let output_type_int = match of_type {
+            SCStreamOutputType::Screen => 0,
+            SCStreamOutputType::Audio => 1,
+            SCStreamOutputType::Microphone => 2,
+        };
+        // The handlers take over the reference, as they do Swift's
+        let sample_buffer = sample.as_ptr();
+        std::mem::forget(sample);
+        sample_handler(self.ptr, sample_buffer, output_type_int);
+    }
+
     /// Start capturing screen content
     ///
     /// This method blocks until the capture operation completes or fails.
     ///
     /// # Errors
     ///
-    /// Returns `SCError::CaptureStartFailed` if the capture fails to start.
+    /// Returns `SCError::SCStreamError` with Apple's error code if `ScreenCaptureKit`
+    /// refuses to start, or `SCError::CaptureStartFailed` for other failures.
     pub fn start_capture(&self) -> Result<(), SCError> {
         let (completion, context) = UnitCompletion::new();
         unsafe { ffi::sc_stream_start_capture(self.ptr, context, UnitCompletion::callback) };
-        completion.wait().map_err(SCError::CaptureStartFailed)
+        completion
+            .wait()
+            .map_err(|e| SCError::from_coded_message(&e, SCError::CaptureStartFailed))
     }
 
     /// Stop capturing screen content
@@ -532,12 +612,9 @@ impl Drop for SCStream {
     fn drop(&mut self) {
         // Clean up all registered handlers
         for (id, of_type) in std::mem::take(&mut self.handler_ids) {
-            // Remove from global registry
-            {
-                let mut registry = HANDLER_REGISTRY.lock().unwrap();
-                if let Some(handlers) = registry.as_mut() {
-                    handlers.remove(&id);
-                }
+            // Remove from global registry, keeping the Swift output while other handlers use it
+            if !unregister(id) {
+                continue;
             }
 
             // Tell Swift to remove the output
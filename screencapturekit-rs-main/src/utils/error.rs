@@ -465,6 +465,35 @@ impl SCError {
         )
     }
 
+    /// Create an error from a bridge message of the form `"CODE:message"`
+    ///
+    /// A known `SCStreamErrorCode` gives an `SCStreamError`; anything else is passed,
+    /// without the code, to `fallback`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use screencapturekit::error::{SCError, SCStreamErrorCode};
+    ///
+    /// let err = SCError::from_coded_message("-3804:Already running", SCError::CaptureStartFailed);
+    /// assert_eq!(err.stream_error_code(), Some(SCStreamErrorCode::AttemptToStartStreamState));
+    ///
+    /// let err = SCError::from_coded_message("0:Something else", SCError::CaptureStartFailed);
+    /// assert_eq!(err, SCError::CaptureStartFailed("Something else".to_string()));
+    /// ```
+    pub fn from_coded_message(text: &str, fallback: impl FnOnce(String) -> Self) -> Self {
+        let Some((code, message)) = text.split_once(':') else {
+            return fallback(text.to_string());
+        };
+        match code.parse::<i32>() {
+            Ok(code) => SCStreamErrorCode::from_raw(code).map_or_else(
+                || fallback(message.to_string()),
+                |code| Self::from_stream_error_code_with_message(code, message),
+            ),
+            Err(_) => fallback(text.to_string()),
+        }
+    }
+
     /// Get the `SCStreamErrorCode` if this is an `SCStreamError`
     ///
     /// # Examples
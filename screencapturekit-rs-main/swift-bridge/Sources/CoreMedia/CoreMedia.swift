@@ -794,6 +794,89 @@ public func cm_sample_buffer_create_for_image_buffer(
     return status
 }
 
+@_cdecl("cm_sample_buffer_create_for_pcm_audio")
+public func cm_sample_buffer_create_for_pcm_audio(
+    _ samples: UnsafePointer<Float>,
+    _ frameCount: Int,
+    _ channelCount: UInt32,
+    _ sampleRate: Double,
+    _ presentationTimeValue: Int64,
+    _ presentationTimeScale: Int32,
+    _ sampleBufferOut: UnsafeMutablePointer<UnsafeMutableRawPointer?>
+) -> Int32 {
+    sampleBufferOut.pointee = nil
+
+    // Interleaved 32-bit float, as ScreenCaptureKit delivers system audio
+    let bytesPerFrame = UInt32(MemoryLayout<Float>.size) * channelCount
+    var streamDescription = AudioStreamBasicDescription(
+        mSampleRate: sampleRate,
+        mFormatID: kAudioFormatLinearPCM,
+        mFormatFlags: kAudioFormatFlagIsFloat | kAudioFormatFlagIsPacked,
+        mBytesPerPacket: bytesPerFrame,
+        mFramesPerPacket: 1,
+        mBytesPerFrame: bytesPerFrame,
+        mChannelsPerFrame: channelCount,
+        mBitsPerChannel: 32,
+        mReserved: 0
+    )
+    var formatDescription: CMAudioFormatDescription?
+    var status = CMAudioFormatDescriptionCreate(
+        allocator: kCFAllocatorDefault,
+        asbd: &streamDescription,
+        layoutSize: 0,
+        layout: nil,
+        magicCookieSize: 0,
+        magicCookie: nil,
+        extensions: nil,
+        formatDescriptionOut: &formatDescription
+    )
+    guard status == noErr, let format = formatDescription else {
+        return status
+    }
+
+    let dataLength = frameCount * Int(bytesPerFrame)
+    var blockBuffer: CMBlockBuffer?
+    status = CMBlockBufferCreateWithMemoryBlock(
+        allocator: kCFAllocatorDefault,
+        memoryBlock: nil,
+        blockLength: dataLength,
+        blockAllocator: kCFAllocatorDefault,
+        customBlockSource: nil,
+        offsetToData: 0,
+        dataLength: dataLength,
+        flags: kCMBlockBufferAssureMemoryNowFlag,
+        blockBufferOut: &blockBuffer
+    )
+    guard status == noErr, let block = blockBuffer else {
+        return status
+    }
+    status = CMBlockBufferReplaceDataBytes(
+        with: samples,
+        blockBuffer: block,
+        offsetIntoDestination: 0,
+        dataLength: dataLength
+    )
+    guard status == noErr else {
+        return status
+    }
+
+    var sampleBuffer: CMSampleBuffer?
+    status = CMAudioSampleBufferCreateReadyWithPacketDescriptions(
+        allocator: kCFAllocatorDefault,
+        dataBuffer: block,
+        formatDescription: format,
+        sampleCount: frameCount,
+        presentationTimeStamp: CMTime(value: CMTimeValue(presentationTimeValue), timescale: presentationTimeScale, flags: .valid, epoch: 0),
+        packetDescriptions: nil,
+        sampleBufferOut: &sampleBuffer
+    )
+    if status == noErr, let buffer = sampleBuffer {
+        sampleBufferOut.pointee = Unmanaged.passRetained(buffer).toOpaque()
+    }
+
+    return status
+}
+
 // MARK: - Hash Functions
 
 @_cdecl("cm_sample_buffer_hash")
@@ -509,7 +509,8 @@ public func removeStreamOutput(
 /// - Parameters:
 ///   - stream: The stream to start
 ///   - context: Opaque context pointer passed back to callback
-///   - callback: Called with context, success/failure and optional error message
+///   - callback: Called with context, success/failure and optional error message,
+///     formatted as "CODE:message" where CODE is the SCStreamError code or 0
 @_cdecl("sc_stream_start_capture")
 public func startStreamCapture(
     _ stream: OpaquePointer,
@@ -522,8 +523,8 @@ public func startStreamCapture(
             try await scStream.startCapture()
             callback(context, true, nil)
         } catch {
-            let bridgeError = SCBridgeError.streamError(error.localizedDescription)
-            bridgeError.description.withCString { callback(context, false, $0) }
+            let code = extractStreamErrorCode(error)
+            "\(code):\(error.localizedDescription)".withCString { callback(context, false, $0) }
         }
     }
 }
@@ -182,6 +182,30 @@ fn test_scerror_from_error_code() {
     }
 }
 
+#[test]
+fn test_scerror_from_coded_message() {
+    // Known code keeps the code and the message
+    let error = SCError::from_coded_message("-3803:No displays", SCError::CaptureStartFailed);
+    match error {
+        SCError::SCStreamError { code, message } => {
+            assert_eq!(code, SCStreamErrorCode::FailedToStart);
+            assert_eq!(message, Some("No displays".to_string()));
+        }
+        _ => panic!("Expected SCStreamError variant"),
+    }
+
+    // Other errors fall back without their code
+    let error = SCError::from_coded_message("0:Timed out", SCError::CaptureStartFailed);
+    assert_eq!(error, SCError::CaptureStartFailed("Timed out".to_string()));
+
+    // Messages without a code are passed on whole
+    let error = SCError::from_coded_message("Stream error: gone", SCError::CaptureStartFailed);
+    assert_eq!(
+        error,
+        SCError::CaptureStartFailed("Stream error: gone".to_string())
+    );
+}
+
 #[test]
 fn test_scerror_stream_error_code_getter() {
     let code = SCStreamErrorCode::InternalError;
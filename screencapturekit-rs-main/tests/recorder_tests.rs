@@ -0,0 +1,213 @@
+//! Recorder tests
+//!
+//! Tests for `Recorder` and its frame and audio `Subscribers`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use screencapturekit::cm::{CMSampleBuffer, CMTime, CVPixelBuffer, CVPixelBufferPool};
+use screencapturekit::output::CVPixelBufferIOSurface;
+use screencapturekit::prelude::*;
+use screencapturekit::recorder::{Recorder, Subscribers};
+
+#[test]
+fn test_subscribers_start_empty() {
+    let subscribers = Subscribers::new();
+    assert_eq!(subscribers.frame_subscriber_count(), 0);
+    assert_eq!(subscribers.audio_subscriber_count(), 0);
+}
+
+#[test]
+fn test_subscription_ids_are_unique() {
+    let subscribers = Subscribers::new();
+    let frame = subscribers.on_frame(|_, _| {});
+    let audio = subscribers.on_audio(|_, _, _| {});
+    let another_frame = subscribers.on_frame(|_, _| {});
+
+    assert_ne!(frame, audio);
+    assert_ne!(frame, another_frame);
+    assert_ne!(audio, another_frame);
+    assert_eq!(subscribers.frame_subscriber_count(), 2);
+    assert_eq!(subscribers.audio_subscriber_count(), 1);
+}
+
+#[test]
+fn test_unsubscribe_removes_only_that_subscription() {
+    let subscribers = Subscribers::new();
+    let frame = subscribers.on_frame(|_, _| {});
+    let audio = subscribers.on_audio(|_, _, _| {});
+
+    assert!(subscribers.unsubscribe(audio));
+    assert_eq!(subscribers.frame_subscriber_count(), 1);
+    assert_eq!(subscribers.audio_subscriber_count(), 0);
+
+    assert!(subscribers.unsubscribe(frame));
+    assert_eq!(subscribers.frame_subscriber_count(), 0);
+}
+
+#[test]
+fn test_unsubscribe_twice() {
+    let subscribers = Subscribers::new();
+    let id = subscribers.on_frame(|_, _| {});
+
+    assert!(subscribers.unsubscribe(id));
+    assert!(!subscribers.unsubscribe(id));
+}
+
+#[test]
+fn test_clones_share_subscribers() {
+    let subscribers = Subscribers::new();
+    let handle = subscribers.clone();
+    let id = handle.on_audio(|_, _, _| {});
+
+    assert_eq!(subscribers.audio_subscriber_count(), 1);
+    assert!(subscribers.unsubscribe(id));
+    assert_eq!(handle.audio_subscriber_count(), 0);
+}
+
+#[test]
+fn test_subscribers_send_sync() {
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+
+    assert_send::<Subscribers>();
+    assert_sync::<Subscribers>();
+    assert_send::<Recorder>();
+}
+
+#[test]
+fn test_frames_reach_every_subscriber() {
+    // Pool buffers are backed by an IOSurface, as captured frames are
+    let pool = CVPixelBufferPool::create(64, 64, 0x4247_5241, 1).expect("Failed to create pool");
+    let pixel_buffer = pool
+        .create_pixel_buffer()
+        .expect("Failed to create pixel buffer");
+    assert!(pixel_buffer.is_backed_by_iosurface());
+    let sample = CMSampleBuffer::create_for_image_buffer(
+        &pixel_buffer,
+        CMTime::new(0, 30),
+        CMTime::new(1, 30),
+    )
+    .expect("Failed to create sample buffer");
+
+    let subscribers = Subscribers::new();
+    let first = Arc::new(AtomicUsize::new(0));
+    let second = Arc::new(AtomicUsize::new(0));
+    let audio = Arc::new(AtomicUsize::new(0));
+    {
+        let first = Arc::clone(&first);
+        subscribers.on_frame(move |_, _| {
+            first.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+    {
+        let second = Arc::clone(&second);
+        subscribers.on_frame(move |surface, timestamp| {
+            assert_eq!(surface.width(), 64);
+            assert_eq!(timestamp.value, 0);
+            second.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+    {
+        let audio = Arc::clone(&audio);
+        subscribers.on_audio(move |_, _, _| {
+            audio.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    subscribers.did_output_sample_buffer(sample, SCStreamOutputType::Screen);
+
+    assert_eq!(first.load(Ordering::Relaxed), 1);
+    assert_eq!(second.load(Ordering::Relaxed), 1);
+    assert_eq!(audio.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn test_frames_without_subscribers() {
+    let pixel_buffer =
+        CVPixelBuffer::create(64, 64, 0x4247_5241).expect("Failed to create pixel buffer");
+    let sample = CMSampleBuffer::create_for_image_buffer(
+        &pixel_buffer,
+        CMTime::new(0, 30),
+        CMTime::new(1, 30),
+    )
+    .expect("Failed to create sample buffer");
+
+    let subscribers = Subscribers::new();
+    subscribers.did_output_sample_buffer(sample, SCStreamOutputType::Screen);
+}
+
+#[test]
+fn test_recorder_creation() {
+    let Ok(content) = SCShareableContent::get() else {
+        println!("⚠ Skipping - no screen recording permission");
+        return;
+    };
+
+    if content.displays().is_empty() {
+        println!("⚠ No displays available");
+        return;
+    }
+
+    let display = &content.displays()[0];
+    let filter = SCContentFilter::builder().display(display).build();
+    let config = SCStreamConfiguration::default();
+
+    let recorder = Recorder::new(&filter, &config);
+    let id = recorder.on_frame(|_, _| {});
+    recorder.on_audio(|_, _, _| {});
+
+    assert_eq!(recorder.subscribers().frame_subscriber_count(), 1);
+    assert_eq!(recorder.subscribers().audio_subscriber_count(), 1);
+    assert!(recorder.unsubscribe(id));
+
+    println!("✓ Recorder created with subscribers");
+}
+
+#[test]
+fn test_recorder_passes_audio_to_subscribers() {
+    let Ok(content) = SCShareableContent::get() else {
+        println!("⚠ Skipping - no screen recording permission");
+        return;
+    };
+
+    if content.displays().is_empty() {
+        println!("⚠ No displays available");
+        return;
+    }
+
+    let display = &content.displays()[0];
+    let filter = SCContentFilter::builder().display(display).build();
+    let config = SCStreamConfiguration::default().with_captures_audio(true);
+
+    let recorder = Recorder::new(&filter, &config);
+    let frames = Arc::new(AtomicUsize::new(0));
+    let audio = Arc::new(AtomicUsize::new(0));
+    {
+        let frames = Arc::clone(&frames);
+        recorder.on_frame(move |_, _| {
+            frames.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+    {
+        let audio = Arc::clone(&audio);
+        recorder.on_audio(move |buffers, timestamp, source| {
+            assert_eq!(buffers.num_buffers(), 1);
+            assert_eq!(timestamp.value, 0);
+            assert_eq!(source, SCStreamOutputType::Audio);
+            audio.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    // 10 ms of stereo silence at 48 kHz, through the stream as captured audio is
+    let samples = vec![0.0f32; 480 * 2];
+    let sample =
+        CMSampleBuffer::create_for_pcm_audio(&samples, 2, 48_000.0, CMTime::new(0, 48_000))
+            .expect("Failed to create sample buffer");
+    recorder
+        .stream()
+        .dispatch_sample(sample, SCStreamOutputType::Audio);
+
+    assert_eq!(audio.load(Ordering::Relaxed), 1);
+    assert_eq!(frames.load(Ordering::Relaxed), 0);
+}
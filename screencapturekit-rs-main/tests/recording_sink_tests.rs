@@ -0,0 +1,250 @@
+//! Recording sink tests
+//!
+//! Tests for `RecordingSink`, the bundled sinks and attaching sinks to a `Recorder`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use screencapturekit::cm::{CMSampleBuffer, CMTime, CVPixelBufferPool};
+use screencapturekit::output::IOSurface;
+use screencapturekit::prelude::*;
+use screencapturekit::recorder::Recorder;
+use screencapturekit::recording_sink::{CallbackSink, RecordingSink, SinkStatus};
+
+/// Sink that counts its lifecycle calls and can be made to fail
+struct TestSink {
+    starts: Arc<AtomicUsize>,
+    stops: Arc<AtomicUsize>,
+    fail_start: bool,
+}
+
+impl TestSink {
+    fn new(fail_start: bool) -> (Self, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let starts = Arc::new(AtomicUsize::new(0));
+        let stops = Arc::new(AtomicUsize::new(0));
+        let sink = Self {
+            starts: Arc::clone(&starts),
+            stops: Arc::clone(&stops),
+            fail_start,
+        };
+        (sink, starts, stops)
+    }
+}
+
+impl RecordingSink for TestSink {
+    fn name(&self) -> String {
+        "test".to_string()
+    }
+
+    fn start(&mut self, _stream: &SCStream) -> SCResult<()> {
+        self.starts.fetch_add(1, Ordering::Relaxed);
+        if self.fail_start {
+            Err(SCError::StreamError("refused".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn stop(&mut self, _stream: &SCStream) -> SCResult<()> {
+        self.stops.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Sink that counts the frames written to it, and can be made to fail writing them
+struct FrameSink {
+    frames: Arc<AtomicUsize>,
+    fail_write: bool,
+}
+
+impl FrameSink {
+    fn new(fail_write: bool) -> (Self, Arc<AtomicUsize>) {
+        let frames = Arc::new(AtomicUsize::new(0));
+        let sink = Self {
+            frames: Arc::clone(&frames),
+            fail_write,
+        };
+        (sink, frames)
+    }
+}
+
+impl RecordingSink for FrameSink {
+    fn name(&self) -> String {
+        "frames".to_string()
+    }
+
+    fn write_frame(&mut self, surface: &IOSurface, _timestamp: CMTime) -> SCResult<()> {
+        assert_eq!(surface.width(), 64);
+        self.frames.fetch_add(1, Ordering::Relaxed);
+        if self.fail_write {
+            Err(SCError::StreamError("disconnected".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A frame as captured ones arrive, backed by an IOSurface
+fn test_frame() -> CMSampleBuffer {
+    let pool = CVPixelBufferPool::create(64, 64, 0x4247_5241, 1).expect("Failed to create pool");
+    let pixel_buffer = pool
+        .create_pixel_buffer()
+        .expect("Failed to create pixel buffer");
+    CMSampleBuffer::create_for_image_buffer(&pixel_buffer, CMTime::new(0, 30), CMTime::new(1, 30))
+        .expect("Failed to create sample buffer")
+}
+
+fn test_recorder() -> Option<Recorder> {
+    let Ok(content) = SCShareableContent::get() else {
+        println!("⚠ Skipping - no screen recording permission");
+        return None;
+    };
+    let Some(display) = content.displays().into_iter().next() else {
+        println!("⚠ No displays available");
+        return None;
+    };
+    let filter = SCContentFilter::builder().display(&display).build();
+    Some(Recorder::new(&filter, &SCStreamConfiguration::default()))
+}
+
+#[test]
+fn test_callback_sink_defaults() {
+    let sink = CallbackSink::new();
+    assert_eq!(sink.name(), "callback");
+    assert!(!sink.receives_samples());
+}
+
+#[test]
+fn test_callback_sink_with_callbacks() {
+    let sink = CallbackSink::new()
+        .with_name("frames")
+        .on_frame(|_, _| {})
+        .on_audio(|_, _, _| {});
+    assert_eq!(sink.name(), "frames");
+    assert!(sink.receives_samples());
+}
+
+#[test]
+fn test_sink_status_display() {
+    assert_eq!(SinkStatus::Running.to_string(), "running");
+    let failed = SinkStatus::Failed(SCError::StreamError("disconnected".to_string()));
+    assert!(failed.to_string().starts_with("failed: "));
+    assert!(failed.to_string().contains("disconnected"));
+}
+
+#[test]
+fn test_sinks_are_send() {
+    fn assert_send<T: Send>() {}
+
+    assert_send::<CallbackSink>();
+    assert_send::<Box<dyn RecordingSink>>();
+}
+
+#[test]
+fn test_add_and_remove_sink() {
+    let Some(recorder) = test_recorder() else {
+        return;
+    };
+    let (sink, starts, stops) = TestSink::new(false);
+
+    let id = recorder.add_sink(sink).expect("Sink should start");
+    assert_eq!(starts.load(Ordering::Relaxed), 1);
+    assert_eq!(recorder.sink_ids(), vec![id]);
+    assert_eq!(recorder.sink_status(id), Some(SinkStatus::Running));
+    assert_eq!(recorder.subscribers().frame_subscriber_count(), 1);
+
+    recorder.remove_sink(id).expect("Sink should stop");
+    assert_eq!(stops.load(Ordering::Relaxed), 1);
+    assert!(recorder.sink_ids().is_empty());
+    assert_eq!(recorder.sink_status(id), None);
+    assert_eq!(recorder.subscribers().frame_subscriber_count(), 0);
+
+    assert!(recorder.remove_sink(id).is_err());
+}
+
+#[test]
+fn test_sink_that_fails_to_start_is_not_added() {
+    let Some(recorder) = test_recorder() else {
+        return;
+    };
+    let (sink, starts, stops) = TestSink::new(true);
+
+    assert!(recorder.add_sink(sink).is_err());
+    assert_eq!(starts.load(Ordering::Relaxed), 1);
+    assert_eq!(stops.load(Ordering::Relaxed), 0);
+    assert!(recorder.sink_ids().is_empty());
+}
+
+#[test]
+fn test_sinks_have_independent_lifecycles() {
+    let Some(recorder) = test_recorder() else {
+        return;
+    };
+    let (first, _, first_stops) = TestSink::new(false);
+    let (second, _, second_stops) = TestSink::new(false);
+
+    let first = recorder.add_sink(first).expect("Sink should start");
+    let second = recorder.add_sink(second).expect("Sink should start");
+    assert_ne!(first, second);
+
+    recorder.remove_sink(first).expect("Sink should stop");
+    assert_eq!(first_stops.load(Ordering::Relaxed), 1);
+    assert_eq!(second_stops.load(Ordering::Relaxed), 0);
+    assert_eq!(recorder.sink_status(second), Some(SinkStatus::Running));
+}
+
+#[test]
+fn test_dropping_recorder_stops_sinks() {
+    let Some(recorder) = test_recorder() else {
+        return;
+    };
+    let (sink, _, stops) = TestSink::new(false);
+    recorder.add_sink(sink).expect("Sink should start");
+
+    drop(recorder);
+    assert_eq!(stops.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn test_frames_reach_sink() {
+    let Some(recorder) = test_recorder() else {
+        return;
+    };
+    let (sink, frames) = FrameSink::new(false);
+    let id = recorder.add_sink(sink).expect("Sink should start");
+
+    recorder
+        .subscribers()
+        .did_output_sample_buffer(test_frame(), SCStreamOutputType::Screen);
+
+    assert_eq!(frames.load(Ordering::Relaxed), 1);
+    assert_eq!(recorder.sink_status(id), Some(SinkStatus::Running));
+}
+
+#[test]
+fn test_failing_sink_does_not_stop_the_others() {
+    let Some(recorder) = test_recorder() else {
+        return;
+    };
+    let (failing, failing_frames) = FrameSink::new(true);
+    let (healthy, healthy_frames) = FrameSink::new(false);
+    let failing = recorder.add_sink(failing).expect("Sink should start");
+    let healthy = recorder.add_sink(healthy).expect("Sink should start");
+
+    for _ in 0..2 {
+        recorder
+            .subscribers()
+            .did_output_sample_buffer(test_frame(), SCStreamOutputType::Screen);
+    }
+
+    // The failing sink got the frame it failed on and none after it
+    assert_eq!(failing_frames.load(Ordering::Relaxed), 1);
+    assert_eq!(
+        recorder.sink_status(failing),
+        Some(SinkStatus::Failed(SCError::StreamError(
+            "disconnected".to_string()
+        )))
+    );
+    assert_eq!(healthy_frames.load(Ordering::Relaxed), 2);
+    assert_eq!(recorder.sink_status(healthy), Some(SinkStatus::Running));
+}